@@ -0,0 +1,164 @@
+// File bench.rs
+// This module contains the built-in benchmarks, run with `collatz_app bench`.
+// For starting values of increasing size, it measures the sequence generation, the statistics,
+// the stopping-time backends used by the range scans and the chart rendering,
+// and prints everything as a single comparison table.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::{cache, collatz};
+#[cfg(feature = "gpu")]
+use crate::gpu;
+
+/// Sizes of the starting values benchmarked (each row of the table starts at one of them).
+pub const SIZES: [u64; 6] = [
+    1_000,
+    1_000_000,
+    1_000_000_000,
+    1_000_000_000_000,
+    1_000_000_000_000_000,
+    1_000_000_000_000_000_000,
+];
+
+// Number of sequences generated per size (for the sequence and statistics columns).
+const SEQUENCES_PER_SIZE: u64 = 2_000;
+
+// Number of starting values scanned per size and backend.
+const SCAN_LENGTH: u64 = 200_000;
+
+// Size of the memoization table prepared for the cached backend (not included in the timings).
+const CACHE_TABLE_SIZE: u64 = 1_000_000;
+
+/// Runs `f` once and returns its result along with the time it took.
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let timer = Instant::now();
+    let result = f();
+    (result, timer.elapsed())
+}
+
+/// Formats a duration per operation with a readable unit (ns, µs, ms or s).
+pub fn format_per_op(total: Duration, operations: u64) -> String {
+    let nanos = total.as_nanos() as f64 / operations.max(1) as f64;
+    if nanos < 1_000.0 {
+        format!("{:.0} ns", nanos)
+    } else if nanos < 1_000_000.0 {
+        format!("{:.1} µs", nanos / 1_000.0)
+    } else if nanos < 1_000_000_000.0 {
+        format!("{:.1} ms", nanos / 1_000_000.0)
+    } else {
+        format!("{:.2} s", nanos / 1_000_000_000.0)
+    }
+}
+
+/// Formats a throughput in millions of values per second.
+pub fn format_rate(total: Duration, values: u64) -> String {
+    let seconds = total.as_secs_f64();
+    if seconds == 0.0 {
+        return "-".to_string();
+    }
+    format!("{:.2} M/s", values as f64 / seconds / 1_000_000.0)
+}
+
+/// Runs every benchmark and prints the comparison table.
+///
+/// # Arguments
+/// * `render_chart` - Renders the chart of one starting value (the chart code lives with the interface).
+pub fn run(render_chart: &dyn Fn(u64) -> Result<(), String>) {
+    // The cached backend gets a prepared in-memory table (never saved, so the user's cache is untouched).
+    let mut memo = cache::StoppingTimeCache::new(std::env::temp_dir().join("collatz_bench_unused_cache.bin"));
+    memo.extend_to(CACHE_TABLE_SIZE);
+
+    let mut columns = vec!["Start", "Sequence", "Stats", "Scan u64", "Scan u128", "Scan auto", "Scan cached"];
+    if cfg!(feature = "gpu") {
+        columns.push("Scan GPU");
+    }
+    columns.push("Chart");
+
+    println!(
+        "Sequence/Stats: time per sequence ({} sequences), Scan: starting values per second ({} values), Chart: time per chart\n",
+        SEQUENCES_PER_SIZE, SCAN_LENGTH
+    );
+    println!("{}", columns.iter().map(|c| format!("{:>12}", c)).collect::<String>());
+
+    for size in SIZES {
+        let mut cells = vec![format!("{:.0e}", size as f64)];
+
+        // Sequence generation, then the statistics of the same sequences.
+        let (sequences, elapsed) = time(|| {
+            (size..size + SEQUENCES_PER_SIZE)
+                .map(collatz::generate_sequence)
+                .collect::<Vec<_>>()
+        });
+        cells.push(format_per_op(elapsed, SEQUENCES_PER_SIZE));
+        let (_, elapsed) = time(|| {
+            for sequence in &sequences {
+                black_box(collatz::calculate_stats(sequence));
+            }
+        });
+        cells.push(format_per_op(elapsed, SEQUENCES_PER_SIZE));
+
+        // Stopping-time backends of the range scans.
+        let scan_end = size + SCAN_LENGTH - 1;
+        let (_, elapsed) = time(|| black_box(collatz::stopping_times_in_range(size, scan_end)));
+        cells.push(format_rate(elapsed, SCAN_LENGTH));
+        let (_, elapsed) = time(|| {
+            for n in size..=scan_end {
+                black_box(collatz::total_stopping_time_wide(n));
+            }
+        });
+        cells.push(format_rate(elapsed, SCAN_LENGTH));
+        let (_, elapsed) = time(|| {
+            for n in size..=scan_end {
+                black_box(collatz::total_stopping_time_auto(n));
+            }
+        });
+        cells.push(format_rate(elapsed, SCAN_LENGTH));
+        let (_, elapsed) = time(|| {
+            for n in size..=scan_end {
+                black_box(memo.stopping_time(n));
+            }
+        });
+        cells.push(format_rate(elapsed, SCAN_LENGTH));
+        #[cfg(feature = "gpu")]
+        {
+            let (result, elapsed) = time(|| gpu::stopping_times_in_range(size, scan_end));
+            cells.push(match result {
+                Ok(_) => format_rate(elapsed, SCAN_LENGTH),
+                Err(_) => "n/a".to_string(),
+            });
+        }
+
+        // Chart rendering (one chart, from sequence generation to the PNG file).
+        let (result, elapsed) = time(|| render_chart(size));
+        cells.push(match result {
+            Ok(()) => format_per_op(elapsed, 1),
+            Err(_) => "failed".to_string(),
+        });
+
+        println!("{}", cells.iter().map(|c| format!("{:>12}", c)).collect::<String>());
+    }
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `format_per_op`.
+    #[test]
+    fn test_format_per_op() {
+        assert_eq!(format_per_op(Duration::from_nanos(500), 1), "500 ns");
+        assert_eq!(format_per_op(Duration::from_micros(3_000), 2), "1.5 ms");
+        assert_eq!(format_per_op(Duration::from_secs(3), 1), "3.00 s");
+        // No operation does not divide by zero.
+        assert_eq!(format_per_op(Duration::from_micros(2), 0), "2.0 µs");
+    }
+
+    // Test function for `format_rate`.
+    #[test]
+    fn test_format_rate() {
+        assert_eq!(format_rate(Duration::from_secs(2), 3_000_000), "1.50 M/s");
+        assert_eq!(format_rate(Duration::ZERO, 10), "-");
+    }
+}
@@ -0,0 +1,188 @@
+// File big_uint.rs
+// This module contains the arbitrary-precision unsigned integers of the widest arithmetic of the trajectories.
+// A trajectory whose terms outgrow 128 bits goes on with `BigUint`, which only has the operations
+// the Collatz map needs (3n + 1, halving, comparisons and decimal writing).
+
+use std::cmp::Ordering;
+
+/// An unsigned integer of any size.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    limbs: Vec<u64>, // 64-bit digits, least significant first, without leading zero digits (none for 0)
+}
+
+impl BigUint {
+    /// Returns the integer if it fits in a `u128`.
+    pub fn to_u128(&self) -> Option<u128> {
+        match self.limbs[..] {
+            [] => Some(0),
+            [low] => Some(low as u128),
+            [low, high] => Some((high as u128) << 64 | low as u128),
+            _ => None,
+        }
+    }
+
+    /// Tells whether the integer is 1.
+    pub fn is_one(&self) -> bool {
+        self.limbs == [1]
+    }
+
+    /// Tells whether the integer is odd.
+    pub fn is_odd(&self) -> bool {
+        self.limbs.first().is_some_and(|low| low & 1 == 1)
+    }
+
+    /// Returns the number of binary digits of the integer (0 for 0).
+    pub fn bits(&self) -> u64 {
+        match self.limbs.last() {
+            Some(high) => (self.limbs.len() as u64 - 1) * 64 + (64 - high.leading_zeros()) as u64,
+            None => 0,
+        }
+    }
+
+    /// Returns the number of trailing zero bits of the integer (0 for 0).
+    pub fn trailing_zeros(&self) -> u64 {
+        match self.limbs.iter().position(|&limb| limb != 0) {
+            Some(index) => index as u64 * 64 + self.limbs[index].trailing_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    /// Divides the integer by 2^`bits`, dropping the remainder.
+    pub fn shift_right(&mut self, bits: u64) {
+        let (limbs, bits) = ((bits / 64) as usize, (bits % 64) as u32);
+        self.limbs.drain(..limbs.min(self.limbs.len()));
+        if bits > 0 {
+            for index in 0..self.limbs.len() {
+                let high = self.limbs.get(index + 1).map_or(0, |next| next << (64 - bits));
+                self.limbs[index] = self.limbs[index] >> bits | high;
+            }
+        }
+        self.normalize();
+    }
+
+    /// Replaces the integer n by 3n + 1.
+    pub fn triple_plus_one(&mut self) {
+        let mut carry = 1u128;
+        for limb in self.limbs.iter_mut() {
+            let product = *limb as u128 * 3 + carry;
+            *limb = product as u64;
+            carry = product >> 64;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u64);
+        }
+    }
+
+    /// Removes the leading zero digits.
+    fn normalize(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(value: u128) -> Self {
+        let mut integer = BigUint { limbs: vec![value as u64, (value >> 64) as u64] };
+        integer.normalize();
+        integer
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        BigUint::from(value as u128)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Without leading zero digits, the longer integer is the larger one.
+        self.limbs.len().cmp(&other.limbs.len()).then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The integer is divided by 10^19 (the largest power of 10 that fits in a u64) until nothing is left:
+        // the remainders are its decimal digits, 19 at a time, least significant first.
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut limbs = self.limbs.clone();
+        let mut chunks = Vec::new();
+        while !limbs.is_empty() {
+            let mut remainder = 0u128;
+            for limb in limbs.iter_mut().rev() {
+                let dividend = remainder << 64 | *limb as u128;
+                *limb = (dividend / CHUNK as u128) as u64;
+                remainder = dividend % CHUNK as u128;
+            }
+            chunks.push(remainder as u64);
+            while limbs.last() == Some(&0) {
+                limbs.pop();
+            }
+        }
+        match chunks.split_last() {
+            Some((most, rest)) => {
+                write!(f, "{}", most)?;
+                rest.iter().rev().try_for_each(|chunk| write!(f, "{:019}", chunk))
+            }
+            None => write!(f, "0"),
+        }
+    }
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `BigUint::to_u128` and the conversions.
+    #[test]
+    fn test_conversions() {
+        for value in [0, 1, u64::MAX as u128, u64::MAX as u128 + 1, u128::MAX] {
+            assert_eq!(BigUint::from(value).to_u128(), Some(value));
+        }
+        assert_eq!(BigUint::from(0u64), BigUint::default());
+        assert_eq!(BigUint::from(u128::MAX).bits(), 128);
+        assert!(BigUint::from(1u64).is_one());
+    }
+
+    // Test function for `BigUint::triple_plus_one` and `BigUint::shift_right`.
+    #[test]
+    fn test_collatz_steps() {
+        // 3 * (2^128 - 1) + 1 = 2^129 + 2^128 - 2 no longer fits in 128 bits.
+        let mut integer = BigUint::from(u128::MAX);
+        integer.triple_plus_one();
+        assert_eq!(integer.to_u128(), None);
+        assert_eq!(integer.bits(), 130);
+        assert_eq!(integer.trailing_zeros(), 1);
+        assert!(!integer.is_odd());
+        // Halving it gives 3 * 2^127 - 1, whose 128 low bits are 2^127 - 1.
+        integer.shift_right(1);
+        assert!(integer.is_odd());
+        integer.shift_right(2);
+        assert_eq!(integer.to_u128(), Some((3u128 << 125) - 1));
+        integer.shift_right(200);
+        assert_eq!(integer, BigUint::default());
+    }
+
+    // Test function for the ordering and the decimal writing.
+    #[test]
+    fn test_ordering_and_display() {
+        let mut large = BigUint::from(u128::MAX);
+        large.triple_plus_one();
+        assert!(large > BigUint::from(u128::MAX));
+        assert!(BigUint::from(2u64) < BigUint::from(3u64));
+        assert_eq!(BigUint::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(large.to_string(), "1020847100762815390390123822295304634366");
+        assert_eq!(BigUint::default().to_string(), "0");
+        assert_eq!(BigUint::from(10_000_000_000_000_000_000u128).to_string(), "10000000000000000000");
+    }
+}
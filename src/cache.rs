@@ -0,0 +1,278 @@
+// File cache.rs
+// This module contains the persistent stopping-time cache.
+// The total stopping times of the starting values 1..=len are kept in a table, which is used
+// to memoize range scans: a trajectory is only followed until it drops into the table.
+// The table is stored between runs as a compact binary file in the user's data directory,
+// so repeated sweeps do not recompute it from scratch.
+
+use crate::collatz;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+// Magic bytes at the start of the cache file (the trailing digit is the format version).
+const MAGIC: &[u8; 4] = b"CST1";
+
+/// Largest number of starting values kept in the table (2 bytes each, so 100 MB on disk).
+pub const MAX_CACHED_VALUES: u64 = 50_000_000;
+
+/// Returns the directory where the application keeps its data between runs.
+/// Falls back to the current directory if the platform has no data directory.
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("collatz_visualizer")
+}
+
+/// Returns the default location of the cache file, in the application's data directory.
+pub fn default_path() -> PathBuf {
+    data_dir().join("stopping_times.bin")
+}
+
+/// Holds the memoized total stopping times of the starting values 1..=len.
+#[derive(Debug)]
+pub struct StoppingTimeCache {
+    path: PathBuf, // File the table is loaded from and saved to
+    times: Vec<u16>, // times[i] is the total stopping time of i + 1
+    loaded: bool, // Whether the file has been read yet (it is read on first use)
+    saved_len: usize, // Number of entries currently on disk, to skip useless writes
+    lookups: u64, // Number of starting values asked for by the range scans
+    hits: u64, // Number of them already in the table when asked for
+}
+
+impl StoppingTimeCache {
+    /// Creates an empty cache backed by the file at `path`.
+    /// The file is not read until the cache is first used.
+    pub fn new(path: PathBuf) -> Self {
+        StoppingTimeCache {
+            path,
+            times: Vec::new(),
+            loaded: false,
+            saved_len: 0,
+            lookups: 0,
+            hits: 0,
+        }
+    }
+
+    /// Returns the number of starting values whose stopping time is in the table.
+    pub fn cached_values(&self) -> u64 {
+        self.times.len() as u64
+    }
+
+    /// Returns the memory taken by the table, in bytes.
+    pub fn memory_bytes(&self) -> u64 {
+        (self.times.capacity() * std::mem::size_of::<u16>()) as u64
+    }
+
+    /// Returns (the number of starting values found in the table, the number of starting values asked for)
+    /// by the range scans since the application started.
+    pub fn hit_counts(&self) -> (u64, u64) {
+        (self.hits, self.lookups)
+    }
+
+    /// Saves the table, then drops it from memory. It is read again from the file on next use.
+    pub fn unload(&mut self) -> io::Result<()> {
+        self.save()?;
+        self.times = Vec::new();
+        self.loaded = false;
+        self.saved_len = 0;
+        Ok(())
+    }
+
+    /// Reads the table from disk, once. A missing or unreadable file leaves the table empty:
+    /// the cache only saves time, it never changes a result.
+    fn ensure_loaded(&mut self) {
+        if self.loaded {
+            return;
+        }
+        self.loaded = true;
+        if let Ok(times) = read_table(&self.path) {
+            self.saved_len = times.len();
+            self.times = times;
+        }
+    }
+
+    /// Computes the total stopping time of `start`, using the table once the trajectory drops into it.
+    ///
+    /// # Returns
+    /// * Same as `collatz::total_stopping_time`.
+    pub fn stopping_time(&self, start: u64) -> Option<usize> {
+        // Follow the trajectory until the table takes over (an empty table lets it run down to 1).
+        let (steps, current) = collatz::descend_to(start, self.cached_values())?;
+        let cached = self.times.get((current - 1) as usize).map_or(0, |&time| time as usize);
+        Some(steps + cached)
+    }
+
+    /// Grows the table so that it covers the starting values up to `end` (at most `MAX_CACHED_VALUES`).
+    /// Each new entry only follows its trajectory until it falls below the starting value.
+    pub fn extend_to(&mut self, end: u64) {
+        self.ensure_loaded();
+        let end = end.min(MAX_CACHED_VALUES);
+        while self.cached_values() < end {
+            let n = self.cached_values() + 1;
+            // Every n below the cap reaches 1 without overflow, and well within 65535 steps.
+            let time = self.stopping_time(n).unwrap_or(0);
+            self.times.push(time as u16);
+        }
+    }
+
+    /// Computes the total stopping time of every starting value in `range_start..=range_end`,
+    /// extending the table first when the range starts inside (or right after) it.
+    ///
+    /// # Returns
+    /// * Same as `collatz::stopping_times_in_range`.
+    pub fn stopping_times_in_range(&mut self, range_start: u64, range_end: u64) -> Vec<(u64, usize)> {
+        self.ensure_loaded();
+        // The values already in the table are hits; the other ones are computed (and stored, when they extend it).
+        self.lookups += (range_end + 1).saturating_sub(range_start);
+        self.hits += (range_end.min(self.cached_values()) + 1).saturating_sub(range_start);
+        if range_start <= self.cached_values() + 1 {
+            self.extend_to(range_end);
+        }
+        (range_start..=range_end)
+            .filter_map(|n| self.stopping_time(n).map(|t| (n, t)))
+            .collect()
+    }
+
+    /// Writes the table to disk if it grew since it was loaded or last saved.
+    pub fn save(&mut self) -> io::Result<()> {
+        if self.times.len() <= self.saved_len {
+            return Ok(());
+        }
+        write_table(&self.path, &self.times)?;
+        self.saved_len = self.times.len();
+        Ok(())
+    }
+}
+
+/// Reads a table written by `write_table`.
+fn read_table(path: &Path) -> io::Result<Vec<u16>> {
+    let mut bytes = Vec::new();
+    fs::File::open(path)?.read_to_end(&mut bytes)?;
+
+    // Header: magic bytes, then the number of entries (u64, little endian).
+    if bytes.len() < 12 || &bytes[..4] != MAGIC {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Not a stopping-time cache file"));
+    }
+    let count = u64::from_le_bytes(bytes[4..12].try_into().unwrap());
+    let body = &bytes[12..];
+    // The count is checked first: a corrupted one would overflow when doubled.
+    if count > MAX_CACHED_VALUES || body.len() as u64 != count * 2 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Truncated stopping-time cache file"));
+    }
+
+    Ok(body
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect())
+}
+
+/// Writes the table to `path`: magic bytes, entry count, then one u16 per entry.
+/// The data goes to a temporary file first, renamed at the end, so a crash never leaves a half-written cache.
+fn write_table(path: &Path, times: &[u16]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut bytes = Vec::with_capacity(12 + times.len() * 2);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&(times.len() as u64).to_le_bytes());
+    for time in times {
+        bytes.extend_from_slice(&time.to_le_bytes());
+    }
+
+    let temp_path = path.with_extension("tmp");
+    let mut file = fs::File::create(&temp_path)?;
+    file.write_all(&bytes)?;
+    file.sync_all()?;
+    fs::rename(&temp_path, path)
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    // Test function for `StoppingTimeCache::stopping_times_in_range`.
+    #[test]
+    fn test_stopping_times_in_range() {
+        let path = std::env::temp_dir().join("collatz_cache_test_range.bin");
+        let mut cache = StoppingTimeCache::new(path);
+        cache.loaded = true; // Do not read a file left by a previous run.
+
+        // Memoized results are identical to the direct computation.
+        assert_eq!(cache.stopping_times_in_range(1, 20_000), collatz::stopping_times_in_range(1, 20_000));
+        assert_eq!(cache.cached_values(), 20_000);
+
+        // A range far beyond the table is not stored, but still uses it.
+        let far = 1u64 << 40;
+        assert_eq!(cache.stopping_times_in_range(far, far + 500), collatz::stopping_times_in_range(far, far + 500));
+        assert_eq!(cache.cached_values(), 20_000);
+
+        // Overflow is reported like in `collatz`.
+        assert_eq!(cache.stopping_time((u64::MAX - 1) / 3 + 1), None);
+        assert_eq!(cache.stopping_time(0), None);
+    }
+
+    // Test function for `StoppingTimeCache::save` (round trip through the file).
+    #[test]
+    fn test_save() {
+        let temp = TempPath::new("collatz_cache_test_save.bin");
+        let path = temp.path().to_path_buf();
+
+        let mut cache = StoppingTimeCache::new(path.clone());
+        cache.extend_to(1000);
+        cache.save().unwrap();
+
+        let mut reloaded = StoppingTimeCache::new(path.clone());
+        reloaded.ensure_loaded();
+        assert_eq!(reloaded.times, cache.times);
+
+        // A corrupted file is ignored.
+        fs::write(&path, b"garbage").unwrap();
+        let mut corrupted = StoppingTimeCache::new(path.clone());
+        corrupted.ensure_loaded();
+        assert_eq!(corrupted.cached_values(), 0);
+        // So is a file whose count is corrupted.
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&u64::MAX.to_le_bytes());
+        fs::write(&path, bytes).unwrap();
+        let mut corrupted = StoppingTimeCache::new(path.clone());
+        corrupted.ensure_loaded();
+        assert_eq!(corrupted.cached_values(), 0);
+    }
+
+    // Test function for `StoppingTimeCache::hit_counts`.
+    #[test]
+    fn test_hit_counts() {
+        let path = std::env::temp_dir().join("collatz_cache_test_hits.bin");
+        let mut cache = StoppingTimeCache::new(path);
+        cache.loaded = true; // Do not read a file left by a previous run.
+
+        // The values added to the table are misses; the values already in it are hits.
+        cache.stopping_times_in_range(1, 20_000);
+        assert_eq!(cache.hit_counts(), (0, 20_000));
+        cache.stopping_times_in_range(19_001, 21_000);
+        assert_eq!(cache.hit_counts(), (1_000, 22_000));
+
+        // A range far beyond the table only has misses.
+        let far = 1u64 << 40;
+        cache.stopping_times_in_range(far, far + 499);
+        assert_eq!(cache.hit_counts(), (1_000, 22_500));
+    }
+
+    // Test function for `StoppingTimeCache::unload`.
+    #[test]
+    fn test_unload() {
+        let temp = TempPath::new("collatz_cache_test_unload.bin");
+
+        // An unloaded table is saved, and read again on next use.
+        let mut cache = StoppingTimeCache::new(temp.path().to_path_buf());
+        cache.extend_to(2000);
+        cache.unload().unwrap();
+        assert_eq!((cache.cached_values(), cache.memory_bytes()), (0, 0));
+        cache.ensure_loaded();
+        assert_eq!(cache.cached_values(), 2000);
+    }
+}
@@ -0,0 +1,367 @@
+// File chart_area.rs
+// This module contains the widget wrapping the chart image to make it interactive.
+// It turns the pointer and touch events over the chart into gestures (see `chart_view`):
+// dragging with the mouse or a finger pans the chart, pinching with two fingers or turning the mouse wheel
+// zooms it around the fingers or the cursor (only along the X axis with Ctrl held).
+// A right click opens the context menu of the chart, drawn over the window where the chart was clicked.
+// Positions are given as horizontal fractions of the chart image, which is drawn centered
+// in the widget with its aspect ratio kept (`ContentFit::Contain`).
+
+use crate::chart_view::{Gesture, TouchTracker};
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::widget::{tree, Operation, Tree};
+use iced::advanced::{mouse, overlay, renderer, Clipboard, Shell, Widget};
+use iced::event::{self, Event};
+use iced::{keyboard, touch, Element, Length, Point, Rectangle, Size};
+
+/// Width of the chart images divided by their height (800 x 400 at the default resolution).
+const CHART_ASPECT_RATIO: f32 = 2.0;
+
+/// Scrolled pixels counted as one notch of the mouse wheel (touchpads scroll by pixels).
+const PIXELS_PER_NOTCH: f32 = 50.0;
+
+/// Interactive area around the chart image.
+pub struct ChartArea<'a, Message, Renderer> {
+    content: Element<'a, Message, Renderer>,     // The chart image
+    on_gesture: Option<fn(Gesture) -> Message>,  // Message sent for each gesture (None: the chart stays as it is)
+    on_right_click: Option<fn(f32) -> Message>,  // Message sent for a right click, with its position on the image
+    menu: Option<(Element<'a, Message, Renderer>, Message)>, // Context menu, and the message closing it
+}
+
+/// Pointers currently on the chart.
+#[derive(Default)]
+struct State {
+    drag: Option<f32>,      // Last position of the mouse, while its left button is held on the chart
+    touches: TouchTracker,  // Fingers on the chart
+    modifiers: keyboard::Modifiers, // Keys held (Ctrl zooms the X axis alone)
+    menu_at: Point,         // Position of the last right click, where the context menu opens
+}
+
+impl<'a, Message, Renderer> ChartArea<'a, Message, Renderer> {
+    /// Wraps the chart image, which does nothing until the messages below are set.
+    pub fn new(content: impl Into<Element<'a, Message, Renderer>>) -> Self {
+        ChartArea { content: content.into(), on_gesture: None, on_right_click: None, menu: None }
+    }
+
+    /// Sends `on_gesture` for the gestures made on the chart.
+    pub fn on_gesture(mut self, on_gesture: fn(Gesture) -> Message) -> Self {
+        self.on_gesture = Some(on_gesture);
+        self
+    }
+
+    /// Sends `on_right_click` when the chart is right-clicked, with the horizontal position of the click
+    /// on the image (see `image_fraction`).
+    pub fn on_right_click(mut self, on_right_click: fn(f32) -> Message) -> Self {
+        self.on_right_click = Some(on_right_click);
+        self
+    }
+
+    /// Shows a context menu at the position of the last right click. A click elsewhere sends `on_dismiss`.
+    pub fn menu(mut self, menu: impl Into<Element<'a, Message, Renderer>>, on_dismiss: Message) -> Self {
+        self.menu = Some((menu.into(), on_dismiss));
+        self
+    }
+}
+
+/// Returns the horizontal position of `x` on the chart image drawn in `bounds`
+/// (0.0 at its left edge, 1.0 at its right edge; outside the image below 0.0 or above 1.0).
+fn image_fraction(bounds: Rectangle, x: f32) -> f32 {
+    let width = bounds.width.min(bounds.height * CHART_ASPECT_RATIO).max(1.0);
+    let left = bounds.x + (bounds.width - width) / 2.0;
+    (x - left) / width
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for ChartArea<'a, Message, Renderer>
+where
+    Message: Clone,
+    Renderer: renderer::Renderer,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        std::iter::once(&self.content).chain(self.menu.as_ref().map(|(menu, _)| menu)).map(Tree::new).collect()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        let children: Vec<_> = std::iter::once(&self.content).chain(self.menu.as_ref().map(|(menu, _)| menu)).collect();
+        tree.diff_children(&children);
+    }
+
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.as_widget().layout(renderer, limits)
+    }
+
+    fn operate(&self, tree: &mut Tree, layout: Layout<'_>, renderer: &Renderer, operation: &mut dyn Operation<Message>) {
+        self.content.as_widget().operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        if let event::Status::Captured = self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event.clone(),
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        ) {
+            return event::Status::Captured;
+        }
+
+        let bounds = layout.bounds();
+        let state = tree.state.downcast_mut::<State>();
+        if let (Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)), Some(on_right_click)) =
+            (&event, self.on_right_click)
+        {
+            let Some(position) = cursor.position_over(bounds) else {
+                return event::Status::Ignored;
+            };
+            state.menu_at = position;
+            shell.publish(on_right_click(image_fraction(bounds, position.x)));
+            return event::Status::Captured;
+        }
+        let Some(on_gesture) = self.on_gesture else {
+            return event::Status::Ignored;
+        };
+        let gesture = match event {
+            // Mouse: dragging with the left button pans. The drag goes on outside the chart until released.
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let Some(position) = cursor.position_over(bounds) else {
+                    return event::Status::Ignored;
+                };
+                state.drag = Some(image_fraction(bounds, position.x));
+                return event::Status::Captured;
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                return match state.drag.take() {
+                    Some(_) => event::Status::Captured,
+                    None => event::Status::Ignored,
+                };
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                let Some(previous) = state.drag else {
+                    return event::Status::Ignored;
+                };
+                let position = image_fraction(bounds, position.x);
+                state.drag = Some(position);
+                Some(Gesture::Pan(position - previous))
+            }
+
+            // Mouse wheel: forward zooms in around the cursor, backward zooms out.
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                let Some(position) = cursor.position_over(bounds) else {
+                    return event::Status::Ignored;
+                };
+                let notches = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_NOTCH,
+                };
+                // A horizontal scroll is left to the enclosing widgets.
+                if notches == 0.0 {
+                    return event::Status::Ignored;
+                }
+                Some(Gesture::Wheel {
+                    notches,
+                    anchor: image_fraction(bounds, position.x),
+                    x_only: state.modifiers.control(),
+                })
+            }
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                state.modifiers = modifiers;
+                return event::Status::Ignored;
+            }
+
+            // Touch: one finger pans, two fingers pinch (see `TouchTracker`).
+            Event::Touch(touch::Event::FingerPressed { id, position }) => {
+                if !bounds.contains(position) {
+                    return event::Status::Ignored;
+                }
+                state.touches.press(id.0, image_fraction(bounds, position.x));
+                return event::Status::Captured;
+            }
+            Event::Touch(touch::Event::FingerMoved { id, position }) => {
+                state.touches.moved(id.0, image_fraction(bounds, position.x))
+            }
+            Event::Touch(touch::Event::FingerLifted { id, .. } | touch::Event::FingerLost { id, .. }) => {
+                state.touches.lift(id.0);
+                None
+            }
+            _ => None,
+        };
+
+        match gesture {
+            Some(gesture) => {
+                shell.publish(on_gesture(gesture));
+                event::Status::Captured
+            }
+            None => event::Status::Ignored,
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+        if state.drag.is_some() {
+            mouse::Interaction::Grabbing
+        } else if self.on_gesture.is_some() && cursor.is_over(layout.bounds()) {
+            mouse::Interaction::Grab
+        } else {
+            self.content.as_widget().mouse_interaction(&tree.children[0], layout, cursor, viewport, renderer)
+        }
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(&tree.children[0], renderer, theme, style, layout, cursor, viewport);
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+    ) -> Option<overlay::Element<'b, Message, Renderer>> {
+        let menu_at = tree.state.downcast_ref::<State>().menu_at;
+        let (content_tree, menu_tree) = tree.children.split_at_mut(1);
+        match (&mut self.menu, menu_tree.first_mut()) {
+            (Some((menu, on_dismiss)), Some(tree)) => {
+                Some(overlay::Element::new(menu_at, Box::new(Menu { content: menu, tree, on_dismiss })))
+            }
+            _ => self.content.as_widget_mut().overlay(&mut content_tree[0], layout, renderer),
+        }
+    }
+}
+
+/// Context menu of the chart, drawn over the window.
+struct Menu<'a, 'b, Message, Renderer> {
+    content: &'b mut Element<'a, Message, Renderer>, // The entries of the menu
+    tree: &'b mut Tree,                              // State of the entries
+    on_dismiss: &'b Message,                         // Message sent by a click outside the menu
+}
+
+impl<'a, 'b, Message, Renderer> overlay::Overlay<Message, Renderer> for Menu<'a, 'b, Message, Renderer>
+where
+    Message: Clone,
+    Renderer: renderer::Renderer,
+{
+    fn layout(&self, renderer: &Renderer, bounds: Size, position: Point) -> layout::Node {
+        let mut node = self.content.as_widget().layout(renderer, &layout::Limits::new(Size::ZERO, bounds));
+        // Kept in the window: opened near its right or bottom edge, the menu goes to the left or up.
+        let size = node.size();
+        node.move_to(Point::new(
+            position.x.min(bounds.width - size.width).max(0.0),
+            position.y.min(bounds.height - size.height).max(0.0),
+        ));
+        node
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Renderer::Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+    ) {
+        self.content.as_widget().draw(self.tree, renderer, theme, style, layout, cursor, &layout.bounds());
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        let outside = match event {
+            Event::Mouse(mouse::Event::ButtonPressed(_)) => !cursor.is_over(layout.bounds()),
+            Event::Touch(touch::Event::FingerPressed { position, .. }) => !layout.bounds().contains(position),
+            _ => false,
+        };
+        if outside {
+            shell.publish(self.on_dismiss.clone());
+            return event::Status::Captured;
+        }
+        self.content.as_widget_mut().on_event(self.tree, event, layout, cursor, renderer, clipboard, shell, &layout.bounds())
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(self.tree, layout, cursor, viewport, renderer)
+    }
+}
+
+impl<'a, Message, Renderer> From<ChartArea<'a, Message, Renderer>> for Element<'a, Message, Renderer>
+where
+    Message: 'a + Clone,
+    Renderer: 'a + renderer::Renderer,
+{
+    fn from(area: ChartArea<'a, Message, Renderer>) -> Self {
+        Element::new(area)
+    }
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `image_fraction`.
+    #[test]
+    fn test_image_fraction() {
+        // A wide area: the 800 x 400 image is 800 pixels wide, centered (100 pixels on each side).
+        let bounds = Rectangle { x: 0.0, y: 0.0, width: 1000.0, height: 400.0 };
+        assert_eq!(image_fraction(bounds, 100.0), 0.0);
+        assert_eq!(image_fraction(bounds, 500.0), 0.5);
+        assert!(image_fraction(bounds, 50.0) < 0.0);
+        // A narrow area: the image fills its width.
+        let bounds = Rectangle { x: 10.0, y: 0.0, width: 400.0, height: 400.0 };
+        assert_eq!(image_fraction(bounds, 410.0), 1.0);
+    }
+}
@@ -0,0 +1,130 @@
+// File chart_cache.rs
+// This module contains the cache of recently rendered charts.
+// When a sequence chart is replaced, its temporary image is kept with the fingerprint of what it shows
+// (chart kind, sequences, options) instead of being deleted. Going back to a previous comparison,
+// e.g. from the history panel, then shows its chart at once instead of rendering it again.
+// Only a few charts are kept: the least recently used one is deleted when the cache is full.
+
+use std::collections::VecDeque;
+
+/// Least recently used cache of chart images, keyed by the fingerprint of what they show.
+#[derive(Debug)]
+pub struct ChartCache {
+    capacity: usize, // Largest number of charts kept
+    entries: VecDeque<(u64, String)>, // (fingerprint, image path), the most recently used last
+    hits: u64, // Number of charts found in the cache when asked for
+    lookups: u64, // Number of charts asked for
+}
+
+impl ChartCache {
+    /// Creates an empty cache keeping at most `capacity` charts.
+    pub fn new(capacity: usize) -> Self {
+        ChartCache {
+            capacity,
+            entries: VecDeque::with_capacity(capacity),
+            hits: 0,
+            lookups: 0,
+        }
+    }
+
+    /// Takes the chart with this fingerprint out of the cache, if it is there.
+    /// It becomes the current chart, so it no longer belongs to the cache until it is replaced.
+    ///
+    /// # Arguments
+    /// * `fingerprint` - Fingerprint of the wanted chart.
+    ///
+    /// # Returns
+    /// * `Some(path)` - The path of its image.
+    /// * `None` - The chart is not in the cache.
+    pub fn take(&mut self, fingerprint: u64) -> Option<String> {
+        self.lookups += 1;
+        let path = self.remove(fingerprint);
+        if path.is_some() {
+            self.hits += 1;
+        }
+        path
+    }
+
+    /// Removes the chart with this fingerprint from the cache, if it is there, and returns the path of its image.
+    fn remove(&mut self, fingerprint: u64) -> Option<String> {
+        let index = self.entries.iter().position(|(key, _)| *key == fingerprint)?;
+        self.entries.remove(index).map(|(_, path)| path)
+    }
+
+    /// Returns the number of charts in the cache.
+    pub fn chart_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns (the number of charts found in the cache, the number of charts asked for) since it was created.
+    pub fn hit_counts(&self) -> (u64, u64) {
+        (self.hits, self.lookups)
+    }
+
+    /// Puts a chart into the cache, as the most recently used one.
+    ///
+    /// # Arguments
+    /// * `fingerprint` - Fingerprint of what the chart shows.
+    /// * `path` - Path of its image.
+    ///
+    /// # Returns
+    /// * `Vec<String>` - The images no longer in the cache, to delete: the least recently used ones
+    ///   if it was full, and the previous image with the same fingerprint, if any.
+    pub fn insert(&mut self, fingerprint: u64, path: String) -> Vec<String> {
+        let mut evicted: Vec<String> = self.remove(fingerprint).into_iter().collect();
+        self.entries.push_back((fingerprint, path));
+        while self.entries.len() > self.capacity {
+            if let Some((_, oldest)) = self.entries.pop_front() {
+                evicted.push(oldest);
+            }
+        }
+        evicted
+    }
+
+    /// Empties the cache and returns the paths of all its images, to delete.
+    pub fn drain(&mut self) -> Vec<String> {
+        self.entries.drain(..).map(|(_, path)| path).collect()
+    }
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `ChartCache::insert` and `ChartCache::take`.
+    #[test]
+    fn test_insert_and_take() {
+        let mut cache = ChartCache::new(2);
+        assert!(cache.insert(1, "a.png".to_string()).is_empty());
+        assert!(cache.insert(2, "b.png".to_string()).is_empty());
+
+        // Taking a chart removes it from the cache.
+        assert_eq!(cache.take(1), Some("a.png".to_string()));
+        assert_eq!(cache.take(1), None);
+        assert_eq!(cache.hit_counts(), (1, 2));
+        assert_eq!(cache.chart_count(), 1);
+
+        // The same fingerprint replaces the previous image.
+        assert_eq!(cache.insert(2, "b2.png".to_string()), vec!["b.png".to_string()]);
+        assert_eq!(cache.drain(), vec!["b2.png".to_string()]);
+    }
+
+    // Test function for the eviction of the least recently used chart.
+    #[test]
+    fn test_eviction() {
+        let mut cache = ChartCache::new(2);
+        cache.insert(1, "a.png".to_string());
+        cache.insert(2, "b.png".to_string());
+
+        // Taking then inserting a chart back makes it the most recently used one.
+        let path = cache.take(1).unwrap();
+        cache.insert(1, path);
+        assert_eq!(cache.insert(3, "c.png".to_string()), vec!["b.png".to_string()]);
+
+        let mut remaining = cache.drain();
+        remaining.sort();
+        assert_eq!(remaining, vec!["a.png".to_string(), "c.png".to_string()]);
+        assert!(cache.drain().is_empty());
+    }
+}
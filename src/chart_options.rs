@@ -0,0 +1,501 @@
+// File chart_options.rs
+// This module contains the user settings of the sequence chart, edited in the chart options form.
+// The defaults reproduce the automatic chart, so every setting is optional: a blank field
+// simply keeps what the application would have drawn on its own.
+
+use crate::chart_view::ChartView;
+
+/// Line widths offered for the series, in pixels (before scaling).
+pub const LINE_WIDTHS: [u32; 5] = [1, 2, 3, 4, 5];
+
+/// Font sizes offered for the tick labels, in the order shown in the selector.
+pub const TICK_SIZES: [u32; 6] = [10, 12, 14, 16, 18, 20];
+
+/// Text scales offered for the chart, in percent: every text of the chart (caption, axis descriptions,
+/// tick labels, legend) is enlarged, so that it stays legible once the image is shrunk into a paper or a slide.
+pub const TEXT_SCALES: [u32; 5] = [100, 125, 150, 200, 250];
+
+/// How the numbers of the axis ticks are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TickFormat {
+    Plain,      // 1234567
+    Grouped,    // 1,234,567
+    Compact,    // 1.23M
+    Scientific, // 1.23e6
+}
+
+impl TickFormat {
+    /// Every format, in the order shown in the selector.
+    pub const ALL: [TickFormat; 4] = [
+        TickFormat::Plain,
+        TickFormat::Grouped,
+        TickFormat::Compact,
+        TickFormat::Scientific,
+    ];
+
+    /// Writes a tick value in this format.
+    pub fn format(self, value: u64) -> String {
+        match self {
+            TickFormat::Plain => value.to_string(),
+            TickFormat::Grouped => {
+                let digits = value.to_string();
+                let mut grouped = String::new();
+                for (i, digit) in digits.chars().enumerate() {
+                    if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                        grouped.push(',');
+                    }
+                    grouped.push(digit);
+                }
+                grouped
+            }
+            TickFormat::Compact => {
+                // Largest suffix not above the value; small values are written as they are.
+                const SUFFIXES: [(f64, &str); 6] =
+                    [(1e18, "E"), (1e15, "P"), (1e12, "T"), (1e9, "G"), (1e6, "M"), (1e3, "k")];
+                match SUFFIXES.iter().find(|(unit, _)| value as f64 >= *unit) {
+                    Some((unit, suffix)) => {
+                        let scaled = format!("{:.2}", value as f64 / unit);
+                        let scaled = scaled.trim_end_matches('0').trim_end_matches('.');
+                        format!("{}{}", scaled, suffix)
+                    }
+                    None => value.to_string(),
+                }
+            }
+            TickFormat::Scientific if value < 10 => value.to_string(),
+            TickFormat::Scientific => format!("{:.2e}", value as f64).replace(".00e", "e"),
+        }
+    }
+}
+
+impl std::fmt::Display for TickFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            TickFormat::Plain => "1234567",
+            TickFormat::Grouped => "1,234,567",
+            TickFormat::Compact => "1.23M",
+            TickFormat::Scientific => "1.23e6",
+        })
+    }
+}
+
+/// How the Y axis is shared between the two sequences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum YScaling {
+    Shared,     // One scale for both sequences (the highest peak sets it)
+    Normalized, // Each sequence divided by its own maximum, so both shapes fill the chart
+    DualAxis,   // The second sequence on its own scale, read on a second Y axis on the right
+}
+
+impl YScaling {
+    /// Every scaling, in the order shown in the selector.
+    pub const ALL: [YScaling; 3] = [YScaling::Shared, YScaling::Normalized, YScaling::DualAxis];
+}
+
+impl std::fmt::Display for YScaling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            YScaling::Shared => "Shared",
+            YScaling::Normalized => "Each to its max",
+            YScaling::DualAxis => "Second axis",
+        })
+    }
+}
+
+/// How the two sequences are laid out in the sequence chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChartLayout {
+    Overlay,        // Both sequences in the same chart
+    SmallMultiples, // One panel per sequence, stacked vertically with the same X range
+}
+
+impl ChartLayout {
+    /// Every layout, in the order shown in the selector.
+    pub const ALL: [ChartLayout; 2] = [ChartLayout::Overlay, ChartLayout::SmallMultiples];
+}
+
+impl std::fmt::Display for ChartLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            ChartLayout::Overlay => "Overlay",
+            ChartLayout::SmallMultiples => "Stacked panels",
+        })
+    }
+}
+
+/// Which step of the sequences is drawn at the same X position, to compare them from that point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SequenceAlignment {
+    Start, // Both sequences begin at step 0
+    Peak,  // The maxima line up, to compare the climbs and the descents
+    End,   // The final 1s line up (right-aligned), to compare the tails
+}
+
+impl SequenceAlignment {
+    /// Every alignment, in the order shown in the selector.
+    pub const ALL: [SequenceAlignment; 3] = [SequenceAlignment::Start, SequenceAlignment::Peak, SequenceAlignment::End];
+
+    /// Returns the step of a sequence that is aligned: 0, the first maximum, or the last term.
+    pub fn anchor(self, sequence: &[u64]) -> usize {
+        match self {
+            SequenceAlignment::Start => 0,
+            SequenceAlignment::Peak => {
+                let max = sequence.iter().copied().max().unwrap_or(0);
+                sequence.iter().position(|&v| v == max).unwrap_or(0)
+            }
+            SequenceAlignment::End => sequence.len().saturating_sub(1),
+        }
+    }
+
+    /// Computes where the sequences are placed on the X axis once aligned.
+    ///
+    /// # Arguments
+    /// * `sequences` - The sequences of the chart; empty ones are ignored.
+    ///
+    /// # Returns
+    /// * `(usize, usize)` - The X position of the aligned steps (the origin of the relative steps),
+    ///   and the number of X positions needed to draw every sequence.
+    ///   A sequence is shifted right by the origin minus its anchor.
+    pub fn frame(self, sequences: &[&[u64]]) -> (usize, usize) {
+        let sequences = sequences.iter().filter(|sequence| !sequence.is_empty());
+        let origin = sequences.clone().map(|sequence| self.anchor(sequence)).max().unwrap_or(0);
+        let width = sequences.map(|sequence| origin - self.anchor(sequence) + sequence.len()).max().unwrap_or(0);
+        (origin, width)
+    }
+
+    /// Returns the description of the X axis: steps are counted from the aligned step.
+    pub fn axis_label(self) -> &'static str {
+        match self {
+            SequenceAlignment::Start => "Step",
+            SequenceAlignment::Peak => "Steps from the peak",
+            SequenceAlignment::End => "Steps from the final 1",
+        }
+    }
+}
+
+impl std::fmt::Display for SequenceAlignment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            SequenceAlignment::Start => "At the start",
+            SequenceAlignment::Peak => "At the peak",
+            SequenceAlignment::End => "At the final 1",
+        })
+    }
+}
+
+/// How the line of a series is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineStyle {
+    Solid,
+    Dashed,
+}
+
+impl LineStyle {
+    /// Every line style, in the order shown in the selector.
+    pub const ALL: [LineStyle; 2] = [LineStyle::Solid, LineStyle::Dashed];
+}
+
+impl std::fmt::Display for LineStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            LineStyle::Solid => "Solid",
+            LineStyle::Dashed => "Dashed",
+        })
+    }
+}
+
+/// Shape of the markers drawn on the points of a series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarkerShape {
+    Circle,
+    Square,
+    Triangle,
+    Cross,
+}
+
+impl MarkerShape {
+    /// Every marker shape, in the order shown in the selector.
+    pub const ALL: [MarkerShape; 4] = [MarkerShape::Circle, MarkerShape::Square, MarkerShape::Triangle, MarkerShape::Cross];
+}
+
+impl std::fmt::Display for MarkerShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            MarkerShape::Circle => "Circle",
+            MarkerShape::Square => "Square",
+            MarkerShape::Triangle => "Triangle",
+            MarkerShape::Cross => "Cross",
+        })
+    }
+}
+
+/// Colors given to the series of the charts, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Palette {
+    #[default]
+    OkabeIto, // Okabe-Ito: told apart with the common color vision deficiencies
+    Viridis,  // Samples of viridis, also readable in grayscale
+    Classic,  // Pure red, blue and green, as in the first versions
+}
+
+impl Palette {
+    /// Every palette, in the order shown in the selector.
+    pub const ALL: [Palette; 3] = [Palette::OkabeIto, Palette::Viridis, Palette::Classic];
+
+    /// Returns the colors of the palette, as (red, green, blue), in the order given to the series.
+    pub fn colors(self) -> &'static [(u8, u8, u8)] {
+        match self {
+            // Blue, vermillion, bluish green, orange, reddish purple, sky blue (yellow is too light on white).
+            Palette::OkabeIto => &[(0, 114, 178), (213, 94, 0), (0, 158, 115), (230, 159, 0), (204, 121, 167), (86, 180, 233)],
+            // Consecutive series far apart on the viridis scale, without its lightest yellow.
+            Palette::Viridis => &[(59, 82, 139), (94, 201, 98), (68, 1, 84), (33, 145, 140), (181, 222, 43)],
+            Palette::Classic => &[(255, 0, 0), (0, 0, 255), (0, 150, 0)],
+        }
+    }
+
+    /// Returns the color of the series `index`; the colors start over after the last one.
+    pub fn color(self, index: usize) -> (u8, u8, u8) {
+        let colors = self.colors();
+        colors[index % colors.len()]
+    }
+}
+
+impl std::fmt::Display for Palette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Palette::OkabeIto => "Okabe-Ito",
+            Palette::Viridis => "Viridis",
+            Palette::Classic => "Red/blue",
+        })
+    }
+}
+
+/// Style of one series of the sequence chart. Besides its color, a series can be told apart
+/// by its line and its markers, which still works when the chart is printed in grayscale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SeriesStyle {
+    pub line: LineStyle,     // Solid or dashed line
+    pub markers: bool,       // Whether a marker is drawn on every point
+    pub marker: MarkerShape, // Shape of the markers
+}
+
+/// User settings applied when drawing the sequence chart.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChartOptions {
+    pub caption: String,  // Replaces the generated caption when not blank
+    pub subtitle: String, // Drawn under the caption when not blank
+    pub x_label: String,  // Replaces the X axis description ("Step") when not blank
+    pub y_label: String,  // Replaces the Y axis description ("Value"...) when not blank
+    pub tick_size: u32,   // Font size of the tick labels (before scaling)
+    pub tick_format: TickFormat, // How the tick values are written
+    pub series_names: [String; 2], // Legend labels of the two series ("Sequence n" when blank)
+    pub series_styles: [SeriesStyle; 2], // Line and marker styles of the two series
+    pub palette: Palette, // Colors of the series
+    pub line_width: u32,  // Width of the series lines, in pixels (before scaling)
+    pub text_scale: u32,  // Size of the texts of the chart, in percent of their normal size
+    pub antialias: bool,  // Whether the chart is supersampled, for smooth lines
+    pub log_scale: bool,  // Whether the Y axis is logarithmic
+    pub y_scaling: YScaling, // Whether the sequences share the Y scale
+    pub layout: ChartLayout, // Whether the sequences are overlaid or in separate panels
+    pub alignment: SequenceAlignment, // Which steps of the sequences share the same X position
+    pub view: Option<ChartView>, // Window of X positions shown once zoomed or panned (None: every step)
+    pub scale: u32,       // Resolution multiplier: 1 for the screen, more for printing
+    pub step_stride: usize, // Steps between two plotted terms: 1, or more for sequences kept downsampled
+}
+
+impl Default for ChartOptions {
+    fn default() -> Self {
+        ChartOptions {
+            caption: String::new(),
+            subtitle: String::new(),
+            x_label: String::new(),
+            y_label: String::new(),
+            tick_size: 12,
+            tick_format: TickFormat::Plain,
+            series_names: [String::new(), String::new()],
+            // Solid lines without markers; each series has its own marker shape once they are enabled.
+            series_styles: [
+                SeriesStyle { line: LineStyle::Solid, markers: false, marker: MarkerShape::Circle },
+                SeriesStyle { line: LineStyle::Solid, markers: false, marker: MarkerShape::Square },
+            ],
+            palette: Palette::default(),
+            line_width: 1,
+            text_scale: 100,
+            antialias: false,
+            log_scale: false,
+            y_scaling: YScaling::Shared,
+            layout: ChartLayout::Overlay,
+            alignment: SequenceAlignment::Start,
+            view: None,
+            scale: 1,
+            step_stride: 1,
+        }
+    }
+}
+
+impl ChartOptions {
+    /// Returns the caption to draw: the custom one if set, otherwise the generated one.
+    ///
+    /// # Arguments
+    /// * `generated` - Caption built by the application (e.g. "Collatz Conjecture -- 27 and 31").
+    pub fn caption_or(&self, generated: String) -> String {
+        non_blank(&self.caption).map_or(generated, str::to_string)
+    }
+
+    /// Returns a font size of the chart with the text scale applied (before the resolution multiplier).
+    ///
+    /// # Arguments
+    /// * `base` - Font size at the normal text scale, e.g. 20 for the caption.
+    pub fn text_size(&self, base: u32) -> u32 {
+        (base * self.text_scale / 100).max(1)
+    }
+
+    /// Returns the subtitle to draw under the caption, if any.
+    pub fn subtitle(&self) -> Option<&str> {
+        non_blank(&self.subtitle)
+    }
+
+    /// Returns the X axis description: the custom one if set, otherwise `generated`.
+    pub fn x_label_or<'a>(&'a self, generated: &'a str) -> &'a str {
+        non_blank(&self.x_label).unwrap_or(generated)
+    }
+
+    /// Returns the Y axis description: the custom one if set, otherwise `generated`.
+    pub fn y_label_or<'a>(&'a self, generated: &'a str) -> &'a str {
+        non_blank(&self.y_label).unwrap_or(generated)
+    }
+
+    /// Returns the legend label of a series: its custom name if set, otherwise "Sequence n".
+    ///
+    /// # Arguments
+    /// * `index` - 0 for the first series, 1 for the second, 2 and above for the extra series
+    ///   of the neighborhood compare, the family mode and the sibling finder (which have no custom name).
+    /// * `value` - Starting value of the series.
+    pub fn series_label(&self, index: usize, value: u64) -> String {
+        self.series_names
+            .get(index)
+            .and_then(|name| non_blank(name))
+            .map_or_else(|| format!("Sequence {}", value), str::to_string)
+    }
+
+    /// Returns the style of a series. The extra series (neighborhood compare, family mode, sibling finder),
+    /// which have no style of their own, get a dashed line with triangle markers (if markers are shown at all).
+    pub fn series_style(&self, index: usize) -> SeriesStyle {
+        self.series_styles.get(index).copied().unwrap_or(SeriesStyle {
+            line: LineStyle::Dashed,
+            markers: self.series_styles.iter().any(|style| style.markers),
+            marker: MarkerShape::Triangle,
+        })
+    }
+}
+
+/// Describes a starting value along with its custom name, if any (e.g. "27 (record candidate)").
+/// Used by the text exports (statistics, clipboard), which always show the value itself.
+pub fn describe_value(value: u64, name: &str) -> String {
+    match non_blank(name) {
+        Some(name) => format!("{} ({})", value, name),
+        None => value.to_string(),
+    }
+}
+
+/// Returns the trimmed text, or None if it is empty or only whitespace.
+fn non_blank(text: &str) -> Option<&str> {
+    Some(text.trim()).filter(|text| !text.is_empty())
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `ChartOptions::caption_or` and `ChartOptions::subtitle`.
+    #[test]
+    fn test_caption_or() {
+        let mut options = ChartOptions::default();
+        assert_eq!(options.caption_or("Collatz Conjecture -- 27".to_string()), "Collatz Conjecture -- 27");
+        assert_eq!(options.subtitle(), None);
+
+        options.caption = "  Record candidate ".to_string();
+        options.subtitle = "Computed on 64 bits".to_string();
+        assert_eq!(options.caption_or("Collatz Conjecture -- 27".to_string()), "Record candidate");
+        assert_eq!(options.subtitle(), Some("Computed on 64 bits"));
+
+        // A blank field keeps the automatic text.
+        options.caption = "   ".to_string();
+        assert_eq!(options.caption_or("Generated".to_string()), "Generated");
+
+        options.y_label = "Altitude".to_string();
+        assert_eq!(options.x_label_or("Step"), "Step");
+        assert_eq!(options.y_label_or("Value"), "Altitude");
+    }
+
+    // Test function for `ChartOptions::series_label`, `ChartOptions::series_style` and `describe_value`.
+    #[test]
+    fn test_series_label() {
+        let mut options = ChartOptions::default();
+        options.series_names[1] = "record candidate".to_string();
+        assert_eq!(options.series_label(0, 27), "Sequence 27");
+        assert_eq!(options.series_label(1, 31), "record candidate");
+        assert_eq!(options.series_label(2, 28), "Sequence 28");
+        assert_eq!(options.series_style(1), options.series_styles[1]);
+        assert_eq!(options.series_style(2).line, LineStyle::Dashed);
+
+        assert_eq!(describe_value(27, ""), "27");
+        assert_eq!(describe_value(31, " record candidate "), "31 (record candidate)");
+    }
+
+    // Test function for `TickFormat::format`.
+    #[test]
+    fn test_tick_format() {
+        assert_eq!(TickFormat::Plain.format(1_234_567), "1234567");
+        assert_eq!(TickFormat::Grouped.format(1_234_567), "1,234,567");
+        assert_eq!(TickFormat::Grouped.format(123), "123");
+        assert_eq!(TickFormat::Grouped.format(100_000), "100,000");
+        assert_eq!(TickFormat::Compact.format(1_234_567), "1.23M");
+        assert_eq!(TickFormat::Compact.format(9_000), "9k");
+        assert_eq!(TickFormat::Compact.format(999), "999");
+        assert_eq!(TickFormat::Scientific.format(1_234_567), "1.23e6");
+        assert_eq!(TickFormat::Scientific.format(2_000), "2e3");
+        assert_eq!(TickFormat::Scientific.format(0), "0");
+    }
+
+    // Test function for `SequenceAlignment::anchor` and `SequenceAlignment::frame`.
+    #[test]
+    fn test_alignment() {
+        let short: &[u64] = &[6, 3, 10, 5, 16, 8, 4, 2, 1]; // Peak at step 4
+        let long: &[u64] = &[7, 22, 11, 34, 17, 52, 26, 13, 40, 20, 10, 5, 16, 8, 4, 2, 1]; // Peak at step 5
+        assert_eq!(SequenceAlignment::Peak.anchor(short), 4);
+        assert_eq!(SequenceAlignment::End.anchor(long), 16);
+
+        assert_eq!(SequenceAlignment::Start.frame(&[short, long]), (0, 17));
+        // The short sequence is shifted right by one step so that both peaks are at X = 5.
+        assert_eq!(SequenceAlignment::Peak.frame(&[short, long]), (5, 17));
+        // Right-aligned: the short sequence starts at X = 8, both end at X = 16.
+        assert_eq!(SequenceAlignment::End.frame(&[short, long]), (16, 17));
+        assert_eq!(SequenceAlignment::End.frame(&[&[], short]), (8, 9));
+    }
+
+    // Test function for `ChartOptions::text_size`.
+    #[test]
+    fn test_text_size() {
+        let mut options = ChartOptions::default();
+        assert_eq!(options.text_size(15), 15);
+        options.text_scale = 150;
+        assert_eq!(options.text_size(15), 22);
+        assert_eq!(options.text_size(options.tick_size), 18);
+    }
+
+    // Test function for `Palette::color`.
+    #[test]
+    fn test_palette() {
+        assert_eq!(Palette::default(), Palette::OkabeIto);
+        assert_eq!(Palette::Classic.color(1), (0, 0, 255));
+        // The colors start over after the last one.
+        assert_eq!(Palette::Classic.color(3), Palette::Classic.color(0));
+        for palette in Palette::ALL {
+            let colors = palette.colors();
+            assert!(colors.len() >= 3, "{}", palette);
+            // No two series of a palette share a color.
+            assert!(colors.iter().enumerate().all(|(i, color)| !colors[..i].contains(color)), "{}", palette);
+        }
+    }
+}
@@ -0,0 +1,310 @@
+// File chart_view.rs
+// This module contains the zoom and pan state of the sequence chart: the window of steps it shows.
+// Gestures on the chart (dragging, pinching on a touchscreen or a trackpad, the mouse wheel) move or resize
+// the window, and the chart is drawn again for it, with the Y axis fitted to the terms in view
+// (or kept at the range of the whole sequences after a zoom with Ctrl held: only the X axis is zoomed).
+// The gestures arrive as positions on the chart image; the helpers below turn them into fractions
+// of the plotting area, where the steps are drawn.
+
+/// Smallest number of X positions shown once zoomed in.
+pub const MIN_VIEW_WIDTH: usize = 4;
+
+/// Width of the window multiplied by each notch of the mouse wheel turned forward (zooming in).
+const WHEEL_ZOOM: f32 = 0.8;
+
+/// Left and right edges of the plotting area of the sequence chart, as fractions of the image width
+/// (margin and Y labels on the left, margin on the right, at the default tick size).
+/// Larger tick labels move the left edge a little: the gestures only need an approximate anchor.
+const PLOT_LEFT: f32 = 70.0 / 800.0;
+const PLOT_RIGHT: f32 = 790.0 / 800.0;
+
+/// Window of X positions shown by the sequence chart: `start..end`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChartView {
+    pub start: usize, // First X position shown
+    pub end: usize,   // X position after the last one shown
+    pub fit_y: bool,  // Y axis fitted to the terms in view (false: kept at the range of the whole sequences)
+}
+
+impl ChartView {
+    /// Returns the window showing the whole chart, of `len` X positions.
+    pub fn full(len: usize) -> Self {
+        ChartView::new(0, len)
+    }
+
+    /// Returns the window `start..end`, with the Y axis fitted to it.
+    pub fn new(start: usize, end: usize) -> Self {
+        ChartView { start, end, fit_y: true }
+    }
+
+    /// Returns the number of X positions shown.
+    pub fn width(self) -> usize {
+        self.end - self.start
+    }
+
+    /// Tells whether the window shows the whole chart of `len` X positions.
+    pub fn is_full(self, len: usize) -> bool {
+        self.start == 0 && self.end >= len
+    }
+
+    /// Returns the window resized by `factor` around an anchor, which stays at the same place.
+    ///
+    /// # Arguments
+    /// * `factor` - Below 1 zooms in (fewer steps shown), above 1 zooms out.
+    /// * `anchor` - Position of the anchor in the window, from 0.0 (left edge) to 1.0 (right edge).
+    /// * `len` - Number of X positions of the whole chart: the window stays within them.
+    pub fn zoomed(self, factor: f32, anchor: f32, len: usize) -> Self {
+        let anchor = anchor.clamp(0.0, 1.0) as f64;
+        let width = self.width() as f64;
+        let new_width = (width * factor.max(0.0) as f64).round().clamp(MIN_VIEW_WIDTH.min(len) as f64, len as f64);
+        let pivot = self.start as f64 + anchor * width;
+        let start = (pivot - anchor * new_width).round().max(0.0) as usize;
+        ChartView { start, end: start + new_width as usize, ..self }.clamped(len)
+    }
+
+    /// Returns the X position shown at a place of the plotting area, from 0.0 (left edge) to 1.0 (right edge).
+    pub fn position_at(self, fraction: f32) -> usize {
+        let position = self.start as f64 + fraction.clamp(0.0, 1.0) as f64 * self.width() as f64;
+        (position.round() as usize).min(self.end.saturating_sub(1)).max(self.start)
+    }
+
+    /// Returns the window moved by `shift` times its width (positive: towards the later steps).
+    pub fn panned(self, shift: f32, len: usize) -> Self {
+        let offset = (shift as f64 * self.width() as f64).round() as i64;
+        let start = (self.start as i64 + offset).max(0) as usize;
+        ChartView { start, end: start + self.width(), ..self }.clamped(len)
+    }
+
+    /// Returns the window moved back within `0..len` (and shrunk if it is wider).
+    pub fn clamped(self, len: usize) -> Self {
+        let width = self.width().min(len);
+        let start = self.start.min(len - width);
+        ChartView { start, end: start + width, ..self }
+    }
+}
+
+/// Converts a horizontal position on the chart image (0.0 at its left edge, 1.0 at its right edge)
+/// into a position in the plotting area (0.0 at the first X position shown, 1.0 after the last one).
+pub fn plot_fraction(image_fraction: f32) -> f32 {
+    ((image_fraction - PLOT_LEFT) / (PLOT_RIGHT - PLOT_LEFT)).clamp(0.0, 1.0)
+}
+
+/// Converts a horizontal move on the chart image (in fractions of its width)
+/// into a move in the plotting area (in fractions of the window shown).
+pub fn plot_shift(image_shift: f32) -> f32 {
+    image_shift / (PLOT_RIGHT - PLOT_LEFT)
+}
+
+/// Change of the view asked by a gesture on the chart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    Pan(f32),           // Horizontal move, in fractions of the image width (positive: to the right)
+    Pinch { factor: f32, anchor: f32 }, // Width of the window multiplied by `factor` around `anchor` (image fraction)
+    Wheel { notches: f32, anchor: f32, x_only: bool }, // Mouse wheel turned around `anchor` (forward: positive)
+}
+
+/// Gestures too small to change the view yet (e.g. a drag of a few pixels over a chart of few steps),
+/// kept until they add up to a whole step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureAccumulator {
+    shift: f32,  // Pending move, in fractions of the window shown (positive: towards the later steps)
+    factor: f32, // Pending zoom factor
+}
+
+impl Default for GestureAccumulator {
+    fn default() -> Self {
+        GestureAccumulator { shift: 0.0, factor: 1.0 }
+    }
+}
+
+impl GestureAccumulator {
+    /// Adds a gesture to the pending ones and returns the view they lead to.
+    /// Dragging to the right brings the earlier steps into view.
+    ///
+    /// # Arguments
+    /// * `view` - Current view.
+    /// * `gesture` - The new gesture.
+    /// * `len` - Number of X positions of the whole chart.
+    pub fn apply(&mut self, view: ChartView, gesture: Gesture, len: usize) -> ChartView {
+        match gesture {
+            Gesture::Pan(shift) => {
+                self.shift -= plot_shift(shift);
+                let panned = view.panned(self.shift, len);
+                if (self.shift as f64 * view.width() as f64).round() != 0.0 {
+                    self.shift = 0.0;
+                }
+                panned
+            }
+            Gesture::Pinch { factor, anchor } => {
+                self.factor *= factor;
+                let zoomed = view.zoomed(self.factor, plot_fraction(anchor), len);
+                // Past the limits, the zoom is not kept either: the opposite gesture acts at once.
+                if zoomed != view || zoomed.width() <= MIN_VIEW_WIDTH.min(len) || zoomed.width() >= len {
+                    self.factor = 1.0;
+                }
+                zoomed
+            }
+            Gesture::Wheel { notches, anchor, x_only } => {
+                // Ctrl switches the Y axis at once, even when the window cannot be zoomed further.
+                let view = ChartView { fit_y: !x_only, ..view };
+                self.apply(view, Gesture::Pinch { factor: WHEEL_ZOOM.powf(notches), anchor }, len)
+            }
+        }
+    }
+}
+
+/// Fingers touching the chart, to recognize the gestures: one finger drags the chart, two fingers pinch it.
+/// Positions are horizontal fractions of the chart image.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TouchTracker {
+    fingers: Vec<(u64, f32)>, // (finger, last position) of each finger down, in the order they touched
+}
+
+impl TouchTracker {
+    /// Records a finger touching the chart.
+    pub fn press(&mut self, finger: u64, position: f32) {
+        self.fingers.retain(|&(id, _)| id != finger);
+        self.fingers.push((finger, position));
+    }
+
+    /// Forgets a finger leaving the chart.
+    pub fn lift(&mut self, finger: u64) {
+        self.fingers.retain(|&(id, _)| id != finger);
+    }
+
+    /// Records a finger moving, and returns the gesture it makes:
+    /// a pan with a single finger, a pinch with two (the distance between them sets the zoom).
+    /// Other fingers are ignored.
+    pub fn moved(&mut self, finger: u64, position: f32) -> Option<Gesture> {
+        let index = self.fingers.iter().position(|&(id, _)| id == finger)?;
+        let previous = self.fingers[index].1;
+        self.fingers[index].1 = position;
+        match self.fingers.len() {
+            1 => Some(Gesture::Pan(position - previous)),
+            2 => {
+                let other = self.fingers[1 - index].1;
+                let (before, after) = ((previous - other).abs(), (position - other).abs());
+                // Fingers on top of each other give no distance to compare.
+                (before > f32::EPSILON && after > f32::EPSILON)
+                    .then(|| Gesture::Pinch { factor: before / after, anchor: (position + other) / 2.0 })
+            }
+            _ => None,
+        }
+    }
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `ChartView::zoomed`.
+    #[test]
+    fn test_zoomed() {
+        let view = ChartView::full(100);
+        assert!(view.is_full(100));
+        // Zooming in around the middle keeps the middle in place.
+        assert_eq!(view.zoomed(0.5, 0.5, 100), ChartView::new(25, 75));
+        // Around the left edge, the start stays.
+        assert_eq!(view.zoomed(0.5, 0.0, 100), ChartView::new(0, 50));
+        // The window never gets below the minimum width, nor beyond the chart.
+        assert_eq!(view.zoomed(0.001, 0.5, 100).width(), MIN_VIEW_WIDTH);
+        assert_eq!(ChartView::new(80, 100).zoomed(4.0, 1.0, 100), ChartView::new(20, 100));
+        assert_eq!(ChartView::new(80, 100).zoomed(10.0, 1.0, 100), ChartView::full(100));
+    }
+
+    // Test function for `ChartView::panned`.
+    #[test]
+    fn test_panned() {
+        let view = ChartView::new(20, 40);
+        assert_eq!(view.panned(0.5, 100), ChartView::new(30, 50));
+        // The window stops at the edges of the chart.
+        assert_eq!(view.panned(-5.0, 100), ChartView::new(0, 20));
+        assert_eq!(view.panned(10.0, 100), ChartView::new(80, 100));
+        // A window left from a longer chart is brought back.
+        assert_eq!(view.clamped(30), ChartView::new(10, 30));
+    }
+
+    // Test function for `ChartView::position_at`.
+    #[test]
+    fn test_position_at() {
+        let view = ChartView::new(20, 40);
+        assert_eq!(view.position_at(0.0), 20);
+        assert_eq!(view.position_at(0.5), 30);
+        // The right edge is after the last position shown.
+        assert_eq!(view.position_at(1.0), 39);
+        assert_eq!(ChartView::full(0).position_at(0.5), 0);
+    }
+
+    // Test function for `plot_fraction` and `plot_shift`.
+    #[test]
+    fn test_plot_fraction() {
+        assert_eq!(plot_fraction(0.0), 0.0);
+        assert_eq!(plot_fraction(1.0), 1.0);
+        assert!((plot_fraction(0.5375) - 0.5).abs() < 1e-6);
+        // Moving across the plotting area moves the view by its whole width.
+        assert!((plot_shift(0.9) - 1.0).abs() < 1e-6);
+    }
+
+    // Test function for `GestureAccumulator::apply`.
+    #[test]
+    fn test_gesture_accumulator() {
+        let mut pending = GestureAccumulator::default();
+        let view = ChartView::new(40, 50);
+        // Drags of a tenth of a step do nothing until they add up to more than half a step
+        // (dragging to the left brings the later steps).
+        let step = -plot_shift_inverse(0.01);
+        for _ in 0..4 {
+            assert_eq!(pending.apply(view, Gesture::Pan(step), 100), view);
+        }
+        assert_eq!(pending.apply(view, Gesture::Pan(step * 2.0), 100), ChartView::new(41, 51));
+
+        // Pinching in by 1% at a time zooms once the width changes by a whole step.
+        let pinch = Gesture::Pinch { factor: 0.99, anchor: 0.5 };
+        let mut zoomed = view;
+        for _ in 0..6 {
+            zoomed = pending.apply(zoomed, pinch, 100);
+        }
+        assert_eq!(zoomed.width(), 9);
+    }
+
+    // Test function for `GestureAccumulator::apply` with the mouse wheel.
+    #[test]
+    fn test_wheel() {
+        let mut pending = GestureAccumulator::default();
+        let view = ChartView::full(100);
+        // A notch forward around the middle shows 80% of the steps; with Ctrl, the Y axis stays as it was.
+        let zoomed = pending.apply(view, Gesture::Wheel { notches: 1.0, anchor: 0.5375, x_only: false }, 100);
+        assert_eq!(zoomed, ChartView::new(10, 90));
+        let zoomed = pending.apply(zoomed, Gesture::Wheel { notches: -1.0, anchor: 0.5375, x_only: true }, 100);
+        assert_eq!(zoomed, ChartView { fit_y: false, ..ChartView::full(100) });
+    }
+
+    /// Returns the move on the image that moves the view by `shift` (inverse of `plot_shift`).
+    fn plot_shift_inverse(shift: f32) -> f32 {
+        shift * (PLOT_RIGHT - PLOT_LEFT)
+    }
+
+    // Test function for `TouchTracker`.
+    #[test]
+    fn test_touch_tracker() {
+        let mut touches = TouchTracker::default();
+        touches.press(1, 0.5);
+        assert_eq!(touches.moved(1, 0.6), Some(Gesture::Pan(0.6 - 0.5)));
+
+        // A second finger turns the moves into a pinch: spreading the fingers zooms in.
+        touches.press(2, 0.2);
+        match touches.moved(1, 0.8) {
+            Some(Gesture::Pinch { factor, anchor }) => {
+                assert!((factor - 0.4 / 0.6).abs() < 1e-6);
+                assert!((anchor - 0.5).abs() < 1e-6);
+            }
+            other => panic!("{:?}", other),
+        }
+
+        // Unknown fingers are ignored; once one finger is lifted, the other pans again.
+        assert_eq!(touches.moved(3, 0.1), None);
+        touches.lift(1);
+        assert_eq!(touches.moved(2, 0.3), Some(Gesture::Pan(0.3 - 0.2)));
+    }
+}
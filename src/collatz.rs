@@ -2,6 +2,11 @@
 // This module contains the logic related to the Collatz conjecture.
 // It defines how to generate a Collatz sequence and how to calculate statistics on this sequence.
 
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::big_uint::BigUint;
+
 /// Computes the Collatz sequence for a positive integer `start`.
 ///
 /// The Syracuse conjecture defines a sequence as follows:
@@ -12,17 +17,31 @@
 ///
 /// # Arguments
 /// * `start` - The positive integer (`u64`) from which the sequence begins. `u64` stands for "unsigned 64-bit integer",
-/// an unsigned integer (positive or zero) stored in 64 bits.
+///   an unsigned integer (positive or zero) stored in 64 bits.
 ///
 /// # Returns
 /// * `Vec<u64>` - A vector (a dynamic list) containing all the numbers in the sequence,
-/// starting with `start` and ending with 1.
+///   starting with `start` and ending with 1.
 ///
 /// # Note
 /// The conjecture states that any Collatz sequence reaches 1 for any positive starting integer.
 /// This function includes a check to avoid a potential overflow
 /// when calculating `3 * n + 1` for very large `u64` numbers.
 pub fn generate_sequence(start: u64) -> Vec<u64> {
+    generate_sequence_limited(start, usize::MAX)
+}
+
+/// Default largest number of steps computed for a trajectory (see `generate_sequence_limited`).
+/// The known trajectories of starting values below 2^64 reach 1 in a few thousand steps at most,
+/// so it mostly stops the variant maps.
+pub const DEFAULT_STEP_LIMIT: usize = 10_000;
+
+/// Computes the Collatz sequence of `start`, like `generate_sequence`, but stops after `max_steps` steps
+/// even if 1 was not reached, to protect the application from extremely long trajectories.
+///
+/// # Returns
+/// * `Vec<u64>` - At most `max_steps + 1` terms, starting with `start` (see `is_truncated`).
+pub fn generate_sequence_limited(start: u64, max_steps: usize) -> Vec<u64> {
     // Special case for 0. Although the conjecture concerns integers > 0,
     // we handle this case to avoid an infinite loop (0 -> 0).
     if start == 0 {
@@ -36,12 +55,12 @@ pub fn generate_sequence(start: u64) -> Vec<u64> {
     
     sequence.push(current); // Adds the starting value to the sequence.
     
-    // Loop until the current value is 1 (the sequence's stop condition).
-    while current != 1 {
+    // Loop until the current value is 1 (the sequence's stop condition), or the step limit is reached.
+    while current != 1 && sequence.len() <= max_steps {
         // Check if the current number is even.
-        if current % 2 == 0 {
+        if current.is_multiple_of(2) {
             // If even, divide by 2 to get the next number.
-            current = current / 2;
+            current /= 2;
         } else {
             // If odd, multiply by 3 and add 1.
             // We need to check for potential integer overflow before performing the calculation (3 * n + 1).
@@ -62,6 +81,158 @@ pub fn generate_sequence(start: u64) -> Vec<u64> {
     sequence
 }
 
+/// Yields the terms of the Collatz sequence of `start` one by one, exactly like `generate_sequence_limited`
+/// (same step limit, same stop before an overflow), without keeping them: for sequences too long to be stored
+/// (see `sequence_budget`).
+pub fn trajectory(start: u64, max_steps: usize) -> impl Iterator<Item = u64> {
+    let mut next = Some(start);
+    let mut count = 0; // Number of terms yielded so far
+    let mut overflowed = false;
+    std::iter::from_fn(move || {
+        let term = next.take()?;
+        count += 1;
+        // The loop condition of `generate_sequence_limited`, checked with the terms yielded so far.
+        if term != 0 && term != 1 && count <= max_steps && !overflowed {
+            next = if term.is_multiple_of(2) {
+                Some(term / 2)
+            } else if term > (u64::MAX - 1) / 3 {
+                // Like `generate_sequence_limited`, the term before the overflow is repeated, and the sequence stops.
+                overflowed = true;
+                Some(term)
+            } else {
+                Some(3 * term + 1)
+            };
+        }
+        Some(term)
+    })
+}
+
+/// Computes the glide of `start`, like `generate_sequence_limited`: the trajectory stops as soon as a term
+/// drops below the starting value (that term included), or after `max_steps` steps, or before a term that would overflow.
+/// The glide is the part of the trajectory that decides whether `start` itself can be a counterexample.
+///
+/// # Returns
+/// * `Vec<u64>` - At most `max_steps + 1` terms, starting with `start` (see `is_glide_truncated`).
+///   1 has no lower value to reach: its glide is `[1]`.
+pub fn generate_glide_limited(start: u64, max_steps: usize) -> Vec<u64> {
+    let mut sequence = vec![start];
+    let mut current = start;
+    while current > 1 && sequence.len() <= max_steps {
+        current = if current.is_multiple_of(2) {
+            current / 2
+        } else if current > (u64::MAX - 1) / 3 {
+            break;
+        } else {
+            3 * current + 1
+        };
+        sequence.push(current);
+        if current < start {
+            break;
+        }
+    }
+    sequence
+}
+
+/// Tells whether a glide computed by `generate_glide_limited` was cut at the step limit
+/// (it took `max_steps` steps without dropping below its starting value).
+pub fn is_glide_truncated(sequence: &[u64], max_steps: usize) -> bool {
+    sequence.len() == max_steps.saturating_add(1) && sequence.last() >= sequence.first()
+}
+
+/// Computes the glide of `start` without storing the terms: the number of steps before the trajectory
+/// first drops below `start` (its stopping time). Glides are short, so a range scan of glides is
+/// much faster than one of total stopping times.
+///
+/// # Returns
+/// * `Some(steps)` - The length of the glide (0 for `start == 1`).
+/// * `None` - If `start` is 0 or if the computation would overflow a `u64`.
+pub fn glide(start: u64) -> Option<usize> {
+    match start {
+        0 => return None,
+        1 => return Some(0),
+        _ => {}
+    }
+    let mut current = start;
+    let mut steps = 0;
+    while current >= start {
+        current = if current.is_multiple_of(2) { current / 2 } else { current.checked_mul(3)?.checked_add(1)? };
+        steps += 1;
+    }
+    Some(steps)
+}
+
+/// Computes the glide of every starting value in `range_start..=range_end`.
+///
+/// # Returns
+/// * `Vec<(u64, usize)>` - Pairs of (starting value, glide), in increasing order.
+///   Starting values whose computation would overflow (and 0) are left out.
+pub fn glides_in_range(range_start: u64, range_end: u64) -> Vec<(u64, usize)> {
+    (range_start..=range_end)
+        .filter_map(|n| glide(n).map(|steps| (n, steps)))
+        .collect()
+}
+
+/// Tells whether a sequence computed by `generate_sequence_limited` was cut at the step limit
+/// (it took `max_steps` steps without reaching 1).
+pub fn is_truncated(sequence: &[u64], max_steps: usize) -> bool {
+    sequence.len() == max_steps.saturating_add(1) && sequence.last() != Some(&1)
+}
+
+/// How the end of a trajectory is shown once it reaches 1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TailHandling {
+    #[default]
+    StopAtOne,   // The sequence stops at the first 1
+    RepeatCycle, // The 4 -> 2 -> 1 cycle is appended a chosen number of times, to illustrate it
+    TrimDescent, // The final descent through powers of two is cut, so the sequence stops at the first power of two
+}
+
+impl TailHandling {
+    /// Every handling, in the order shown in the selector.
+    pub const ALL: [TailHandling; 3] = [TailHandling::StopAtOne, TailHandling::RepeatCycle, TailHandling::TrimDescent];
+
+    /// Applies the handling to a sequence that reached 1. A sequence cut before 1 (step limit, overflow) is left as it is.
+    ///
+    /// # Arguments
+    /// * `sequence` - The sequence, as computed by `generate_sequence_limited`.
+    /// * `cycles` - Number of 4 -> 2 -> 1 cycles appended by `RepeatCycle`.
+    pub fn apply(self, sequence: &mut Vec<u64>, cycles: usize) {
+        if sequence.last() != Some(&1) {
+            return;
+        }
+        match self {
+            TailHandling::StopAtOne => {}
+            TailHandling::RepeatCycle => sequence.extend([4, 2, 1].repeat(cycles)),
+            TailHandling::TrimDescent => {
+                // Once a power of two is reached, every following term is one.
+                let first_power = sequence.iter().position(|term| term.is_power_of_two()).unwrap_or(0);
+                sequence.truncate(first_power + 1);
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for TailHandling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            TailHandling::StopAtOne => "Stop at 1",
+            TailHandling::RepeatCycle => "Repeat 4-2-1",
+            TailHandling::TrimDescent => "Trim 2^k descent",
+        })
+    }
+}
+
+/// Returns the values whose next term is `value`, i.e. the reverse Collatz map: 2 * value always
+/// (unless it overflows), and (value - 1) / 3 when it is an odd number greater than 1 (value = 4 mod 6).
+/// 1 is left out, since its trajectory stops there instead of going on to 4.
+pub fn predecessors(value: u64) -> Vec<u64> {
+    let mut predecessors: Vec<u64> = value.checked_mul(2).into_iter().collect();
+    if value % 6 == 4 && value > 4 {
+        predecessors.push((value - 1) / 3);
+    }
+    predecessors
+}
+
 /// Holds statistics calculated from a Collatz sequence.
 pub struct CollatzStats {
     pub length: usize,           // Sequence length (total flight time)
@@ -70,6 +241,9 @@ pub struct CollatzStats {
     pub even_count: usize,       // Number of even values
     pub odd_count: usize,        // Number of odd values
     pub stopping_time: usize,    // Stop time (number of steps to reach a value < start)
+    pub max_bit_length: u32,     // Largest number of binary digits of a term
+    pub average_bit_length: f64, // Average number of binary digits of the terms
+    pub max_bit_length_index: usize, // Position of the first term with the largest bit length
 }
 
 /// Calculates various statistics for a given Collatz sequence.
@@ -81,7 +255,7 @@ pub struct CollatzStats {
 /// # Returns
 ///
 /// * `CollatzStats` - A struct containing the calculated statistics.
-///                    Returns default/zero stats if the input sequence is empty.
+///   Returns default/zero stats if the input sequence is empty.
 pub fn calculate_stats(sequence: &[u64]) -> CollatzStats {
     // If the sequence is empty, return default statistics.
     if sequence.is_empty() {
@@ -92,6 +266,9 @@ pub fn calculate_stats(sequence: &[u64]) -> CollatzStats {
             even_count: 0,
             odd_count: 0,
             stopping_time: 0,
+            max_bit_length: 0,
+            average_bit_length: 0.0,
+            max_bit_length_index: 0,
         };
     }
 
@@ -134,6 +311,13 @@ pub fn calculate_stats(sequence: &[u64]) -> CollatzStats {
         .find(|&(_, &value)| value < start_value)
         .map(|(index, _)| index)
         .unwrap_or(length - 1); 
+
+    // The bit length (number of binary digits) summarizes the size of the terms without writing them out.
+    // `max_by_key` keeps the last maximum, so the first one is searched for explicitly.
+    let bit_length = |n: u64| u64::BITS - n.leading_zeros();
+    let max_bit_length = bit_length(*max_value);
+    let max_bit_length_index = sequence.iter().position(|&n| bit_length(n) == max_bit_length).unwrap_or(0);
+    let average_bit_length = sequence.iter().map(|&n| bit_length(n) as f64).sum::<f64>() / length as f64;
     
     // Return the populated statistics struct.
     CollatzStats {
@@ -143,42 +327,1783 @@ pub fn calculate_stats(sequence: &[u64]) -> CollatzStats {
         even_count,
         odd_count,
         stopping_time,
+        max_bit_length,
+        average_bit_length,
+        max_bit_length_index,
     }
 }
 
-// Test module: Contains unit tests for the functions in this file.
-// This code only runs when you execute `cargo test`.
-#[cfg(test)]
-mod tests {
-    // Import everything from the parent module (the code above).
-    use super::*;
+/// Computes the total stopping time of `start`, i.e. the number of steps needed to reach 1.
+///
+/// Unlike `generate_sequence`, this function does not store the terms of the sequence:
+/// it only counts the steps, which makes it suitable for scanning large ranges of starting values.
+///
+/// # Arguments
+/// * `start` - The positive integer from which the sequence begins.
+///
+/// # Returns
+/// * `Some(steps)` - The number of steps needed to reach 1 (0 for `start == 1`).
+/// * `None` - If `start` is 0 or if the computation would overflow a `u64`.
+pub fn total_stopping_time(start: u64) -> Option<usize> {
+    // Following the trajectory down to 1 gives the total stopping time.
+    descend_to(start, 1).map(|(steps, _)| steps)
+}
 
-    // Test function for `generate_sequence`.
-    #[test]
-    fn test_generate_sequence() {
-        // Test case 1: Starting with n = 6.
-        let sequence = generate_sequence(6);
-        // Assert that the generated sequence matches the expected sequence.
-        assert_eq!(sequence, vec![6, 3, 10, 5, 16, 8, 4, 2, 1]);
-        
-        // Test case 2: Starting with n = 1 (base case).
-        let sequence = generate_sequence(1);
-        assert_eq!(sequence, vec![1]);
+/// Follows the trajectory of `start` until it reaches a value no greater than `floor`.
+///
+/// This is the hot loop of the range scans, so the steps are taken in batches:
+/// - all the consecutive halvings at once, with a single shift by the number of trailing zeros;
+/// - 3n + 1 (always even for an odd n) is directly followed by its halving: (3n + 1) / 2,
+///   computed as n + n / 2 + 1 so that it cannot overflow, and counted as 2 steps.
+///
+/// # Arguments
+/// * `start` - The positive integer from which the sequence begins.
+/// * `floor` - The value to get down to (at least 1).
+///
+/// # Returns
+/// * `Some((steps, value))` - The first value no greater than `floor` met (at the end of a batch),
+///   and the number of steps needed to reach it.
+/// * `None` - If `start` is 0 or if the computation would overflow a `u64`.
+pub fn descend_to(start: u64, floor: u64) -> Option<(usize, u64)> {
+    // 0 never reaches 1 (0 -> 0 -> ...), so it has no stopping time.
+    if start == 0 {
+        return None;
     }
 
-    // Test function for `calculate_stats`.
-    #[test]
-    fn test_calculate_stats() {
-        // Test case 1: Using the sequence for n = 6.
-        let sequence = generate_sequence(6); // vec![6, 3, 10, 5, 16, 8, 4, 2, 1]
-        let stats = calculate_stats(&sequence);
-        
-        // Assert that each statistic matches the expected value.
-        assert_eq!(stats.length, 9);
-        assert_eq!(stats.max_value, 16);
-        assert_eq!(stats.max_value_index, 4);
-        assert_eq!(stats.even_count, 6);
-        assert_eq!(stats.odd_count, 3);
-        assert_eq!(stats.stopping_time, 1);
+    let floor = floor.max(1);
+    let mut current = start;
+    let mut steps = 0;
+
+    while current > floor {
+        let zeros = current.trailing_zeros();
+        current >>= zeros;
+        steps += zeros as usize;
+        if current <= floor {
+            break;
+        }
+        // Same overflow guard as in `generate_sequence` (the 3n + 1 term itself must fit in a u64).
+        if current > (u64::MAX - 1) / 3 {
+            return None;
+        }
+        current = current + (current >> 1) + 1;
+        steps += 2;
+    }
+
+    Some((steps, current))
+}
+
+/// Computes the total stopping time of `start` using 128-bit intermediate values.
+///
+/// Trajectories starting near 2^60 routinely climb far above `u64::MAX`, which makes
+/// `total_stopping_time` give up on them. Widening the arithmetic to `u128` lets such
+/// starting values be evaluated (the peak of a trajectory would have to exceed 2^128 to fail).
+///
+/// # Returns
+/// * `Some(steps)` - The number of steps needed to reach 1.
+/// * `None` - If `start` is 0 or if the computation would overflow a `u128`.
+pub fn total_stopping_time_wide(start: u64) -> Option<usize> {
+    if start == 0 {
+        return None;
+    }
+
+    let mut current = start as u128;
+    let mut steps = 0;
+
+    // Same batched stepping as `total_stopping_time`.
+    loop {
+        let zeros = current.trailing_zeros();
+        current >>= zeros;
+        steps += zeros as usize;
+        if current == 1 {
+            break;
+        }
+        if current > (u128::MAX - 1) / 3 {
+            return None;
+        }
+        current = current + (current >> 1) + 1;
+        steps += 2;
+    }
+
+    Some(steps)
+}
+
+/// Integer type holding the terms of a trajectory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arithmetic {
+    U64,  // Native 64-bit integers, the fastest
+    U128, // 128-bit integers, about twice as slow
+    Big,  // Arbitrary precision (`BigUint`), much slower, for the terms beyond 128 bits
+}
+
+impl std::fmt::Display for Arithmetic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Arithmetic::U64 => "64-bit",
+            Arithmetic::U128 => "128-bit",
+            Arithmetic::Big => "arbitrary precision",
+        })
+    }
+}
+
+/// Largest number of bits of a term followed with arbitrary precision. A trajectory of a 64-bit starting value
+/// climbing that high would disprove the conjecture: it is reported (no stopping time) rather than followed forever.
+pub const MAX_BIG_BITS: u64 = 1 << 16;
+
+/// Total stopping time computed by `total_stopping_time_auto`, with the arithmetic it needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoStoppingTime {
+    pub steps: Option<usize>,        // Number of steps needed to reach 1 (None for 0, or beyond `MAX_BIG_BITS`)
+    pub arithmetic: Arithmetic,      // Widest arithmetic used
+    pub promoted_at: Option<usize>,  // Step at which the computation switched to 128 bits, if it did
+    pub big_at: Option<usize>,       // Step at which the computation switched to arbitrary precision, if it did
+}
+
+/// Computes the total stopping time of `start`, choosing the arithmetic on the way:
+/// the computation runs on `u64`, switches to `u128` at the first term whose 3n + 1 would not fit,
+/// and to `BigUint` at the first one that would not fit in 128 bits either,
+/// so that most starting values get the speed of `total_stopping_time` and the others the reach they need.
+///
+/// # Returns
+/// * `AutoStoppingTime` - The steps, with the arithmetic used (`steps` is None if `start` is 0).
+pub fn total_stopping_time_auto(start: u64) -> AutoStoppingTime {
+    let mut result = AutoStoppingTime { steps: None, arithmetic: Arithmetic::U64, promoted_at: None, big_at: None };
+    if start == 0 {
+        return result;
+    }
+
+    // Same batched stepping as `total_stopping_time`, until 64 bits are not enough.
+    let mut current = start;
+    let mut steps = 0;
+    loop {
+        let zeros = current.trailing_zeros();
+        current >>= zeros;
+        steps += zeros as usize;
+        if current == 1 {
+            result.steps = Some(steps);
+            return result;
+        }
+        if current > (u64::MAX - 1) / 3 {
+            break;
+        }
+        current = current + (current >> 1) + 1;
+        steps += 2;
+    }
+
+    // The next steps run on 128 bits, from the odd term that would overflow.
+    result.arithmetic = Arithmetic::U128;
+    result.promoted_at = Some(steps);
+    let mut current = current as u128;
+    loop {
+        if current > (u128::MAX - 1) / 3 {
+            break;
+        }
+        current = current + (current >> 1) + 1;
+        steps += 2;
+        let zeros = current.trailing_zeros();
+        current >>= zeros;
+        steps += zeros as usize;
+        if current == 1 {
+            result.steps = Some(steps);
+            return result;
+        }
+    }
+
+    // The remaining steps run with arbitrary precision, from the odd term that would overflow 128 bits.
+    result.arithmetic = Arithmetic::Big;
+    result.big_at = Some(steps);
+    result.steps = big_stopping_time(BigUint::from(current), steps);
+    result
+}
+
+/// Finishes a total stopping time with arbitrary precision.
+///
+/// # Arguments
+/// * `current` - The odd term reached so far.
+/// * `steps` - Number of steps taken to reach it.
+///
+/// # Returns
+/// * `Some(steps)` - The number of steps needed to reach 1.
+/// * `None` - If a term grows beyond `MAX_BIG_BITS` bits.
+fn big_stopping_time(mut current: BigUint, mut steps: usize) -> Option<usize> {
+    while !current.is_one() {
+        if current.is_odd() {
+            if current.bits() > MAX_BIG_BITS {
+                return None;
+            }
+            current.triple_plus_one();
+            steps += 1;
+        }
+        let zeros = current.trailing_zeros();
+        current.shift_right(zeros);
+        steps += zeros as usize;
+    }
+    Some(steps)
+}
+
+/// Term of a trajectory in the narrowest arithmetic it fits in (see `wide_trajectory`).
+#[derive(Debug, Clone)]
+enum WideTerm {
+    U64(u64),
+    U128(u128),
+    Big(BigUint),
+}
+
+impl WideTerm {
+    /// Applies one step of the Collatz map, switching to a wider arithmetic if 3n + 1 would not fit.
+    fn step(&mut self) {
+        *self = match std::mem::replace(self, WideTerm::U64(0)) {
+            WideTerm::U64(n) if n % 2 == 0 => WideTerm::U64(n / 2),
+            WideTerm::U64(n) if n <= (u64::MAX - 1) / 3 => WideTerm::U64(3 * n + 1),
+            WideTerm::U64(n) => WideTerm::U128(3 * n as u128 + 1),
+            WideTerm::U128(n) if n % 2 == 0 => WideTerm::U128(n / 2),
+            WideTerm::U128(n) if n <= (u128::MAX - 1) / 3 => WideTerm::U128(3 * n + 1),
+            WideTerm::U128(n) => {
+                let mut n = BigUint::from(n);
+                n.triple_plus_one();
+                WideTerm::Big(n)
+            }
+            WideTerm::Big(mut n) => {
+                if n.is_odd() {
+                    n.triple_plus_one();
+                } else {
+                    n.shift_right(1);
+                }
+                WideTerm::Big(n)
+            }
+        };
+    }
+
+    fn is_odd(&self) -> bool {
+        match self {
+            WideTerm::U64(n) => n % 2 == 1,
+            WideTerm::U128(n) => n % 2 == 1,
+            WideTerm::Big(n) => n.is_odd(),
+        }
+    }
+
+    fn is_one(&self) -> bool {
+        match self {
+            WideTerm::U64(n) => *n == 1,
+            WideTerm::U128(n) => *n == 1,
+            WideTerm::Big(n) => n.is_one(),
+        }
+    }
+
+    /// Returns the number of binary digits of the term.
+    fn bits(&self) -> u64 {
+        match self {
+            WideTerm::U64(n) => (u64::BITS - n.leading_zeros()) as u64,
+            WideTerm::U128(n) => (u128::BITS - n.leading_zeros()) as u64,
+            WideTerm::Big(n) => n.bits(),
+        }
+    }
+
+    fn to_big(&self) -> BigUint {
+        match self {
+            WideTerm::U64(n) => BigUint::from(*n),
+            WideTerm::U128(n) => BigUint::from(*n),
+            WideTerm::Big(n) => n.clone(),
+        }
+    }
+
+    /// Tells whether the term is larger than `other` (without converting it when both fit in 128 bits).
+    fn exceeds(&self, other: &BigUint) -> bool {
+        match (self, other.to_u128()) {
+            (WideTerm::U64(n), Some(other)) => *n as u128 > other,
+            (WideTerm::U128(n), Some(other)) => *n > other,
+            (WideTerm::Big(n), _) => n > other,
+            (_, None) => false,
+        }
+    }
+}
+
+/// Statistics of a whole trajectory computed by `wide_trajectory`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WideTrajectory {
+    pub steps: usize,       // Number of steps computed (to 1, or to the step limit)
+    pub reached_one: bool,  // Whether the trajectory reached 1 within the step limit
+    pub peak: BigUint,      // Highest term
+    pub peak_step: usize,   // Step of the highest term
+    pub odd_count: usize,   // Number of odd terms
+    pub max_bits: u64,      // Number of binary digits of the highest term
+}
+
+/// Follows the trajectory of `start` with the automatic arithmetic of `total_stopping_time_auto`:
+/// each term is computed on 64 bits, 128 bits or with arbitrary precision, whichever it needs,
+/// so that a trajectory whose `u64` sequence stops at an overflow is described to its end.
+///
+/// # Arguments
+/// * `start` - The starting value (0 gives a trajectory of a single term).
+/// * `max_steps` - Step limit, as for `generate_sequence_limited`.
+///
+/// # Returns
+/// * `WideTrajectory` - The length, peak and parity counts of the whole trajectory.
+pub fn wide_trajectory(start: u64, max_steps: usize) -> WideTrajectory {
+    let mut term = WideTerm::U64(start);
+    let mut trajectory = WideTrajectory {
+        steps: 0,
+        reached_one: start == 1,
+        peak: BigUint::from(start),
+        peak_step: 0,
+        odd_count: (start % 2) as usize,
+        max_bits: 0,
+    };
+    while start != 0 && !term.is_one() && trajectory.steps < max_steps {
+        term.step();
+        trajectory.steps += 1;
+        if term.is_odd() {
+            trajectory.odd_count += 1;
+        }
+        // No term comes back before 1: the maximum is reached once.
+        if term.exceeds(&trajectory.peak) {
+            trajectory.peak = term.to_big();
+            trajectory.peak_step = trajectory.steps;
+        }
+        trajectory.reached_one = term.is_one();
+    }
+    trajectory.max_bits = trajectory.peak.bits();
+    trajectory
+}
+
+/// Computes the bit length of every term of the trajectory of `start`, with the automatic arithmetic
+/// of `wide_trajectory`: unlike the terms, the bit lengths of a trajectory outgrowing 64 bits can be plotted.
+///
+/// # Arguments
+/// * `start` - The starting value.
+/// * `max_steps` - Step limit, as for `generate_sequence_limited`.
+///
+/// # Returns
+/// * `Vec<u64>` - The number of binary digits of each term, from `start` to 1 (or to the step limit).
+pub fn wide_bit_lengths(start: u64, max_steps: usize) -> Vec<u64> {
+    let mut term = WideTerm::U64(start);
+    let mut bits = vec![term.bits()];
+    while start != 0 && !term.is_one() && bits.len() <= max_steps {
+        term.step();
+        bits.push(term.bits());
+    }
+    bits
+}
+
+/// Computes the total stopping time of every starting value in `range_start..=range_end`.
+///
+/// # Returns
+/// * `Vec<(u64, usize)>` - Pairs of (starting value, total stopping time), in increasing order.
+///   Starting values whose computation would overflow (and 0) are left out.
+pub fn stopping_times_in_range(range_start: u64, range_end: u64) -> Vec<(u64, usize)> {
+    (range_start..=range_end)
+        .filter_map(|n| total_stopping_time(n).map(|t| (n, t)))
+        .collect()
+}
+
+/// Computes the highest value reached by the trajectory of `start` (its peak, or maximum excursion)
+/// and its total stopping time in a single pass, without storing the terms.
+///
+/// # Returns
+/// * `Some((peak, steps))` - The highest term of the sequence (`start` itself if it never climbs higher),
+///   and the number of steps to reach 1.
+/// * `None` - If `start` is 0 or if the computation would overflow a `u64`.
+pub fn peak_and_stopping_time(start: u64) -> Option<(u64, usize)> {
+    if start == 0 {
+        return None;
+    }
+    let mut current = start;
+    let mut peak = start;
+    let mut steps = 0;
+    while current != 1 {
+        let zeros = current.trailing_zeros();
+        current >>= zeros;
+        steps += zeros as usize;
+        if current == 1 {
+            break;
+        }
+        // The peak is always a 3n + 1 term: halvings only go down.
+        if current > (u64::MAX - 1) / 3 {
+            return None;
+        }
+        current = 3 * current + 1;
+        steps += 1;
+        peak = peak.max(current);
+    }
+    Some((peak, steps))
+}
+
+/// Computes the peak of every starting value in `range_start..=range_end`, and summarizes their
+/// total stopping times on the way (see `peak_and_stopping_time`).
+///
+/// # Returns
+/// * `Vec<(u64, u64)>` - Pairs of (starting value, peak), in increasing order.
+///   Starting values whose computation would overflow (and 0) are left out.
+/// * `RangeSummary` - The summary of the total stopping times of the range.
+pub fn peaks_in_range(range_start: u64, range_end: u64) -> (Vec<(u64, u64)>, RangeSummary) {
+    let mut tally = StoppingTimeTally::new(range_start, range_end, 1);
+    let peaks = (range_start..=range_end)
+        .filter_map(|n| {
+            let (peak, time) = peak_and_stopping_time(n)?;
+            tally.add(n, time);
+            Some((n, peak))
+        })
+        .collect();
+    (peaks, tally.summary())
+}
+
+/// Counts, for every term of the trajectory of `value`, how many trajectories starting in
+/// `range_start..=range_end` pass through it.
+///
+/// The range is scanned in parallel, and each trajectory is only followed until it meets the trajectory
+/// of `value`: from then on, it goes through all the following terms too.
+///
+/// # Returns
+/// * `Vec<(u64, u64)>` - Each term of the trajectory of `value` (from `value` down to 1), with the number
+///   of starting values of the range whose trajectory passes through it. The first count is that of `value`.
+///   Starting values whose trajectory would overflow are not counted; `value` 0 gives an empty list.
+pub fn passes_through(value: u64, range_start: u64, range_end: u64) -> Vec<(u64, u64)> {
+    if value == 0 {
+        return Vec::new();
+    }
+    let trajectory = generate_sequence(value);
+    let positions: HashMap<u64, usize> = trajectory.iter().enumerate().map(|(i, &term)| (term, i)).collect();
+
+    // How many trajectories first meet the trajectory of `value` at each of its terms.
+    let first_meetings = (range_start.max(1)..=range_end)
+        .into_par_iter()
+        .fold(
+            || vec![0u64; trajectory.len()],
+            |mut meetings, start| {
+                let mut current = start;
+                loop {
+                    if let Some(&position) = positions.get(&current) {
+                        meetings[position] += 1;
+                        break;
+                    }
+                    if current.is_multiple_of(2) {
+                        current /= 2;
+                    } else if current > (u64::MAX - 1) / 3 {
+                        break;
+                    } else {
+                        current = 3 * current + 1;
+                    }
+                }
+                meetings
+            },
+        )
+        .reduce(
+            || vec![0u64; trajectory.len()],
+            |mut a, b| {
+                a.iter_mut().zip(b).for_each(|(a, b)| *a += b);
+                a
+            },
+        );
+
+    // A trajectory meeting the term i also goes through all the terms after it.
+    let mut passing = 0;
+    trajectory
+        .iter()
+        .zip(first_meetings)
+        .map(|(&term, meetings)| {
+            passing += meetings;
+            (term, passing)
+        })
+        .collect()
+}
+
+/// Returns the "hubs" of a trajectory: the terms where the most other trajectories join it,
+/// from the counts of `passes_through`, in decreasing order of joining trajectories.
+///
+/// # Returns
+/// * `Vec<(u64, u64)>` - At most `top` terms, with the number of trajectories joining there
+///   (passing through the term but not through the one before it).
+pub fn hub_values(counts: &[(u64, u64)], top: usize) -> Vec<(u64, u64)> {
+    let mut hubs: Vec<(u64, u64)> = counts
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, &(term, count))| (term, count - counts[i - 1].1))
+        .filter(|&(_, joining)| joining > 0)
+        .collect();
+    hubs.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    hubs.truncate(top);
+    hubs
+}
+
+/// Ranks the values visited the most often by the trajectories of `range_start..=range_end`.
+///
+/// The powers of 2 are left out: they form the trivial tail (16, 8, 4, 2, 1) that every trajectory ends with,
+/// so they would fill the ranking without telling anything about the "highways" the trajectories share.
+///
+/// # Returns
+/// * `Vec<(u64, u64)>` - At most `top` values, with the number of trajectories visiting them,
+///   in decreasing order of visits (then increasing order of value).
+///   Trajectories that would overflow are left out.
+pub fn most_visited(range_start: u64, range_end: u64, top: usize) -> Vec<(u64, u64)> {
+    let visits = (range_start.max(1)..=range_end)
+        .into_par_iter()
+        .fold(HashMap::new, |mut visits: HashMap<u64, u64>, start| {
+            let sequence = generate_sequence(start);
+            // `generate_sequence` stops early on overflow: such a trajectory never reaches 1.
+            if sequence.last() == Some(&1) {
+                for term in sequence.into_iter().filter(|term| !term.is_power_of_two()) {
+                    *visits.entry(term).or_insert(0) += 1;
+                }
+            }
+            visits
+        })
+        .reduce(HashMap::new, |mut a, b| {
+            for (term, count) in b {
+                *a.entry(term).or_insert(0) += count;
+            }
+            a
+        });
+
+    let mut ranking: Vec<(u64, u64)> = visits.into_iter().collect();
+    ranking.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranking.truncate(top);
+    ranking
+}
+
+/// Union of the trajectories of several starting values, as a directed graph:
+/// every term is a node, with an edge to the next term of its trajectory.
+/// As each term has a single successor, the trajectories can only merge, never split.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrajectoryGraph {
+    pub starts: Vec<u64>,                // Starting values, in the order given
+    pub successors: BTreeMap<u64, u64>,  // Each term with the next one (the last terms have none)
+    pub nodes: Vec<u64>,                 // Every term, in increasing order
+}
+
+impl TrajectoryGraph {
+    /// Returns the number of edges of the graph.
+    pub fn edge_count(&self) -> usize {
+        self.successors.len()
+    }
+
+    /// Returns the merge nodes: the terms reached from two different terms, where trajectories funnel together,
+    /// with their number of predecessors, in increasing order of term.
+    pub fn merge_nodes(&self) -> Vec<(u64, usize)> {
+        let mut in_degrees: BTreeMap<u64, usize> = BTreeMap::new();
+        for &next in self.successors.values() {
+            *in_degrees.entry(next).or_insert(0) += 1;
+        }
+        in_degrees.into_iter().filter(|&(_, degree)| degree >= 2).collect()
+    }
+}
+
+/// Builds the graph of the trajectories of `starts` (see `TrajectoryGraph`).
+///
+/// # Arguments
+/// * `starts` - Starting values (duplicates are merged; 0 has the single node 0).
+/// * `max_steps` - Largest number of steps followed per trajectory (see `generate_sequence_limited`).
+pub fn merged_graph(starts: &[u64], max_steps: usize) -> TrajectoryGraph {
+    let mut successors = BTreeMap::new();
+    let mut nodes = Vec::new();
+    for &start in starts {
+        let sequence = generate_sequence_limited(start, max_steps);
+        nodes.extend_from_slice(&sequence);
+        for pair in sequence.windows(2) {
+            successors.insert(pair[0], pair[1]);
+        }
+    }
+    nodes.sort_unstable();
+    nodes.dedup();
+    TrajectoryGraph {
+        starts: starts.to_vec(),
+        successors,
+        nodes,
+    }
+}
+
+/// Describes the first term of a sequence that does not follow the Collatz rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mismatch {
+    pub index: usize,          // Index of the first wrong term
+    pub expected: Option<u64>, // Term given by the rule (None if 3n+1 does not fit in 64 bits)
+    pub found: u64,            // Term found in the sequence
+}
+
+/// Checks a sequence (e.g. imported from a file) against the Collatz rule:
+/// it is recomputed from its first term, one step at a time.
+///
+/// # Returns
+/// * `Ok(())` - Every term follows from the previous one.
+/// * `Err(Mismatch)` - The first term that disagrees with the rule.
+pub fn check_sequence(sequence: &[u64]) -> Result<(), Mismatch> {
+    for (index, pair) in sequence.windows(2).enumerate() {
+        let expected = if pair[0].is_multiple_of(2) {
+            Some(pair[0] / 2)
+        } else {
+            pair[0].checked_mul(3).and_then(|n| n.checked_add(1))
+        };
+        if expected != Some(pair[1]) {
+            return Err(Mismatch { index: index + 1, expected, found: pair[1] });
+        }
+    }
+    Ok(())
+}
+
+/// Rule of the Collatz map applied to a term to get the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepRule {
+    Halve,          // Even n -> n / 2
+    TriplePlusOne,  // Odd n -> 3n + 1
+}
+
+/// One step of a sequence, with the rule that gives the next term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnnotatedStep {
+    pub index: usize,   // Step number (index of `value` in the sequence)
+    pub value: u64,     // Term the rule is applied to
+    pub rule: StepRule, // Rule applied, given by the parity of `value`
+    pub next: u64,      // Next term of the sequence
+}
+
+impl AnnotatedStep {
+    /// Explains the step in words, e.g. "27 is odd → 3×27+1 = 82" or "82 is even → 82/2 = 41".
+    pub fn describe(&self) -> String {
+        match self.rule {
+            StepRule::Halve => format!("{} is even → {}/2 = {}", self.value, self.value, self.next),
+            StepRule::TriplePlusOne => format!("{} is odd → 3×{}+1 = {}", self.value, self.value, self.next),
+        }
+    }
+}
+
+/// Annotates every step of a sequence with the rule applied to go from one term to the next.
+///
+/// The rule is chosen by the parity of each term, and the next term is the one found in the sequence,
+/// so an imported sequence is explained as it is (check it with `check_sequence` first).
+///
+/// # Returns
+/// * `Vec<AnnotatedStep>` - One step per pair of consecutive terms (none for a single term).
+pub fn annotate_steps(sequence: &[u64]) -> Vec<AnnotatedStep> {
+    sequence
+        .windows(2)
+        .enumerate()
+        .map(|(index, pair)| AnnotatedStep {
+            index,
+            value: pair[0],
+            rule: if pair[0].is_multiple_of(2) { StepRule::Halve } else { StepRule::TriplePlusOne },
+            next: pair[1],
+        })
+        .collect()
+}
+
+/// Measures how much of two trajectories is "shared highway": the terms they have in common
+/// compared to all the terms they visit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathCompression {
+    pub first_terms: usize,     // Number of terms of the first sequence
+    pub second_terms: usize,    // Number of terms of the second sequence
+    pub distinct_values: usize, // Number of different values among the terms of both sequences
+    pub shared_values: usize,   // Number of values visited by both sequences
+}
+
+impl PathCompression {
+    /// Returns the share of the terms of both sequences saved by merging them into a single path,
+    /// from 0 (nothing in common) to almost 1 (the same path), i.e. 1 - distinct values / terms.
+    pub fn compression(&self) -> f64 {
+        let terms = self.first_terms + self.second_terms;
+        if terms == 0 {
+            return 0.0;
+        }
+        1.0 - self.distinct_values as f64 / terms as f64
+    }
+
+    /// Returns the share of the terms of the first sequence that the second one visits too.
+    pub fn first_overlap(&self) -> f64 {
+        self.shared_values as f64 / self.first_terms.max(1) as f64
+    }
+
+    /// Returns the share of the terms of the second sequence that the first one visits too.
+    pub fn second_overlap(&self) -> f64 {
+        self.shared_values as f64 / self.second_terms.max(1) as f64
+    }
+}
+
+/// Compares the terms of two sequences (see `PathCompression`).
+/// A trajectory never visits a value twice, so the values of a sequence are its terms.
+pub fn path_compression(first: &[u64], second: &[u64]) -> PathCompression {
+    let first_values: HashSet<u64> = first.iter().copied().collect();
+    let second_values: HashSet<u64> = second.iter().copied().collect();
+    let shared_values = first_values.intersection(&second_values).count();
+    PathCompression {
+        first_terms: first.len(),
+        second_terms: second.len(),
+        distinct_values: first_values.len() + second_values.len() - shared_values,
+        shared_values,
+    }
+}
+
+/// Returns the doublings 2n, 4n, 8n... of a starting value, the family of n: each of them reaches n
+/// by halving steps, then follows its trajectory. There are `count` of them, or fewer if they would overflow.
+pub fn doublings(value: u64, count: usize) -> Vec<u64> {
+    std::iter::successors(value.checked_mul(2), |member| member.checked_mul(2))
+        .take(count)
+        .collect()
+}
+
+/// The end shared by several sequences, e.g. the trajectory of n followed by all of its doublings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedTail {
+    pub terms: usize,        // Number of terms at the end of every sequence that are the same in all of them
+    pub offsets: Vec<usize>, // Steps taken by each sequence before the shared tail, in the order of the sequences
+}
+
+/// Finds the end shared by several sequences (see `SharedTail`). Empty sequences are left out.
+pub fn shared_tail(sequences: &[&[u64]]) -> SharedTail {
+    let sequences: Vec<&[u64]> = sequences.iter().copied().filter(|sequence| !sequence.is_empty()).collect();
+    let shortest = sequences.iter().map(|sequence| sequence.len()).min().unwrap_or(0);
+    // Counted from the end: the terms stay shared until a sequence differs from the first one.
+    let terms = (0..shortest)
+        .take_while(|&back| {
+            let term = |sequence: &[u64]| sequence[sequence.len() - 1 - back];
+            sequences.iter().all(|sequence| term(sequence) == term(sequences[0]))
+        })
+        .count();
+    SharedTail {
+        terms,
+        offsets: sequences.iter().map(|sequence| sequence.len() - terms).collect(),
+    }
+}
+
+/// Holds a short summary of the total stopping times over a range of starting values.
+#[derive(Debug, Clone)]
+pub struct RangeSummary {
+    pub range_start: u64,       // First starting value of the range (inclusive)
+    pub range_end: u64,         // Last starting value of the range (inclusive)
+    pub max_time: usize,        // Highest total stopping time found in the range
+    pub max_time_start: u64,    // Starting value reaching `max_time`
+    pub mean_time: f64,         // Average total stopping time over the range
+    pub skipped: usize,         // Starting values skipped because of a u64 overflow
+}
+
+/// Summarizes the stopping times computed by `stopping_times_in_range` over `range_start..=range_end`.
+pub fn summarize_stopping_times(range_start: u64, range_end: u64, times: &[(u64, usize)]) -> RangeSummary {
+    // Find the highest stopping time (and who reaches it) and the average.
+    let (max_time_start, max_time) = times.iter()
+        .max_by_key(|&&(_, t)| t)
+        .copied()
+        .unwrap_or((range_start, 0));
+    let mean_time = if times.is_empty() {
+        0.0
+    } else {
+        times.iter().map(|&(_, t)| t as f64).sum::<f64>() / times.len() as f64
+    };
+    // Every value of the range that is missing from `times` was skipped.
+    let range_len = (range_end - range_start).saturating_add(1);
+    let skipped = range_len.saturating_sub(times.len() as u64) as usize;
+
+    RangeSummary {
+        range_start,
+        range_end,
+        max_time,
+        max_time_start,
+        mean_time,
+        skipped,
+    }
+}
+
+/// Holds the counts of the total stopping times of a range, filled while the range is scanned,
+/// so that its charts never need every (starting value, stopping time) pair at once.
+///
+/// The range is split into columns of starting values, and each column counts its starting values
+/// per exact stopping time: the density chart rebins the stopping times once the longest one is known,
+/// and the histogram and the cumulative distribution sum the columns.
+#[derive(Debug, Clone)]
+pub struct StoppingTimeTally {
+    pub range_start: u64,           // First starting value of the range (inclusive)
+    pub range_end: u64,             // Last starting value of the range (inclusive)
+    columns: Vec<Vec<u64>>,         // `columns[x][t]`: starting values of column x with stopping time t
+    count: u64,                     // Starting values counted
+    total_time: u64,                // Sum of their stopping times
+    longest: Option<(usize, u64)>,  // Highest stopping time, and the largest starting value reaching it
+}
+
+impl StoppingTimeTally {
+    /// Creates an empty tally of `range_start..=range_end` (with `range_end >= range_start`).
+    ///
+    /// # Arguments
+    /// * `columns` - Number of columns along the starting values,
+    ///   clamped to the size of the range so that no column is left structurally empty.
+    pub fn new(range_start: u64, range_end: u64, columns: usize) -> Self {
+        let range_len = (range_end - range_start).saturating_add(1);
+        StoppingTimeTally {
+            range_start,
+            range_end,
+            columns: vec![Vec::new(); columns.clamp(1, range_len.min(usize::MAX as u64) as usize)],
+            count: 0,
+            total_time: 0,
+            longest: None,
+        }
+    }
+
+    /// Counts the stopping time `time` of the starting value `start`.
+    pub fn add(&mut self, start: u64, time: usize) {
+        let range_len = (self.range_end - self.range_start) as u128 + 1;
+        let x = ((start - self.range_start) as u128 * self.columns.len() as u128 / range_len) as usize;
+        let column = &mut self.columns[x];
+        if column.len() <= time {
+            column.resize(time + 1, 0);
+        }
+        column[time] += 1;
+        self.count += 1;
+        self.total_time += time as u64;
+        self.longest = self.longest.max(Some((time, start)));
+    }
+
+    /// Adds the counts of another tally of the same range and columns (see `tally_stopping_times`).
+    pub fn merge(mut self, other: StoppingTimeTally) -> Self {
+        for (column, other) in self.columns.iter_mut().zip(other.columns) {
+            if column.len() < other.len() {
+                column.resize(other.len(), 0);
+            }
+            column.iter_mut().zip(other).for_each(|(count, other)| *count += other);
+        }
+        self.count += other.count;
+        self.total_time += other.total_time;
+        self.longest = self.longest.max(other.longest);
+        self
+    }
+
+    /// Returns the number of starting values counted.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the number of starting values of the whole range per stopping time (index = stopping time).
+    pub fn time_counts(&self) -> Vec<u64> {
+        let mut counts = vec![0u64; self.columns.iter().map(Vec::len).max().unwrap_or(0)];
+        for column in &self.columns {
+            counts.iter_mut().zip(column).for_each(|(count, &c)| *count += c);
+        }
+        counts
+    }
+
+    /// Summarizes the counts, like `summarize_stopping_times` (the values not counted were skipped).
+    pub fn summary(&self) -> RangeSummary {
+        let (max_time, max_time_start) = self.longest.unwrap_or((0, self.range_start));
+        let range_len = (self.range_end - self.range_start).saturating_add(1);
+        RangeSummary {
+            range_start: self.range_start,
+            range_end: self.range_end,
+            max_time,
+            max_time_start,
+            mean_time: if self.count == 0 { 0.0 } else { self.total_time as f64 / self.count as f64 },
+            skipped: range_len.saturating_sub(self.count) as usize,
+        }
+    }
+}
+
+/// Computes the total stopping time of every starting value in `range_start..=range_end` and counts them.
+///
+/// The range is scanned in parallel: each thread fills its own tally, and the tallies are merged,
+/// so the memory used only depends on the number of columns and on the longest stopping time.
+///
+/// # Arguments
+/// * `columns` - Number of columns along the starting values (see `StoppingTimeTally::new`).
+///
+/// # Returns
+/// * `StoppingTimeTally` - The counts. Starting values whose computation would overflow (and 0) are left out.
+pub fn tally_stopping_times(range_start: u64, range_end: u64, columns: usize) -> StoppingTimeTally {
+    (range_start..=range_end)
+        .into_par_iter()
+        .fold(
+            || StoppingTimeTally::new(range_start, range_end, columns),
+            |mut tally, n| {
+                if let Some(t) = total_stopping_time(n) {
+                    tally.add(n, t);
+                }
+                tally
+            },
+        )
+        .reduce(|| StoppingTimeTally::new(range_start, range_end, columns), StoppingTimeTally::merge)
+}
+
+/// Holds a 2D histogram of (starting value, total stopping time) over a range of starting values.
+///
+/// The X axis (starting values) is split into `x_bins` columns and the Y axis
+/// (total stopping times, from 0 to `summary.max_time`) into `y_bins` rows.
+/// `counts[x * y_bins + y]` holds the number of starting values falling into cell `(x, y)`.
+#[derive(Debug, Clone)]
+pub struct StoppingTimeDensity {
+    pub summary: RangeSummary,  // Summary of the range
+    pub x_bins: usize,          // Number of columns (starting value bins)
+    pub y_bins: usize,          // Number of rows (stopping time bins)
+    pub counts: Vec<u32>,       // Cell counts, column by column
+    pub max_count: u32,         // Highest cell count (used to scale colors)
+}
+
+impl StoppingTimeDensity {
+    /// Returns the number of starting values that fell into cell `(x, y)`.
+    pub fn count(&self, x: usize, y: usize) -> u32 {
+        self.counts[x * self.y_bins + y]
+    }
+}
+
+/// Bins the total stopping times counted by a tally: its columns are the bins along the starting values.
+///
+/// # Arguments
+/// * `tally` - The counts of the range, filled while it was scanned (by any backend).
+/// * `y_bins` - Number of bins along the stopping time axis.
+///
+/// # Returns
+/// * `StoppingTimeDensity` - The binned counts and a short summary of the range.
+///   Bins are clamped to the number of stopping times so that no row is left structurally empty.
+pub fn stopping_time_density(tally: &StoppingTimeTally, y_bins: usize) -> StoppingTimeDensity {
+    let summary = tally.summary();
+    let max_time = summary.max_time;
+
+    // Stopping times up to 50 cannot fill 100 rows: clamp the bin count.
+    let x_bins = tally.columns.len();
+    let y_bins = y_bins.clamp(1, max_time + 1);
+
+    // Drop the counts of every stopping time into its cell.
+    let mut counts = vec![0u32; x_bins * y_bins];
+    for (x, column) in tally.columns.iter().enumerate() {
+        for (t, &c) in column.iter().enumerate() {
+            counts[x * y_bins + t * y_bins / (max_time + 1)] += c as u32;
+        }
+    }
+    let max_count = counts.iter().copied().max().unwrap_or(0);
+
+    StoppingTimeDensity {
+        summary,
+        x_bins,
+        y_bins,
+        counts,
+        max_count,
+    }
+}
+
+/// Holds the distribution of the total stopping times over a range of starting values,
+/// in bins of equal width along the stopping time axis.
+/// `counts[i]` holds the number of starting values whose total stopping time lies in
+/// `min_time + i * bin_width .. min_time + (i + 1) * bin_width`.
+#[derive(Debug, Clone)]
+pub struct StoppingTimeHistogram {
+    pub summary: RangeSummary, // Summary of the range
+    pub min_time: usize,       // Lowest total stopping time found (start of the first bin)
+    pub bin_width: usize,      // Number of stopping times per bin
+    pub counts: Vec<u64>,      // Number of starting values per bin
+}
+
+/// Bins the total stopping times counted by a tally into at most `bins` bins.
+///
+/// # Arguments
+/// * `tally` - The counts of the range, as returned by `tally_stopping_times` (its columns do not matter).
+/// * `bins` - Largest number of bins; the bins are as narrow as possible (at least one stopping time each).
+pub fn stopping_time_histogram(tally: &StoppingTimeTally, bins: usize) -> StoppingTimeHistogram {
+    let summary = tally.summary();
+    let time_counts = tally.time_counts();
+    let min_time = time_counts.iter().position(|&c| c > 0).unwrap_or(0);
+    let spread = summary.max_time.saturating_sub(min_time) + 1;
+    let bin_width = spread.div_ceil(bins.max(1));
+
+    let mut counts = vec![0u64; spread.div_ceil(bin_width)];
+    for (t, &c) in time_counts.iter().enumerate().skip(min_time) {
+        counts[(t - min_time) / bin_width] += c;
+    }
+
+    StoppingTimeHistogram {
+        summary,
+        min_time,
+        bin_width,
+        counts,
+    }
+}
+
+/// Computes the empirical cumulative distribution of total stopping times.
+///
+/// # Arguments
+/// * `time_counts` - Number of starting values per stopping time (index = stopping time),
+///   as returned by `StoppingTimeTally::time_counts`.
+///
+/// # Returns
+/// * `Vec<(usize, f64)>` - Every stopping time found, in increasing order, with the fraction of the
+///   starting values whose stopping time is lower or equal (the last fraction is 1).
+pub fn stopping_time_cdf(time_counts: &[u64]) -> Vec<(usize, f64)> {
+    let total = time_counts.iter().sum::<u64>() as f64;
+    let mut below = 0;
+    let mut cdf: Vec<(usize, f64)> = Vec::new();
+    for (t, &count) in time_counts.iter().enumerate().filter(|&(_, &count)| count > 0) {
+        below += count;
+        cdf.push((t, below as f64 / total));
+    }
+    cdf
+}
+
+/// The Collatz-like maps that can be compared with each other.
+///
+/// Every map divides even numbers by 2; they only differ in the rule applied to odd numbers.
+/// They work on signed integers (`i64`) so that negative starting values can be explored too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollatzMap {
+    Classic,        // Odd n -> 3n + 1 (the classic Collatz map)
+    ThreeNMinusOne, // Odd n -> 3n - 1
+    FiveNPlusOne,   // Odd n -> 5n + 1
+}
+
+impl CollatzMap {
+    /// Every supported map, in display order.
+    pub const ALL: [CollatzMap; 3] = [
+        CollatzMap::Classic,
+        CollatzMap::ThreeNMinusOne,
+        CollatzMap::FiveNPlusOne,
+    ];
+
+    /// Returns the short mathematical name of the map (e.g. "3n+1").
+    pub fn name(self) -> &'static str {
+        match self {
+            CollatzMap::Classic => "3n+1",
+            CollatzMap::ThreeNMinusOne => "3n-1",
+            CollatzMap::FiveNPlusOne => "5n+1",
+        }
+    }
+
+    /// Applies the map once to `n`.
+    ///
+    /// # Returns
+    /// * `Some(next)` - The next term of the trajectory.
+    /// * `None` - If the computation would overflow an `i64`.
+    pub fn step(self, n: i64) -> Option<i64> {
+        if n % 2 == 0 {
+            return Some(n / 2);
+        }
+        // `checked_*` operations return None instead of silently wrapping around.
+        match self {
+            CollatzMap::Classic => n.checked_mul(3)?.checked_add(1),
+            CollatzMap::ThreeNMinusOne => n.checked_mul(3)?.checked_sub(1),
+            CollatzMap::FiveNPlusOne => n.checked_mul(5)?.checked_add(1),
+        }
+    }
+}
+
+/// Explains why the generation of a variant trajectory stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    ReachedOne, // The trajectory reached 1 (or the fixed point 0).
+    Cycle,      // The trajectory came back to a value it had already visited.
+    StepLimit,  // The maximum number of steps was reached.
+    Overflow,   // The next term does not fit in an `i64`.
+    Divergent,  // The terms stayed above the bound of the `DivergenceRule` for too many steps.
+}
+
+impl Termination {
+    /// Returns a short human-readable description, used in the statistics panel.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Termination::ReachedOne => "reached 1",
+            Termination::Cycle => "entered a cycle",
+            Termination::StepLimit => "truncated at the step limit",
+            Termination::Overflow => "overflow",
+            Termination::Divergent => "apparently divergent",
+        }
+    }
+}
+
+/// Tells when a variant trajectory is considered divergent: its terms stay above `bound`
+/// (in absolute value) for `steps` consecutive steps. This is only a heuristic (nothing is proven),
+/// but it stops runaway trajectories such as those of 5n+1 long before they overflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DivergenceRule {
+    pub bound: u64,   // Absolute value above which a term counts as runaway growth
+    pub steps: usize, // Number of consecutive runaway terms that make the trajectory divergent
+}
+
+impl Default for DivergenceRule {
+    fn default() -> Self {
+        DivergenceRule { bound: 1_000_000_000_000, steps: 20 }
+    }
+}
+
+/// Describes the cycle a trajectory ended up in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleInfo {
+    pub entry_index: usize, // Index (step) of the first term belonging to the cycle
+    pub length: usize,      // Number of distinct values in the cycle
+    pub members: Vec<i64>,  // Values of the cycle, starting at the entry point
+}
+
+impl CycleInfo {
+    /// Returns the value through which the trajectory enters the cycle.
+    pub fn entry_value(&self) -> i64 {
+        self.members[0]
+    }
+}
+
+/// A cycle of one of the `CollatzMap` variants known from the literature.
+#[derive(Debug, PartialEq, Eq)]
+pub struct KnownCycle {
+    pub name: &'static str,       // Short name, after the member with the smallest absolute value
+    pub map: CollatzMap,          // Map under which the values cycle
+    pub members: &'static [i64],  // Values of the cycle, in trajectory order
+}
+
+/// The known non-trivial cycles of the supported maps (the 4-2-1 cycle of 3n+1 is left out,
+/// since trajectories stop as soon as they reach 1).
+///
+/// 3n+1 on negative integers behaves like 3n-1 on positive integers (up to the sign),
+/// so both maps share the same cycle shapes.
+pub const KNOWN_CYCLES: &[KnownCycle] = &[
+    KnownCycle { name: "cycle of -1", map: CollatzMap::Classic, members: &[-1, -2] },
+    KnownCycle { name: "cycle of -5", map: CollatzMap::Classic, members: &[-5, -14, -7, -20, -10] },
+    KnownCycle {
+        name: "cycle of -17",
+        map: CollatzMap::Classic,
+        members: &[
+            -17, -50, -25, -74, -37, -110, -55, -164, -82,
+            -41, -122, -61, -182, -91, -272, -136, -68, -34,
+        ],
+    },
+    KnownCycle { name: "cycle of -1", map: CollatzMap::ThreeNMinusOne, members: &[-1, -4, -2] },
+    KnownCycle { name: "cycle of 5", map: CollatzMap::ThreeNMinusOne, members: &[5, 14, 7, 20, 10] },
+    KnownCycle {
+        name: "cycle of 17",
+        map: CollatzMap::ThreeNMinusOne,
+        members: &[17, 50, 25, 74, 37, 110, 55, 164, 82, 41, 122, 61, 182, 91, 272, 136, 68, 34],
+    },
+    KnownCycle { name: "cycle of -1", map: CollatzMap::FiveNPlusOne, members: &[-1, -4, -2] },
+    KnownCycle {
+        name: "cycle of 13",
+        map: CollatzMap::FiveNPlusOne,
+        members: &[13, 66, 33, 166, 83, 416, 208, 104, 52, 26],
+    },
+    KnownCycle {
+        name: "cycle of 17",
+        map: CollatzMap::FiveNPlusOne,
+        members: &[17, 86, 43, 216, 108, 54, 27, 136, 68, 34],
+    },
+];
+
+/// Looks up a detected cycle in `KNOWN_CYCLES`.
+///
+/// A cycle can be entered through any of its members, so the comparison
+/// ignores where the detected cycle starts.
+///
+/// # Returns
+/// * `Some(known)` - The known cycle of `map` having the same members.
+/// * `None` - If the cycle is not part of the catalog.
+pub fn identify_cycle(map: CollatzMap, cycle: &CycleInfo) -> Option<&'static KnownCycle> {
+    KNOWN_CYCLES.iter().find(|known| {
+        known.map == map
+            && known.members.len() == cycle.members.len()
+            && cycle.members.iter().all(|value| known.members.contains(value))
+    })
+}
+
+/// Holds the trajectory of a starting value under one of the `CollatzMap` variants.
+#[derive(Debug, Clone)]
+pub struct VariantTrajectory {
+    pub map: CollatzMap,           // Map used to generate the trajectory
+    pub sequence: Vec<i64>,        // Terms of the trajectory, starting value included
+    pub termination: Termination,  // Why the generation stopped
+    pub cycle: Option<CycleInfo>,  // The cycle reached, if `termination` is `Cycle`
+}
+
+/// Computes the trajectory of `start` under `map`.
+///
+/// Unlike the classic 3n+1 map, the variants do not always reach 1: 3n-1 has several cycles
+/// and 5n+1 trajectories may grow without bound. The generation therefore stops when:
+/// - the value 1 (or 0) is reached,
+/// - the trajectory entered a cycle (the sequence then holds the path to the cycle
+///   followed by exactly one turn of the cycle),
+/// - `max_steps` steps were performed,
+/// - the terms stayed above the bound of `divergence` for its number of steps (apparent divergence),
+/// - the next term would overflow an `i64`.
+///
+/// Cycles are detected with Brent's algorithm: a "tortoise" index stays put while the newest
+/// term (the "hare") moves forward, and is teleported to the hare every power of two steps.
+/// The hare meets the tortoise exactly `length` steps after the last teleport once both are
+/// inside the cycle, without having to remember every visited value.
+///
+/// # Arguments
+/// * `map` - The map to apply.
+/// * `start` - The starting value (may be negative).
+/// * `max_steps` - The maximum number of steps to perform.
+/// * `divergence` - When to give up on a trajectory growing without bound.
+pub fn generate_variant_sequence(
+    map: CollatzMap,
+    start: i64,
+    max_steps: usize,
+    divergence: DivergenceRule,
+) -> VariantTrajectory {
+    let mut sequence = vec![start];
+    let mut current = start;
+    let mut runaway = 0; // Number of consecutive terms above the divergence bound
+
+    // Brent's algorithm state.
+    let mut tortoise = 0; // Index of the tortoise in `sequence`
+    let mut power = 1;    // Current power of two (distance before the next teleport)
+    let mut lam = 0;      // Distance between the tortoise and the hare
+
+    let termination = loop {
+        if current == 1 || current == 0 {
+            break Termination::ReachedOne;
+        }
+        if sequence.len() > max_steps {
+            break Termination::StepLimit;
+        }
+        current = match map.step(current) {
+            Some(next) => next,
+            None => break Termination::Overflow,
+        };
+        sequence.push(current);
+
+        runaway = if current.unsigned_abs() > divergence.bound { runaway + 1 } else { 0 };
+        if runaway >= divergence.steps {
+            break Termination::Divergent;
+        }
+
+        lam += 1;
+        if current == sequence[tortoise] {
+            // The hare caught up with the tortoise: the cycle is `lam` values long.
+            break Termination::Cycle;
+        }
+        if lam == power {
+            tortoise = sequence.len() - 1;
+            power *= 2;
+            lam = 0;
+        }
+    };
+
+    let cycle = if termination == Termination::Cycle {
+        // The entry point is the first index `mu` such that sequence[mu] == sequence[mu + lam].
+        let mu = (0..sequence.len() - lam)
+            .find(|&i| sequence[i] == sequence[i + lam])
+            .unwrap_or(0);
+        // Keep the path to the cycle and a single turn of it.
+        sequence.truncate(mu + lam);
+        Some(CycleInfo {
+            entry_index: mu,
+            length: lam,
+            members: sequence[mu..].to_vec(),
+        })
+    } else {
+        None
+    };
+
+    VariantTrajectory { map, sequence, termination, cycle }
+}
+
+// Test module: Contains unit tests for the functions in this file.
+// This code only runs when you execute `cargo test`.
+#[cfg(test)]
+mod tests {
+    // Import everything from the parent module (the code above).
+    use super::*;
+
+    // Test function for `generate_sequence`.
+    #[test]
+    fn test_generate_sequence() {
+        // Test case 1: Starting with n = 6.
+        let sequence = generate_sequence(6);
+        // Assert that the generated sequence matches the expected sequence.
+        assert_eq!(sequence, vec![6, 3, 10, 5, 16, 8, 4, 2, 1]);
+        
+        // Test case 2: Starting with n = 1 (base case).
+        let sequence = generate_sequence(1);
+        assert_eq!(sequence, vec![1]);
+    }
+
+    // Test function for `generate_sequence_limited`.
+    #[test]
+    fn test_generate_sequence_limited() {
+        // 27 needs 111 steps: cut after 10 of them.
+        let sequence = generate_sequence_limited(27, 10);
+        assert_eq!(sequence.len(), 11);
+        assert_eq!(sequence[..], generate_sequence(27)[..11]);
+        assert!(is_truncated(&sequence, 10));
+
+        // A limit that is not reached changes nothing.
+        let sequence = generate_sequence_limited(6, 8);
+        assert_eq!(sequence, vec![6, 3, 10, 5, 16, 8, 4, 2, 1]);
+        assert!(!is_truncated(&sequence, 8));
+        assert!(!is_truncated(&generate_sequence(27), usize::MAX));
+    }
+
+    // Test function for `trajectory`.
+    #[test]
+    fn test_trajectory() {
+        for (start, max_steps) in [(27, 10), (27, DEFAULT_STEP_LIMIT), (6, 8), (1, 5), (0, 5), (u64::MAX, 20)] {
+            assert_eq!(trajectory(start, max_steps).collect::<Vec<_>>(), generate_sequence_limited(start, max_steps));
+        }
+    }
+
+    // Test function for `generate_glide_limited` and `is_glide_truncated`.
+    #[test]
+    fn test_generate_glide_limited() {
+        // 7 -> 22 -> 11 -> 34 -> 17 -> 52 -> 26 -> 13 -> 40 -> 20 -> 10 -> 5: below 7 at step 11.
+        let glide = generate_glide_limited(7, DEFAULT_STEP_LIMIT);
+        assert_eq!(glide, generate_sequence(7)[..12]);
+        assert!(!is_glide_truncated(&glide, DEFAULT_STEP_LIMIT));
+        assert_eq!(generate_glide_limited(6, 10), vec![6, 3]);
+        assert_eq!(generate_glide_limited(1, 10), vec![1]);
+
+        // Cut at the step limit before dropping below the start.
+        let cut = generate_glide_limited(27, 10);
+        assert_eq!(cut.len(), 11);
+        assert!(is_glide_truncated(&cut, 10));
+    }
+
+    // Test function for `glide` and `glides_in_range`.
+    #[test]
+    fn test_glide() {
+        assert_eq!(glide(7), Some(11));
+        assert_eq!(glide(27), Some(96));
+        assert_eq!((glide(1), glide(2), glide(0)), (Some(0), Some(1), None));
+        // The glide is the stopping time of the statistics.
+        for start in 1..500 {
+            assert_eq!(glide(start), Some(calculate_stats(&generate_sequence(start)).stopping_time));
+        }
+        assert_eq!(glides_in_range(0, 4), vec![(1, 0), (2, 1), (3, 6), (4, 1)]);
+    }
+
+    // Test function for `TailHandling::apply`.
+    #[test]
+    fn test_tail_handling() {
+        let tail = |handling: TailHandling, start: u64, cycles: usize| {
+            let mut sequence = generate_sequence(start);
+            handling.apply(&mut sequence, cycles);
+            sequence
+        };
+        assert_eq!(tail(TailHandling::StopAtOne, 6, 2), vec![6, 3, 10, 5, 16, 8, 4, 2, 1]);
+        assert_eq!(tail(TailHandling::RepeatCycle, 2, 2), vec![2, 1, 4, 2, 1, 4, 2, 1]);
+        assert_eq!(tail(TailHandling::TrimDescent, 6, 0), vec![6, 3, 10, 5, 16]);
+        assert_eq!(tail(TailHandling::TrimDescent, 8, 0), vec![8]);
+
+        // A sequence cut before 1 is left as it is.
+        let mut sequence = generate_sequence_limited(27, 10);
+        TailHandling::RepeatCycle.apply(&mut sequence, 3);
+        assert_eq!(sequence.len(), 11);
+    }
+
+    // Test function for `predecessors`.
+    #[test]
+    fn test_predecessors() {
+        assert_eq!(predecessors(16), vec![32, 5]);
+        assert_eq!(predecessors(10), vec![20, 3]);
+        assert_eq!(predecessors(8), vec![16]);
+        // 1 -> 4 is not a step of a trajectory, which stops at 1.
+        assert_eq!(predecessors(4), vec![8]);
+        assert_eq!(predecessors(u64::MAX), Vec::<u64>::new());
+        // Every predecessor steps back to the value.
+        for value in 1..200 {
+            for predecessor in predecessors(value) {
+                assert_eq!(generate_sequence(predecessor)[1], value);
+            }
+        }
+    }
+
+    // Test function for `check_sequence`.
+    #[test]
+    fn test_check_sequence() {
+        assert_eq!(check_sequence(&generate_sequence(27)), Ok(()));
+        assert_eq!(check_sequence(&[7]), Ok(()));
+        assert_eq!(check_sequence(&[]), Ok(()));
+
+        // 6 -> 3 -> 10, not 11.
+        assert_eq!(
+            check_sequence(&[6, 3, 11, 34]),
+            Err(Mismatch { index: 2, expected: Some(10), found: 11 })
+        );
+
+        // 3n+1 beyond 64 bits: no term can follow.
+        let big = u64::MAX - 2; // Odd
+        assert_eq!(check_sequence(&[big, 1]), Err(Mismatch { index: 1, expected: None, found: 1 }));
+    }
+
+    // Test function for `annotate_steps`.
+    #[test]
+    fn test_annotate_steps() {
+        let steps = annotate_steps(&generate_sequence(6));
+        assert_eq!(steps.len(), 8);
+        assert_eq!(steps[0], AnnotatedStep { index: 0, value: 6, rule: StepRule::Halve, next: 3 });
+        assert_eq!(steps[1].rule, StepRule::TriplePlusOne);
+        assert_eq!(steps[1].describe(), "3 is odd → 3×3+1 = 10");
+        assert_eq!(steps[7].describe(), "2 is even → 2/2 = 1");
+        assert!(annotate_steps(&[1]).is_empty());
+    }
+
+    // Test function for `calculate_stats`.
+    #[test]
+    fn test_calculate_stats() {
+        // Test case 1: Using the sequence for n = 6.
+        let sequence = generate_sequence(6); // vec![6, 3, 10, 5, 16, 8, 4, 2, 1]
+        let stats = calculate_stats(&sequence);
+        
+        // Assert that each statistic matches the expected value.
+        assert_eq!(stats.length, 9);
+        assert_eq!(stats.max_value, 16);
+        assert_eq!(stats.max_value_index, 4);
+        assert_eq!(stats.even_count, 6);
+        assert_eq!(stats.odd_count, 3);
+        assert_eq!(stats.stopping_time, 1);
+        // Bit lengths 3, 2, 4, 3, 5, 4, 3, 2, 1.
+        assert_eq!(stats.max_bit_length, 5);
+        assert_eq!(stats.max_bit_length_index, 4);
+        assert_eq!(stats.average_bit_length, 3.0);
+
+        // 7 -> 22 -> 11 -> 34 -> 17 -> 52 -> ...: 34 already has the 6 bits of the maximum, 52.
+        let stats = calculate_stats(&generate_sequence(7));
+        assert_eq!((stats.max_value_index, stats.max_bit_length_index), (5, 3));
+    }
+
+    // Test function for `total_stopping_time`.
+    #[test]
+    fn test_total_stopping_time() {
+        // 6 -> 3 -> 10 -> 5 -> 16 -> 8 -> 4 -> 2 -> 1: 8 steps.
+        assert_eq!(total_stopping_time(6), Some(8));
+        assert_eq!(total_stopping_time(27), Some(111));
+        assert_eq!(total_stopping_time(1), Some(0));
+        assert_eq!(total_stopping_time(0), None);
+
+        // The batched stepping gives the same counts as the one-step-at-a-time sequence.
+        for n in 1..10_000 {
+            assert_eq!(total_stopping_time(n), Some(generate_sequence(n).len() - 1));
+        }
+        // Overflow is detected at the same point as in `generate_sequence`.
+        // (the smallest odd value whose 3n + 1 term does not fit in a u64).
+        assert_eq!(total_stopping_time((u64::MAX - 1) / 3 + 1), None);
+    }
+
+    // Test function for `total_stopping_time_wide`.
+    #[test]
+    fn test_total_stopping_time_wide() {
+        // Both versions agree whenever the narrow one succeeds.
+        for n in 1..1000 {
+            assert_eq!(total_stopping_time_wide(n), total_stopping_time(n));
+        }
+        // Near u64::MAX the narrow version gives up, the wide one does not.
+        assert_eq!(total_stopping_time(u64::MAX), None);
+        assert!(total_stopping_time_wide(u64::MAX).is_some());
+    }
+
+    // Test function for `total_stopping_time_auto`.
+    #[test]
+    fn test_total_stopping_time_auto() {
+        // Small values stay on 64 bits.
+        let auto = total_stopping_time_auto(27);
+        assert_eq!(
+            auto,
+            AutoStoppingTime { steps: Some(111), arithmetic: Arithmetic::U64, promoted_at: None, big_at: None }
+        );
+        assert_eq!(total_stopping_time_auto(0).steps, None);
+
+        // Near u64::MAX the computation is promoted, and agrees with the 128-bit version.
+        let auto = total_stopping_time_auto(u64::MAX);
+        assert_eq!(auto.steps, total_stopping_time_wide(u64::MAX));
+        assert_eq!((auto.arithmetic, auto.promoted_at), (Arithmetic::U128, Some(0)));
+        // 2^64 - 2 is halved once on 64 bits, then 2^63 - 1 needs 128 bits.
+        let auto = total_stopping_time_auto(u64::MAX - 1);
+        assert_eq!(auto.steps, total_stopping_time_wide(u64::MAX - 1));
+        assert_eq!(auto.promoted_at, Some(1));
+    }
+
+    // Test function for `big_stopping_time`.
+    #[test]
+    fn test_big_stopping_time() {
+        // Arbitrary precision agrees with the narrower arithmetics.
+        assert_eq!(big_stopping_time(BigUint::from(27u64), 0), Some(111));
+        assert_eq!(big_stopping_time(BigUint::from(u64::MAX), 0), total_stopping_time_wide(u64::MAX));
+        // 3n + 1 of 2^128 - 1 no longer fits in 128 bits: the term goes on with arbitrary precision.
+        let mut term = WideTerm::U128(u128::MAX);
+        term.step();
+        assert!(matches!(&term, WideTerm::Big(n) if n.bits() == 130));
+    }
+
+    // Test function for `wide_trajectory`.
+    #[test]
+    fn test_wide_trajectory() {
+        let sequence = generate_sequence(27);
+        let stats = calculate_stats(&sequence);
+        let wide = wide_trajectory(27, 10_000);
+        assert_eq!((wide.steps, wide.reached_one, wide.peak_step, wide.odd_count), (111, true, stats.max_value_index, stats.odd_count));
+        assert_eq!(wide.peak, BigUint::from(stats.max_value));
+
+        // The u64 sequence of 2^64 - 1 stops at once; its whole trajectory goes on to 1 beyond 64 bits.
+        let wide = wide_trajectory(u64::MAX, 10_000);
+        assert_eq!(Some(wide.steps), total_stopping_time_auto(u64::MAX).steps);
+        assert!(wide.reached_one && wide.max_bits > 64);
+        // The step limit stops it.
+        let wide = wide_trajectory(u64::MAX, 5);
+        assert_eq!((wide.steps, wide.reached_one), (5, false));
+    }
+
+    // Test function for `wide_bit_lengths`.
+    #[test]
+    fn test_wide_bit_lengths() {
+        let bits = wide_bit_lengths(27, 10_000);
+        let expected: Vec<u64> = generate_sequence(27).into_iter().map(crate::transform::bit_length).collect();
+        assert_eq!(bits, expected);
+
+        // The u64 sequence of 2^64 - 1 stops at once: its bit lengths follow the whole trajectory, beyond 64 bits.
+        let bits = wide_bit_lengths(u64::MAX, 10_000);
+        let wide = wide_trajectory(u64::MAX, 10_000);
+        assert_eq!(bits.len(), wide.steps + 1);
+        assert_eq!((bits[0], bits[1], bits[bits.len() - 1]), (64, 66, 1));
+        assert_eq!(bits.iter().max(), Some(&wide.max_bits));
+        // The step limit stops it.
+        assert_eq!(wide_bit_lengths(u64::MAX, 5).len(), 6);
+    }
+
+    // Test function for `stopping_time_density`.
+    #[test]
+    fn test_stopping_time_density() {
+        let mut tally = StoppingTimeTally::new(1, 100, 10);
+        stopping_times_in_range(1, 100).into_iter().for_each(|(n, t)| tally.add(n, t));
+        let density = stopping_time_density(&tally, 20);
+
+        // Every starting value of the range lands in exactly one cell.
+        assert_eq!(density.counts.iter().map(|&c| c as usize).sum::<usize>(), 100);
+        assert_eq!(density.summary.skipped, 0);
+        // 97 has the longest trajectory below 100 (118 steps).
+        assert_eq!(density.summary.max_time, 118);
+        assert_eq!(density.summary.max_time_start, 97);
+
+        // Bins are clamped to the size of the range.
+        let small = stopping_time_density(&StoppingTimeTally::new(5, 7, 200), 200);
+        assert_eq!(small.x_bins, 3);
+        assert_eq!((small.summary.skipped, small.max_count), (3, 0));
+    }
+
+    // Test function for `tally_stopping_times` and `StoppingTimeTally`.
+    #[test]
+    fn test_tally_stopping_times() {
+        let times = stopping_times_in_range(1, 1000);
+        let tally = tally_stopping_times(1, 1000, 7);
+        assert_eq!(tally.count(), 1000);
+        // The parallel counts are those of the plain scan, whichever order the threads went in.
+        let mut counts = vec![0u64; 179];
+        for &(_, t) in &times {
+            counts[t] += 1;
+        }
+        assert_eq!(tally.time_counts(), counts);
+        let summary = summarize_stopping_times(1, 1000, &times);
+        let tallied = tally.summary();
+        assert_eq!((tallied.max_time, tallied.max_time_start, tallied.skipped), (summary.max_time, summary.max_time_start, 0));
+        assert!((tallied.mean_time - summary.mean_time).abs() < 1e-9);
+
+        // Values added one by one land in the same columns.
+        let mut added = StoppingTimeTally::new(1, 1000, 7);
+        for &(n, t) in &times {
+            added.add(n, t);
+        }
+        assert_eq!(added.columns, tally.columns);
+        assert_eq!(StoppingTimeTally::new(1, 10, 4).summary().skipped, 10);
+    }
+
+    // Test function for `stopping_time_histogram`.
+    #[test]
+    fn test_stopping_time_histogram() {
+        // From 0 (for 1) to 118 steps (for 97): 119 stopping times in bins of 12.
+        let histogram = stopping_time_histogram(&tally_stopping_times(1, 100, 1), 10);
+        assert_eq!((histogram.min_time, histogram.bin_width, histogram.counts.len()), (0, 12, 10));
+        assert_eq!(histogram.counts.iter().sum::<u64>(), 100);
+        // 1, 2 and 4 to 8 and 16 reach 1 in fewer than 12 steps, among others.
+        assert_eq!(histogram.counts[0], stopping_times_in_range(1, 100).iter().filter(|&&(_, t)| t < 12).count() as u64);
+
+        // Bins are never narrower than one stopping time.
+        let narrow = stopping_time_histogram(&tally_stopping_times(1, 100, 1), 1000);
+        assert_eq!((narrow.bin_width, narrow.counts.len()), (1, 119));
+        // The bins start at the shortest stopping time found.
+        let late = stopping_time_histogram(&tally_stopping_times(12, 13, 1), 10);
+        assert_eq!((late.min_time, late.counts.clone()), (9, vec![2]));
+    }
+
+    // Test function for `peak_and_stopping_time` and `peaks_in_range`.
+    #[test]
+    fn test_peak() {
+        assert_eq!(peak_and_stopping_time(27), Some((9232, 111)));
+        assert_eq!(peak_and_stopping_time(1), Some((1, 0)));
+        assert_eq!(peak_and_stopping_time(8), Some((8, 3))); // Powers of 2 only go down.
+        assert_eq!(peak_and_stopping_time(0), None);
+        let (peaks, summary) = peaks_in_range(1, 200);
+        for (n, p) in peaks {
+            assert_eq!(p, calculate_stats(&generate_sequence(n)).max_value);
+        }
+        // The stopping times are those of the plain scan.
+        let times = summarize_stopping_times(1, 200, &stopping_times_in_range(1, 200));
+        assert_eq!((summary.max_time, summary.max_time_start, summary.skipped), (times.max_time, times.max_time_start, 0));
+    }
+
+    // Test function for `passes_through` and `hub_values`.
+    #[test]
+    fn test_passes_through() {
+        // Counting directly: the trajectories of 1..=100 containing each term of the trajectory of 10.
+        let counts = passes_through(10, 1, 100);
+        assert_eq!(counts.iter().map(|&(term, _)| term).collect::<Vec<_>>(), vec![10, 5, 16, 8, 4, 2, 1]);
+        for &(term, count) in &counts {
+            let expected = (1..=100).filter(|&n| generate_sequence(n).contains(&term)).count() as u64;
+            assert_eq!(count, expected, "through {}", term);
+        }
+        // Every trajectory reaches 1.
+        assert_eq!(counts.last(), Some(&(1, 100)));
+
+        // The hubs are the terms where the counts jump the most.
+        let hubs = hub_values(&counts, 2);
+        assert_eq!(hubs.len(), 2);
+        assert!(hubs[0].1 >= hubs[1].1);
+        let jump = |term: u64| {
+            let i = counts.iter().position(|&(t, _)| t == term).unwrap();
+            counts[i].1 - counts[i - 1].1
+        };
+        assert_eq!(hubs[0].1, jump(hubs[0].0));
+        assert!(passes_through(0, 1, 100).is_empty());
+    }
+
+    // Test function for `most_visited`.
+    #[test]
+    fn test_most_visited() {
+        let ranking = most_visited(1, 100, 5);
+        assert_eq!(ranking.len(), 5);
+        // 5 -> 16 is the highway of most trajectories (those not ending with 64 -> 32 -> 16 or 256 -> ...).
+        assert_eq!(ranking[0].0, 5);
+        for &(term, count) in &ranking {
+            assert!(!term.is_power_of_two());
+            let expected = (1..=100).filter(|&n| generate_sequence(n).contains(&term)).count() as u64;
+            assert_eq!(count, expected, "visits of {}", term);
+        }
+        assert!(ranking.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    }
+
+    // Test function for `merged_graph`.
+    #[test]
+    fn test_merged_graph() {
+        // 6 -> 3 -> 10 -> 5 -> 16 -> 8 -> 4 -> 2 -> 1 and 20 -> 10: both trajectories merge at 10.
+        let graph = merged_graph(&[6, 20], DEFAULT_STEP_LIMIT);
+        assert_eq!(graph.nodes, vec![1, 2, 3, 4, 5, 6, 8, 10, 16, 20]);
+        assert_eq!(graph.edge_count(), 9);
+        assert_eq!(graph.successors.get(&20), Some(&10));
+        assert_eq!(graph.merge_nodes(), vec![(10, 2)]);
+
+        // A start inside another trajectory adds no merge node.
+        assert!(merged_graph(&[6, 10], DEFAULT_STEP_LIMIT).merge_nodes().is_empty());
+    }
+
+    // Test function for `doublings`.
+    #[test]
+    fn test_doublings() {
+        assert_eq!(doublings(27, 3), vec![54, 108, 216]);
+        assert_eq!(doublings(27, 0), Vec::<u64>::new());
+        // The doublings stop before overflowing.
+        assert_eq!(doublings(u64::MAX / 2, 5), vec![u64::MAX - 1]);
+    }
+
+    // Test function for `shared_tail`.
+    #[test]
+    fn test_shared_tail() {
+        // The doublings of 27 are 27 preceded by one halving step per doubling.
+        let family: Vec<Vec<u64>> = [27, 54, 108, 216].iter().map(|&value| generate_sequence(value)).collect();
+        let sequences: Vec<&[u64]> = family.iter().map(Vec::as_slice).collect();
+        let tail = shared_tail(&sequences);
+        assert_eq!(tail.terms, 112);
+        assert_eq!(tail.offsets, vec![0, 1, 2, 3]);
+
+        // 6 -> 3 -> 10 -> ... and 20 -> 10 -> ...: the tail starts at 10.
+        let tail = shared_tail(&[&generate_sequence(6), &[], &generate_sequence(20)]);
+        assert_eq!((tail.terms, tail.offsets), (7, vec![2, 1]));
+        assert_eq!(shared_tail(&[]).terms, 0);
+    }
+
+    // Test function for `path_compression`.
+    #[test]
+    fn test_path_compression() {
+        // 6 -> 3 -> 10 -> 5 -> 16 -> 8 -> 4 -> 2 -> 1 and 20 -> 10 -> ...: 7 terms shared.
+        let compression = path_compression(&generate_sequence(6), &generate_sequence(20));
+        assert_eq!(compression.shared_values, 7);
+        assert_eq!(compression.distinct_values, 10);
+        assert!((compression.compression() - (1.0 - 10.0 / 17.0)).abs() < 1e-12);
+        assert!((compression.first_overlap() - 7.0 / 9.0).abs() < 1e-12);
+        assert_eq!(compression.second_overlap(), 7.0 / 8.0);
+        // A sequence compared with itself is entirely shared.
+        let same = path_compression(&generate_sequence(27), &generate_sequence(27));
+        assert_eq!((same.first_overlap(), same.compression()), (1.0, 0.5));
+    }
+
+    // Test function for `stopping_time_cdf`.
+    #[test]
+    fn test_stopping_time_cdf() {
+        // 1 to 4 reach 1 in 0, 1, 7 and 2 steps.
+        let cdf = stopping_time_cdf(&tally_stopping_times(1, 4, 1).time_counts());
+        assert_eq!(cdf, vec![(0, 0.25), (1, 0.5), (2, 0.75), (7, 1.0)]);
+        // Equal stopping times make a single step of the curve: 12 and 13 both take 9 steps.
+        assert_eq!(stopping_time_cdf(&tally_stopping_times(12, 13, 1).time_counts()), vec![(9, 1.0)]);
+        assert!(stopping_time_cdf(&[]).is_empty());
+    }
+
+    // Test function for `generate_variant_sequence`.
+    #[test]
+    fn test_generate_variant_sequence() {
+        // Under 3n+1, the variant generator agrees with `generate_sequence`.
+        let classic = generate_variant_sequence(CollatzMap::Classic, 6, 1000, DivergenceRule::default());
+        assert_eq!(classic.sequence, vec![6, 3, 10, 5, 16, 8, 4, 2, 1]);
+        assert_eq!(classic.termination, Termination::ReachedOne);
+
+        // Under 3n-1, 5 enters the cycle 5 -> 14 -> 7 -> 20 -> 10 -> 5.
+        let minus = generate_variant_sequence(CollatzMap::ThreeNMinusOne, 5, 1000, DivergenceRule::default());
+        assert_eq!(minus.sequence, vec![5, 14, 7, 20, 10]);
+        assert_eq!(minus.termination, Termination::Cycle);
+        assert_eq!(minus.cycle, Some(CycleInfo {
+            entry_index: 0,
+            length: 5,
+            members: vec![5, 14, 7, 20, 10],
+        }));
+
+        // 3n-1 from 17: 17 -> 50 -> 25 -> 74 -> 37 -> ... -> 17, an 18-value cycle.
+        let long_cycle = generate_variant_sequence(CollatzMap::ThreeNMinusOne, 17, 1000, DivergenceRule::default());
+        assert_eq!(long_cycle.cycle.as_ref().map(|c| c.length), Some(18));
+
+        // 3n-1 from 3: 3 -> 8 -> 4 -> 2 -> 1 (stops at 1, no cycle reported).
+        let tail = generate_variant_sequence(CollatzMap::ThreeNMinusOne, 3, 1000, DivergenceRule::default());
+        assert_eq!(tail.termination, Termination::ReachedOne);
+        assert_eq!(tail.cycle, None);
+
+        // 3n-1 from 40: 40 -> 20 -> 10 -> 5 -> 14 -> 7 -> 20, the cycle is entered at step 1.
+        let entry = generate_variant_sequence(CollatzMap::ThreeNMinusOne, 40, 1000, DivergenceRule::default());
+        let cycle = entry.cycle.expect("40 enters a cycle under 3n-1");
+        assert_eq!(cycle.entry_index, 1);
+        assert_eq!(cycle.entry_value(), 20);
+        assert_eq!(entry.sequence.len(), cycle.entry_index + cycle.length);
+
+        // Negative starts: -1 -> -2 -> -1 under 3n+1.
+        let negative = generate_variant_sequence(CollatzMap::Classic, -1, 1000, DivergenceRule::default());
+        assert_eq!(negative.sequence, vec![-1, -2]);
+        assert_eq!(negative.termination, Termination::Cycle);
+
+        // The step limit is respected.
+        let limited = generate_variant_sequence(CollatzMap::Classic, 27, 10, DivergenceRule::default());
+        assert_eq!(limited.sequence.len(), 11);
+        assert_eq!(limited.termination, Termination::StepLimit);
+
+        // 5n+1 from 7 grows without bound: it is stopped once it stays above the bound.
+        let rule = DivergenceRule { bound: 1_000_000, steps: 10 };
+        let runaway = generate_variant_sequence(CollatzMap::FiveNPlusOne, 7, 10_000, rule);
+        assert_eq!(runaway.termination, Termination::Divergent);
+        assert!(runaway.sequence.iter().rev().take(10).all(|term| term.unsigned_abs() > rule.bound));
+        let default_rule = generate_variant_sequence(CollatzMap::FiveNPlusOne, 7, 10_000, DivergenceRule::default());
+        assert!(default_rule.sequence.len() > runaway.sequence.len());
+    }
+
+    // Test function for `identify_cycle`.
+    #[test]
+    fn test_identify_cycle() {
+        // 40 enters the 5-cycle of 3n-1 through 20.
+        let trajectory = generate_variant_sequence(CollatzMap::ThreeNMinusOne, 40, 1000, DivergenceRule::default());
+        let known = identify_cycle(trajectory.map, trajectory.cycle.as_ref().unwrap());
+        assert_eq!(known.map(|k| k.name), Some("cycle of 5"));
+
+        // -34 enters the cycle of -17 under 3n+1.
+        let trajectory = generate_variant_sequence(CollatzMap::Classic, -34, 1000, DivergenceRule::default());
+        let known = identify_cycle(trajectory.map, trajectory.cycle.as_ref().unwrap());
+        assert_eq!(known.map(|k| k.name), Some("cycle of -17"));
+
+        // The same members under another map are not a match.
+        let cycle = CycleInfo { entry_index: 0, length: 5, members: vec![5, 14, 7, 20, 10] };
+        assert_eq!(identify_cycle(CollatzMap::FiveNPlusOne, &cycle), None);
+
+        // Every cataloged cycle is really a cycle of its map.
+        for known in KNOWN_CYCLES {
+            for (i, &value) in known.members.iter().enumerate() {
+                let next = known.members[(i + 1) % known.members.len()];
+                assert_eq!(known.map.step(value), Some(next), "{} of {}", known.name, known.map.name());
+            }
+        }
     }
 }
@@ -0,0 +1,235 @@
+// File deep_link.rs
+// This module contains the `collatz://` links, which open the application with parameters already applied,
+// e.g. `collatz://visualize?n=27&m=31&log=1` charts the sequences of 27 and 31 on a log scale.
+// The "Copy link" button writes the link of the current exploration, to share it with other users.
+// The operating system starts the application with the link as its first argument,
+// once the scheme has been registered (`register-links` subcommand, see `register`).
+
+use std::path::Path;
+
+/// Scheme of the links handled by the application.
+pub const SCHEME: &str = "collatz";
+
+/// Parameters of a `collatz://visualize` link.
+/// The values are integers written as in the inputs: the application checks them like typed values
+/// (e.g. the map comparison accepts negative starting values, the sequences do not).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeepLink {
+    pub first: Option<String>,       // First starting value (`n`)
+    pub second: Option<String>,      // Second starting value (`m`)
+    pub chart: Option<String>,       // Chart kind (`chart`, e.g. "scatter"); the sequences when absent
+    pub range_start: Option<String>, // First starting value of a range chart (`from`)
+    pub range_end: Option<String>,   // Last starting value of a range chart (`to`)
+    pub log_scale: bool,             // Whether the Y axis is logarithmic (`log=1`)
+}
+
+impl DeepLink {
+    /// Writes the link, e.g. `collatz://visualize?n=27&m=31&log=1` (absent parameters are left out).
+    pub fn to_link(&self) -> String {
+        let parameters = [
+            ("n", self.first.as_deref()),
+            ("m", self.second.as_deref()),
+            ("chart", self.chart.as_deref()),
+            ("from", self.range_start.as_deref()),
+            ("to", self.range_end.as_deref()),
+            ("log", Some("1").filter(|_| self.log_scale)),
+        ];
+        let query: Vec<String> = parameters
+            .iter()
+            .filter_map(|(key, value)| value.map(|value| format!("{}={}", key, value)))
+            .collect();
+        format!("{}://visualize?{}", SCHEME, query.join("&"))
+    }
+}
+
+/// Returns true if a command line argument is a link of the application (rather than an option).
+pub fn is_deep_link(arg: &str) -> bool {
+    arg.len() > SCHEME.len()
+        && arg[..SCHEME.len()].eq_ignore_ascii_case(SCHEME)
+        && arg[SCHEME.len()..].starts_with(':')
+}
+
+/// Reads the parameters of a link.
+/// Unknown parameters are ignored, so that links written by newer versions still open.
+///
+/// # Arguments
+/// * `link` - The link, e.g. `collatz://visualize?n=27&m=31&log=1`.
+///
+/// # Returns
+/// * `Ok(DeepLink)` - The parameters to apply.
+/// * `Err(String)` - Why the link cannot be opened.
+pub fn parse(link: &str) -> Result<DeepLink, String> {
+    if !is_deep_link(link) {
+        return Err(format!("Not a {}:// link: {}", SCHEME, link));
+    }
+    let rest = link[SCHEME.len() + 1..].trim_start_matches('/');
+    let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+    // Some browsers add a trailing slash to the host ("collatz://visualize/?n=27").
+    if !action.trim_end_matches('/').eq_ignore_ascii_case("visualize") {
+        return Err(format!("Unknown link action: {}", action));
+    }
+
+    let mut deep_link = DeepLink::default();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        match key {
+            "n" => deep_link.first = Some(parse_value(key, value)?),
+            "m" => deep_link.second = Some(parse_value(key, value)?),
+            "chart" => deep_link.chart = Some(value.to_ascii_lowercase()),
+            "from" => deep_link.range_start = Some(parse_value(key, value)?),
+            "to" => deep_link.range_end = Some(parse_value(key, value)?),
+            "log" => deep_link.log_scale = matches!(value, "1" | "true" | "yes" | "on" | ""),
+            _ => {}
+        }
+    }
+
+    let values = [&deep_link.first, &deep_link.second, &deep_link.range_start, &deep_link.range_end];
+    if values.iter().all(|value| value.is_none()) {
+        return Err("The link has no starting value (n, m, from or to)".to_string());
+    }
+    Ok(deep_link)
+}
+
+/// Reads a starting value of a link: an integer, possibly negative.
+fn parse_value(key: &str, value: &str) -> Result<String, String> {
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("Invalid value for {} in the link: {}", key, value));
+    }
+    Ok(value.to_string())
+}
+
+/// Registers the application as the handler of the `collatz://` links for the current user.
+/// Linux: a desktop entry in the user's applications, set as the default handler with `xdg-mime`.
+/// Windows: the URL protocol keys under HKEY_CURRENT_USER, with `reg`.
+/// macOS only associates schemes with application bundles, through their Info.plist.
+///
+/// # Arguments
+/// * `executable` - Path of the application, started with the link as its argument.
+///
+/// # Returns
+/// * `Ok(String)` - What was registered.
+/// * `Err(String)` - Why the registration failed.
+pub fn register(executable: &Path) -> Result<String, String> {
+    let executable = executable.to_string_lossy();
+
+    if cfg!(target_os = "windows") {
+        let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+        let command = format!("\"{}\" \"%1\"", executable);
+        let entries: [(String, &str, String); 3] = [
+            (key.clone(), "/ve", "URL:Collatz Conjecture Visualizer".to_string()),
+            (key.clone(), "/v", String::new()), // Empty "URL Protocol" value: marks the key as a scheme
+            (format!(r"{}\shell\open\command", key), "/ve", command),
+        ];
+        for (key, kind, data) in entries {
+            let mut reg = std::process::Command::new("reg");
+            reg.args(["add", &key, kind]);
+            if kind == "/v" {
+                reg.arg("URL Protocol");
+            }
+            let status = reg.args(["/d", &data, "/f"]).status().map_err(|e| e.to_string())?;
+            if !status.success() {
+                return Err(format!("reg add {} failed", key));
+            }
+        }
+        Ok(format!("{}:// links registered for {}", SCHEME, executable))
+    } else if cfg!(target_os = "macos") {
+        Err(format!(
+            "On macOS, {}:// links are declared in the Info.plist of an application bundle (CFBundleURLTypes)",
+            SCHEME
+        ))
+    } else {
+        let applications = dirs::data_dir()
+            .ok_or_else(|| "No user data directory".to_string())?
+            .join("applications");
+        std::fs::create_dir_all(&applications).map_err(|e| e.to_string())?;
+        let desktop_file = format!("{}-visualizer.desktop", SCHEME);
+        std::fs::write(applications.join(&desktop_file), desktop_entry(&executable)).map_err(|e| e.to_string())?;
+
+        let status = std::process::Command::new("xdg-mime")
+            .args(["default", &desktop_file, &format!("x-scheme-handler/{}", SCHEME)])
+            .status()
+            .map_err(|e| format!("xdg-mime: {}", e))?;
+        if !status.success() {
+            return Err("xdg-mime failed".to_string());
+        }
+        Ok(format!("{}:// links registered with {}", SCHEME, applications.join(desktop_file).display()))
+    }
+}
+
+/// Content of the Linux desktop entry handling the links (`%u` is replaced by the link).
+fn desktop_entry(executable: &str) -> String {
+    format!(
+        "[Desktop Entry]\nType=Application\nName=Collatz Conjecture Visualizer\nExec=\"{}\" %u\n\
+         Terminal=false\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+        executable, SCHEME
+    )
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `parse`.
+    #[test]
+    fn test_parse() {
+        let link = parse("collatz://visualize?n=27&m=31&log=1").unwrap();
+        assert_eq!(link.first.as_deref(), Some("27"));
+        assert_eq!(link.second.as_deref(), Some("31"));
+        assert!(link.log_scale);
+
+        let link = parse("collatz://visualize/?n=-17&chart=Comparison&theme=dark").unwrap();
+        assert_eq!(link.first.as_deref(), Some("-17"));
+        assert_eq!(link.chart.as_deref(), Some("comparison"));
+        assert!(!link.log_scale);
+
+        let link = parse("COLLATZ:visualize?chart=scatter&from=1&to=100000").unwrap();
+        assert_eq!((link.range_start.as_deref(), link.range_end.as_deref()), (Some("1"), Some("100000")));
+
+        assert!(parse("collatz://visualize").is_err()); // No value
+        assert!(parse("collatz://visualize?n=abc").is_err());
+        assert!(parse("collatz://visualize?n=-").is_err());
+        assert!(parse("collatz://delete?n=27").is_err());
+        assert!(parse("https://example.com/?n=27").is_err());
+    }
+
+    // Test function for `DeepLink::to_link` (the written links are read back unchanged).
+    #[test]
+    fn test_to_link() {
+        let link = DeepLink {
+            first: Some("27".to_string()),
+            second: Some("31".to_string()),
+            log_scale: true,
+            ..DeepLink::default()
+        };
+        assert_eq!(link.to_link(), "collatz://visualize?n=27&m=31&log=1");
+        assert_eq!(parse(&link.to_link()).unwrap(), link);
+
+        let range = DeepLink {
+            chart: Some("density".to_string()),
+            range_start: Some("1".to_string()),
+            range_end: Some("5000".to_string()),
+            ..DeepLink::default()
+        };
+        assert_eq!(range.to_link(), "collatz://visualize?chart=density&from=1&to=5000");
+        assert_eq!(parse(&range.to_link()).unwrap(), range);
+    }
+
+    // Test function for `is_deep_link`.
+    #[test]
+    fn test_is_deep_link() {
+        assert!(is_deep_link("collatz://visualize?n=27"));
+        assert!(!is_deep_link("--window"));
+        assert!(!is_deep_link("bench"));
+        assert!(!is_deep_link("collatz"));
+    }
+
+    // Test function for `desktop_entry`.
+    #[test]
+    fn test_desktop_entry() {
+        let entry = desktop_entry("/usr/bin/collatz_app");
+        assert!(entry.contains("Exec=\"/usr/bin/collatz_app\" %u\n"));
+        assert!(entry.contains("MimeType=x-scheme-handler/collatz;\n"));
+    }
+}
@@ -0,0 +1,240 @@
+// File diagnostics.rs
+// This module contains the timing breakdown shown in the diagnostics panel.
+// Each phase of a visualization (parse, generate, stats, chart render, encode, disk write)
+// is timed separately, so that users can see where the time goes for big inputs.
+// It also formats the throughput and time remaining of background jobs, and the resources
+// the application holds: the memory of the stored sequences and caches, and the temporary files.
+
+use std::time::{Duration, Instant};
+
+/// Holds the time spent in each phase of a visualization, in the order the phases ran.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PhaseTimings {
+    pub phases: Vec<(&'static str, Duration)>, // (phase name, time spent)
+}
+
+impl PhaseTimings {
+    /// Creates an empty breakdown.
+    pub fn new() -> Self {
+        PhaseTimings::default()
+    }
+
+    /// Adds `elapsed` to the phase `name` (phases running several times, e.g. once per sequence, add up).
+    pub fn add(&mut self, name: &'static str, elapsed: Duration) {
+        match self.phases.iter_mut().find(|(phase, _)| *phase == name) {
+            Some((_, total)) => *total += elapsed,
+            None => self.phases.push((name, elapsed)),
+        }
+    }
+
+    /// Runs `f`, adds the time it took to the phase `name` and returns its result.
+    pub fn time<T>(&mut self, name: &'static str, f: impl FnOnce() -> T) -> T {
+        let timer = Instant::now();
+        let result = f();
+        self.add(name, timer.elapsed());
+        result
+    }
+
+    /// Appends the phases of another breakdown (e.g. the chart phases, timed in a background task).
+    pub fn extend(&mut self, other: PhaseTimings) {
+        for (name, elapsed) in other.phases {
+            self.add(name, elapsed);
+        }
+    }
+
+    /// Returns the time spent over all the phases.
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|(_, elapsed)| *elapsed).sum()
+    }
+
+    /// Formats the breakdown as a table: one line per phase with its time and share of the total.
+    pub fn format(&self) -> String {
+        let total = self.total();
+        let mut lines: Vec<String> = self
+            .phases
+            .iter()
+            .map(|(name, elapsed)| {
+                let share = if total.is_zero() {
+                    0.0
+                } else {
+                    elapsed.as_secs_f64() / total.as_secs_f64() * 100.0
+                };
+                format!("{:<14} {:>10.3} ms {:>6.1}%", name, elapsed.as_secs_f64() * 1000.0, share)
+            })
+            .collect();
+        lines.push(format!("{:<14} {:>10.3} ms", "Total", total.as_secs_f64() * 1000.0));
+        lines.join("\n")
+    }
+}
+
+/// Progress line of a background job: throughput and estimated time remaining.
+///
+/// # Arguments
+/// * `done` - Number of items processed so far.
+/// * `total` - Number of items of the whole job.
+/// * `elapsed` - Time spent processing the `done` items.
+///
+/// # Returns
+/// * `String` - E.g. "1.25 M items/s, about 2 min 05 s remaining" (no estimate before the first measurement).
+pub fn format_eta(done: u64, total: u64, elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs_f64();
+    if done == 0 || seconds <= 0.0 {
+        return "estimating time remaining...".to_string();
+    }
+
+    let rate = done as f64 / seconds;
+    let rate_text = if rate >= 1_000_000.0 {
+        format!("{:.2} M items/s", rate / 1_000_000.0)
+    } else if rate >= 1_000.0 {
+        format!("{:.1} k items/s", rate / 1_000.0)
+    } else {
+        format!("{:.1} items/s", rate)
+    };
+
+    let remaining = (total.saturating_sub(done) as f64 / rate).round() as u64;
+    let remaining_text = if remaining >= 3600 {
+        format!("{} h {:02} min", remaining / 3600, remaining % 3600 / 60)
+    } else if remaining >= 60 {
+        format!("{} min {:02} s", remaining / 60, remaining % 60)
+    } else {
+        format!("{} s", remaining)
+    };
+
+    format!("{}, about {} remaining", rate_text, remaining_text)
+}
+
+/// Memory and disk held by the application, as shown in the diagnostics panel.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResourceUsage {
+    pub sequence_bytes: u64,                  // Memory of the stored sequences (the two sequences and the extra series)
+    pub scan_bytes: u64,                      // Memory of the results of the range scan
+    pub stopping_times: Option<(u64, u64)>,   // (memory, cached values) of the stopping-time table; None while a scan uses it
+    pub stopping_time_hits: (u64, u64),       // (hits, lookups) of the stopping-time table
+    pub cached_charts: usize,                 // Number of recent charts kept as temporary images
+    pub chart_hits: (u64, u64),               // (hits, lookups) of the recent charts
+    pub temp_files: Option<(usize, u64)>,     // (number, total size) of the temporary files, once counted
+}
+
+impl ResourceUsage {
+    /// Formats the usage as a table, one resource per line.
+    pub fn format(&self) -> String {
+        let stopping_times = match self.stopping_times {
+            Some((bytes, values)) => format!("{} ({} values)", format_bytes(bytes), values),
+            None => "in use by a range scan".to_string(),
+        };
+        let temp_files = match self.temp_files {
+            Some((count, bytes)) => format!("{} files, {}", count, format_bytes(bytes)),
+            None => "not counted yet".to_string(),
+        };
+        [
+            format!("{:<20} {}", "Stored sequences", format_bytes(self.sequence_bytes)),
+            format!("{:<20} {}", "Range scan results", format_bytes(self.scan_bytes)),
+            format!("{:<20} {}", "Stopping-time table", stopping_times),
+            format!("{:<20} {}", "Table hit rate", format_hit_rate(self.stopping_time_hits)),
+            format!("{:<20} {} charts", "Recent charts", self.cached_charts),
+            format!("{:<20} {}", "Chart hit rate", format_hit_rate(self.chart_hits)),
+            format!("{:<20} {}", "Temporary files", temp_files),
+        ]
+        .join("\n")
+    }
+}
+
+/// Writes a number of bytes with the largest fitting unit, e.g. "512 B", "1.5 KB", "12.3 MB".
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Writes the hit rate of a cache from its (hits, lookups), e.g. "75.0% (3 of 4)".
+pub fn format_hit_rate((hits, lookups): (u64, u64)) -> String {
+    if lookups == 0 {
+        "no lookup yet".to_string()
+    } else {
+        format!("{:.1}% ({} of {})", hits as f64 / lookups as f64 * 100.0, hits, lookups)
+    }
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `PhaseTimings::add` and `PhaseTimings::total`.
+    #[test]
+    fn test_add() {
+        let mut timings = PhaseTimings::new();
+        timings.add("Generate", Duration::from_millis(3));
+        timings.add("Stats", Duration::from_millis(1));
+        timings.add("Generate", Duration::from_millis(2)); // Second sequence
+
+        assert_eq!(
+            timings.phases,
+            vec![("Generate", Duration::from_millis(5)), ("Stats", Duration::from_millis(1))]
+        );
+        assert_eq!(timings.total(), Duration::from_millis(6));
+
+        let mut chart = PhaseTimings::new();
+        chart.add("Chart render", Duration::from_millis(4));
+        timings.extend(chart);
+        assert_eq!(timings.phases.len(), 3);
+        assert_eq!(timings.total(), Duration::from_millis(10));
+    }
+
+    // Test function for `PhaseTimings::format`.
+    #[test]
+    fn test_format() {
+        let mut timings = PhaseTimings::new();
+        timings.add("Parse", Duration::from_millis(1));
+        timings.add("Disk write", Duration::from_millis(3));
+
+        let table = timings.format();
+        assert_eq!(table.lines().count(), 3);
+        assert!(table.lines().next().unwrap().ends_with("25.0%"));
+        assert!(table.lines().last().unwrap().starts_with("Total"));
+    }
+
+    // Test function for `format_eta`.
+    #[test]
+    fn test_format_eta() {
+        // 1000 items in 2 s: 500 items/s, 3000 items left = 6 s.
+        assert_eq!(format_eta(1000, 4000, Duration::from_secs(2)), "500.0 items/s, about 6 s remaining");
+        // 2.5 M items/s, 375 M items left = 150 s.
+        assert_eq!(
+            format_eta(5_000_000, 380_000_000, Duration::from_secs(2)),
+            "2.50 M items/s, about 2 min 30 s remaining"
+        );
+        assert_eq!(format_eta(1, 7201, Duration::from_secs(1)), "1.0 items/s, about 2 h 00 min remaining");
+        // Nothing measured yet.
+        assert_eq!(format_eta(0, 10, Duration::ZERO), "estimating time remaining...");
+    }
+
+    // Test function for `format_bytes` and `format_hit_rate`.
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GB");
+        assert_eq!(format_hit_rate((3, 4)), "75.0% (3 of 4)");
+        assert_eq!(format_hit_rate((0, 0)), "no lookup yet");
+    }
+
+    // Test function for `ResourceUsage::format`.
+    #[test]
+    fn test_resource_usage() {
+        let usage = ResourceUsage { sequence_bytes: 896, temp_files: Some((2, 2048)), ..ResourceUsage::default() };
+        let table = usage.format();
+        assert_eq!(table.lines().count(), 7);
+        assert!(table.lines().next().unwrap().ends_with("896 B"));
+        assert!(table.contains("in use by a range scan"));
+        assert!(table.lines().last().unwrap().ends_with("2 files, 2.0 KB"));
+    }
+}
@@ -0,0 +1,133 @@
+// File difference.rs
+// This module contains the pointwise comparison of two sequences, drawn by the difference chart.
+// Each step where both sequences still have a term gives one point: the difference of the terms,
+// or the logarithm of their ratio, so that the chart shows exactly where two trajectories diverge.
+
+/// Quantity plotted by the difference chart at each overlapping step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DifferenceMode {
+    Difference, // sequence1[i] - sequence2[i]
+    LogRatio,   // log10(sequence1[i] / sequence2[i])
+}
+
+impl DifferenceMode {
+    /// Every mode, in the order shown in the selector.
+    pub const ALL: [DifferenceMode; 2] = [DifferenceMode::Difference, DifferenceMode::LogRatio];
+
+    /// Computes the plotted values of two sequences, one per overlapping step.
+    pub fn apply(self, sequence1: &[u64], sequence2: &[u64]) -> Vec<f64> {
+        match self {
+            DifferenceMode::Difference => pointwise_difference(sequence1, sequence2)
+                .into_iter()
+                .map(|d| d as f64)
+                .collect(),
+            DifferenceMode::LogRatio => log_ratio(sequence1, sequence2),
+        }
+    }
+
+    /// Returns the description of the Y axis for this mode.
+    pub fn axis_label(self) -> &'static str {
+        match self {
+            DifferenceMode::Difference => "First - second",
+            DifferenceMode::LogRatio => "log10(first / second)",
+        }
+    }
+}
+
+impl std::fmt::Display for DifferenceMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            DifferenceMode::Difference => "Difference",
+            DifferenceMode::LogRatio => "Log ratio",
+        })
+    }
+}
+
+/// Computes sequence1[i] - sequence2[i] for every step where both sequences have a term.
+/// The terms are 64-bit, so the difference is computed on 128 bits to keep its sign without overflow.
+///
+/// # Arguments
+/// * `sequence1` - The first sequence.
+/// * `sequence2` - The second sequence.
+///
+/// # Returns
+/// * `Vec<i128>` - One difference per overlapping step (as long as the shorter sequence).
+pub fn pointwise_difference(sequence1: &[u64], sequence2: &[u64]) -> Vec<i128> {
+    sequence1
+        .iter()
+        .zip(sequence2)
+        .map(|(&a, &b)| i128::from(a) - i128::from(b))
+        .collect()
+}
+
+/// Computes log10(sequence1[i] / sequence2[i]) for every step where both sequences have a term:
+/// 0 where the terms are equal, 1 where the first one is ten times the second, -1 for the opposite.
+/// A null term (only possible in a sequence read from a file) is taken as 1, like on a log scale.
+///
+/// # Returns
+/// * `Vec<f64>` - One log-ratio per overlapping step (as long as the shorter sequence).
+pub fn log_ratio(sequence1: &[u64], sequence2: &[u64]) -> Vec<f64> {
+    sequence1
+        .iter()
+        .zip(sequence2)
+        .map(|(&a, &b)| (a.max(1) as f64).log10() - (b.max(1) as f64).log10())
+        .collect()
+}
+
+/// Finds the step from which the two sequences are identical until the end of the shorter one,
+/// i.e. where their trajectories have merged at the same step.
+///
+/// # Returns
+/// * `Some(step)` - The first step of the identical tail.
+/// * `None` - The last overlapping terms differ (or a sequence is empty).
+pub fn identical_from(sequence1: &[u64], sequence2: &[u64]) -> Option<usize> {
+    let overlap = sequence1.len().min(sequence2.len());
+    let identical = sequence1[..overlap]
+        .iter()
+        .rev()
+        .zip(sequence2[..overlap].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    (identical > 0).then(|| overlap - identical)
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `pointwise_difference`.
+    #[test]
+    fn test_pointwise_difference() {
+        assert_eq!(pointwise_difference(&[6, 3, 10, 5], &[7, 22, 11]), vec![-1, -19, -1]);
+        assert_eq!(pointwise_difference(&[u64::MAX], &[0]), vec![i128::from(u64::MAX)]);
+        assert!(pointwise_difference(&[1], &[]).is_empty());
+    }
+
+    // Test function for `log_ratio`.
+    #[test]
+    fn test_log_ratio() {
+        let ratios = log_ratio(&[100, 5, 1], &[10, 5, 100]);
+        assert_eq!(ratios.len(), 3);
+        assert!((ratios[0] - 1.0).abs() < 1e-12);
+        assert_eq!(ratios[1], 0.0);
+        assert!((ratios[2] + 2.0).abs() < 1e-12);
+    }
+
+    // Test function for `identical_from`.
+    #[test]
+    fn test_identical_from() {
+        // 12 -> 6 -> 3 -> 10 and 13 -> 40 -> 20 -> 10: merged at step 3.
+        assert_eq!(identical_from(&[12, 6, 3, 10, 5], &[13, 40, 20, 10, 5]), Some(3));
+        assert_eq!(identical_from(&[4, 2, 1], &[4, 2, 1]), Some(0));
+        assert_eq!(identical_from(&[6, 3], &[7, 22]), None);
+        assert_eq!(identical_from(&[], &[1]), None);
+    }
+
+    // Test function for `DifferenceMode::apply`.
+    #[test]
+    fn test_apply() {
+        assert_eq!(DifferenceMode::Difference.apply(&[10, 5], &[4, 8]), vec![6.0, -3.0]);
+        assert_eq!(DifferenceMode::LogRatio.apply(&[10, 5], &[10, 5]), vec![0.0, 0.0]);
+    }
+}
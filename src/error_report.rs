@@ -0,0 +1,106 @@
+// File error_report.rs
+// This module contains the reports of unexpected failures (chart backend errors, I/O errors).
+// Such a failure opens the error dialog, which can write a report for a bug report:
+// the error, the version of the application and the system, then the latest lines of the application log,
+// which the application keeps in memory (see `RecentLog`).
+
+use std::collections::VecDeque;
+
+/// Number of log lines kept for the error reports.
+pub const LOG_LINES: usize = 50;
+
+/// Latest lines of the application log (warnings, errors, runs), oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct RecentLog {
+    lines: VecDeque<String>, // At most `LOG_LINES` lines
+}
+
+impl RecentLog {
+    /// Adds a line to the log, dropping the oldest one if the log is full.
+    ///
+    /// # Arguments
+    /// * `time` - When it happened, e.g. "2026-10-15T14:00:00+02:00".
+    /// * `line` - What happened.
+    pub fn push(&mut self, time: &str, line: &str) {
+        if self.lines.len() == LOG_LINES {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(format!("{} {}", time, line));
+    }
+
+    /// Returns the lines of the log, oldest first.
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.iter().cloned().collect()
+    }
+}
+
+/// An unexpected failure, as shown in the error dialog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub message: String, // What failed and why, e.g. "Error generating chart: ..."
+    pub time: String,    // When it happened (RFC 3339)
+}
+
+/// Writes the report of a failure, to be attached to a bug report.
+///
+/// # Arguments
+/// * `failure` - The failure.
+/// * `version` - Version of the application.
+/// * `log` - Latest lines of the application log, oldest first.
+pub fn report_text(failure: &Failure, version: &str, log: &[String]) -> String {
+    let mut report = format!(
+        "Collatz Conjecture Visualizer error report\n\
+         Version: {}\n\
+         System: {} {}\n\
+         Time: {}\n\
+         Error: {}\n\
+         \n\
+         Recent log lines:\n",
+        version,
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        failure.time,
+        failure.message,
+    );
+    if log.is_empty() {
+        report.push_str("(none)\n");
+    }
+    for line in log {
+        report.push_str(line);
+        report.push('\n');
+    }
+    report
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `RecentLog::push`.
+    #[test]
+    fn test_recent_log() {
+        let mut log = RecentLog::default();
+        for index in 0..LOG_LINES + 2 {
+            log.push("2026-10-15T14:00:00+02:00", &format!("line {}", index));
+        }
+        let lines = log.lines();
+        assert_eq!(lines.len(), LOG_LINES);
+        // The two oldest lines are dropped.
+        assert_eq!(lines[0], "2026-10-15T14:00:00+02:00 line 2");
+    }
+
+    // Test function for `report_text`.
+    #[test]
+    fn test_report_text() {
+        let failure = Failure {
+            message: "Error generating chart: out of memory".to_string(),
+            time: "2026-10-15T14:00:00+02:00".to_string(),
+        };
+        let report = report_text(&failure, "0.1.0", &["2026-10-15T13:59:58+02:00 Visualize 27".to_string()]);
+        assert!(report.contains("Version: 0.1.0\n"));
+        assert!(report.contains("Error: Error generating chart: out of memory\n"));
+        assert!(report.ends_with("Recent log lines:\n2026-10-15T13:59:58+02:00 Visualize 27\n"));
+        assert!(report_text(&failure, "0.1.0", &[]).ends_with("(none)\n"));
+    }
+}
@@ -0,0 +1,225 @@
+// File experiment_log.rs
+// This module contains the experiment log: when enabled, every "Visualize" run is appended to a file
+// as one JSON object per line (JSON lines), with its time, its inputs and the key statistics of its sequences.
+// A long exploration session thus leaves a trail that can be analyzed afterwards, e.g. with `jq` or pandas:
+// {"timestamp":"2026-10-15T14:03:12+02:00","chart":"sequences","inputs":["27","31"],
+//  "sequences":[{"value":27,"flight_time":111,"peak":9232,"stopping_time":96},{"value":31,...}]}
+// The history panel reads the latest runs back from the log, to run them again with one click.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::json::{self, json_string, Json};
+
+/// Returns the default location of the experiment log, in the application's data directory.
+pub fn default_path() -> PathBuf {
+    crate::cache::data_dir().join("experiments.jsonl")
+}
+
+/// Key statistics of a sequence charted by a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequenceRecord {
+    pub value: u64,           // Starting value
+    pub flight_time: usize,   // Number of steps to reach 1
+    pub peak: u64,            // Maximum value reached
+    pub stopping_time: usize, // Number of steps to fall below the starting value
+}
+
+/// One "Visualize" run, as written in the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunRecord {
+    pub timestamp: String,  // Local time of the run, RFC 3339
+    pub chart: String,      // Chart kind, as in the links (e.g. "sequences", "density")
+    pub inputs: [String; 2], // The two values as typed, or the bounds of the range for range charts
+    pub sequences: [Option<SequenceRecord>; 2], // Statistics of each charted sequence, if any
+}
+
+impl RunRecord {
+    /// Writes the record as a single line of JSON (without the line break).
+    pub fn to_json_line(&self) -> String {
+        let sequences: Vec<String> = self
+            .sequences
+            .iter()
+            .map(|sequence| match sequence {
+                Some(s) => format!(
+                    "{{\"value\":{},\"flight_time\":{},\"peak\":{},\"stopping_time\":{}}}",
+                    s.value, s.flight_time, s.peak, s.stopping_time
+                ),
+                None => "null".to_string(),
+            })
+            .collect();
+        format!(
+            "{{\"timestamp\":{},\"chart\":{},\"inputs\":[{},{}],\"sequences\":[{}]}}",
+            json_string(&self.timestamp),
+            json_string(&self.chart),
+            json_string(&self.inputs[0]),
+            json_string(&self.inputs[1]),
+            sequences.join(",")
+        )
+    }
+}
+
+/// Reads a line of the log back into a record.
+///
+/// # Arguments
+/// * `line` - A line written by `RunRecord::to_json_line`.
+///
+/// # Returns
+/// * `Ok(RunRecord)` - The run.
+/// * `Err(String)` - If the line is not a record of the log.
+pub fn parse_line(line: &str) -> Result<RunRecord, String> {
+    let json = json::parse(line)?;
+    let text = |key: &str| json.get(key).and_then(Json::as_str).map(str::to_string);
+    let missing = |key: &str| format!("No {} in the record", key);
+
+    let inputs = match json.get("inputs") {
+        Some(Json::Array(items)) if items.len() == 2 => [
+            items[0].as_str().unwrap_or_default().to_string(),
+            items[1].as_str().unwrap_or_default().to_string(),
+        ],
+        _ => return Err(missing("inputs")),
+    };
+    let sequence = |json: &Json| -> Option<SequenceRecord> {
+        Some(SequenceRecord {
+            value: json.get("value")?.as_u64()?,
+            flight_time: json.get("flight_time")?.as_u64()? as usize,
+            peak: json.get("peak")?.as_u64()?,
+            stopping_time: json.get("stopping_time")?.as_u64()? as usize,
+        })
+    };
+    let sequences = match json.get("sequences") {
+        Some(Json::Array(items)) => [items.first().and_then(sequence), items.get(1).and_then(sequence)],
+        _ => [None, None],
+    };
+
+    Ok(RunRecord {
+        timestamp: text("timestamp").ok_or_else(|| missing("timestamp"))?,
+        chart: text("chart").ok_or_else(|| missing("chart"))?,
+        inputs,
+        sequences,
+    })
+}
+
+/// Reads the latest runs of the log, newest first. Lines that are not records are skipped,
+/// so that a line cut short (e.g. by a crash) does not hide the rest of the history.
+///
+/// # Arguments
+/// * `path` - Location of the log.
+/// * `limit` - Largest number of runs to return.
+///
+/// # Returns
+/// * `Ok(Vec<RunRecord>)` - The runs (none if the log does not exist yet).
+/// * `Err(String)` - If the file cannot be read.
+pub fn read_latest(path: &Path, limit: usize) -> Result<Vec<RunRecord>, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Error reading the experiment log: {}", e)),
+    };
+    Ok(content
+        .lines()
+        .rev()
+        .filter_map(|line| parse_line(line).ok())
+        .take(limit)
+        .collect())
+}
+
+/// Appends a record to the log, creating the file (and its directory) if needed.
+///
+/// # Arguments
+/// * `path` - Location of the log.
+/// * `record` - The run to append.
+///
+/// # Returns
+/// * `Ok(())` - The record was written.
+/// * `Err(String)` - If the file cannot be written.
+pub fn append(path: &Path, record: &RunRecord) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Error opening the experiment log: {}", e))?;
+    // A single write per line, so that two windows logging at once do not interleave their records.
+    file.write_all(format!("{}\n", record.to_json_line()).as_bytes())
+        .map_err(|e| format!("Error writing the experiment log: {}", e))
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    // Test function for `RunRecord::to_json_line`.
+    #[test]
+    fn test_to_json_line() {
+        let record = RunRecord {
+            timestamp: "2026-10-15T14:03:12+02:00".to_string(),
+            chart: "sequences".to_string(),
+            inputs: ["27".to_string(), "".to_string()],
+            sequences: [
+                Some(SequenceRecord { value: 27, flight_time: 111, peak: 9232, stopping_time: 96 }),
+                None,
+            ],
+        };
+        assert_eq!(
+            record.to_json_line(),
+            "{\"timestamp\":\"2026-10-15T14:03:12+02:00\",\"chart\":\"sequences\",\"inputs\":[\"27\",\"\"],\
+             \"sequences\":[{\"value\":27,\"flight_time\":111,\"peak\":9232,\"stopping_time\":96},null]}"
+        );
+    }
+
+    // Test function for `parse_line` (the written lines are read back unchanged).
+    #[test]
+    fn test_parse_line() {
+        let record = RunRecord {
+            timestamp: "2026-10-15T14:03:12+02:00".to_string(),
+            chart: "sequences".to_string(),
+            inputs: ["18446744073709551615".to_string(), "say \"hi\"\n\u{1}é".to_string()],
+            sequences: [
+                None,
+                Some(SequenceRecord { value: u64::MAX, flight_time: 111, peak: 9232, stopping_time: 96 }),
+            ],
+        };
+        assert_eq!(parse_line(&record.to_json_line()), Ok(record));
+
+        // Written by hand: spaces and unknown keys are accepted.
+        let record = parse_line(r#"{ "chart": "density", "note": [1, true, {}], "timestamp": "t", "inputs": ["1", "9"] }"#).unwrap();
+        assert_eq!(record.inputs, ["1".to_string(), "9".to_string()]);
+        assert_eq!(record.sequences, [None, None]);
+
+        assert!(parse_line("{\"timestamp\":\"t\",\"chart\":\"seq").is_err());
+        assert!(parse_line("{\"timestamp\":\"t\"}").is_err());
+        assert!(parse_line("").is_err());
+    }
+
+    // Test function for `append`.
+    #[test]
+    fn test_append() {
+        let temp = TempPath::new("collatz_experiments_test.jsonl");
+        let path = temp.path();
+        let record = RunRecord {
+            timestamp: "t".to_string(),
+            chart: "density".to_string(),
+            inputs: ["1".to_string(), "1000".to_string()],
+            sequences: [None, None],
+        };
+        append(path, &record).unwrap();
+        append(path, &record).unwrap();
+        let content = fs::read_to_string(path).unwrap();
+        assert_eq!(content.lines().count(), 2);
+        assert_eq!(content.lines().next().unwrap(), record.to_json_line());
+
+        // The latest runs are read back newest first, skipping the broken lines.
+        let newer = RunRecord { timestamp: "u".to_string(), ..record.clone() };
+        fs::write(path, format!("{}\n{{broken\n{}\n", record.to_json_line(), newer.to_json_line())).unwrap();
+        assert_eq!(read_latest(path, 10).unwrap(), vec![newer.clone(), record]);
+        assert_eq!(read_latest(path, 1).unwrap(), vec![newer]);
+        fs::remove_file(path).unwrap();
+        assert_eq!(read_latest(path, 10).unwrap(), Vec::new());
+    }
+}
@@ -0,0 +1,255 @@
+// File focus_ring.rs
+// This module contains the keyboard focus of the buttons, checkboxes and selectors, which iced only gives to text inputs.
+// Each of them is wrapped in a `FocusRing`: Tab and Shift+Tab (`focus_next`, `focus_previous`) go through
+// the rings and the text inputs in the order of the window, the focused ring is drawn around its widget,
+// and the keys that activate it are turned into the pointer events the widget already handles:
+// a click for a button or a checkbox, a Ctrl + wheel notch for a selector (which selects the next or previous option).
+
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::widget::{operation, tree, Operation, Tree};
+use iced::advanced::{mouse, overlay, renderer, Clipboard, Shell, Widget};
+use iced::event::{self, Event};
+use iced::keyboard::{self, KeyCode};
+use iced::{touch, Color, Element, Length, Rectangle, Theme};
+
+/// Width of the ring drawn around the focused widget, in pixels.
+const RING_WIDTH: f32 = 2.0;
+
+/// How the focused widget is activated from the keyboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Activation {
+    Press, // Enter or Space clicks it (buttons, checkboxes)
+    Cycle, // Enter, Space or Down selects the next option, Up the previous one (selectors)
+}
+
+/// Keyboard focus around a widget.
+pub struct FocusRing<'a, Message, Renderer> {
+    content: Element<'a, Message, Renderer>, // The focusable widget
+    activation: Activation,                  // What the activation keys do
+}
+
+/// Focus of a ring.
+#[derive(Default)]
+struct State {
+    focused: bool,                  // Whether the ring has the keyboard focus
+    modifiers: keyboard::Modifiers, // Keys held, given back to the selector after a simulated Ctrl + wheel
+}
+
+impl operation::Focusable for State {
+    fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    fn unfocus(&mut self) {
+        self.focused = false;
+    }
+}
+
+/// Wraps a button or a checkbox: Enter or Space clicks it while it has the focus.
+pub fn press<'a, Message, Renderer>(content: impl Into<Element<'a, Message, Renderer>>) -> FocusRing<'a, Message, Renderer> {
+    FocusRing { content: content.into(), activation: Activation::Press }
+}
+
+/// Wraps a selector (pick list): Enter, Space or the down arrow selects its next option while it has the focus,
+/// and the up arrow its previous option.
+pub fn cycle<'a, Message, Renderer>(content: impl Into<Element<'a, Message, Renderer>>) -> FocusRing<'a, Message, Renderer> {
+    FocusRing { content: content.into(), activation: Activation::Cycle }
+}
+
+/// Returns the pointer events simulating the activation of a widget from a key, if the key activates it.
+///
+/// # Arguments
+/// * `activation` - How the widget is activated.
+/// * `key_code` - The key pressed.
+/// * `modifiers` - Keys held, given back to the widget after the simulated events.
+fn activation_events(activation: Activation, key_code: KeyCode, modifiers: keyboard::Modifiers) -> Option<Vec<Event>> {
+    let click = || {
+        vec![
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)),
+        ]
+    };
+    // A selector changes its option with Ctrl + wheel: down is the next option, up the previous one.
+    let notch = |y: f32| {
+        vec![
+            Event::Keyboard(keyboard::Event::ModifiersChanged(keyboard::Modifiers::COMMAND)),
+            Event::Mouse(mouse::Event::WheelScrolled { delta: mouse::ScrollDelta::Lines { x: 0.0, y } }),
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)),
+        ]
+    };
+    match (activation, key_code) {
+        (Activation::Press, KeyCode::Enter | KeyCode::NumpadEnter | KeyCode::Space) => Some(click()),
+        (Activation::Cycle, KeyCode::Enter | KeyCode::NumpadEnter | KeyCode::Space | KeyCode::Down) => Some(notch(-1.0)),
+        (Activation::Cycle, KeyCode::Up) => Some(notch(1.0)),
+        _ => None,
+    }
+}
+
+impl<'a, Message, Renderer> Widget<Message, Renderer> for FocusRing<'a, Message, Renderer>
+where
+    Renderer: renderer::Renderer<Theme = Theme>,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn width(&self) -> Length {
+        self.content.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.as_widget().height()
+    }
+
+    fn layout(&self, renderer: &Renderer, limits: &layout::Limits) -> layout::Node {
+        self.content.as_widget().layout(renderer, limits)
+    }
+
+    fn operate(&self, tree: &mut Tree, layout: Layout<'_>, renderer: &Renderer, operation: &mut dyn Operation<Message>) {
+        operation.focusable(tree.state.downcast_mut::<State>(), None);
+        self.content.as_widget().operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        let state = tree.state.downcast_mut::<State>();
+        match &event {
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => state.modifiers = *modifiers,
+            // The ring is for the keyboard: using the pointer or Escape hides it.
+            Event::Mouse(mouse::Event::ButtonPressed(_))
+            | Event::Touch(touch::Event::FingerPressed { .. })
+            | Event::Keyboard(keyboard::Event::KeyPressed { key_code: KeyCode::Escape, .. }) => state.focused = false,
+            Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. }) if state.focused => {
+                if let Some(events) = activation_events(self.activation, *key_code, state.modifiers) {
+                    // The simulated events happen at the center of the widget, and only reach it.
+                    let center = mouse::Cursor::Available(layout.bounds().center());
+                    for simulated in events {
+                        self.content.as_widget_mut().on_event(
+                            &mut tree.children[0],
+                            simulated,
+                            layout,
+                            center,
+                            renderer,
+                            clipboard,
+                            shell,
+                            viewport,
+                        );
+                    }
+                    return event::Status::Captured;
+                }
+            }
+            _ => {}
+        }
+        self.content.as_widget_mut().on_event(&mut tree.children[0], event, layout, cursor, renderer, clipboard, shell, viewport)
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(&tree.children[0], layout, cursor, viewport, renderer)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.content.as_widget().draw(&tree.children[0], renderer, theme, style, layout, cursor, viewport);
+        if tree.state.downcast_ref::<State>().focused {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: layout.bounds().expand(RING_WIDTH),
+                    border_radius: 4.0.into(),
+                    border_width: RING_WIDTH,
+                    border_color: theme.extended_palette().primary.strong.color,
+                },
+                Color::TRANSPARENT,
+            );
+        }
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+    ) -> Option<overlay::Element<'b, Message, Renderer>> {
+        self.content.as_widget_mut().overlay(&mut tree.children[0], layout, renderer)
+    }
+}
+
+impl<'a, Message, Renderer> From<FocusRing<'a, Message, Renderer>> for Element<'a, Message, Renderer>
+where
+    Message: 'a,
+    Renderer: 'a + renderer::Renderer<Theme = Theme>,
+{
+    fn from(ring: FocusRing<'a, Message, Renderer>) -> Self {
+        Element::new(ring)
+    }
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `activation_events`.
+    #[test]
+    fn test_activation_events() {
+        let held = keyboard::Modifiers::SHIFT;
+        // Enter and Space click a button; other keys are left to the window.
+        let events = activation_events(Activation::Press, KeyCode::Space, held).unwrap();
+        assert_eq!(events[0], Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)));
+        assert_eq!(events.len(), 2);
+        assert!(activation_events(Activation::Press, KeyCode::Down, held).is_none());
+        assert!(activation_events(Activation::Press, KeyCode::A, held).is_none());
+
+        // A selector gets a Ctrl + wheel notch (down for the next option), then the keys held are given back.
+        let events = activation_events(Activation::Cycle, KeyCode::Enter, held).unwrap();
+        assert_eq!(
+            events[1],
+            Event::Mouse(mouse::Event::WheelScrolled { delta: mouse::ScrollDelta::Lines { x: 0.0, y: -1.0 } })
+        );
+        assert_eq!(events[2], Event::Keyboard(keyboard::Event::ModifiersChanged(held)));
+        let events = activation_events(Activation::Cycle, KeyCode::Up, held).unwrap();
+        assert_eq!(
+            events[1],
+            Event::Mouse(mouse::Event::WheelScrolled { delta: mouse::ScrollDelta::Lines { x: 0.0, y: 1.0 } })
+        );
+    }
+}
@@ -0,0 +1,172 @@
+// File fractal.rs
+// This module contains the extension of the Collatz map to the complex plane, and its escape-time fractal.
+// The map f(z) = (2 + 7z - (2 + 5z) cos(pi z)) / 4 agrees with the Collatz map on the integers
+// (cos(pi n) is 1 for even n and -1 for odd n, giving n / 2 and 3n + 1), and is defined everywhere else.
+// Iterating it from each point of a window and counting the steps before the point escapes
+// draws the fractal: the integers stay bounded, and the regions around them form intricate shapes.
+
+use rayon::prelude::*;
+use std::f64::consts::PI;
+
+/// Largest number of iterations per point: points still bounded by then are considered not escaping.
+pub const MAX_ITERATIONS: u32 = 100;
+
+/// Modulus beyond which a point is considered escaped.
+pub const ESCAPE_RADIUS: f64 = 1e6;
+
+/// A complex number (only the operations needed by the map).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Complex {
+    pub re: f64, // Real part
+    pub im: f64, // Imaginary part
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Self {
+        Complex { re, im }
+    }
+
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(self.re * other.re - self.im * other.im, self.re * other.im + self.im * other.re)
+    }
+
+    fn scale(self, factor: f64) -> Complex {
+        Complex::new(self.re * factor, self.im * factor)
+    }
+
+    /// cos(a + bi) = cos(a) cosh(b) - i sin(a) sinh(b).
+    fn cos(self) -> Complex {
+        Complex::new(self.re.cos() * self.im.cosh(), -self.re.sin() * self.im.sinh())
+    }
+
+    /// Returns the squared modulus |z|^2.
+    pub fn norm_sqr(self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+}
+
+/// Applies the complex Collatz map once: f(z) = (2 + 7z - (2 + 5z) cos(pi z)) / 4.
+pub fn collatz_step(z: Complex) -> Complex {
+    let two = Complex::new(2.0, 0.0);
+    let cos = z.scale(PI).cos();
+    two.add(z.scale(7.0)).sub(two.add(z.scale(5.0)).mul(cos)).scale(0.25)
+}
+
+/// Counts the iterations of the map needed for `z` to escape.
+///
+/// # Returns
+/// * `Some(n)` - The point left the disk of radius `ESCAPE_RADIUS` (or overflowed) after `n` iterations.
+/// * `None` - The point was still bounded after `max_iterations` iterations.
+pub fn escape_time(z: Complex, max_iterations: u32) -> Option<u32> {
+    let mut z = z;
+    for iteration in 0..max_iterations {
+        let norm = z.norm_sqr();
+        if !norm.is_finite() || norm > ESCAPE_RADIUS * ESCAPE_RADIUS {
+            return Some(iteration);
+        }
+        z = collatz_step(z);
+    }
+    None
+}
+
+/// Region of the complex plane drawn by the fractal chart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FractalWindow {
+    pub center: Complex, // Point at the center of the chart
+    pub width: f64,      // Width of the window along the real axis (the height follows the image ratio)
+}
+
+impl Default for FractalWindow {
+    fn default() -> Self {
+        FractalWindow { center: Complex::new(0.0, 0.0), width: 10.0 }
+    }
+}
+
+impl FractalWindow {
+    /// Returns the real and imaginary ranges of the window, for an image of `columns` x `rows` pixels.
+    pub fn ranges(&self, columns: u32, rows: u32) -> ((f64, f64), (f64, f64)) {
+        let height = self.width * rows as f64 / columns.max(1) as f64;
+        (
+            (self.center.re - self.width / 2.0, self.center.re + self.width / 2.0),
+            (self.center.im - height / 2.0, self.center.im + height / 2.0),
+        )
+    }
+
+    /// Returns the window with the same center and its width multiplied by `factor`
+    /// (below 1 zooms in, above 1 zooms out).
+    pub fn zoomed(&self, factor: f64) -> FractalWindow {
+        FractalWindow { width: self.width * factor, ..*self }
+    }
+}
+
+/// Computes the escape time of every pixel of the window, in parallel (one row per task).
+///
+/// # Arguments
+/// * `window` - Region of the complex plane.
+/// * `columns` - Width of the image, in pixels.
+/// * `rows` - Height of the image, in pixels (the first row is the top of the window).
+/// * `max_iterations` - Largest number of iterations per pixel.
+///
+/// # Returns
+/// * `Vec<Option<u32>>` - The escape times, row by row (see `escape_time`).
+pub fn render(window: &FractalWindow, columns: u32, rows: u32, max_iterations: u32) -> Vec<Option<u32>> {
+    let ((re_min, re_max), (im_min, im_max)) = window.ranges(columns, rows);
+    let re_step = (re_max - re_min) / columns.max(1) as f64;
+    let im_step = (im_max - im_min) / rows.max(1) as f64;
+    (0..rows)
+        .into_par_iter()
+        .flat_map_iter(|row| {
+            let im = im_max - (row as f64 + 0.5) * im_step;
+            (0..columns).map(move |column| {
+                escape_time(Complex::new(re_min + (column as f64 + 0.5) * re_step, im), max_iterations)
+            })
+        })
+        .collect()
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `collatz_step`.
+    #[test]
+    fn test_collatz_step() {
+        // On the integers, the map is the Collatz map.
+        for (n, next) in [(6.0, 3.0), (3.0, 10.0), (1.0, 4.0), (4.0, 2.0)] {
+            let z = collatz_step(Complex::new(n, 0.0));
+            assert!((z.re - next).abs() < 1e-9 && z.im.abs() < 1e-9, "f({}) = {:?}", n, z);
+        }
+    }
+
+    // Test function for `escape_time`.
+    #[test]
+    fn test_escape_time() {
+        // 1 -> 4 -> 2 -> 1 stays bounded.
+        assert_eq!(escape_time(Complex::new(1.0, 0.0), MAX_ITERATIONS), None);
+        // Far from the real axis, cos(pi z) explodes at once.
+        assert!(escape_time(Complex::new(0.5, 5.0), MAX_ITERATIONS).is_some_and(|n| n <= 3));
+        assert_eq!(escape_time(Complex::new(2e6, 0.0), MAX_ITERATIONS), Some(0));
+    }
+
+    // Test function for `render` and the window.
+    #[test]
+    fn test_render() {
+        let window = FractalWindow::default();
+        assert_eq!(window.ranges(100, 50), ((-5.0, 5.0), (-2.5, 2.5)));
+        assert_eq!(window.zoomed(0.5).width, 5.0);
+
+        let times = render(&window, 20, 10, MAX_ITERATIONS);
+        assert_eq!(times.len(), 200);
+        // The top row is far from the real axis: everything escapes there.
+        assert!(times[..20].iter().all(Option::is_some));
+    }
+}
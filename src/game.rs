@@ -0,0 +1,122 @@
+// File game.rs
+// This module contains the "Guess" game: the application draws a random starting value,
+// the player guesses its flight time and its peak, then the answer is revealed with its chart.
+// Each round earns points according to how close the guesses were, and the score adds up over the rounds.
+//
+// A game goes through two phases, over and over:
+//   Guessing --reveal--> Revealed --next round--> Guessing ...
+
+use crate::collatz;
+
+/// Largest starting value drawn by the game (the same bound as "Randomize").
+pub const MAX_START: u64 = 10_000;
+
+/// Result of a round, once its answer is revealed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundResult {
+    pub flight_time: usize,                 // Actual number of steps to reach 1
+    pub peak: u64,                          // Actual maximum value reached
+    pub guessed_flight_time: Option<usize>, // Flight time guessed by the player, if any
+    pub guessed_peak: Option<u64>,          // Peak guessed by the player, if any
+    pub points: u32,                        // Points earned by both guesses
+}
+
+/// Phase of the current round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Guessing,               // The player is typing the guesses
+    Revealed(RoundResult),  // The answer is shown
+}
+
+/// State of a game: the current round and the score so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub start: u64,   // Starting value of the current round
+    pub phase: Phase, // Phase of the current round
+    pub score: u32,   // Points earned over the revealed rounds
+    pub rounds: u32,  // Number of revealed rounds
+}
+
+impl Game {
+    /// Starts a game, with a first round on `start`.
+    pub fn new(start: u64) -> Self {
+        Game { start, phase: Phase::Guessing, score: 0, rounds: 0 }
+    }
+
+    /// Reveals the answer of the current round and adds its points to the score.
+    /// A round already revealed is left as it is.
+    ///
+    /// # Arguments
+    /// * `guessed_flight_time` - Flight time guessed by the player (None: no guess).
+    /// * `guessed_peak` - Peak guessed by the player (None: no guess).
+    pub fn reveal(&mut self, guessed_flight_time: Option<usize>, guessed_peak: Option<u64>) -> RoundResult {
+        if let Phase::Revealed(result) = self.phase {
+            return result;
+        }
+        let stats = collatz::calculate_stats(&collatz::generate_sequence(self.start));
+        let flight_time = stats.length - 1;
+        let points = guessed_flight_time.map_or(0, |guess| points_for(flight_time as u64, guess as u64))
+            + guessed_peak.map_or(0, |guess| points_for(stats.max_value, guess));
+        let result = RoundResult { flight_time, peak: stats.max_value, guessed_flight_time, guessed_peak, points };
+        self.phase = Phase::Revealed(result);
+        self.score += points;
+        self.rounds += 1;
+        result
+    }
+
+    /// Starts the next round on `start`, keeping the score.
+    pub fn next_round(&mut self, start: u64) {
+        self.start = start;
+        self.phase = Phase::Guessing;
+    }
+}
+
+/// Points earned by a guess: 5 if exact, 3 within 10% of the actual value, 1 within 25%, 0 otherwise.
+pub fn points_for(actual: u64, guess: u64) -> u32 {
+    let error = actual.abs_diff(guess) as f64 / actual.max(1) as f64;
+    if error == 0.0 {
+        5
+    } else if error <= 0.10 {
+        3
+    } else if error <= 0.25 {
+        1
+    } else {
+        0
+    }
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `points_for`.
+    #[test]
+    fn test_points_for() {
+        assert_eq!(points_for(111, 111), 5);
+        assert_eq!(points_for(100, 95), 3);
+        assert_eq!(points_for(100, 120), 1);
+        assert_eq!(points_for(100, 200), 0);
+        assert_eq!(points_for(0, 0), 5);
+    }
+
+    // Test function for a round of the game.
+    #[test]
+    fn test_reveal() {
+        // 27 reaches 1 in 111 steps, with a peak of 9232.
+        let mut game = Game::new(27);
+        let result = game.reveal(Some(111), Some(5000));
+        assert_eq!((result.flight_time, result.peak), (111, 9232));
+        assert_eq!(result.points, 5);
+        assert_eq!((game.score, game.rounds), (5, 1));
+
+        // Revealing again does not count the round twice.
+        game.reveal(Some(111), Some(9232));
+        assert_eq!((game.score, game.rounds), (5, 1));
+
+        game.next_round(6);
+        assert_eq!(game.phase, Phase::Guessing);
+        assert_eq!(game.reveal(None, None).points, 0);
+        assert_eq!((game.score, game.rounds), (5, 2));
+    }
+}
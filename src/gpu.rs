@@ -0,0 +1,225 @@
+// File gpu.rs
+// This module contains the GPU backend for range scans (compiled with the "gpu" feature).
+// Total stopping times are computed by a wgpu compute shader, one invocation per starting value.
+// WGSL has no 64-bit integers, so each value is stored as two 32-bit halves (low, high)
+// and the shader performs the halving and the 3n + 1 step with explicit carries.
+
+use wgpu::util::DeviceExt;
+
+// Compute shader: computes the total stopping time of `base + index` for every index of the chunk.
+// Overflowing trajectories (and the unlikely runaway ones) are reported as OVERFLOW.
+const SHADER: &str = r#"
+struct Params {
+    base_lo: u32,
+    base_hi: u32,
+    count: u32,
+    max_steps: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read_write> times: array<u32>;
+
+const OVERFLOW: u32 = 0xffffffffu;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if (index >= params.count) {
+        return;
+    }
+
+    // n = base + index, with the carry of the low half.
+    var lo = params.base_lo + index;
+    var hi = params.base_hi + select(0u, 1u, lo < params.base_lo);
+    var steps = 0u;
+
+    loop {
+        if (hi == 0u && lo == 1u) {
+            break;
+        }
+        if (steps >= params.max_steps) {
+            steps = OVERFLOW;
+            break;
+        }
+        if ((lo & 1u) == 0u) {
+            // n / 2: shift both halves, the lowest bit of `hi` moves into `lo`.
+            lo = (lo >> 1u) | (hi << 31u);
+            hi = hi >> 1u;
+        } else {
+            // Same guard as the CPU: n > (2^64 - 2) / 3 would overflow.
+            if (hi > 0x55555555u || (hi == 0x55555555u && lo > 0x55555554u)) {
+                steps = OVERFLOW;
+                break;
+            }
+            // 3n + 1 = n + 2n + 1.
+            let lo2 = lo << 1u;
+            let hi2 = (hi << 1u) | (lo >> 31u);
+            let sum_lo = lo + lo2;
+            var sum_hi = hi + hi2 + select(0u, 1u, sum_lo < lo);
+            let plus_lo = sum_lo + 1u;
+            sum_hi = sum_hi + select(0u, 1u, plus_lo == 0u);
+            lo = plus_lo;
+            hi = sum_hi;
+        }
+        steps = steps + 1u;
+    }
+
+    times[index] = steps;
+}
+"#;
+
+// Value written by the shader when a trajectory cannot be computed.
+const OVERFLOW: u32 = u32::MAX;
+
+// Workgroup size declared in the shader, and the largest number of workgroups per dispatch.
+const WORKGROUP_SIZE: u64 = 64;
+const MAX_WORKGROUPS: u64 = 65_535;
+
+// Safety limit on the number of steps per trajectory (no u64 trajectory comes close).
+const MAX_STEPS: u32 = 100_000;
+
+/// Computes the total stopping time of every starting value in `range_start..=range_end` on the GPU.
+///
+/// # Returns
+/// * `Ok(times)` - Pairs of (starting value, total stopping time), like `collatz::stopping_times_in_range`.
+/// * `Err(message)` - If no GPU is available or if the GPU work fails.
+pub fn stopping_times_in_range(range_start: u64, range_end: u64) -> Result<Vec<(u64, usize)>, String> {
+    let range_start = range_start.max(1);
+    if range_end < range_start {
+        return Ok(Vec::new());
+    }
+
+    // Device setup. wgpu is asynchronous; the scan already runs in a background task, so block on it.
+    let instance = wgpu::Instance::new(wgpu::InstanceDescriptor::default());
+    let adapter = futures::executor::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::HighPerformance,
+        force_fallback_adapter: false,
+        compatible_surface: None,
+    }))
+    .ok_or_else(|| "No GPU adapter available".to_string())?;
+    let (device, queue) = futures::executor::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: Some("collatz scan"),
+            features: wgpu::Features::empty(),
+            limits: wgpu::Limits::downlevel_defaults(),
+        },
+        None,
+    ))
+    .map_err(|e| format!("GPU device error: {}", e))?;
+
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("collatz scan shader"),
+        source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("collatz scan pipeline"),
+        layout: None,
+        module: &module,
+        entry_point: "main",
+    });
+
+    // Buffers are sized for the largest chunk and reused for every chunk.
+    let chunk_size = (WORKGROUP_SIZE * MAX_WORKGROUPS).min(range_end - range_start + 1);
+    let output_size = chunk_size * std::mem::size_of::<u32>() as u64;
+    let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("collatz scan params"),
+        contents: &[0u8; 16],
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    });
+    let output = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("collatz scan output"),
+        size: output_size,
+        usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        mapped_at_creation: false,
+    });
+    let staging = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("collatz scan staging"),
+        size: output_size,
+        usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("collatz scan bind group"),
+        layout: &pipeline.get_bind_group_layout(0),
+        entries: &[
+            wgpu::BindGroupEntry { binding: 0, resource: params.as_entire_binding() },
+            wgpu::BindGroupEntry { binding: 1, resource: output.as_entire_binding() },
+        ],
+    });
+
+    let mut times = Vec::with_capacity((range_end - range_start + 1).min(usize::MAX as u64) as usize);
+    let mut base = range_start;
+    loop {
+        let count = chunk_size.min(range_end - base + 1);
+
+        // Parameters of this chunk: base value split into halves, number of values, step limit.
+        let mut bytes = Vec::with_capacity(16);
+        for word in [base as u32, (base >> 32) as u32, count as u32, MAX_STEPS] {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        queue.write_buffer(&params, 0, &bytes);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: None });
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(count.div_ceil(WORKGROUP_SIZE) as u32, 1, 1);
+        }
+        let byte_count = count * std::mem::size_of::<u32>() as u64;
+        encoder.copy_buffer_to_buffer(&output, 0, &staging, 0, byte_count);
+        queue.submit(Some(encoder.finish()));
+
+        // Read the results back.
+        let slice = staging.slice(..byte_count);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("GPU read error: {}", e))?;
+        {
+            let data = slice.get_mapped_range();
+            for (offset, word) in data.chunks_exact(4).enumerate() {
+                let t = u32::from_le_bytes([word[0], word[1], word[2], word[3]]);
+                if t != OVERFLOW {
+                    times.push((base + offset as u64, t as usize));
+                }
+            }
+        }
+        staging.unmap();
+
+        // `base + count - 1` is the last value of the chunk; stop before overflowing past `range_end`.
+        if range_end - base < count {
+            break;
+        }
+        base += count;
+    }
+
+    Ok(times)
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collatz;
+
+    // The GPU results must match the CPU ones, including around the overflow limit.
+    // Machines without any adapter skip the comparison.
+    #[test]
+    fn test_stopping_times_in_range() {
+        for (start, end) in [(1, 100_000), (u64::MAX / 3 - 100, u64::MAX / 3 + 100), (u64::MAX - 50, u64::MAX)] {
+            match stopping_times_in_range(start, end) {
+                Ok(times) => assert_eq!(times, collatz::stopping_times_in_range(start, end)),
+                Err(e) => {
+                    println!("Skipping GPU test: {}", e);
+                    return;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,181 @@
+// File i18n.rs
+// This module contains the catalog of the texts of the interface that are looked up by key,
+// starting with the explanations of the statistics shown in their tooltips.
+// Keys are stable identifiers ("stat.flight_time.help"): a translation is another table with the same keys.
+
+/// Language of the interface texts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+}
+
+/// English texts, by key.
+const ENGLISH: &[(&str, &str)] = &[
+    (
+        "stat.flight_time.help",
+        "Flight time: number of steps needed to reach 1 from the starting value \
+         (also called the total stopping time).",
+    ),
+    (
+        "stat.max_altitude.help",
+        "Maximum altitude: highest value reached by the sequence before it falls back to 1.",
+    ),
+    (
+        "stat.max_step.help",
+        "Step of the maximum: number of steps taken before the maximum altitude is reached.",
+    ),
+    (
+        "stat.even_count.help",
+        "Even values: number of even terms, each of which is halved to give the next term.",
+    ),
+    (
+        "stat.odd_count.help",
+        "Odd values: number of odd terms (the final 1 included); each of them but 1 is followed by 3n+1.",
+    ),
+    (
+        "stat.downtime.help",
+        "Downtime: number of steps before the sequence first drops below its starting value \
+         (also called the stopping time).",
+    ),
+    (
+        "stat.max_bit_length.help",
+        "Maximum bit length: number of binary digits of the highest term, i.e. its size on a log2 scale \
+         (9232 = 0b10010000010000 has 14).",
+    ),
+    (
+        "stat.average_bit_length.help",
+        "Average bit length: mean number of binary digits of the terms, summarizing their size \
+         without writing out huge values.",
+    ),
+    (
+        "stat.bit_length_step.help",
+        "Step of the bit-length peak: number of steps taken before a term first reaches the maximum bit length.",
+    ),
+    (
+        "stat.truncated.help",
+        "Truncated at: the sequence was stopped at the step limit before reaching 1, \
+         so the other statistics only describe its first steps.",
+    ),
+    (
+        "stat.arithmetic.help",
+        "Arithmetic: integer size needed by the terms. The computation runs on 64 bits, switches to 128 bits \
+         at the first term whose 3n+1 would not fit, and to arbitrary precision beyond 128 bits.",
+    ),
+    (
+        "stat.full_flight_time.help",
+        "Full flight time: number of steps needed to reach 1, for a sequence stopped before 1 -- \
+         its terms outgrew 64 bits (computed on 128 bits or with arbitrary precision), or glide only \
+         (the chart and the other statistics stop there).",
+    ),
+    (
+        "stat.full_trajectory.help",
+        "Full trajectory: the terms of this sequence outgrew 64 bits, so the chart and the statistics above stop there; \
+         these statistics follow the whole trajectory on 128 bits or with arbitrary precision.",
+    ),
+    (
+        "stat.distinct_values.help",
+        "Distinct values: number of different values visited by the two sequences together.",
+    ),
+    (
+        "stat.shared_values.help",
+        "Shared values: number of values visited by both sequences -- once they meet, \
+         they follow the same path (the \"shared highway\") down to 1.",
+    ),
+    (
+        "stat.compression.help",
+        "Path compression: share of the terms saved by drawing both sequences as a single path, \
+         1 - distinct values / terms of both (50% for two identical sequences).",
+    ),
+    (
+        "stat.first_overlap.help",
+        "Overlap of the first: share of the terms of the first sequence also visited by the second.",
+    ),
+    (
+        "stat.second_overlap.help",
+        "Overlap of the second: share of the terms of the second sequence also visited by the first.",
+    ),
+    (
+        "stat.glide_only.help",
+        "Glide only: the sequence was stopped as soon as it dropped below its starting value (its glide), \
+         so the flight time and the other statistics only describe the glide; the full flight time follows.",
+    ),
+    (
+        "stat.downsampled.help",
+        "Downsampled: the sequence does not fit in the memory budget, so its statistics were computed \
+         while it was generated and only one term in this many was kept for the chart.",
+    ),
+    (
+        "stat.tail.help",
+        "Tail shown: how the end of the sequence is drawn -- the 4-2-1 cycle repeated, or the final descent \
+         through powers of two trimmed. The statistics are those of the trajectory stopped at the first 1.",
+    ),
+    (
+        "stat.family_members.help",
+        "Family members: number of sequences of the family mode, n and its doublings 2n, 4n...",
+    ),
+    (
+        "stat.shared_tail.help",
+        "Shared tail: number of terms at the end of every sequence of the family -- the trajectory of n, \
+         which each doubling joins after halving back down to n.",
+    ),
+    (
+        "stat.tail_offsets.help",
+        "Steps before the tail: halving steps taken by each member (n, 2n, 4n...) before joining the shared tail; \
+         each doubling shifts the same curve one step to the right.",
+    ),
+    ("tour.inputs.title", "1/4 -- The values"),
+    (
+        "tour.inputs.text",
+        "Type a starting value here (27 is filled in as an example), and optionally a second one to compare. \
+         The small buttons beside each value explore its neighborhood: +1, -1, x2, next odd.",
+    ),
+    ("tour.buttons.title", "2/4 -- The actions"),
+    (
+        "tour.buttons.text",
+        "\"Visualize\" computes the sequences and draws their chart; ◀ and ▶ (or the arrow keys) step through \
+         consecutive values. The other buttons save, copy, print or share what is on screen.",
+    ),
+    ("tour.chart.title", "3/4 -- The chart"),
+    (
+        "tour.chart.text",
+        "Each sequence is drawn step by step: even terms are halved, odd terms become 3n+1, until 1 is reached. \
+         The chart selector above offers other views, e.g. of whole ranges of starting values.",
+    ),
+    ("tour.statistics.title", "4/4 -- The statistics"),
+    (
+        "tour.statistics.text",
+        "The key figures of each sequence, e.g. its flight time and maximum altitude. \
+         Hover a name to get it explained, and use \"Copy\" to copy a value alone.",
+    ),
+];
+
+/// Returns the text of a key in a language, or the key itself if the catalog has no such text,
+/// so that a missing translation shows up in the interface instead of an empty text.
+///
+/// # Arguments
+/// * `language` - Language of the text.
+/// * `key` - Key of the text, e.g. "stat.flight_time.help".
+pub fn text(language: Language, key: &str) -> &str {
+    let catalog = match language {
+        Language::English => ENGLISH,
+    };
+    catalog
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map_or(key, |(_, text)| text)
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `text`.
+    #[test]
+    fn test_text() {
+        assert!(text(Language::English, "stat.flight_time.help").starts_with("Flight time"));
+        // An unknown key is shown as is.
+        assert_eq!(text(Language::English, "no.such.key"), "no.such.key");
+    }
+}
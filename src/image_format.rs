@@ -0,0 +1,180 @@
+// File image_format.rs
+// This module contains the image formats the charts can be saved in.
+// Charts are always rendered as PNG files; "Save the graph" and the automatic copies then convert them
+// into the chosen format, with a quality setting for the lossy one (JPEG).
+// PNG files also carry a description of the run (starting values, map, version of the application)
+// as tEXt chunks, so that a saved figure still tells what it shows months later,
+// and can be opened again to reconstruct its run.
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::ImageEncoder;
+use std::fs;
+use std::path::Path;
+
+/// Format of the saved charts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Png,  // Lossless, the format the charts are rendered in
+    Jpeg, // Lossy, with a quality setting
+    Bmp,  // Uncompressed
+    WebP, // Lossless WebP (the encoder has no lossy mode)
+}
+
+/// Qualities offered for the lossy formats, from 1 (smallest file) to 100 (best image).
+pub const QUALITIES: [u8; 5] = [60, 75, 85, 95, 100];
+
+/// Quality of the lossy formats by default.
+pub const DEFAULT_QUALITY: u8 = 85;
+
+impl ImageFormat {
+    /// Every format, in the order shown in the selector.
+    pub const ALL: [ImageFormat; 4] = [ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::Bmp, ImageFormat::WebP];
+
+    /// Returns the extension of the files of this format (without the dot).
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Bmp => "bmp",
+            ImageFormat::WebP => "webp",
+        }
+    }
+
+    /// Returns true if the format loses details, so that its quality can be chosen.
+    pub fn is_lossy(self) -> bool {
+        self == ImageFormat::Jpeg
+    }
+}
+
+impl std::fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            ImageFormat::Png => "PNG",
+            ImageFormat::Jpeg => "JPEG",
+            ImageFormat::Bmp => "BMP",
+            ImageFormat::WebP => "WebP",
+        })
+    }
+}
+
+/// Encodes an image in a format.
+///
+/// # Arguments
+/// * `image` - The image (charts have no transparency).
+/// * `format` - Format to encode the image in.
+/// * `quality` - Quality of the lossy formats (1 to 100), ignored by the others.
+///
+/// # Returns
+/// * `Ok(Vec<u8>)` - The content of the image file.
+/// * `Err(String)` - If the encoder failed.
+pub fn encode(image: &image::RgbImage, format: ImageFormat, quality: u8) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let (width, height) = image.dimensions();
+    let color = image::ColorType::Rgb8;
+    let result = match format {
+        ImageFormat::Png => image::codecs::png::PngEncoder::new(&mut bytes).write_image(image, width, height, color),
+        ImageFormat::Jpeg => JpegEncoder::new_with_quality(&mut bytes, quality.clamp(1, 100))
+            .write_image(image, width, height, color),
+        ImageFormat::Bmp => image::codecs::bmp::BmpEncoder::new(&mut bytes).write_image(image, width, height, color),
+        ImageFormat::WebP => WebPEncoder::new_lossless(&mut bytes).write_image(image, width, height, color),
+    };
+    result.map_err(|e| format!("Error encoding the image as {}: {}", format, e))?;
+    Ok(bytes)
+}
+
+/// Encodes an image as PNG, with text entries stored in tEXt chunks.
+///
+/// # Arguments
+/// * `image` - The image.
+/// * `text` - Keyword and text of each entry (Latin-1; keywords of 1 to 79 characters).
+pub fn encode_png_with_text(image: &image::RgbImage, text: &[(String, String)]) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, image.width(), image.height());
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    for (keyword, value) in text {
+        encoder
+            .add_text_chunk(keyword.clone(), value.clone())
+            .map_err(|e| format!("Invalid metadata {}: {}", keyword, e))?;
+    }
+    let mut writer = encoder.write_header().map_err(|e| format!("Error encoding the image as PNG: {}", e))?;
+    writer.write_image_data(image.as_raw()).map_err(|e| format!("Error encoding the image as PNG: {}", e))?;
+    writer.finish().map_err(|e| format!("Error encoding the image as PNG: {}", e))?;
+    Ok(bytes)
+}
+
+/// Reads the text entries of a PNG file (tEXt, zTXt and iTXt chunks placed before the image data).
+///
+/// # Returns
+/// * `Ok(Vec<(String, String)>)` - Keyword and text of each entry, in the order of the file.
+/// * `Err(String)` - If the bytes are not a PNG file.
+pub fn read_png_text(bytes: &[u8]) -> Result<Vec<(String, String)>, String> {
+    let reader = png::Decoder::new(bytes).read_info().map_err(|e| format!("Not a PNG image: {}", e))?;
+    let info = reader.info();
+    let mut text: Vec<(String, String)> = info
+        .uncompressed_latin1_text
+        .iter()
+        .map(|chunk| (chunk.keyword.clone(), chunk.text.clone()))
+        .collect();
+    text.extend(info.compressed_latin1_text.iter().filter_map(|chunk| Some((chunk.keyword.clone(), chunk.get_text().ok()?))));
+    text.extend(info.utf8_text.iter().filter_map(|chunk| Some((chunk.keyword.clone(), chunk.get_text().ok()?))));
+    Ok(text)
+}
+
+/// Writes a copy of a rendered chart (PNG file) in a format.
+///
+/// # Arguments
+/// * `source` - The rendered chart.
+/// * `target` - File to write.
+/// * `format` - Format of the copy.
+/// * `quality` - Quality of the lossy formats (see `encode`).
+/// * `text` - Description of the run, written into PNG copies (see `encode_png_with_text`);
+///   the other formats are written without it.
+pub fn save_as(
+    source: &Path,
+    target: &Path,
+    format: ImageFormat,
+    quality: u8,
+    text: &[(String, String)],
+) -> Result<(), String> {
+    let image = image::open(source).map_err(|e| format!("Error reading the chart: {}", e))?.to_rgb8();
+    let bytes = match format {
+        ImageFormat::Png => encode_png_with_text(&image, text)?,
+        _ => encode(&image, format, quality)?,
+    };
+    fs::write(target, bytes).map_err(|e| format!("Error writing {}: {}", target.display(), e))
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `encode`.
+    #[test]
+    fn test_encode() {
+        let image = image::RgbImage::from_fn(16, 8, |x, y| image::Rgb([(x * 16) as u8, (y * 32) as u8, 128]));
+        for format in ImageFormat::ALL {
+            let bytes = encode(&image, format, DEFAULT_QUALITY).unwrap();
+            // Every encoded image is read back with its size.
+            let decoded = image::load_from_memory(&bytes).unwrap();
+            assert_eq!((decoded.width(), decoded.height()), (16, 8), "{}", format);
+        }
+        // A lower quality gives a smaller JPEG file.
+        let noisy = image::RgbImage::from_fn(64, 64, |x, y| image::Rgb([(x * y % 251) as u8, (x ^ y) as u8, (x + y) as u8]));
+        assert!(encode(&noisy, ImageFormat::Jpeg, 60).unwrap().len() < encode(&noisy, ImageFormat::Jpeg, 100).unwrap().len());
+    }
+
+    // Test function for `encode_png_with_text`.
+    #[test]
+    fn test_encode_png_with_text() {
+        let image = image::RgbImage::new(4, 4);
+        let text = vec![("Starting values".to_string(), "27, 31".to_string())];
+        let bytes = encode_png_with_text(&image, &text).unwrap();
+        assert_eq!(read_png_text(&bytes).unwrap(), text);
+        // An empty keyword is not allowed by PNG.
+        assert!(encode_png_with_text(&image, &[(String::new(), "x".to_string())]).is_err());
+        assert!(read_png_text(b"27,82,41").is_err());
+    }
+}
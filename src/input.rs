@@ -0,0 +1,315 @@
+// File input.rs
+// This module contains the clean-up of the numbers typed or pasted in the inputs.
+// Numbers copied from documents or spreadsheets are often written with digit group separators
+// ("1 000 000", "1,000,000", "1_000_000"), which are removed before the value is parsed.
+// It also checks the starting values, so that each input can show its own error under the field,
+// and applies the small adjustments of the buttons beside the inputs (+1, -1, x2, next odd).
+
+/// Returns true for the characters separating the digit groups of a number:
+/// spaces (including the non-breaking and narrow ones), commas and underscores.
+fn is_group_separator(c: char) -> bool {
+    matches!(c, ' ' | ',' | '_' | '\u{a0}' | '\u{202f}' | '\u{2009}')
+}
+
+/// Removes the digit group separators of a number, e.g. "1 000 000" or "1,000,000" -> "1000000".
+/// The separators are only removed when they group the digits by three, so that a list of values
+/// pasted in a single input ("27, 31") is not silently merged into another number.
+///
+/// # Arguments
+/// * `text` - Content of the input.
+///
+/// # Returns
+/// * `String` - The number without separators, or `text` unchanged if it is not a grouped number
+///   (including a number still being typed, e.g. "1 00").
+pub fn normalize_number(text: &str) -> String {
+    let trimmed = text.trim();
+    let (sign, digits) = match trimmed.strip_prefix(['-', '+']) {
+        Some(rest) => (&trimmed[..1], rest),
+        None => ("", trimmed),
+    };
+    if !digits.contains(is_group_separator) {
+        return text.to_string();
+    }
+
+    // Every group after the first has exactly three digits: "1,000,000", not "27, 31" or "1,,000".
+    let groups: Vec<&str> = digits.split(is_group_separator).collect();
+    let is_grouped = groups.iter().all(|group| !group.is_empty() && group.bytes().all(|b| b.is_ascii_digit()))
+        && groups[1..].iter().all(|group| group.len() == 3);
+    if is_grouped {
+        format!("{}{}", sign, groups.concat())
+    } else {
+        text.to_string()
+    }
+}
+
+/// Reads the starting value of a sequence: a whole number from 1 to 2^64 - 1.
+///
+/// # Arguments
+/// * `text` - Content of the input.
+///
+/// # Returns
+/// * `Ok(Some(u64))` - The value.
+/// * `Ok(None)` - The input is empty.
+/// * `Err(String)` - Why the value is invalid, shown under the input.
+pub fn parse_start_value(text: &str) -> Result<Option<u64>, String> {
+    match parse_integer(text)? {
+        None => Ok(None),
+        Some(value) if value <= 0 => Err("Must be greater than 0".to_string()),
+        Some(value) => u64::try_from(value)
+            .map(Some)
+            .map_err(|_| "Too large (the limit is 2^64 - 1)".to_string()),
+    }
+}
+
+/// Reads the starting value of the map comparison: a whole number other than 0, possibly negative.
+///
+/// # Returns
+/// * `Ok(Some(i64))` - The value.
+/// * `Ok(None)` - The input is empty.
+/// * `Err(String)` - Why the value is invalid, shown under the input.
+pub fn parse_signed_start_value(text: &str) -> Result<Option<i64>, String> {
+    match parse_integer(text)? {
+        None => Ok(None),
+        Some(0) => Err("Must not be 0".to_string()),
+        Some(value) => i64::try_from(value)
+            .map(Some)
+            .map_err(|_| "Too large (the limits are -2^63 and 2^63 - 1)".to_string()),
+    }
+}
+
+/// Reads a whole number, with room beyond the 64-bit limits to tell "too large" from "not a number".
+fn parse_integer(text: &str) -> Result<Option<i128>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let digits = text.strip_prefix(['-', '+']).unwrap_or(text);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err("Not a whole number".to_string());
+    }
+    // Beyond i128, the number is far too large anyway.
+    text.parse::<i128>()
+        .map(Some)
+        .map_err(|_| "Too large (the limit is 2^64 - 1)".to_string())
+}
+
+/// Adjustment applied to a starting value by the buttons beside the inputs,
+/// to explore the neighborhood of a value without retyping it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Adjustment {
+    Increment, // n + 1
+    Decrement, // n - 1
+    Double,    // 2n (whose trajectory is the one of n, one step longer)
+    NextOdd,   // Smallest odd number above n
+}
+
+impl Adjustment {
+    /// Every adjustment, in the order of the buttons.
+    pub const ALL: [Adjustment; 4] = [Adjustment::Increment, Adjustment::Decrement, Adjustment::Double, Adjustment::NextOdd];
+
+    /// Applies the adjustment to a value.
+    pub fn apply(self, value: i128) -> i128 {
+        match self {
+            Adjustment::Increment => value + 1,
+            Adjustment::Decrement => value - 1,
+            Adjustment::Double => value * 2,
+            Adjustment::NextOdd if value % 2 != 0 => value + 2,
+            Adjustment::NextOdd => value + 1,
+        }
+    }
+}
+
+impl std::fmt::Display for Adjustment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Adjustment::Increment => "+1",
+            Adjustment::Decrement => "\u{2212}1", // Minus sign, as wide as the plus sign
+            Adjustment::Double => "\u{d7}2",
+            Adjustment::NextOdd => "Next odd",
+        })
+    }
+}
+
+/// Applies an adjustment to the starting value of an input.
+///
+/// # Arguments
+/// * `text` - Content of the input.
+/// * `adjustment` - The adjustment to apply.
+/// * `signed` - Whether the value may be negative (map comparison).
+///
+/// # Returns
+/// * `Some(String)` - The new content of the input.
+/// * `None` - The input holds no valid value, or the adjusted value would be invalid (e.g. 1 - 1 = 0).
+pub fn adjust_start_value(text: &str, adjustment: Adjustment, signed: bool) -> Option<String> {
+    let value = if signed {
+        i128::from(parse_signed_start_value(text).ok()??)
+    } else {
+        i128::from(parse_start_value(text).ok()??)
+    };
+    let adjusted = adjustment.apply(value).to_string();
+
+    // The adjusted value is checked like a typed one.
+    let valid = if signed {
+        matches!(parse_signed_start_value(&adjusted), Ok(Some(_)))
+    } else {
+        matches!(parse_start_value(&adjusted), Ok(Some(_)))
+    };
+    valid.then_some(adjusted)
+}
+
+/// Relation deriving the second value from the first in the linked input mode,
+/// so that the second input follows the first one for pairwise comparisons.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InputLink {
+    #[default]
+    Off,        // The two values are typed independently
+    Successor,  // n + 1
+    Double,     // 2n
+    Collatz,    // 3n + 1 (the step taken from an odd n)
+    FlipLowBit, // n XOR 1 (the other number of the pair {2k, 2k + 1})
+}
+
+impl InputLink {
+    /// Every relation, in the order of the list.
+    pub const ALL: [InputLink; 5] = [
+        InputLink::Off,
+        InputLink::Successor,
+        InputLink::Double,
+        InputLink::Collatz,
+        InputLink::FlipLowBit,
+    ];
+
+    /// Applies the relation to a value.
+    ///
+    /// # Returns
+    /// * `Some(u64)` - The derived value.
+    /// * `None` - The mode is off, or the derived value is not a valid starting value (overflow, 1 XOR 1 = 0).
+    pub fn apply(self, value: u64) -> Option<u64> {
+        match self {
+            InputLink::Off => None,
+            InputLink::Successor => value.checked_add(1),
+            InputLink::Double => value.checked_mul(2),
+            InputLink::Collatz => value.checked_mul(3)?.checked_add(1),
+            InputLink::FlipLowBit => Some(value ^ 1).filter(|&derived| derived > 0),
+        }
+    }
+}
+
+impl std::fmt::Display for InputLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            InputLink::Off => "Off",
+            InputLink::Successor => "n+1",
+            InputLink::Double => "2n",
+            InputLink::Collatz => "3n+1",
+            InputLink::FlipLowBit => "n XOR 1",
+        })
+    }
+}
+
+/// Derives the content of the second input from the first one in the linked input mode.
+///
+/// # Arguments
+/// * `text` - Content of the first input.
+/// * `link` - The relation between the two values.
+///
+/// # Returns
+/// * `Some(String)` - The content of the second input: the derived value, or nothing if the first input
+///   holds no valid value or the derived value is invalid.
+/// * `None` - The mode is off: the second input is left as it is.
+pub fn linked_value(text: &str, link: InputLink) -> Option<String> {
+    if link == InputLink::Off {
+        return None;
+    }
+    let derived = parse_start_value(text).ok().flatten().and_then(|value| link.apply(value));
+    Some(derived.map_or_else(String::new, |value| value.to_string()))
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `normalize_number`.
+    #[test]
+    fn test_normalize_number() {
+        assert_eq!(normalize_number("1 000 000"), "1000000");
+        assert_eq!(normalize_number("1,000,000"), "1000000");
+        assert_eq!(normalize_number("1_000_000"), "1000000");
+        assert_eq!(normalize_number("1\u{a0}000\u{202f}000"), "1000000");
+        assert_eq!(normalize_number("  27 000 "), "27000");
+        assert_eq!(normalize_number("-1,000"), "-1000");
+
+        // Plain numbers and text being typed are left as they are.
+        assert_eq!(normalize_number("27"), "27");
+        assert_eq!(normalize_number(" 27"), " 27");
+        assert_eq!(normalize_number("1 00"), "1 00");
+        assert_eq!(normalize_number("1,"), "1,");
+
+        // Not a grouped number: kept, so that the error is reported instead of a wrong value.
+        assert_eq!(normalize_number("27, 31"), "27, 31");
+        assert_eq!(normalize_number("1,,000"), "1,,000");
+        assert_eq!(normalize_number("1,0a0"), "1,0a0");
+    }
+
+    // Test function for `parse_start_value`.
+    #[test]
+    fn test_parse_start_value() {
+        assert_eq!(parse_start_value(" 27 "), Ok(Some(27)));
+        assert_eq!(parse_start_value("18446744073709551615"), Ok(Some(u64::MAX)));
+        assert_eq!(parse_start_value(""), Ok(None));
+        assert_eq!(parse_start_value("0"), Err("Must be greater than 0".to_string()));
+        assert_eq!(parse_start_value("-5"), Err("Must be greater than 0".to_string()));
+        assert_eq!(parse_start_value("27a"), Err("Not a whole number".to_string()));
+        assert_eq!(parse_start_value("2.5"), Err("Not a whole number".to_string()));
+        assert!(parse_start_value("18446744073709551616").unwrap_err().starts_with("Too large"));
+        assert!(parse_start_value(&"9".repeat(50)).unwrap_err().starts_with("Too large"));
+    }
+
+    // Test function for `parse_signed_start_value`.
+    #[test]
+    fn test_parse_signed_start_value() {
+        assert_eq!(parse_signed_start_value("-17"), Ok(Some(-17)));
+        assert_eq!(parse_signed_start_value("27"), Ok(Some(27)));
+        assert_eq!(parse_signed_start_value("  "), Ok(None));
+        assert_eq!(parse_signed_start_value("0"), Err("Must not be 0".to_string()));
+        assert!(parse_signed_start_value("9223372036854775808").unwrap_err().starts_with("Too large"));
+    }
+
+    // Test function for `adjust_start_value`.
+    #[test]
+    fn test_adjust_start_value() {
+        assert_eq!(adjust_start_value("27", Adjustment::Increment, false).as_deref(), Some("28"));
+        assert_eq!(adjust_start_value(" 27", Adjustment::Decrement, false).as_deref(), Some("26"));
+        assert_eq!(adjust_start_value("27", Adjustment::Double, false).as_deref(), Some("54"));
+        assert_eq!(adjust_start_value("27", Adjustment::NextOdd, false).as_deref(), Some("29"));
+        assert_eq!(adjust_start_value("28", Adjustment::NextOdd, false).as_deref(), Some("29"));
+
+        // Negative values only for the map comparison, which skips 0.
+        assert_eq!(adjust_start_value("-3", Adjustment::NextOdd, true).as_deref(), Some("-1"));
+        assert_eq!(adjust_start_value("-4", Adjustment::Double, true).as_deref(), Some("-8"));
+        assert_eq!(adjust_start_value("1", Adjustment::Decrement, true), None);
+
+        // Invalid results and invalid inputs are left alone.
+        assert_eq!(adjust_start_value("1", Adjustment::Decrement, false), None);
+        assert_eq!(adjust_start_value("18446744073709551615", Adjustment::Increment, false), None);
+        assert_eq!(adjust_start_value("", Adjustment::Increment, false), None);
+        assert_eq!(adjust_start_value("abc", Adjustment::Double, false), None);
+    }
+
+    // Test function for `InputLink::apply` and `linked_value`.
+    #[test]
+    fn test_linked_value() {
+        assert_eq!(linked_value("27", InputLink::Successor).as_deref(), Some("28"));
+        assert_eq!(linked_value("27", InputLink::Double).as_deref(), Some("54"));
+        assert_eq!(linked_value("27", InputLink::Collatz).as_deref(), Some("82"));
+        assert_eq!(linked_value("27", InputLink::FlipLowBit).as_deref(), Some("26"));
+        assert_eq!(linked_value("26", InputLink::FlipLowBit).as_deref(), Some("27"));
+
+        // Invalid values empty the second input; the mode off leaves it alone.
+        assert_eq!(linked_value("1", InputLink::FlipLowBit).as_deref(), Some(""));
+        assert_eq!(linked_value("18446744073709551615", InputLink::Successor).as_deref(), Some(""));
+        assert_eq!(linked_value("abc", InputLink::Double).as_deref(), Some(""));
+        assert_eq!(linked_value("27", InputLink::Off), None);
+    }
+}
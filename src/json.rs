@@ -0,0 +1,216 @@
+// File json.rs
+// This module contains the hand-written JSON reader and writer shared by the files of the application:
+// the experiment log (one JSON object per line), the exported comparisons of runs, and the imported sequences.
+// Numbers are kept as text, so that 64-bit integers (and bigger ones) are read back exactly.
+
+/// A JSON value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(String), // Kept as text, so that 64-bit integers are read back exactly
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Returns the value of a key of an object.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Json::Number(number) => number.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+/// Minimal JSON reader: the documents read are small (lines of a log, imported sequences).
+struct JsonReader<'a> {
+    text: &'a str,
+    position: usize, // Byte offset of the next character to read
+}
+
+impl JsonReader<'_> {
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consumes `expected` (after any whitespace), or fails.
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.text[self.position..].starts_with(expected) {
+            self.position += expected.len_utf8();
+            Ok(())
+        } else {
+            Err(format!("Expected '{}' at offset {}", expected, self.position))
+        }
+    }
+
+    /// Consumes `expected` if it comes next (after any whitespace).
+    fn accept(&mut self, expected: char) -> bool {
+        self.expect(expected).is_ok()
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        let rest = &self.text[self.position..];
+        match rest.chars().next() {
+            Some('{') => {
+                self.position += 1;
+                let mut fields = Vec::new();
+                if !self.accept('}') {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.string()?;
+                        self.expect(':')?;
+                        fields.push((key, self.value()?));
+                        if self.accept('}') {
+                            break;
+                        }
+                        self.expect(',')?;
+                    }
+                }
+                Ok(Json::Object(fields))
+            }
+            Some('[') => {
+                self.position += 1;
+                let mut items = Vec::new();
+                if !self.accept(']') {
+                    loop {
+                        items.push(self.value()?);
+                        if self.accept(']') {
+                            break;
+                        }
+                        self.expect(',')?;
+                    }
+                }
+                Ok(Json::Array(items))
+            }
+            Some('"') => self.string().map(Json::String),
+            _ if rest.starts_with("null") => {
+                self.position += 4;
+                Ok(Json::Null)
+            }
+            _ if rest.starts_with("true") => {
+                self.position += 4;
+                Ok(Json::Bool(true))
+            }
+            _ if rest.starts_with("false") => {
+                self.position += 5;
+                Ok(Json::Bool(false))
+            }
+            _ => {
+                let length = rest
+                    .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')))
+                    .unwrap_or(rest.len());
+                if length == 0 {
+                    return Err(format!("Unexpected character at offset {}", self.position));
+                }
+                self.position += length;
+                Ok(Json::Number(rest[..length].to_string()))
+            }
+        }
+    }
+
+    /// Reads a string literal, undoing the escapes of `json_string`.
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut text = String::new();
+        let mut chars = self.text[self.position..].char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.position += offset + 1;
+                    return Ok(text);
+                }
+                '\\' => match chars.next().map(|(_, c)| c) {
+                    Some('n') => text.push('\n'),
+                    Some('r') => text.push('\r'),
+                    Some('t') => text.push('\t'),
+                    Some('u') => {
+                        let hex: String = (0..4).filter_map(|_| chars.next().map(|(_, c)| c)).collect();
+                        let code = u32::from_str_radix(&hex, 16).map_err(|_| "Invalid \\u escape".to_string())?;
+                        text.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+                    }
+                    Some(other) => text.push(other), // \" \\ \/
+                    None => break,
+                },
+                c => text.push(c),
+            }
+        }
+        Err("Unterminated string".to_string())
+    }
+}
+
+/// Reads a JSON document.
+///
+/// # Returns
+/// * `Ok(Json)` - The value of the document (the text after it is ignored).
+/// * `Err(String)` - If the document is not valid JSON.
+pub fn parse(text: &str) -> Result<Json, String> {
+    JsonReader { text, position: 0 }.value()
+}
+
+/// Writes a text as a JSON string literal, escaping the quotes, backslashes and control characters.
+pub fn json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `parse`.
+    #[test]
+    fn test_parse() {
+        let json = parse(r#" { "a": [1, "18446744073709551616", null, true, false], "b": {"c": -2.5e3} } "#).unwrap();
+        let Some(Json::Array(items)) = json.get("a") else { panic!("no array") };
+        assert_eq!(items[0].as_u64(), Some(1));
+        assert_eq!(items[1].as_str(), Some("18446744073709551616"));
+        assert_eq!(items[2..], [Json::Null, Json::Bool(true), Json::Bool(false)]);
+        assert_eq!(json.get("b").and_then(|b| b.get("c")), Some(&Json::Number("-2.5e3".to_string())));
+        assert_eq!(parse(&json_string("say \"hi\"\n\u{1}é")).unwrap(), Json::String("say \"hi\"\n\u{1}é".to_string()));
+
+        assert!(parse("[1, 2").is_err());
+        assert!(parse("{\"a\" 1}").is_err());
+        assert!(parse("").is_err());
+    }
+
+    // Test function for `json_string`.
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("27"), "\"27\"");
+        assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\n\"");
+        assert_eq!(json_string("\u{1}"), "\"\\u0001\"");
+    }
+}
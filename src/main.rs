@@ -1,19 +1,66 @@
+mod bench;
+mod big_uint;
+mod cache;
+mod chart_cache;
+mod chart_area;
+mod chart_options;
+mod chart_view;
 mod collatz;
+mod deep_link;
+mod diagnostics;
+mod difference;
+mod error_report;
+mod experiment_log;
+mod focus_ring;
+mod fractal;
+mod game;
+mod i18n;
+mod image_format;
+mod input;
+mod json;
+mod notification;
+#[cfg(feature = "gpu")]
+mod gpu;
+mod representation;
+mod results_db;
+mod run_diff;
+mod sampling;
+mod sequence_budget;
+mod sequence_file;
+mod sequence_table;
+mod settings;
+mod siblings;
+mod stats_grid;
+mod summary;
+#[cfg(test)]
+mod test_support;
+mod trajectory_graph;
+mod transform;
+mod tutorial;
+mod verification;
+mod work_plan;
+mod workers;
 
 use iced::{
     widget::{
         button, column, container, row, text, text_input, vertical_space, horizontal_space,
-        scrollable, image,
+        scrollable, image, pick_list, checkbox, tooltip, radio, progress_bar,
     },
-    executor, Application, Command, Element, Length, Settings, Theme, Color, Alignment,
+    executor, Application, Command, Element, Length, Settings, Theme, Color, Alignment, Subscription,
 };
+use futures::SinkExt; // Sending messages from a subscription.
 use plotters::prelude::*; // Drawing charts.
+use plotters::chart::{DualCoordChartContext, SeriesAnno}; // Dual-axis charts and legend entries.
 use plotters::style::Color as PlottersColor; // To avoid conflicts with iced::Color.
 use rand::Rng; // Random number generation
 use std::path::PathBuf; // Working with file paths.
 use clipboard::{ClipboardContext, ClipboardProvider}; // Copying text to the system clipboard.
 use chrono::Local; // Getting the current date and time (used for filenames).
 use std::fs; // Standard library file system utilities.
+use std::sync::{Arc, Mutex}; // Sharing the stopping-time cache with background tasks.
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering}; // Cancelling and following the running job of a work plan.
+use std::collections::VecDeque; // Queue of the jobs of a work plan.
+use std::hash::{Hash, Hasher}; // Fingerprint of the current chart.
 
 // ==========================================================================
 //                              Application State
@@ -34,23 +81,602 @@ pub struct CollatzApp {
     // Vectors to store the generated Collatz sequence for value1/value2.
     sequence1: Vec<u64>,
     sequence2: Vec<u64>,
+    // Series drawn with value 1 in place of value 2: its neighbors n - 1 and n + 1 (neighborhood compare),
+    // its doublings 2n, 4n... (family mode) or the siblings chosen in the sibling finder. Empty otherwise.
+    extra_series: Vec<(u64, Vec<u64>)>,
+    requested_series: Option<Vec<u64>>, // Extra series drawn by the next "Visualize" (neighbors, chosen siblings)
+    family_mode: bool, // Whether value 1 is drawn with its doublings (family mode)
+    family_doublings: usize, // Number of doublings of value 1 drawn in the family mode
+    tail_handling: collatz::TailHandling, // How the end of a sequence that reached 1 is shown
+    tail_cycles: usize, // Number of 4-2-1 cycles appended by `TailHandling::RepeatCycle`
+    glide_only: bool, // Whether the sequences stop once below their start, and the range scans compute glides
+
+    // Custom names of sequence1/sequence2, used in the legend and the exports (blank: automatic name).
+    name1: String,
+    name2: String,
     
     // Statistics
     // Option containing statistics for sequence1/sequence2, if calculated.
     stats1: Option<collatz::CollatzStats>,
     stats2: Option<collatz::CollatzStats>,
+    // Total stopping time of each value with the arithmetic it needed (computed sequences only).
+    arithmetic1: Option<collatz::AutoStoppingTime>,
+    arithmetic2: Option<collatz::AutoStoppingTime>,
+    // Whole trajectory of each sequence stopped by an overflow, followed beyond 64 bits.
+    wide1: Option<collatz::WideTrajectory>,
+    wide2: Option<collatz::WideTrajectory>,
+    // Bit lengths of the terms of these whole trajectories (empty for the other sequences), kept one in
+    // `step_stride` like the sequences: the bit-length view plots them in place of the sequences cut at the overflow.
+    wide_bits: [Vec<u64>; 2],
     
     // Application State Flags
     error_message: String, // String to display error messages to the user.
     chart_saved: bool, // Flag to indicate if the chart was successfully saved recently.
+    chart_printed: bool, // Flag to indicate if the chart was successfully sent to the printer recently.
+    view_exported: bool, // Flag to indicate if the full view was successfully exported recently.
+    sequences_exported: bool, // Flag to indicate if the sequences were successfully exported recently.
+    steps_exported: bool, // Flag to indicate if the step-by-step explanation was successfully exported recently.
+    table_exported: bool, // Flag to indicate if the sequence table was successfully exported recently.
+    run_diff_exported: bool, // Flag to indicate if the comparison of two runs was successfully exported recently.
+    graph_exported: bool, // Flag to indicate if the edges of the merged graph were successfully exported recently.
+    results_exported: bool, // Flag to indicate if the results of the database browser were successfully exported recently.
     copied_to_clipboard: bool, // Flag to indicate if the sequences were successfully copied recently.
+    link_copied: bool, // Flag to indicate if the link of the exploration was copied recently.
+    stat_copied: Option<&'static str>, // Label of the statistic whose value was copied recently, if any.
+    summary_copied: bool, // Flag to indicate if the accessible summary was copied recently.
+    chart_gestures: chart_view::GestureAccumulator, // Gestures on the chart too small to move it yet
+    gesture_render_pending: bool, // Whether gestures moved the chart while it was being drawn, so it is drawn again once done
+    chart_menu: Option<f32>, // Horizontal position of the right click on the chart image, while its context menu is open
+    auto_visualize: bool, // Whether the adjustment buttons beside the inputs re-run the visualization.
+    input_link: input::InputLink, // Relation deriving value 2 from value 1 (linked input mode), or Off
+    log_experiments: bool, // Whether every "Visualize" run is appended to the experiment log.
+    store_results: bool, // Whether the statistics of the computed sequences are kept in the results database.
+    language: i18n::Language, // Language of the texts looked up in the i18n catalog (statistic explanations...).
     
     // Chart
     // Option storing the file path to the currently generated chart image.
     // This is likely a temporary file until saved permanently.
     chart_path: Option<String>,
+    // Number of the latest chart generation: a chart finishing after a newer one was started is discarded,
+    // so that rapid clicks on "Visualize" or "Randomize" always end on the latest chart.
+    chart_generation: u64,
+    // Generation of the sequence chart being rendered, if any: gestures during its rendering wait for it.
+    sequence_render: Option<u64>,
+    // Fingerprint of what the chart of the latest generation shows (chart kind, sequences, options),
+    // so that "Visualize" without any change keeps it instead of drawing it again. None for other charts.
+    chart_fingerprint: Option<u64>,
+    // Fingerprint of the chart on screen (`chart_path`), under which it is cached when replaced.
+    shown_fingerprint: Option<u64>,
+    // Recently replaced sequence charts, shown again at once when their inputs come back.
+    recent_charts: chart_cache::ChartCache,
+
+    // Kind of chart produced by the "Visualize" button.
+    chart_kind: ChartKind,
+
+    // Transform applied to each term before plotting (sequence chart only).
+    transform: transform::TermTransform,
+
+    // Quantity plotted by the difference chart (difference or log-ratio of the terms).
+    difference_mode: difference::DifferenceMode,
+
+    // Settings of the sequence chart (custom caption...), and whether their form is shown.
+    chart_options: chart_options::ChartOptions,
+    show_chart_options: bool,
+
+    // Encoding of the terms in exported binary sequence files.
+    binary_encoding: sequence_file::BinaryEncoding,
+
+    // Whether a permanent copy of every generated chart is saved, the folder it goes to (text, as typed;
+    // the current directory when blank, like "Save the graph") and the path of the last copy.
+    auto_save: bool,
+    auto_save_dir_input: String,
+    auto_saved_to: Option<String>,
+
+    // Format of the saved charts ("Save the graph" and the automatic copies), and the quality of the lossy ones.
+    image_format: image_format::ImageFormat,
+    image_quality: u8,
+
+    // Path of the sequence file to import (text, as typed), and the file the current
+    // first sequence was imported from (None when it was computed by "Visualize").
+    import_path_input: String,
+    imported_from: Option<String>,
+
+    // Result of the "Verify" action on the imported sequence (None until it is run).
+    import_check: Option<Result<(), collatz::Mismatch>>,
+
+    // Whether the base-3 companion panel is shown next to the sequence charts.
+    show_ternary: bool,
+
+    // Whether the step-by-step explanation panel (the rule applied at each step) is shown.
+    show_steps: bool,
+    // Text of the steps panel, written when the sequences or their names change rather than on every frame.
+    steps_shown: String,
+
+    // Sequence table panel: whether it is shown, the sequence it lists (0 or 1), its columns, its quick filters,
+    // its selected rows, and the format the selected rows are copied in.
+    show_table: bool,
+    table_sequence: usize,
+    table_columns: Vec<sequence_table::Column>,
+    table_filters: Vec<sequence_table::Filter>,
+    table_selection: sequence_table::RowSelection,
+    table_format: sequence_table::CopyFormat,
+
+    // Sibling finder panel: whether it is shown, its budget of steps and bound (as typed),
+    // the siblings found for `siblings_of`, and the siblings ticked to be plotted.
+    show_siblings: bool,
+    sibling_steps_input: String,
+    sibling_bound_input: String,
+    siblings_of: Option<u64>,
+    siblings: Vec<siblings::Sibling>,
+    sibling_selection: Vec<u64>,
+
+    // Keys held (Shift extends the selection of the sequence table).
+    modifiers: iced::keyboard::Modifiers,
+
+    // Settings kept between runs (see `settings`), written back when one of them changes.
+    user_settings: settings::UserSettings,
+
+    // Current step of the guided tour, None when it is not shown.
+    tour: Option<tutorial::TourStep>,
+
+    // Run described by the metadata of an imported chart image, waiting for the user to restore it:
+    // the description shown and the link of the run.
+    chart_run: Option<(String, deep_link::DeepLink)>,
+
+    // Range inputs
+    // Strings holding the bounds of the range of starting values (used by range charts).
+    range_start_input: String,
+    range_end_input: String,
+
+    // Summary of the last range computation, if any.
+    range_summary: Option<collatz::RangeSummary>,
+
+    // Current streamed range scan (density or scatter chart), if any.
+    range_scan: Option<RangeScan>,
+    range_scan_job: u64,
+
+    // Backend computing the stopping times of exhaustive range scans.
+    scan_backend: ScanBackend,
+
+    // Memoized stopping times, persisted between runs (used by the CPU range scans).
+    stopping_time_cache: Arc<Mutex<cache::StoppingTimeCache>>,
+
+    // Modulus used to color the range scatter by total stopping time mod k (text, as typed).
+    // Empty (or 1) draws a single-color scatter.
+    scatter_modulus_input: String,
+
+    // Largest number of bins of the stopping-time histogram (text, as typed).
+    histogram_bins_input: String,
+
+    // Cumulative distribution of the stopping times of the last range (label of the range and curve),
+    // and that of the range before it, drawn underneath for comparison.
+    cdf_current: Option<CdfCurve>,
+    cdf_reference: Option<CdfCurve>,
+
+    // Counts of the trajectories through the first value, for the last range computed.
+    through_counts: Option<ThroughCounts>,
+
+    // Most-visited values of the last range computed.
+    most_visited: Option<VisitRanking>,
+
+    // Starting values of the merged trajectory graph (text, as typed: integers separated by commas or spaces),
+    // and the graph on screen.
+    graph_starts_input: String,
+    merged_graph: Option<collatz::TrajectoryGraph>,
+
+    // Divergence rule of the map comparison (text, as typed): a trajectory whose terms stay above the bound
+    // for the number of steps is reported as apparently divergent.
+    divergence_bound_input: String,
+    divergence_steps_input: String,
+
+    // Window of the escape-time fractal (center and width, as typed), and the window of the fractal on screen.
+    fractal_re_input: String,
+    fractal_im_input: String,
+    fractal_width_input: String,
+    fractal_window: Option<fractal::FractalWindow>,
+
+    // Largest number of steps computed for a trajectory (text, as typed), and the limits sequences 1 and 2
+    // were computed with (the extra series of a run share the limit of sequence 1):
+    // a sequence cut at its limit is marked as truncated.
+    step_limit_input: String,
+    step_limits: [usize; 2],
+
+    // Memory budget of the stored sequences in megabytes (text, as typed, and the budget the current sequences
+    // were computed with), and what is known of sequences 1 and 2 when they were kept downsampled to fit in it.
+    memory_budget_input: String,
+    memory_budget: usize,
+    downsampled: [Option<sequence_budget::Downsampled>; 2],
+
+    // Monte Carlo sampling: number of random starting values (text, as typed),
+    // how they are drawn, and the report of the last sample.
+    sample_count_input: String,
+    sampling_distribution: sampling::SamplingDistribution,
+    sample_report: Option<sampling::SampleReport>,
+
+    // Trajectories of the first value under each map variant (map comparison chart).
+    comparison: Vec<collatz::VariantTrajectory>,
+
+    // Range verification: progress of the current (or resumable) verification, whether it is running,
+    // and the number of the current job, so that a chunk of a replaced verification is ignored.
+    verification: Option<verification::Checkpoint>,
+    verification_running: bool,
+    verification_job: u64,
+    // Whether the next verification writes a report when it completes, and the report written last.
+    verification_report: bool,
+    verification_report_path: Option<PathBuf>,
+
+    // Timing breakdown of the last sequence visualization, and whether its panel is expanded.
+    diagnostics: Option<diagnostics::PhaseTimings>,
+    show_diagnostics: bool,
+    // Number and total size of the temporary files, counted when the diagnostics panel is opened.
+    temp_files: Option<(usize, u64)>,
+
+    // Unexpected failure shown in the error dialog, and where its report was saved (or why it could not be).
+    // The latest lines of the application log are kept for the reports.
+    error_dialog: Option<error_report::Failure>,
+    error_report_saved: Option<Result<PathBuf, String>>,
+    recent_log: error_report::RecentLog,
+
+    // Latest runs read from the experiment log (newest first), and whether the history panel is shown.
+    history: Vec<experiment_log::RunRecord>,
+    show_history: bool,
+
+    // Runs of the history ticked to be compared (at most two, kept when the history is read again).
+    compared_runs: Vec<experiment_log::RunRecord>,
+
+    // Results database as read for the browser, whether the browser is shown, its filters as typed
+    // (smallest and largest stopping time, smallest and largest peak, first day) and its sorting.
+    results_db: results_db::ResultsDb,
+    show_results_db: bool,
+    results_filter_inputs: [String; 5],
+    results_sort: results_db::SortColumn,
+    results_descending: bool,
+
+    // Current "Guess" game (None when the game panel is hidden), and the guesses as typed.
+    game: Option<game::Game>,
+    flight_guess_input: String,
+    peak_guess_input: String,
+
+    // Work plan: its file as typed, the jobs waiting to run and the one running (with their number in the plan),
+    // the outcome of each finished job, and the folder the outputs of the plan are written to.
+    plan_path_input: String,
+    plan_queue: VecDeque<(usize, work_plan::PlanJob)>,
+    plan_running: Option<(usize, work_plan::PlanJob)>,
+    plan_done: Vec<(usize, work_plan::PlanJob, Result<String, String>)>,
+    plan_dir: PathBuf,
+
+    // Flag asking the running job of the work plan to stop, the number of values of its range scanned so far,
+    // and whether the jobs panel is shown.
+    plan_cancel: Arc<AtomicBool>,
+    plan_progress: Arc<AtomicU64>,
+    show_jobs: bool,
+
+    // Whether a desktop notification is shown when a background job finishes while the window
+    // is in the background, and whether the window has the focus.
+    notify_jobs: bool,
+    window_focused: bool,
+
+    // Number of worker threads of the parallel computations (a persisted setting, see `workers`).
+    worker_threads: workers::WorkerThreads,
+}
+
+// ==========================================================================
+//                                 Chart Kinds
+// ==========================================================================
+// The different kinds of chart the application can produce.
+// The selected kind decides what "Visualize" computes and draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChartKind {
+    Sequences, // The trajectories of one or two starting values.
+    StoppingTimeDensity, // A 2D histogram of (n, total stopping time) over a range.
+    StoppingTimeScatter, // One point per starting value of a range, at (n, total stopping time).
+    StoppingTimeHistogram, // The distribution of the total stopping times over a range.
+    StoppingTimeCdf, // The empirical cumulative distribution of the total stopping times over a range.
+    PeakScatter, // One point per starting value of a range, at (n, peak), on log-log axes.
+    ThroughValue, // How many trajectories of a range pass through each term of the trajectory of the first value.
+    MostVisited, // The values visited by the most trajectories of a range, ranked.
+    MonteCarlo, // Distribution of total stopping times over random starting values of a huge range.
+    MapComparison, // The first value under several map variants (3n+1, 3n-1, 5n+1).
+    BitMatrix, // The binary digits of every term, one row per step.
+    Difference, // The pointwise difference (or log-ratio) of the two sequences, step by step.
+    Verification, // Checks that every starting value of a (huge) range reaches 1, with checkpoints.
+    Fractal, // The escape-time fractal of the Collatz map extended to the complex plane.
+    MergedGraph, // The union of the trajectories of several starting values, as a tree.
+}
+
+impl ChartKind {
+    // Every chart kind, in the order shown in the selector.
+    const ALL: [ChartKind; 15] = [
+        ChartKind::Sequences,
+        ChartKind::StoppingTimeDensity,
+        ChartKind::StoppingTimeScatter,
+        ChartKind::StoppingTimeHistogram,
+        ChartKind::StoppingTimeCdf,
+        ChartKind::PeakScatter,
+        ChartKind::ThroughValue,
+        ChartKind::MostVisited,
+        ChartKind::MonteCarlo,
+        ChartKind::MapComparison,
+        ChartKind::BitMatrix,
+        ChartKind::Difference,
+        ChartKind::Verification,
+        ChartKind::Fractal,
+        ChartKind::MergedGraph,
+    ];
+
+    /// Returns true if this chart kind is drawn from the sequences of the two value inputs.
+    fn uses_sequences(self) -> bool {
+        matches!(self, ChartKind::Sequences | ChartKind::BitMatrix | ChartKind::Difference)
+    }
+
+    /// Returns true if this chart kind works on a range of starting values
+    /// rather than on the two value inputs.
+    fn uses_range(self) -> bool {
+        matches!(
+            self,
+            ChartKind::StoppingTimeDensity
+                | ChartKind::StoppingTimeScatter
+                | ChartKind::StoppingTimeHistogram
+                | ChartKind::StoppingTimeCdf
+                | ChartKind::PeakScatter
+                | ChartKind::ThroughValue
+                | ChartKind::MostVisited
+                | ChartKind::MonteCarlo
+                | ChartKind::Verification
+        )
+    }
+
+    /// Returns the name of this chart kind in the `collatz://` links (`chart` parameter).
+    fn slug(self) -> &'static str {
+        match self {
+            ChartKind::Sequences => "sequences",
+            ChartKind::StoppingTimeDensity => "density",
+            ChartKind::StoppingTimeScatter => "scatter",
+            ChartKind::StoppingTimeHistogram => "histogram",
+            ChartKind::StoppingTimeCdf => "cdf",
+            ChartKind::PeakScatter => "peaks",
+            ChartKind::ThroughValue => "through",
+            ChartKind::MostVisited => "visited",
+            ChartKind::MonteCarlo => "montecarlo",
+            ChartKind::MapComparison => "comparison",
+            ChartKind::BitMatrix => "bits",
+            ChartKind::Difference => "difference",
+            ChartKind::Verification => "verification",
+            ChartKind::Fractal => "fractal",
+            ChartKind::MergedGraph => "graph",
+        }
+    }
+
+    /// Returns the chart kind named `slug` in a link, if any.
+    fn from_slug(slug: &str) -> Option<ChartKind> {
+        ChartKind::ALL.into_iter().find(|kind| kind.slug() == slug)
+    }
+}
+
+impl std::fmt::Display for ChartKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            ChartKind::Sequences => "Sequences",
+            ChartKind::StoppingTimeDensity => "Stopping-time density",
+            ChartKind::StoppingTimeScatter => "Stopping-time scatter",
+            ChartKind::StoppingTimeHistogram => "Stopping-time histogram",
+            ChartKind::StoppingTimeCdf => "Stopping-time CDF",
+            ChartKind::PeakScatter => "Peak vs n (log-log)",
+            ChartKind::ThroughValue => "Trajectories through a value",
+            ChartKind::MostVisited => "Most-visited values",
+            ChartKind::MonteCarlo => "Monte Carlo sample",
+            ChartKind::MapComparison => "Map comparison",
+            ChartKind::BitMatrix => "Bit matrix",
+            ChartKind::Difference => "Difference",
+            ChartKind::Verification => "Range verification",
+            ChartKind::Fractal => "Escape-time fractal",
+            ChartKind::MergedGraph => "Merged trajectories",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+// ==========================================================================
+//                              Streamed Range Scans
+// ==========================================================================
+// A range scan (density or scatter chart) runs in a subscription, chunk by chunk.
+// After each chunk, the chart is redrawn from the results so far and sent to the interface,
+// so that the chart and the summary fill in progressively instead of appearing only at the end.
+// A paused scan keeps its results in memory; resuming it starts a new subscription from there.
+#[derive(Debug, Clone)]
+struct RangeScan {
+    job: u64, // Number of the scan
+    segment: u64, // Number of times the scan was resumed (with `job`, identifies the subscription)
+    kind: ChartKind, // Density or scatter
+    range_start: u64, // First starting value of the range
+    range_end: u64, // Last starting value of the range
+    modulus: u64, // Number of residue classes coloring the scatter (1 = single color)
+    backend: ScanBackend, // Backend computing the stopping times
+    measure: ScanMeasure, // Total stopping times, or glides in the glide-only mode
+    results: Arc<Mutex<ScanResults>>, // Results computed so far, shared with the subscription
+    scanned: u64, // Number of starting values scanned so far (as last reported)
+    elapsed: std::time::Duration, // Time spent scanning so far (as last reported)
+    running: bool, // Whether the subscription is active (false once done or while paused)
+}
+
+// Results of a range scan, written by its subscription.
+// The position is stored along with the results, so that a scan stopped between a chunk
+// and its report resumes after that chunk.
+#[derive(Debug, Default)]
+struct ScanResults {
+    times: Vec<(u64, usize)>, // (starting value, total stopping time) of a scatter chart, in increasing order
+    tally: Option<collatz::StoppingTimeTally>, // Counts of a density chart, binned as the chunks come
+    scanned: u64, // Number of starting values scanned
+    elapsed: std::time::Duration, // Time spent scanning (excluding pauses), for the throughput
+}
+
+impl RangeScan {
+    /// Returns the number of starting values in the range.
+    fn total(&self) -> u64 {
+        self.range_end - self.range_start + 1
+    }
+}
+
+// Progress report sent by a range scan after each chunk.
+#[derive(Debug, Clone)]
+pub struct RangeScanUpdate {
+    chart_path: String, // Chart drawn from the results so far
+    summary: collatz::RangeSummary, // Summary of the results so far
+    scanned: u64, // Number of starting values scanned so far
+    elapsed: std::time::Duration, // Time spent scanning so far
+}
+
+// ==========================================================================
+//                                Scan Backends
+// ==========================================================================
+// Where the stopping times of exhaustive range scans are computed.
+// The GPU backend is only available when the application is built with the "gpu" feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanBackend {
+    Cpu, // Plain loop on the processor.
+    #[cfg(feature = "gpu")]
+    Gpu, // wgpu compute shader, one invocation per starting value.
+}
+
+impl ScanBackend {
+    // Every backend compiled into the application.
+    const ALL: &'static [ScanBackend] = &[
+        ScanBackend::Cpu,
+        #[cfg(feature = "gpu")]
+        ScanBackend::Gpu,
+    ];
+}
+
+impl std::fmt::Display for ScanBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            ScanBackend::Cpu => "CPU",
+            #[cfg(feature = "gpu")]
+            ScanBackend::Gpu => "GPU",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+// What a range scan computes for each starting value: the glide-only mode scans the glides,
+// which are much shorter than the whole trajectories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanMeasure {
+    TotalStoppingTime, // Steps down to 1
+    Glide, // Steps until the trajectory drops below its starting value
+}
+
+impl ScanMeasure {
+    /// Returns the name of the measure, for the Y axis.
+    fn label(self) -> &'static str {
+        match self {
+            ScanMeasure::TotalStoppingTime => "Total stopping time",
+            ScanMeasure::Glide => "Glide",
+        }
+    }
+
+    /// Returns the name of the measures, for the caption of the charts.
+    fn plural(self) -> &'static str {
+        match self {
+            ScanMeasure::TotalStoppingTime => "Total stopping times",
+            ScanMeasure::Glide => "Glides",
+        }
+    }
+}
+
+// Number of bins used by the density chart along each axis.
+const DENSITY_X_BINS: usize = 200;
+const DENSITY_Y_BINS: usize = 100;
+
+// Largest range accepted by the density chart (the scan is exhaustive).
+const MAX_DENSITY_RANGE: u64 = 50_000_000;
+
+// Cumulative distribution of the stopping times of a range: the range ("1 to 1000")
+// and the fraction of its starting values reaching 1 within each number of steps.
+type CdfCurve = (String, Vec<(usize, f64)>);
+
+// Trajectories through a value: the range of starting values ("1 to 1000"), and each term of the trajectory
+// of the value with the number of trajectories of the range passing through it (see `collatz::passes_through`).
+type ThroughCounts = (String, Vec<(u64, u64)>);
+
+// Number of hubs listed by the "trajectories through a value" chart.
+const HUB_COUNT: usize = 5;
+
+// Most-visited values of a range: its first and last starting values, and each value
+// with the number of trajectories visiting it (see `collatz::most_visited`).
+type VisitRanking = (u64, u64, Vec<(u64, u64)>);
+
+// Largest number of starting values of the merged trajectory graph.
+const MAX_GRAPH_STARTS: usize = 100;
+
+// Number of values ranked by the "most-visited values" chart.
+const TOP_VISITED: usize = 20;
+
+// Number of bins of the stopping-time histogram by default.
+const DEFAULT_HISTOGRAM_BINS: usize = 50;
+
+// Largest range accepted by the scatter chart (one point is drawn per starting value).
+const MAX_SCATTER_RANGE: u64 = 200_000;
+
+// Largest modulus whose classes get their own legend entry on the scatter chart.
+const MAX_LEGEND_CLASSES: u64 = 12;
+
+// Largest number of random starting values in a Monte Carlo sample.
+const MAX_SAMPLES: usize = 1_000_000;
+
+// Number of steps of each sequence listed by the steps panel (the exported explanation has them all).
+const MAX_STEPS_SHOWN: usize = 1000;
+
+// Largest size of a bit matrix cell, in pixels, and largest height of the bit matrix image.
+const BIT_CELL_MAX_SIZE: u32 = 16;
+const BIT_MATRIX_MAX_HEIGHT: u32 = 4000;
+
+// A range scan sends about this many progressive updates, each covering at least MIN_STREAM_CHUNK values.
+const STREAM_UPDATES: u64 = 20;
+const MIN_STREAM_CHUNK: u64 = 10_000;
+
+// Resolution multiplier of printed charts (3200 x 1600 pixels, about 300 dpi across a page).
+const PRINT_SCALE: u32 = 4;
+
+// Anti-aliasing: the chart is rendered SUPERSAMPLING times larger, then scaled down to its size.
+// The rendering scale is capped, so that print renders do not need huge buffers.
+const SUPERSAMPLING: u32 = 3;
+const MAX_RENDER_SCALE: u32 = 8;
+
+// Entries of the context menu of the chart (right click).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartMenuEntry {
+    SaveImage, // Same as "Save the graph"
+    CopyImage, // The chart image (PNG) on the clipboard
+    CopyData,  // The terms of the sequences at the step under the cursor on the clipboard
+    ResetZoom, // The whole sequences again
 }
 
+// Number of starting values verified between two checkpoints.
+const VERIFICATION_CHUNK: u64 = 2_000_000;
+
+// Numbers of 4-2-1 cycles offered to be appended to the sequences.
+const TAIL_CYCLES: [usize; 5] = [1, 2, 3, 4, 5];
+
+// Number of siblings listed by the sibling finder, and of siblings plotted at once.
+const MAX_LISTED_SIBLINGS: usize = 500;
+const MAX_PLOTTED_SIBLINGS: usize = 8;
+
+// Number of past runs listed in the history panel.
+const HISTORY_LENGTH: usize = 50;
+
+// Number of results listed by the database browser.
+const MAX_LISTED_RESULTS: usize = 500;
+
+// Number of starting values computed between two checks for the cancellation of a range job of a work plan.
+const PLAN_CHUNK_SIZE: u64 = 1_000_000;
+
+// Numbers of doublings offered for the family mode (n with 2n, with 2n and 4n...).
+const FAMILY_DOUBLINGS: [usize; 6] = [1, 2, 3, 4, 5, 6];
+
+// Number of replaced sequence charts kept in the cache of recent charts.
+const RECENT_CHARTS: usize = 8;
+
 // ==========================================================================
 //                               Messages (events)
 // ==========================================================================
@@ -60,14 +686,180 @@ pub struct CollatzApp {
 pub enum Message {
     Input1Changed(String), // Text in the 1st input box changes. Contains the new text.
     Input2Changed(String), // Text in the 2nd input box changes. Contains the new text.
+    AdjustInput(usize, input::Adjustment), // A "+1", "-1", "x2" or "Next odd" button beside an input is pressed.
+    InputLinkSelected(input::InputLink), // A relation deriving value 2 from value 1 is picked (or Off).
+    AutoVisualizeToggled(bool), // The "Auto-visualize" checkbox is toggled.
+    LogExperimentsToggled(bool), // The "Log runs" checkbox is toggled.
+    StoreResultsToggled(bool), // The "Store results" checkbox is toggled.
+    ShowHistoryToggled(bool), // The "History" checkbox is toggled.
+    RestoreRun(usize), // An entry of the history panel is clicked (index in the history).
+    CompareRunToggled(usize, bool), // The "compare" checkbox of an entry of the history panel is toggled.
+    ExportRunDiff, // "Export diff" button of the history panel is pressed.
+    ShowResultsDbToggled(bool), // The "Results" checkbox is toggled.
+    ResultsFilterChanged(usize, String), // Text in a filter input box of the database browser changes (index of the filter).
+    ResultsSortClicked(results_db::SortColumn), // A column header of the database browser is clicked.
+    OpenStoredResult(u64), // A result of the database browser is clicked (its starting value).
+    ExportResults, // "Export CSV" button of the database browser is pressed.
+    ResultsExported(Result<(), String>), // Result of writing the results of the database browser to a file.
+    RunDiffExported(Result<(), String>), // Result of writing the comparison of two runs to a file.
+    GameToggled(bool), // The "Guess" checkbox is toggled: a game starts, or the current one ends.
+    FlightGuessChanged(String), // Text in the "flight time" guess input box changes.
+    PeakGuessChanged(String), // Text in the "peak" guess input box changes.
+    RevealGuess, // "Reveal" button of the game is pressed.
+    NextGuess, // "Next value" button of the game is pressed.
+    PreviousValue, // The "◀" button (or the left arrow key) is pressed: visualize value 1 - 1.
+    NextValue, // The "▶" button (or the right arrow key) is pressed: visualize value 1 + 1.
+    CompareNeighbors, // The "Compare neighbors" button is pressed: visualize value 1 - 1, value 1 and value 1 + 1 together.
+    FamilyToggled(bool), // The "Family" checkbox is toggled: value 1 is drawn with its doublings, or alone again.
+    FamilyDoublingsSelected(usize), // A number of doublings is picked for the family mode.
+    TailHandlingSelected(collatz::TailHandling), // A handling of the 4-2-1 tail is picked.
+    TailCyclesSelected(usize), // A number of 4-2-1 cycles to append is picked.
+    GlideOnlyToggled(bool), // The "Glide only" checkbox is toggled.
+    FocusNext, // Tab is pressed: the focus moves to the next input, button, checkbox or selector.
+    FocusPrevious, // Shift+Tab is pressed: the focus moves to the previous input, button, checkbox or selector.
+    DefaultAction, // Enter or Space is pressed with no input or control focused: next step of the tour, or "Visualize".
+    ChartKindSelected(ChartKind), // A chart kind is picked in the selector.
+    TransformSelected(transform::TermTransform), // A per-term transform is picked in the selector.
+    DifferenceModeSelected(difference::DifferenceMode), // Difference or log-ratio, for the difference chart.
+    ShowTernaryToggled(bool), // The "Base 3" checkbox is toggled.
+    ShowStepsToggled(bool), // The "Steps" checkbox is toggled.
+    ExportSteps, // "Export steps" button of the step-by-step panel is pressed.
+    ShowTableToggled(bool), // The "Table" checkbox is toggled.
+    TableSequenceSelected(usize), // The sequence listed by the table (0 or 1) is picked.
+    TableColumnToggled(sequence_table::Column, bool), // A column of the sequence table is shown or hidden.
+    TableFilterToggled(sequence_table::Filter, bool), // A quick filter of the sequence table is toggled.
+    SettingsSaved(Result<(), String>), // Result of writing the settings file.
+    TableRowClicked(usize), // A row of the sequence table is clicked (its step).
+    TableFormatSelected(sequence_table::CopyFormat), // A format is picked for the copied rows and the exported table.
+    CopyTableRows, // "Copy selection" button of the sequence table is pressed.
+    ExportTable, // "Export table view" button of the sequence table is pressed.
+    TableExported(Result<(), String>), // Result of writing the sequence table to a file.
+    ModifiersChanged(iced::keyboard::Modifiers), // A modifier key (Shift, Ctrl...) is pressed or released.
+    ShowSiblingsToggled(bool), // The "Siblings" checkbox is toggled.
+    SiblingStepsChanged(String), // Text in the "Steps" input of the sibling finder changes.
+    SiblingBoundChanged(String), // Text in the "Below" input of the sibling finder changes.
+    FindSiblings, // "Find" button of the sibling finder is pressed.
+    SiblingToggled(u64, bool), // The checkbox of a sibling is toggled (to plot it or not).
+    PlotSiblings, // "Plot selected" button of the sibling finder is pressed.
+    ShowTutorial, // "Help" button is pressed: the guided tour starts again.
+    TourNext, // "Next" button of the guided tour is pressed.
+    TourPrevious, // "Back" button of the guided tour is pressed.
+    TourDismissed, // "Close" (or "Done" on the last step) button of the guided tour is pressed.
+    TutorialSeen(Result<(), String>), // Result of writing the marker of the guided tour.
+    StepsExported(Result<(), String>), // Result of writing the step-by-step explanation to a file.
+    ExportGraph, // "Export edges" button of the merged graph is pressed.
+    GraphExported(Result<(), String>), // Result of writing the edges of the merged graph to a file.
+    ShowChartOptionsToggled(bool), // The "Chart options" checkbox is toggled.
+    CaptionChanged(String), // Text in the "caption" input box of the chart options changes.
+    SubtitleChanged(String), // Text in the "subtitle" input box of the chart options changes.
+    XLabelChanged(String), // Text in the "X axis" input box of the chart options changes.
+    YLabelChanged(String), // Text in the "Y axis" input box of the chart options changes.
+    TickSizeSelected(u32), // A tick font size is picked in the chart options.
+    PaletteSelected(chart_options::Palette), // A color palette of the series is picked in the chart options.
+    TextScaleSelected(u32), // A text scale of the chart (percent) is picked in the chart options.
+    ChartGesture(chart_view::Gesture), // The sequence chart is dragged, pinched or zoomed with the mouse wheel.
+    ChartMenuOpened(f32), // The chart is right-clicked (horizontal position on the image).
+    ChartMenuClosed, // The context menu of the chart is dismissed (click elsewhere or Escape).
+    ChartMenuSelected(ChartMenuEntry), // An entry of the context menu of the chart is clicked.
+    TickFormatSelected(chart_options::TickFormat), // A tick format is picked in the chart options.
+    Name1Changed(String), // Text in the "name 1" input box of the chart options changes.
+    Name2Changed(String), // Text in the "name 2" input box of the chart options changes.
+    LineStyleSelected(usize, chart_options::LineStyle), // A line style is picked for a series (0 or 1).
+    MarkersToggled(usize, bool), // The "Markers" checkbox of a series (0 or 1) is toggled.
+    MarkerShapeSelected(usize, chart_options::MarkerShape), // A marker shape is picked for a series (0 or 1).
+    LineWidthSelected(u32), // A line width is picked in the chart options.
+    AntialiasToggled(bool), // The "Anti-aliasing" checkbox of the chart options is toggled.
+    LogScaleToggled(bool), // The "Log scale" checkbox of the chart options is toggled.
+    YScalingSelected(chart_options::YScaling), // A Y scaling (shared, each to its max) is picked in the chart options.
+    LayoutSelected(chart_options::ChartLayout), // A layout (overlay, stacked panels) is picked in the chart options.
+    AlignmentSelected(chart_options::SequenceAlignment), // An alignment (start, peak, final 1) is picked in the chart options.
+    ShowDiagnosticsToggled(bool), // The "Diagnostics" checkbox is toggled.
+    CountTempFiles, // "Refresh" button of the diagnostics panel is pressed.
+    TempFilesCounted(Result<(usize, u64), String>), // Number and total size of the temporary files.
+    FreeMemory, // "Free memory" button of the diagnostics panel is pressed.
+    MemoryFreed(Result<(), String>), // The cached data is dropped.
+    RangeStartChanged(String), // Text in the "range start" input box changes.
+    RangeEndChanged(String), // Text in the "range end" input box changes.
+    ScatterModulusChanged(String), // Text in the "color by T mod k" input box changes.
+    HistogramBinsChanged(String), // Text in the "bins" input box of the histogram changes.
+    GraphStartsChanged(String), // Text in the "starts" input box of the merged graph changes.
+    DivergenceBoundChanged(String), // Text in the "divergent above" input box of the map comparison changes.
+    DivergenceStepsChanged(String), // Text in the "for ... steps" input box of the map comparison changes.
+    StepLimitChanged(String), // Text in the "step limit" input box changes.
+    MemoryBudgetChanged(String), // Text in the "memory budget" input box changes.
+    FractalReChanged(String), // Text in the "center (real part)" input box of the fractal changes.
+    FractalImChanged(String), // Text in the "center (imaginary part)" input box of the fractal changes.
+    FractalWidthChanged(String), // Text in the "width" input box of the fractal changes.
+    ZoomFractal(f64), // A zoom button of the fractal is pressed: the width is multiplied by the factor.
+    ScanBackendSelected(ScanBackend), // A range scan backend is picked.
+    SampleCountChanged(String), // Text in the "samples" input box changes.
+    SamplingDistributionSelected(sampling::SamplingDistribution), // A sampling distribution is picked.
     Visualize, // "Visualize" button is pressed.
     Randomize, // "Randomize" button is pressed.
     SaveChart, // "Save Chart" button is pressed.
     CopyToClipboard, // "Copy" button is pressed.
+    CopyLink, // "Copy link" button is pressed.
+    LinkCopied(Result<(), String>), // Result of placing the link on the clipboard.
+    CopyStat(&'static str, String), // The copy button of a statistic cell is pressed, with its label and value.
+    CopySummary, // "Describe" button is pressed: the accessible summary is copied.
+    SummaryCopied(Result<(), String>), // Result of placing the accessible summary on the clipboard.
+    StatCopied(&'static str, Result<(), String>), // Result of placing a statistic on the clipboard.
+    OpenWindow, // "New window" button is pressed.
+    PrintChart, // "Print" button is pressed.
+    ExportView, // "Export view" button is pressed.
+    ExportBinary, // "Export binary" button is pressed.
+    BinaryEncodingSelected(sequence_file::BinaryEncoding), // An encoding is picked for binary exports.
+    ImportPathChanged(String), // Text in the "file to import" input box changes.
+    AutoSaveToggled(bool), // The "Auto-save to" checkbox is toggled.
+    AutoSaveDirChanged(String), // Text in the auto-save folder input box changes.
+    ImageFormatSelected(image_format::ImageFormat), // A format is picked for the saved charts.
+    ImageQualitySelected(u8), // A quality is picked for the lossy formats.
+    ImportSequence, // "Import" button is pressed.
+    PlanPathChanged(String), // Text in the "work plan" input box changes.
+    RunPlan, // "Run plan" button is pressed.
+    ShowJobsToggled(bool), // The "Jobs" checkbox is toggled.
+    CancelRangeScan, // "Cancel" button of the range scan in the jobs panel is pressed.
+    CancelPlanJob(usize), // "Cancel" button of a job of the work plan is pressed (its number in the plan).
+    PlanProgressTick, // The progress of the running job of the work plan is read again (while the jobs panel is shown).
+    OpenPlanResult(usize), // "Open result" button of a finished job of the work plan is pressed (its number in the plan).
+    NotifyJobsToggled(bool), // The "Notify" checkbox is toggled.
+    WorkerThreadsSelected(workers::WorkerThreads), // A number of worker threads is picked.
+    WindowFocusChanged(bool), // The window gains (true) or loses (false) the focus.
+    FileDropped(PathBuf), // A file is dropped onto the window (imported like a typed path).
+    ChartRunRead(Result<(String, deep_link::DeepLink), String>), // Run described by an imported chart image.
+    ApplyChartRun, // "Restore run" button of an imported chart image is pressed.
+    DismissChartRun, // "Dismiss" button of an imported chart image is pressed.
+    VerifyImport, // "Verify" button of an imported sequence is pressed.
     
-    // Message sent *after* the chart generation task completes.
+    // Message sent *after* the chart generation task completes, with the generation number.
     // Contains Ok(path_string) on success, or Err(error_message) on failure.
-    ChartGenerated(Result<String, String>),
+    ChartGenerated(u64, Result<String, String>),
+
+    // Message sent *after* the sequence chart generation task completes, with the generation number.
+    // Contains the chart path and the timings of the chart phases, or Err(error_message) on failure.
+    SequenceChartGenerated(u64, Result<(String, diagnostics::PhaseTimings), String>),
+
+    // Message sent by a range scan after each chunk, with the job number.
+    // Contains the chart and summary of the results so far, or Err(error_message) on failure.
+    RangeScanProgress(u64, Result<RangeScanUpdate, String>),
+
+    // Message sent *after* the Monte Carlo sampling task completes, with the generation number.
+    // Contains the chart path and the sample report on success, or Err(error_message) on failure.
+    SampleGenerated(u64, Result<(String, sampling::SampleReport), String>),
+    RangeChartGenerated(u64, Result<(String, collatz::RangeSummary), String>),
+    CdfGenerated(u64, Result<(String, collatz::RangeSummary, CdfCurve), String>),
+    ThroughGenerated(u64, Result<(String, ThroughCounts), String>),
+    MostVisitedGenerated(u64, Result<(String, VisitRanking), String>),
+
+    // Message sent *after* a chunk of a range verification completes (and its checkpoint is written).
+    // Contains the job number and the updated checkpoint, or Err(error_message) on failure.
+    VerificationProgress(u64, Result<verification::Checkpoint, String>),
+    ResumeVerification, // "Resume verification" button (or "Resume" in the jobs panel) is pressed.
+    PauseVerification, // "Pause" button of the running verification (or of its line in the jobs panel) is pressed.
+    VerificationReportToggled(bool), // The "Overnight report" checkbox is toggled.
+    VerificationReportWritten(PathBuf, Result<(), String>), // Result of writing the report of a completed verification.
+    PauseRangeScan, // "Pause" button of the running range scan is pressed.
+    ResumeRangeScan, // "Resume" button of the paused range scan is pressed.
 
     // Message sent *after* the chart saving task completes.
     // Contains Ok(()) on success, or Err(error_message) on failure.
@@ -77,6 +869,68 @@ pub enum Message {
     // Contains Ok(()) on success, or Err(error_message) on failure.
     ClipboardCopied(Result<(), String>),
 
+    // Message sent *after* the chart was sent to the printer.
+    // Contains Ok(()) on success, or Err(error_message) on failure.
+    ChartPrinted(Result<(), String>),
+
+    // Message sent *after* a sequence file was read.
+    // Contains the file path and the terms on success, or Err(error_message) on failure.
+    SequenceImported(Result<(String, Vec<u64>), String>),
+
+    // Message sent *after* a work plan was read.
+    // Contains its jobs on success, or Err(error_message) on failure.
+    PlanLoaded(Result<Vec<work_plan::PlanJob>, String>),
+
+    // Message sent *after* the job of a work plan that was running finished.
+    // Contains a line describing its result on success, or Err(error_message) on failure.
+    PlanJobDone(Result<String, String>),
+
+    // Message sent *after* the output of a job was opened with the default application.
+    // Contains Ok(()) on success, or Err(error_message) on failure.
+    PlanResultOpened(Result<(), String>),
+
+    // Message sent *after* the notifier of the system was started.
+    // Contains Ok(()) on success, or Err(error_message) on failure.
+    NotificationSent(Result<(), String>),
+
+    // Message sent *after* the sequences were exported as binary files.
+    // Contains Ok(()) on success, or Err(error_message) on failure.
+    BinaryExported(Result<(), String>),
+
+    // Message sent *after* the full view was exported.
+    // Contains Ok(()) on success, or Err(error_message) on failure.
+    ViewExported(Result<(), String>),
+
+    // Message sent *after* a new window was launched.
+    // Contains Ok(()) on success, or Err(error_message) on failure.
+    WindowOpened(Result<(), String>),
+
+    // Message sent *after* a generated chart was saved automatically.
+    // Contains the path of the copy on success, or Err(error_message) on failure.
+    ChartAutoSaved(Result<String, String>),
+
+    // Message sent *after* a run was appended to the experiment log.
+    // Contains Ok(()) on success, or Err(error_message) on failure.
+    ExperimentLogged(Result<(), String>),
+    ResultsStored(Result<usize, String>),
+    ResultsDbLoaded(Result<results_db::ResultsDb, String>),
+
+    // Message sent *after* the latest runs were read from the experiment log.
+    // Contains the runs (newest first) on success, or Err(error_message) on failure.
+    HistoryLoaded(Result<Vec<experiment_log::RunRecord>, String>),
+
+    // "Save error report" button of the error dialog is pressed, and the report was written (or not) to the path.
+    SaveErrorReport,
+    ErrorReportSaved(PathBuf, Result<(), String>),
+    // "Close" button of the error dialog is pressed.
+    CloseErrorDialog,
+
+    // The window is being closed: its temporary chart is deleted first.
+    CloseRequested,
+
+    // Message sent *after* the chart of a closing window was deleted: the window can close.
+    ReadyToClose(Result<(), String>),
+
     // Message sent *after* the old temporary file cleanup task completes.
     // Contains Ok(()) on success, or Err(error_message) on failure.
     CleanupOldTempFiles(Result<(), String>),
@@ -92,41 +946,207 @@ impl Application for CollatzApp {
     type Executor = executor::Default; // The type of messages our application understands.
     type Message = Message; // The type of messages our application understands. 
     type Theme = Theme; // The theme used for styling the application. Using the default Iced theme.
-    type Flags = (); // Flags are data that can be passed to the application on startup (we don't use any).
+    // Flags are data that can be passed to the application on startup:
+    // the `collatz://` link the application was opened with, if any (or why it could not be read).
+    type Flags = Option<Result<deep_link::DeepLink, String>>;
 
     /// Called once when the application starts.
     /// Initializes the application state (`Self`) and can return an initial `Command`.
     /// The command can be used to perform async tasks or send messages.
-    /// In this case, we don't need to perform any async tasks at startup, so we return `Command::none()`.
-    /// The `flags` parameter can be used to pass data to the application on startup.
-    fn new(_flags: ()) -> (Self, Command<Message>) {
-        // Return the initial state of the application.
-        (
-            Self {
-                // Initialize input strings as empty.
-                input1: String::new(),
-                input2: String::new(),
-
-                // Initialize optional values as None (no values yet).
-                value1: None,
-                value2: None,
-
-                // Initialize sequences as empty vectors.
-                sequence1: Vec::new(),
-                sequence2: Vec::new(),
-
-                // Initialize statistics as None.
-                stats1: None,
-                stats2: None,
-
-                error_message: String::new(), // Initialize error message as empty.
-                chart_saved: false, // Initialize flags as false.
-                copied_to_clipboard: false, // Nothing copied on clipboard yet
-                chart_path: None, // Not chart yet
-            },
+    /// Without a link, we don't need to perform any async tasks at startup, so we return `Command::none()`.
+    /// The `flags` parameter holds the `collatz://` link the application was opened with: its values
+    /// are filled in the inputs and charted right away.
+    fn new(flags: Self::Flags) -> (Self, Command<Message>) {
+        let user_settings = settings::load(&settings::default_path());
+        let memory_budget = user_settings
+            .get(settings::MEMORY_BUDGET)
+            .and_then(|budget| budget.parse::<usize>().ok())
+            .filter(|&budget| budget > 0)
+            .unwrap_or(sequence_budget::DEFAULT_BUDGET_MB);
+        let worker_threads = workers::WorkerThreads::from_setting(user_settings.get(settings::WORKER_THREADS).unwrap_or_default());
+        workers::set_worker_threads(worker_threads);
+        // An unfinished verification from a previous run can be resumed.
+        let checkpoint = verification::load_checkpoint(&verification::default_checkpoint_path())
+            .ok()
+            .filter(|checkpoint| !checkpoint.is_complete());
+        let mut app = Self {
+            // Initialize input strings as empty.
+            input1: String::new(),
+            input2: String::new(),
+
+            // Initialize optional values as None (no values yet).
+            value1: None,
+            value2: None,
+
+            // Initialize sequences as empty vectors.
+            sequence1: Vec::new(),
+            sequence2: Vec::new(),
+            extra_series: Vec::new(),
+            requested_series: None,
+            family_mode: false,
+            family_doublings: 3, // n, 2n, 4n and 8n
+            tail_handling: collatz::TailHandling::StopAtOne, // The sequences stop at the first 1
+            tail_cycles: 2,
+            glide_only: false, // Whole trajectories
+            name1: String::new(), // Automatic names ("Sequence n")
+            name2: String::new(),
+
+            // Initialize statistics as None.
+            stats1: None,
+            stats2: None,
+            arithmetic1: None,
+            arithmetic2: None,
+            wide1: None,
+            wide2: None,
+            wide_bits: [Vec::new(), Vec::new()],
+
+            error_message: String::new(), // Initialize error message as empty.
+            chart_saved: false, // Initialize flags as false.
+            chart_printed: false,
+            view_exported: false,
+            sequences_exported: false,
+            steps_exported: false,
+            table_exported: false,
+            run_diff_exported: false,
+            graph_exported: false,
+            results_exported: false,
+            copied_to_clipboard: false, // Nothing copied on clipboard yet
+            link_copied: false,
+            stat_copied: None,
+            summary_copied: false,
+            chart_gestures: chart_view::GestureAccumulator::default(),
+            gesture_render_pending: false,
+            chart_menu: None,
+            language: i18n::Language::default(),
+            auto_visualize: true, // The buttons are meant for fast exploration
+            input_link: input::InputLink::Off,
+            log_experiments: false, // Nothing is written to disk unless requested
+            store_results: user_settings.get(settings::STORE_RESULTS) == Some("true"),
+            chart_path: None, // Not chart yet
+            chart_generation: 0,
+            sequence_render: None,
+            chart_fingerprint: None,
+            shown_fingerprint: None,
+            recent_charts: chart_cache::ChartCache::new(RECENT_CHARTS),
+            chart_kind: ChartKind::Sequences, // Plain trajectories by default
+            transform: transform::TermTransform::Value, // Plot the terms themselves
+            difference_mode: difference::DifferenceMode::Difference, // Plain difference of the terms
+            chart_options: chart_options::ChartOptions::default(), // Automatic caption
+            show_chart_options: false, // Chart options form hidden until requested
+            binary_encoding: sequence_file::BinaryEncoding::U64, // Fixed-size terms by default
+            auto_save: false, // Charts are only saved on request
+            auto_save_dir_input: String::new(),
+            auto_saved_to: None,
+            image_format: image_format::ImageFormat::Png, // The format the charts are rendered in
+            image_quality: image_format::DEFAULT_QUALITY,
+            import_path_input: String::new(),
+            imported_from: None, // Sequences are computed until a file is imported
+            import_check: None,
+            show_ternary: false, // Base-3 panel hidden until requested
+            show_steps: false, // Step-by-step explanation hidden until requested
+            steps_shown: String::new(), // Written below
+            show_table: false, // Sequence table hidden until requested
+            table_sequence: 0,
+            table_columns: sequence_table::parse_columns(user_settings.get(settings::TABLE_COLUMNS).unwrap_or_default()),
+            table_filters: Vec::new(), // Every row
+            table_selection: sequence_table::RowSelection::default(),
+            table_format: sequence_table::CopyFormat::default(),
+            show_siblings: false, // Sibling finder hidden until requested
+            sibling_steps_input: "10".to_string(),
+            sibling_bound_input: "10000".to_string(),
+            siblings_of: None,
+            siblings: Vec::new(),
+            sibling_selection: Vec::new(),
+            modifiers: iced::keyboard::Modifiers::default(),
+            user_settings,
+            tour: None, // Started below at the first start
+            chart_run: None, // No chart image imported
+            range_start_input: String::from("1"),
+            range_end_input: String::from("100000"),
+            range_summary: None, // No range computed yet
+            range_scan: None, // No range scan running
+            range_scan_job: 0,
+            scan_backend: ScanBackend::Cpu, // The GPU is opt-in
+            // The cache file is only read by the first range scan.
+            stopping_time_cache: Arc::new(Mutex::new(cache::StoppingTimeCache::new(cache::default_path()))),
+            scatter_modulus_input: String::new(), // Single-color scatter by default
+            histogram_bins_input: DEFAULT_HISTOGRAM_BINS.to_string(),
+            cdf_current: None, // No distribution computed yet
+            cdf_reference: None,
+            through_counts: None, // Not computed yet
+            most_visited: None, // Not computed yet
+            graph_starts_input: String::from("27, 31, 41"),
+            merged_graph: None, // No graph drawn yet
+            divergence_bound_input: collatz::DivergenceRule::default().bound.to_string(),
+            divergence_steps_input: collatz::DivergenceRule::default().steps.to_string(),
+            fractal_re_input: fractal::FractalWindow::default().center.re.to_string(),
+            fractal_im_input: fractal::FractalWindow::default().center.im.to_string(),
+            fractal_width_input: fractal::FractalWindow::default().width.to_string(),
+            fractal_window: None, // No fractal drawn yet
+            step_limit_input: collatz::DEFAULT_STEP_LIMIT.to_string(),
+            step_limits: [collatz::DEFAULT_STEP_LIMIT; 2],
+            memory_budget_input: memory_budget.to_string(),
+            memory_budget,
+            downsampled: [None, None], // Nothing computed yet
+            sample_count_input: String::from("10000"),
+            sampling_distribution: sampling::SamplingDistribution::Uniform,
+            sample_report: None, // No sample drawn yet
+            comparison: Vec::new(), // No map comparison yet
+            verification_report: checkpoint.as_ref().is_some_and(|checkpoint| checkpoint.report),
+            verification: checkpoint,
+            verification_running: false,
+            verification_job: 0,
+            verification_report_path: None,
+            diagnostics: None, // Nothing timed yet
+            show_diagnostics: false, // Diagnostics panel collapsed
+            temp_files: None, // Counted when the panel is opened
+            error_dialog: None, // Nothing failed yet
+            error_report_saved: None,
+            recent_log: error_report::RecentLog::default(),
+            history: Vec::new(), // Read from the log when the panel is shown
+            show_history: false,
+            compared_runs: Vec::new(),
+            results_db: results_db::ResultsDb::default(), // Read from the file when the browser is shown
+            show_results_db: false,
+            results_filter_inputs: Default::default(), // Every result
+            results_sort: results_db::SortColumn::LastSeen,
+            results_descending: true, // Latest results first
+            game: None, // Started by the "Guess" checkbox
+            flight_guess_input: String::new(),
+            peak_guess_input: String::new(),
+            plan_path_input: String::new(),
+            plan_queue: VecDeque::new(), // No plan imported yet
+            plan_running: None,
+            plan_done: Vec::new(),
+            plan_dir: PathBuf::new(), // Named when a plan starts
+            plan_cancel: Arc::new(AtomicBool::new(false)),
+            plan_progress: Arc::new(AtomicU64::new(0)),
+            show_jobs: false,
+            notify_jobs: true, // Only shown while the window is in the background
+            window_focused: true,
+            worker_threads,
+        };
+
+        app.refresh_steps_shown();
+
+        // The guided tour starts by itself until it has been dismissed or finished once.
+        if !tutorial::is_seen(&tutorial::default_marker_path()) {
+            app.start_tour();
+        }
+
+        // Apply the link, then visualize as if the button had been pressed.
+        match flags {
+            Some(Ok(link)) => {
+                let command = app.apply_link(link);
+                (app, command)
+            }
+            Some(Err(error)) => {
+                app.error_message = error;
+                (app, Command::none())
+            }
             // No initial command needs to be run when the application starts.
-            Command::none(),
-        )
+            None => (app, Command::none()),
+        }
     }
 
     /// Determines the title of the application window.
@@ -159,12 +1179,26 @@ impl Application for CollatzApp {
     /// This function updates the application's state (`self`) based on the message
     /// and can return a `Command` to perform further actions (like async tasks).
     fn update(&mut self, message: Message) -> Command<Message> {
+        // A new visualization replaces the running range scan (dropping it stops its subscription).
+        if matches!(message, Message::Visualize) {
+            self.range_scan = None;
+            // Value 1 may have been set by anything (tour, random values...): value 2 follows it before the run.
+            self.follow_input_link();
+            // In the family mode, the doublings of value 1 take the place of value 2.
+            if self.family_mode {
+                self.input2 = String::new();
+            }
+        }
+
         match message {
             // --- Input Handling ---
             // When the text in the first input box changes, update the input1 field in the state.
+            // Digit group separators of pasted numbers ("1 000 000", "1,000,000") are removed.
             Message::Input1Changed(value) => {
                 // Update the input1 field in the state with the new text.
-                self.input1 = value;
+                self.input1 = input::normalize_number(&value);
+                // In the linked input mode, value 2 follows.
+                self.follow_input_link();
                 // No further command needed.
                 Command::none()
             }
@@ -172,646 +1206,7038 @@ impl Application for CollatzApp {
             // When the text in the second input box changes, update the input2 field in the state.
             Message::Input2Changed(value) => {
                 // Update the input2 field in the state with the new text.
-                self.input2 = value;
+                self.input2 = input::normalize_number(&value);
                 // No further command needed.
                 Command::none()
             }
             
-            // --- Core Actions ---
-            // When the "Visualize" button is pressed, we need to process the inputs.
-            // This includes parsing the inputs, generating the Collatz sequences,
-            // and creating the chart.
-            Message::Visualize => {
-                // Reset status messages and flags before processing.
-                self.error_message = String::new();
-                self.chart_saved = false;
-                self.copied_to_clipboard = false;
-                
-                // Processing the first input
-                // Parse the first input as a u64 integer.
-                // If parsing fails, set the error message.
-                // If parsing succeeds, generate the Collatz sequence and calculate statistics.
-                match self.input1.trim().parse::<u64>() {
-                    Ok(value) => {
-                        if value == 0 { // Check if the value is greater than 0
-                            self.error_message = "The first value must be greater than 0".to_string();
-                            return Command::none();
-                        }
-                        
-                        self.value1 = Some(value); // Parse the input as a u64.
-                        self.sequence1 = collatz::generate_sequence(value); // Generate the Collatz sequence.
-                        self.stats1 = Some(collatz::calculate_stats(&self.sequence1)); // Calculate statistics.
-                    }
-
-                    // If parsing fails, check if the input is empty.
-                    Err(_) => {
-                        if !self.input1.trim().is_empty() {
-                            self.error_message = "Invalid first value".to_string();
-                        } else {
-                            self.value1 = None;
-                            self.sequence1.clear();
-                            self.stats1 = None;
-                        }
-                    }
+            // When an adjustment button beside an input is pressed, adjust its value,
+            // then visualize it right away if "Auto-visualize" is checked.
+            // A value that cannot be adjusted (invalid input, 1 - 1...) is left as it is.
+            Message::AdjustInput(index, adjustment) => {
+                let signed = index == 0 && self.chart_kind == ChartKind::MapComparison;
+                let input = if index == 0 { &mut self.input1 } else { &mut self.input2 };
+                match input::adjust_start_value(input, adjustment, signed) {
+                    Some(adjusted) => *input = adjusted,
+                    None => return Command::none(),
                 }
-                
-                // Processing the second input
-                match self.input2.trim().parse::<u64>() {
-                    Ok(value) => {
-                        if value == 0 { // Check if the value is greater than 0
-                            self.error_message = "The value must be greater than 0".to_string();
-                            return Command::none();
-                        }
-                        
-                        self.value2 = Some(value);
-                        self.sequence2 = collatz::generate_sequence(value);
-                        self.stats2 = Some(collatz::calculate_stats(&self.sequence2));
-                    }
-                    // If parsing fails, check if the input is empty.
-                    Err(_) => {
-                        if !self.input2.trim().is_empty() {
-                            self.error_message = "Invalid second value".to_string();
-                        } else {
-                            self.value2 = None;
-                            self.sequence2.clear();
-                            self.stats2 = None;
-                        }
-                    }
+                self.follow_input_link();
+                // Range charts do not read the values, so they are not restarted.
+                if self.auto_visualize && !self.chart_kind.uses_range() {
+                    self.update(Message::Visualize)
+                } else {
+                    Command::none()
                 }
-                
-                // If at least one sequence is generated, proceed to generate the chart.
-                // If both sequences are empty, do nothing.
-                if !self.sequence1.is_empty() || !self.sequence2.is_empty() {
-                    // Delete the old temporary file if it exists.
-                    // This is done to avoid cluttering the directory with old files.
-                    // If the chart_path is None, it means no chart was generated yet.
-                    let cleanup_command = if let Some(old_path) = &self.chart_path {
-                        Command::perform(
-                            cleanup_temp_file(old_path.clone()),
-                            Message::CleanupOldTempFiles,
-                        )
-                    } else {
-                        Command::none()
-                    };
-                    
-                    // Generate a new filename for the chart.
-                    // Use the current date and time to ensure uniqueness.
-                    let now = Local::now();
-                    let filename = format!("temp_collatz_{}.png", now.format("%Y%m%d_%H%M%S"));
-                    
-                    // Generate the chart and save it to the temporary file.
-                    // The chart generation is an async task, so we use Command::perform.
-                    // The result of the task will be sent back as a Message::ChartGenerated.
-                    // The chart will be generated with the sequences and values provided.
-                    let generate_command = Command::perform(
-                        generate_chart(
-                            PathBuf::from(&filename),
-                            self.value1,
-                            self.value2,
-                            self.sequence1.clone(),
-                            self.sequence2.clone(),
-                        ),
-                        Message::ChartGenerated,
-                    );
-                    
-                    // Return a batch command that performs both cleanup and chart generation.
-                    // This allows both tasks to run concurrently.
-                    // The cleanup command will run first, and then the chart generation.
-                    // This is a good practice to ensure we don't leave old temporary files behind.
-                    Command::batch(vec![cleanup_command, generate_command])
+            }
+            Message::LogExperimentsToggled(log_experiments) => {
+                self.log_experiments = log_experiments;
+                Command::none()
+            }
+            // Unlike the experiment log, the results database stays enabled between runs.
+            Message::StoreResultsToggled(store_results) => {
+                self.store_results = store_results;
+                self.user_settings.set(settings::STORE_RESULTS, store_results.to_string());
+                Command::perform(save_settings(settings::default_path(), self.user_settings.clone()), Message::SettingsSaved)
+            }
+
+            // The history is read from the log each time the panel is opened,
+            // so that it includes the runs of the other windows.
+            Message::ShowHistoryToggled(show) => {
+                self.show_history = show;
+                if show {
+                    Command::perform(
+                        load_history(experiment_log::default_path(), HISTORY_LENGTH),
+                        Message::HistoryLoaded,
+                    )
                 } else {
-                    Command::none() // No command needed if no sequences are generated.
+                    Command::none()
                 }
             }
-            
-            // When the "Randomize" button is pressed, generate two random numbers
-            // between 1 and 10000 (inclusive) and set them as the input values.
-            // Then, call the Visualize function to generate the sequences and chart.
-            Message::Randomize => {
-                let mut rng = rand::thread_rng(); // Create a random number generator.
-                
-                let max_rand = 10000; // Maximum random number.
-                let random1 = rng.gen_range(1..=max_rand);
-                let random2 = rng.gen_range(1..=max_rand);
-                
-                // Set the random values as input strings.
-                // This will update the input fields in the UI.
-                self.input1 = random1.to_string();
-                self.input2 = random2.to_string();
-                
-                // Call the Visualize function to generate the sequences and chart.
-                // This is done by sending a Message::Visualize.
-                // The Visualize function will parse the inputs and generate the sequences.
-                // If the inputs are valid, it will also generate the chart.
-                self.update(Message::Visualize)
+            Message::HistoryLoaded(result) => {
+                match result {
+                    Ok(history) => self.history = history,
+                    Err(e) => self.error_message = e,
+                }
+                Command::none()
             }
-            
-            // When the "Save Chart" button is pressed, we need to save the generated chart.
-            // If no chart was generated, show an error message.
-            // If a chart was generated, copy it to a new file with a timestamped name.
-            Message::SaveChart => {
-                // Check if there are sequences to save.
-                // If both sequences are empty, show an error message.
-                if self.sequence1.is_empty() && self.sequence2.is_empty() {
-                    self.error_message = "Ne sequence to save".to_string();
-                    return Command::none();
+
+            // Like the history, the results database is read each time the browser is opened.
+            Message::ShowResultsDbToggled(show) => {
+                self.show_results_db = show;
+                if show {
+                    Command::perform(load_results_db(results_db::default_path()), Message::ResultsDbLoaded)
+                } else {
+                    Command::none()
                 }
-                
-                // Check if a chart was generated.
-                // If no chart was generated, show an error message.
-                // The chart_path is an Option<String>, so we need to check if it's Some.
-                // If it's None, it means no chart was generated yet.
-                if self.chart_path.is_none() {
-                    self.error_message = "No graph to save".to_string();
-                    return Command::none();
+            }
+            Message::ResultsDbLoaded(result) => {
+                match result {
+                    Ok(db) => self.results_db = db,
+                    Err(e) => self.error_message = e,
                 }
-                
-                self.chart_saved = false; // Reset the chart saved flag before saving.
-                
-                // Generate a new filename for the saved chart.
-                // Use the current date and time to ensure uniqueness.
-                let now = Local::now();
-                let filename = format!("collatz_{}.png", now.format("%Y%m%d_%H%M%S"));
-                
-                // Create a command to save the chart.
-                // This is an async task, so we use Command::perform.
-                // The result of the task will be sent back as a Message::ChartSaved.
-                // The save_chart function will copy the temporary chart file to a new file.
-                Command::perform(
-                    save_chart(
-                        self.chart_path.clone().unwrap(),
-                        filename,
-                    ),
-                    Message::ChartSaved,
-                )
+                Command::none()
             }
-            
-            // When the "Copy to Clipboard" button is pressed, we need to copy the sequences
-            // to the system clipboard.
-            // If no sequences were generated, show an error message.
-            // If sequences were generated, format them and copy them to the clipboard.
-            Message::CopyToClipboard => {
-                if self.sequence1.is_empty() && self.sequence2.is_empty() {
-                    self.error_message = "No sequence to copy".to_string();
-                    return Command::none();
+            Message::ResultsFilterChanged(index, value) => {
+                self.results_filter_inputs[index] = value;
+                Command::none()
+            }
+            // Clicking the sorted column again reverses the order; another column sorts from the largest.
+            Message::ResultsSortClicked(column) => {
+                if self.results_sort == column {
+                    self.results_descending = !self.results_descending;
+                } else {
+                    self.results_sort = column;
+                    self.results_descending = true;
                 }
-                
-                self.copied_to_clipboard = false; // Reset the copied to clipboard flag before copying.
-                
-                // Create a command to copy the sequences to the clipboard.
-                // This is an async task, so we use Command::perform.
-                // The result of the task will be sent back as a Message::ClipboardCopied.
+                Command::none()
+            }
+            // The results passing the filters of the browser are exported, in its order
+            // (all of them, not only the ones listed); with no filter, the whole database is.
+            Message::ExportResults => {
+                let query = match results_db::ResultsQuery::parse(&self.results_filter_inputs) {
+                    Ok(query) => query,
+                    Err(e) => {
+                        self.error_message = e;
+                        return Command::none();
+                    }
+                };
+                self.error_message = String::new();
+                self.results_exported = false;
+
+                let results = results_db::query(&self.results_db, &query, self.results_sort, self.results_descending);
+                let filename = format!("collatz_results_{}.csv", Local::now().format("%Y%m%d_%H%M%S"));
                 Command::perform(
-                    copy_sequences_to_clipboard(
-                        self.value1,
-                        self.value2,
-                        self.sequence1.clone(),
-                        self.sequence2.clone(),
-                    ),
-                    Message::ClipboardCopied,
+                    write_text_file(PathBuf::from(filename), results_db::to_csv(&results)),
+                    Message::ResultsExported,
                 )
             }
-            
-            // --- Chart Generation ---
-            // When the chart generation task completes, we receive a result.
-            // If the result is Ok, we set the chart path to the generated file.
-            // If the result is Err, we set the error message.
-            // We also clear the error message if the chart was generated successfully.
-            Message::ChartGenerated(result) => {
+            Message::ResultsExported(result) => {
                 match result {
-                    Ok(path) => {
-                        self.chart_path = Some(path);
+                    Ok(()) => {
+                        self.results_exported = true;
                         self.error_message = String::new();
                     }
-                    Err(e) => {
-                        self.error_message = format!("Error generating chart: {}", e);
-                        self.chart_path = None;
-                    }
+                    Err(e) => self.report_failure(format!("Error while exporting: {}", e)),
                 }
-                Command::none() // No further command needed after chart generation.
+                Command::none()
             }
-            
-            // When the chart saving task completes, we receive a result.
-            // If the result is Ok, we set the chart saved flag to true.
-            // If the result is Err, we set the error message.
-            // We also clear the error message if the chart was saved successfully.
-            Message::ChartSaved(result) => {
-                match result {
-                    Ok(()) => {
-                        self.chart_saved = true;
-                        self.error_message = String::new();
+            // A stored result is opened as value 1 of the sequence chart.
+            Message::OpenStoredResult(start) => {
+                self.chart_kind = ChartKind::Sequences;
+                self.input1 = start.to_string();
+                self.input2.clear();
+                self.update(Message::Visualize)
+            }
+
+            // In the "Guess" game, a random starting value is drawn and the player guesses its flight time
+            // and peak; revealing the answer charts the value and adds the points of the round to the score.
+            Message::GameToggled(play) => {
+                self.game = play.then(|| game::Game::new(rand::thread_rng().gen_range(2..=game::MAX_START)));
+                self.flight_guess_input.clear();
+                self.peak_guess_input.clear();
+                Command::none()
+            }
+            Message::FlightGuessChanged(value) => {
+                self.flight_guess_input = input::normalize_number(&value);
+                Command::none()
+            }
+            Message::PeakGuessChanged(value) => {
+                self.peak_guess_input = input::normalize_number(&value);
+                Command::none()
+            }
+            Message::RevealGuess => {
+                let (Ok(flight_guess), Ok(peak_guess)) =
+                    (parse_guess(&self.flight_guess_input), parse_guess(&self.peak_guess_input))
+                else {
+                    self.error_message = "A guess must be a whole number".to_string();
+                    return Command::none();
+                };
+                let Some(game) = self.game.as_mut() else {
+                    return Command::none();
+                };
+                game.reveal(flight_guess.map(|guess| guess as usize), peak_guess);
+
+                // The answer is shown as the chart of the value alone.
+                self.input1 = game.start.to_string();
+                self.input2.clear();
+                self.chart_kind = ChartKind::Sequences;
+                self.update(Message::Visualize)
+            }
+            Message::NextGuess => {
+                if let Some(game) = self.game.as_mut() {
+                    game.next_round(rand::thread_rng().gen_range(2..=game::MAX_START));
+                }
+                self.flight_guess_input.clear();
+                self.peak_guess_input.clear();
+                Command::none()
+            }
+
+            // A past run is restored: its chart kind and inputs, then it is visualized again.
+            Message::RestoreRun(index) => {
+                let Some(run) = self.history.get(index).cloned() else {
+                    return Command::none();
+                };
+                // Runs logged by newer versions may name unknown charts: the sequences are shown instead.
+                self.chart_kind = ChartKind::from_slug(&run.chart).unwrap_or(ChartKind::Sequences);
+                let [first, second] = run.inputs;
+                match self.chart_kind {
+                    kind if kind.uses_range() => {
+                        self.range_start_input = first;
+                        self.range_end_input = second;
                     }
-                    Err(e) => {
-                        self.error_message = format!("Error while saving: {}", e);
+                    ChartKind::Fractal => {
+                        let (re, im) = first.split_once(',').unwrap_or((&first, ""));
+                        self.fractal_re_input = re.trim().to_string();
+                        self.fractal_im_input = im.trim().to_string();
+                        self.fractal_width_input = second;
+                    }
+                    ChartKind::MergedGraph => self.graph_starts_input = first,
+                    _ => {
+                        self.input1 = first;
+                        self.input2 = second;
                     }
                 }
-                Command::none() // No further command needed after chart saving.
+                self.update(Message::Visualize)
             }
-            
-            // When the clipboard copy task completes, we receive a result.
-            // If the result is Ok, we set the copied to clipboard flag to true.
-            // If the result is Err, we set the error message.
-            // We also clear the error message if the copy was successful.
-            Message::ClipboardCopied(result) => {
+
+            // Two runs of the history are compared statistic by statistic; ticking a third one
+            // replaces the one ticked first. The older run comes first in the comparison.
+            Message::CompareRunToggled(index, on) => {
+                let Some(run) = self.history.get(index).cloned() else {
+                    return Command::none();
+                };
+                self.compared_runs.retain(|compared| *compared != run);
+                if on {
+                    self.compared_runs.push(run);
+                    if self.compared_runs.len() > 2 {
+                        self.compared_runs.remove(0);
+                    }
+                }
+                Command::none()
+            }
+            Message::ExportRunDiff => {
+                let [first, second] = &self.compared_runs[..] else {
+                    self.error_message = "Tick two runs of the history to compare them".to_string();
+                    return Command::none();
+                };
+                let (first, second) = if first.timestamp <= second.timestamp { (first, second) } else { (second, first) };
+                self.error_message = String::new();
+                self.run_diff_exported = false;
+
+                let filename = format!("collatz_diff_{}.json", Local::now().format("%Y%m%d_%H%M%S"));
+                Command::perform(
+                    write_text_file(PathBuf::from(filename), run_diff::to_json(first, second)),
+                    Message::RunDiffExported,
+                )
+            }
+            Message::RunDiffExported(result) => {
                 match result {
                     Ok(()) => {
-                        self.copied_to_clipboard = true;
+                        self.run_diff_exported = true;
                         self.error_message = String::new();
                     }
-                    Err(e) => {
-                        self.error_message = format!("Error while copying: {}", e);
+                    Err(e) => self.report_failure(format!("Error while exporting: {}", e)),
+                }
+                Command::none()
+            }
+
+            // When a relation is picked, value 2 is derived from value 1 right away
+            // (the family mode, which does not use value 2, is switched off).
+            // Switching the mode off leaves value 2 as it is, to be edited again.
+            Message::InputLinkSelected(link) => {
+                self.input_link = link;
+                if link != input::InputLink::Off {
+                    self.family_mode = false;
+                }
+                self.follow_input_link();
+                Command::none()
+            }
+
+            Message::AutoVisualizeToggled(auto_visualize) => {
+                self.auto_visualize = auto_visualize;
+                Command::none()
+            }
+
+            // Keyboard navigation: Tab goes through the inputs and the controls (see `focus_ring`) in the order
+            // of the window (Enter in an input visualizes, Enter or Space on a control activates it),
+            // and Enter or Space elsewhere runs the main action on screen.
+            Message::FocusNext => iced::widget::focus_next(),
+            Message::FocusPrevious => iced::widget::focus_previous(),
+            Message::DefaultAction => {
+                if self.tour.is_some() {
+                    self.update(Message::TourNext)
+                } else {
+                    self.update(Message::Visualize)
+                }
+            }
+
+            // The previous/next buttons flip through consecutive starting values like a filmstrip:
+            // the first value is stepped by one and always visualized.
+            Message::PreviousValue | Message::NextValue => {
+                let adjustment = if matches!(message, Message::NextValue) {
+                    input::Adjustment::Increment
+                } else {
+                    input::Adjustment::Decrement
+                };
+                let signed = self.chart_kind == ChartKind::MapComparison;
+                match input::adjust_start_value(&self.input1, adjustment, signed) {
+                    Some(adjusted) if !self.chart_kind.uses_range() => {
+                        self.input1 = adjusted;
+                        self.update(Message::Visualize)
                     }
+                    _ => Command::none(),
                 }
-                Command::none() // No further command needed after clipboard copy.
             }
-            
-            // When the cleanup task completes, we receive a result.
-            // If the result is Ok, we ignore it (cleanup is not critical).
-            // If the result is Err, we print a warning message.
-            // This is done to avoid cluttering the directory with old files.
-            Message::CleanupOldTempFiles(result) => {
-                // On peut ignorer le résultat, car ce n'est pas critique si le nettoyage échoue
-                // Mais on pourrait ajouter un log ou une notification en cas d'erreur
-                if let Err(e) = result {
-                    println!("Warning: Unable to delete old temporary file: {}", e);
+
+            // The neighborhood compare draws n - 1, n and n + 1 on the sequence chart, n being value 1,
+            // to show how differently adjacent starting values behave. Value 2 is cleared
+            // (and the linked input mode switched off), since it would not be drawn.
+            Message::CompareNeighbors => {
+                let Ok(Some(value)) = input::parse_start_value(&self.input1) else {
+                    self.error_message = "Enter value 1 to compare its neighbors".to_string();
+                    return Command::none();
+                };
+                self.chart_kind = ChartKind::Sequences;
+                self.input_link = input::InputLink::Off;
+                self.input2 = String::new();
+                self.family_mode = false;
+                // Value 1 has no neighbor below when it is 1, nor above when it is the largest value.
+                self.requested_series = Some([value.checked_sub(1).filter(|&below| below > 0), value.checked_add(1)].into_iter().flatten().collect());
+                self.update(Message::Visualize)
+            }
+
+            // The family mode draws n with its doublings 2n, 4n..., which share the trajectory of n
+            // shifted by one halving step each. Like the neighborhood compare, it takes the place of value 2.
+            // The chart is redrawn at once if value 1 is already visualized.
+            Message::FamilyToggled(family_mode) => {
+                self.family_mode = family_mode;
+                if family_mode {
+                    self.input_link = input::InputLink::Off;
+                    self.input2 = String::new();
                 }
-                Command::none() // No further command needed after cleanup.
+                self.revisualize()
+            }
+            Message::FamilyDoublingsSelected(doublings) => {
+                self.family_doublings = doublings;
+                self.revisualize()
             }
-        }
-    }
 
-    // ==========================================================================
-    //                              View Function
-    // ==========================================================================
-    /// This function is called to render the application's UI.
-    /// It returns an `Element` that represents the entire UI.
-    /// The UI is built using a combination of widgets (buttons, text inputs, etc.).
-    /// The `view` function is responsible for creating the layout and appearance of the application.
-    /// It uses the current state of the application to determine what to display.
-    fn view(&self) -> Element<Message> {
-        // Title of the application
-        let title = text("Collatz Conjecture Visualizer")
-            .size(28)
-            .style(Color::from_rgb(0.2, 0.4, 0.8));
-        
-        // Input fields
-        // Two text inputs for the user to enter integers.
-        // The first input is required, the second is optional.
-        let input_row = row![
-            text("Value 1:").size(16),
-            text_input("Enter an integer", &self.input1)
-                .on_input(Message::Input1Changed)
-                .padding(10),
-            horizontal_space(Length::Fixed(20.0)),
-            text("Value 2:").size(16),
-            text_input("Enter an integer (optional)", &self.input2)
-                .on_input(Message::Input2Changed)
-                .padding(10),
-        ]
-        .spacing(10)
-        .align_items(Alignment::Center);
-        
-        // Button row
-        // A row of buttons for user actions.
-        // Each button has an action associated with it (e.g., Visualize, Randomize).
-        let button_row = container(
-            row![
-                button("Visualize").on_press(Message::Visualize).padding(10),
-                button("Randomize").on_press(Message::Randomize).padding(10),
-                button("Save the graph").on_press(Message::SaveChart).padding(10),
-                button("Copy the sequence").on_press(Message::CopyToClipboard).padding(10),
-            ]
-            .spacing(10)
-            .align_items(Alignment::Center) // Centre les boutons dans la rangée
-        )
-        .width(Length::Fill) // Force le conteneur à prendre toute la largeur
-        .center_x(); // Centre le conteneur lui-même
-        
-        // Status message
-        // A message to display the status of the application.
-        // This can be an error message, success message, or empty.
-        let status_message = if !self.error_message.is_empty() {
-            text(&self.error_message).style(Color::from_rgb(0.8, 0.2, 0.2))
-        } else if self.chart_saved {
-            text("Sequences copied to clipboard").style(Color::from_rgb(0.2, 0.8, 0.2))
-        } else if self.copied_to_clipboard {
-            text("Sequences copied to clipboard").style(Color::from_rgb(0.2, 0.8, 0.2))
-        } else {
-            text("") // Empty text if no message to display
-        };
-        
-        // Statistics section
-        // This section displays the statistics of the generated sequences.
-        // If no sequences were generated, show a message indicating that.
-        // If sequences were generated, display their statistics.
-        // The statistics include flight time, maximum altitude, even/odd counts, and downtime.
-        // The statistics are displayed in a scrollable container.
-        let stats_content = if self.sequence1.is_empty() && self.sequence2.is_empty() {
-            container(text("No sequence generated"))
-        } else {
-            let mut stats_text = String::new();
-            
-            // Display statistics for the first sequence
-            // If the first sequence exists, display its statistics.
-            // If the first value is None, it means no valid input was provided.
-            if let Some(stats) = &self.stats1 {
-                if let Some(value) = self.value1 {
-                    stats_text.push_str(&format!("Statistics for: {}\n", value));
-                    stats_text.push_str(&format!("Flight time: {} steps\n", stats.length - 1));
-                    stats_text.push_str(&format!("Maximum altitude: {} (at step {})\n", 
-                                               stats.max_value, stats.max_value_index));
-                    stats_text.push_str(&format!("Even values: {}, Odd values: {}\n", 
-                                               stats.even_count, stats.odd_count));
-                    stats_text.push_str(&format!("Downtime: {} steps\n\n", stats.stopping_time));
+            // The tail of the sequences (stopped at 1, 4-2-1 repeated, power-of-two descent trimmed)
+            // is applied when they are computed, so they are computed again.
+            Message::TailHandlingSelected(handling) => {
+                self.tail_handling = handling;
+                self.revisualize()
+            }
+            Message::TailCyclesSelected(cycles) => {
+                self.tail_cycles = cycles;
+                self.revisualize()
+            }
+
+            // The glide-only mode stops the sequences as soon as they drop below their start,
+            // and makes the density and scatter scans compute glides (on the next "Visualize" for them).
+            Message::GlideOnlyToggled(glide_only) => {
+                self.glide_only = glide_only;
+                self.revisualize()
+            }
+
+            // When a chart kind is picked, remember it. The chart is regenerated on the next "Visualize".
+            Message::ChartKindSelected(kind) => {
+                self.chart_kind = kind;
+                Command::none()
+            }
+
+            // Same for the transform: it only affects the next generated chart.
+            Message::TransformSelected(transform) => {
+                self.transform = transform;
+                Command::none()
+            }
+            Message::DifferenceModeSelected(mode) => {
+                self.difference_mode = mode;
+                Command::none()
+            }
+
+            // The base-3 panel is computed from the stored sequences, so it shows up immediately.
+            Message::ShowTernaryToggled(show) => {
+                self.show_ternary = show;
+                Command::none()
+            }
+
+            // The guided tour highlights one part of the window at a time. Closing it (or finishing it)
+            // writes its marker, so that it only starts by itself at the first start.
+            Message::ShowTutorial => {
+                self.start_tour();
+                Command::none()
+            }
+            Message::TourNext => {
+                self.tour = self.tour.and_then(tutorial::TourStep::next);
+                if self.tour.is_none() {
+                    return Command::perform(mark_tutorial_seen(tutorial::default_marker_path()), Message::TutorialSeen);
                 }
+                Command::none()
             }
-            
-            // Display statistics for the second sequence
-            // If the second sequence exists, display its statistics.
-            // If the second value is None, it means no valid input was provided.
-            if let Some(stats) = &self.stats2 {
-                if let Some(value) = self.value2 {
-                    stats_text.push_str(&format!("Statistics for {}:\n", value));
-                    stats_text.push_str(&format!("Flight time: {} steps\n", stats.length - 1));
-                    stats_text.push_str(&format!("Maximum altitude: {} (at step {})\n", 
-                                               stats.max_value, stats.max_value_index));
-                    stats_text.push_str(&format!("Even values: {}, Odd values: {}\n", 
-                                               stats.even_count, stats.odd_count));
-                    stats_text.push_str(&format!("Downtime: {} steps", stats.stopping_time));
+            Message::TourPrevious => {
+                if let Some(previous) = self.tour.and_then(tutorial::TourStep::previous) {
+                    self.tour = Some(previous);
                 }
+                Command::none()
             }
-            
-            // Create a scrollable container for the statistics text
-            // This allows the user to scroll through the statistics if they are too long.
-            container(
-                scrollable(
-                    container(text(&stats_text).size(14))
-                        .padding(10)
-                        .width(Length::Fill)
-                )
-                .height(Length::Fixed(150.0))
-            )
-        };
-        
-        // Style the statistics section
-        // This section has a border and a fixed height.
-        // The background color is set to a light gray.
-        // The text is displayed in a scrollable container.
-        // The statistics section is styled to match the application's theme.
-        // The container has a border and a fixed height.
-        let stats_section = container(stats_content)
-            .width(Length::Fill)
-            .style(|theme: &Theme| {
-                container::Appearance {
-                    border_width: 1.0,
-                    border_color: theme.extended_palette().background.strong.color,
-                    ..Default::default()
+            Message::TourDismissed => {
+                // Escape also lands here, with or without a tour on screen: it closes the context menu
+                // of the chart first, if open.
+                if self.chart_menu.take().is_some() || self.tour.take().is_none() {
+                    return Command::none();
                 }
-            });
-        
-        // Chart section
-        // This section displays the generated chart.
-        // If a chart was generated, display it as an image.
-        // If no chart was generated, display a message indicating that.
+                Command::perform(mark_tutorial_seen(tutorial::default_marker_path()), Message::TutorialSeen)
+            }
+            Message::TutorialSeen(result) => {
+                if let Err(e) = result {
+                    self.report_failure(format!("Error while saving the tutorial state: {}", e));
+                }
+                Command::none()
+            }
+
+            // The step-by-step explanation is written from the stored sequences too,
+            // and can be saved as a text file in the current directory.
+            Message::ShowStepsToggled(show) => {
+                self.show_steps = show;
+                Command::none()
+            }
+            Message::ExportSteps => {
+                if self.sequence1.is_empty() && self.sequence2.is_empty() {
+                    self.error_message = "No sequence to export".to_string();
+                    return Command::none();
+                }
+                if self.refuse_downsampled("export their steps") {
+                    return Command::none();
+                }
+                self.error_message = String::new();
+                self.steps_exported = false;
+                self.table_exported = false;
+                self.run_diff_exported = false;
+
+                let filename = format!("collatz_steps_{}.txt", Local::now().format("%Y%m%d_%H%M%S"));
+                Command::perform(write_text_file(PathBuf::from(filename), self.steps_text(None)), Message::StepsExported)
+            }
+            Message::StepsExported(result) => {
+                match result {
+                    Ok(()) => {
+                        self.steps_exported = true;
+                        self.error_message = String::new();
+                    }
+                    Err(e) => self.report_failure(format!("Error while exporting: {}", e)),
+                }
+                Command::none()
+            }
+
+            // The sibling finder lists the starting values whose trajectories merge with the one of value 1
+            // within a budget of steps, and plots the ticked ones with it.
+            Message::ShowSiblingsToggled(show) => {
+                self.show_siblings = show;
+                Command::none()
+            }
+            Message::SiblingStepsChanged(value) => {
+                self.sibling_steps_input = value;
+                Command::none()
+            }
+            Message::SiblingBoundChanged(value) => {
+                self.sibling_bound_input = input::normalize_number(&value);
+                Command::none()
+            }
+            Message::FindSiblings => {
+                let Ok(Some(value)) = input::parse_start_value(&self.input1) else {
+                    self.error_message = "Enter value 1 to find its siblings".to_string();
+                    return Command::none();
+                };
+                let steps = match self.sibling_steps_input.trim().parse::<usize>() {
+                    Ok(steps) if (1..=siblings::MAX_SIBLING_STEPS).contains(&steps) => steps,
+                    _ => {
+                        self.error_message = format!("The steps must be between 1 and {}", siblings::MAX_SIBLING_STEPS);
+                        return Command::none();
+                    }
+                };
+                let Ok(Some(bound)) = input::parse_start_value(&self.sibling_bound_input) else {
+                    self.error_message = "The bound must be a whole number greater than 0".to_string();
+                    return Command::none();
+                };
+                self.error_message = String::new();
+                self.siblings = siblings::find_siblings(value, steps, bound);
+                self.siblings_of = Some(value);
+                self.sibling_selection.clear();
+                Command::none()
+            }
+            Message::SiblingToggled(sibling, selected) => {
+                if !selected {
+                    self.sibling_selection.retain(|&value| value != sibling);
+                } else if self.sibling_selection.len() < MAX_PLOTTED_SIBLINGS {
+                    self.sibling_selection.push(sibling);
+                } else {
+                    self.error_message = format!("At most {} siblings are plotted at once", MAX_PLOTTED_SIBLINGS);
+                }
+                Command::none()
+            }
+
+            // The siblings are drawn with the value they were found for, in place of value 2.
+            Message::PlotSiblings => {
+                let Some(value) = self.siblings_of else {
+                    return Command::none();
+                };
+                if self.sibling_selection.is_empty() {
+                    self.error_message = "Tick the siblings to plot".to_string();
+                    return Command::none();
+                }
+                self.chart_kind = ChartKind::Sequences;
+                self.input1 = value.to_string();
+                self.input_link = input::InputLink::Off;
+                self.input2 = String::new();
+                self.family_mode = false;
+                let mut selection = self.sibling_selection.clone();
+                selection.sort_unstable();
+                self.requested_series = Some(selection);
+                self.update(Message::Visualize)
+            }
+
+            // The sequence table lists the terms of one sequence; a click selects a row,
+            // a shift-click every row up to it, and the selected rows are copied in the chosen format.
+            Message::ShowTableToggled(show) => {
+                self.show_table = show;
+                Command::none()
+            }
+            Message::TableSequenceSelected(index) => {
+                if self.table_sequence != index {
+                    self.table_sequence = index;
+                    self.table_selection = sequence_table::RowSelection::default();
+                }
+                Command::none()
+            }
+            // The chosen columns are kept in the settings file for the next runs.
+            Message::TableColumnToggled(column, on) => {
+                let mut columns = self.table_columns.clone();
+                columns.retain(|&shown| shown != column);
+                if on {
+                    columns.push(column);
+                }
+                // In the order of the table; the last column shown cannot be hidden.
+                let columns: Vec<_> = sequence_table::Column::ALL.into_iter().filter(|c| columns.contains(c)).collect();
+                if columns.is_empty() {
+                    return Command::none();
+                }
+                self.table_columns = columns;
+                self.user_settings.set(settings::TABLE_COLUMNS, sequence_table::format_columns(&self.table_columns));
+                Command::perform(save_settings(settings::default_path(), self.user_settings.clone()), Message::SettingsSaved)
+            }
+            Message::SettingsSaved(result) => {
+                if let Err(e) = result {
+                    self.report_failure(format!("Error while saving the settings: {}", e));
+                }
+                Command::none()
+            }
+            Message::TableFilterToggled(filter, on) => {
+                self.table_filters.retain(|&active| active != filter);
+                if on {
+                    self.table_filters.push(filter);
+                }
+                Command::none()
+            }
+            Message::TableRowClicked(step) => {
+                self.table_selection.click(step, self.modifiers.shift());
+                Command::none()
+            }
+            Message::TableFormatSelected(format) => {
+                self.table_format = format;
+                Command::none()
+            }
+            Message::CopyTableRows => {
+                let Some(steps) = self.table_selection.range() else {
+                    self.error_message = "No row selected".to_string();
+                    return Command::none();
+                };
+                if self.refuse_downsampled("copy their table") {
+                    return Command::none();
+                }
+                self.copied_to_clipboard = false;
+                self.link_copied = false;
+                self.stat_copied = None;
+                self.summary_copied = false;
+                // The rows hidden by the filters are left out of the selection.
+                let sequence = self.table_rows();
+                let selected: Vec<usize> = sequence_table::filtered_steps(sequence, &self.table_filters)
+                    .into_iter()
+                    .filter(|step| steps.contains(step))
+                    .collect();
+                let rows = sequence_table::format_rows(sequence, &selected, &self.table_columns, self.table_format);
+                Command::perform(copy_text_to_clipboard(rows), |result| Message::StatCopied("Selected rows", result))
+            }
+            // The table is written as displayed: the columns shown, the rows left by the filters
+            // (beyond the rows drawn too), in the chosen format. "Export binary" writes the whole raw sequence.
+            Message::ExportTable => {
+                if self.table_rows().is_empty() {
+                    self.error_message = "No sequence to export".to_string();
+                    return Command::none();
+                }
+                if self.refuse_downsampled("export their table") {
+                    return Command::none();
+                }
+                self.error_message = String::new();
+                self.table_exported = false;
+                self.run_diff_exported = false;
+
+                let sequence = self.table_rows();
+                let steps = sequence_table::filtered_steps(sequence, &self.table_filters);
+                let content = sequence_table::format_rows(sequence, &steps, &self.table_columns, self.table_format);
+                let filename = format!(
+                    "collatz_table_{}_{}.{}",
+                    sequence[0],
+                    Local::now().format("%Y%m%d_%H%M%S"),
+                    self.table_format.extension(),
+                );
+                Command::perform(write_text_file(PathBuf::from(filename), content + "\n"), Message::TableExported)
+            }
+            Message::TableExported(result) => {
+                match result {
+                    Ok(()) => {
+                        self.table_exported = true;
+                        self.error_message = String::new();
+                    }
+                    Err(e) => self.report_failure(format!("Error while exporting: {}", e)),
+                }
+                Command::none()
+            }
+            Message::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+                Command::none()
+            }
+
+            // The edges of the merged graph are written as CSV, for graph tools (Gephi, networkx...).
+            Message::ExportGraph => {
+                let Some(graph) = &self.merged_graph else {
+                    self.error_message = "No graph to export".to_string();
+                    return Command::none();
+                };
+                self.error_message = String::new();
+                self.graph_exported = false;
+
+                let filename = format!("collatz_graph_{}.csv", Local::now().format("%Y%m%d_%H%M%S"));
+                let csv = trajectory_graph::edge_list_csv(graph);
+                Command::perform(write_text_file(PathBuf::from(filename), csv), Message::GraphExported)
+            }
+            Message::GraphExported(result) => {
+                match result {
+                    Ok(()) => {
+                        self.graph_exported = true;
+                        self.error_message = String::new();
+                    }
+                    Err(e) => self.report_failure(format!("Error while exporting: {}", e)),
+                }
+                Command::none()
+            }
+
+            // Chart options are applied by the next "Visualize".
+            Message::ShowChartOptionsToggled(show) => {
+                self.show_chart_options = show;
+                Command::none()
+            }
+            Message::CaptionChanged(value) => {
+                self.chart_options.caption = value;
+                Command::none()
+            }
+            Message::SubtitleChanged(value) => {
+                self.chart_options.subtitle = value;
+                Command::none()
+            }
+            Message::XLabelChanged(value) => {
+                self.chart_options.x_label = value;
+                Command::none()
+            }
+            Message::YLabelChanged(value) => {
+                self.chart_options.y_label = value;
+                Command::none()
+            }
+            Message::TickSizeSelected(size) => {
+                self.chart_options.tick_size = size;
+                Command::none()
+            }
+            // Gestures on the sequence chart move or resize its window of steps, and draw it again
+            // (small moves add up until they change the view by a whole step).
+            Message::ChartGesture(gesture) => {
+                let sequences: Vec<&[u64]> = self
+                    .chart_series_terms()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(position, (_, sequence))| self.plotted_wide_bits(position).unwrap_or(sequence))
+                    .collect();
+                let (_, len) = self.chart_options.alignment.frame(&sequences);
+                if self.chart_kind != ChartKind::Sequences || len == 0 {
+                    return Command::none();
+                }
+                let view = self.chart_options.view.unwrap_or(chart_view::ChartView::full(len)).clamped(len);
+                let moved = self.chart_gestures.apply(view, gesture, len);
+                if moved == view {
+                    return Command::none();
+                }
+                self.chart_options.view = Some(moved).filter(|view| !view.is_full(len));
+                // A drag or a pinch sends many gestures: while the chart is being drawn, only the latest view
+                // is remembered, and drawn once the rendering is done (at most one rendering waits).
+                if self.sequence_render == Some(self.chart_generation) {
+                    self.gesture_render_pending = true;
+                    return Command::none();
+                }
+                self.chart_sequences()
+            }
+            // The context menu of the chart offers the common actions where the chart is:
+            // saving and copying the image, copying the terms under the cursor, and showing the whole sequences.
+            Message::ChartMenuOpened(position) => {
+                self.chart_menu = Some(position);
+                Command::none()
+            }
+            Message::ChartMenuClosed => {
+                self.chart_menu = None;
+                Command::none()
+            }
+            Message::ChartMenuSelected(entry) => {
+                let Some(position) = self.chart_menu.take() else {
+                    return Command::none();
+                };
+                self.copied_to_clipboard = false;
+                self.link_copied = false;
+                self.stat_copied = None;
+                self.summary_copied = false;
+                match entry {
+                    ChartMenuEntry::SaveImage => self.update(Message::SaveChart),
+                    ChartMenuEntry::CopyImage => match &self.chart_path {
+                        Some(path) => Command::perform(copy_image_to_clipboard(path.clone()), |result| {
+                            Message::StatCopied("Chart image", result)
+                        }),
+                        None => Command::none(),
+                    },
+                    ChartMenuEntry::CopyData => {
+                        let data = self.data_at_position(position);
+                        if data.is_empty() {
+                            self.error_message = "No term at the cursor".to_string();
+                            return Command::none();
+                        }
+                        Command::perform(copy_text_to_clipboard(data), |result| Message::StatCopied("Data at the cursor", result))
+                    }
+                    ChartMenuEntry::ResetZoom => {
+                        self.chart_options.view = None;
+                        if self.chart_kind == ChartKind::Sequences {
+                            self.chart_sequences()
+                        } else {
+                            Command::none()
+                        }
+                    }
+                }
+            }
+            Message::TextScaleSelected(text_scale) => {
+                self.chart_options.text_scale = text_scale;
+                Command::none()
+            }
+            Message::PaletteSelected(palette) => {
+                self.chart_options.palette = palette;
+                Command::none()
+            }
+            Message::TickFormatSelected(format) => {
+                self.chart_options.tick_format = format;
+                Command::none()
+            }
+            Message::Name1Changed(value) => {
+                self.name1 = value;
+                self.refresh_steps_shown();
+                Command::none()
+            }
+            Message::Name2Changed(value) => {
+                self.name2 = value;
+                self.refresh_steps_shown();
+                Command::none()
+            }
+            Message::LineStyleSelected(series, line) => {
+                self.chart_options.series_styles[series].line = line;
+                Command::none()
+            }
+            Message::MarkersToggled(series, markers) => {
+                self.chart_options.series_styles[series].markers = markers;
+                Command::none()
+            }
+            Message::MarkerShapeSelected(series, marker) => {
+                self.chart_options.series_styles[series].marker = marker;
+                Command::none()
+            }
+            Message::LineWidthSelected(width) => {
+                self.chart_options.line_width = width;
+                Command::none()
+            }
+            Message::AntialiasToggled(antialias) => {
+                self.chart_options.antialias = antialias;
+                Command::none()
+            }
+
+            Message::LogScaleToggled(log_scale) => {
+                self.chart_options.log_scale = log_scale;
+                Command::none()
+            }
+
+            Message::YScalingSelected(y_scaling) => {
+                self.chart_options.y_scaling = y_scaling;
+                Command::none()
+            }
+
+            Message::LayoutSelected(layout) => {
+                self.chart_options.layout = layout;
+                Command::none()
+            }
+
+            Message::AlignmentSelected(alignment) => {
+                self.chart_options.alignment = alignment;
+                Command::none()
+            }
+
+            Message::ShowDiagnosticsToggled(show) => {
+                self.show_diagnostics = show;
+                if show {
+                    self.update(Message::CountTempFiles)
+                } else {
+                    Command::none()
+                }
+            }
+            // The memory figures are read when the panel is drawn; the temporary files are counted on request.
+            Message::CountTempFiles => Command::perform(count_temp_files(temp_dir()), Message::TempFilesCounted),
+            Message::TempFilesCounted(result) => {
+                self.temp_files = result.ok();
+                Command::none()
+            }
+            // "Free memory" drops the cached data: the recent charts (their images are deleted),
+            // the stopping-time table (saved first, and read again by the next scan),
+            // and the results database while its browser is closed.
+            Message::FreeMemory => {
+                let paths = self.recent_charts.drain();
+                if !self.show_results_db {
+                    self.results_db = results_db::ResultsDb::default();
+                }
+                Command::perform(
+                    off_ui_thread(free_cached_data(self.stopping_time_cache.clone(), paths)),
+                    Message::MemoryFreed,
+                )
+            }
+            Message::MemoryFreed(result) => {
+                if let Err(e) = result {
+                    self.report_failure(e);
+                }
+                self.update(Message::CountTempFiles)
+            }
+
+            // Range bounds are kept as raw text until "Visualize" parses them.
+            Message::RangeStartChanged(value) => {
+                self.range_start_input = input::normalize_number(&value);
+                Command::none()
+            }
+            Message::RangeEndChanged(value) => {
+                self.range_end_input = input::normalize_number(&value);
+                Command::none()
+            }
+            Message::ScatterModulusChanged(value) => {
+                self.scatter_modulus_input = input::normalize_number(&value);
+                Command::none()
+            }
+            Message::HistogramBinsChanged(value) => {
+                self.histogram_bins_input = input::normalize_number(&value);
+                Command::none()
+            }
+            Message::GraphStartsChanged(value) => {
+                self.graph_starts_input = value;
+                Command::none()
+            }
+            Message::DivergenceBoundChanged(value) => {
+                self.divergence_bound_input = input::normalize_number(&value);
+                Command::none()
+            }
+            Message::DivergenceStepsChanged(value) => {
+                self.divergence_steps_input = input::normalize_number(&value);
+                Command::none()
+            }
+            Message::StepLimitChanged(value) => {
+                self.step_limit_input = input::normalize_number(&value);
+                Command::none()
+            }
+            Message::MemoryBudgetChanged(value) => {
+                self.memory_budget_input = input::normalize_number(&value);
+                Command::none()
+            }
+            Message::FractalReChanged(value) => {
+                self.fractal_re_input = value;
+                Command::none()
+            }
+            Message::FractalImChanged(value) => {
+                self.fractal_im_input = value;
+                Command::none()
+            }
+            Message::FractalWidthChanged(value) => {
+                self.fractal_width_input = value;
+                Command::none()
+            }
+            // Zooming keeps the center and redraws the fractal right away.
+            Message::ZoomFractal(factor) => {
+                if let Some(window) = self.parse_fractal_window() {
+                    self.fractal_width_input = window.zoomed(factor).width.to_string();
+                }
+                self.update(Message::Visualize)
+            }
+            Message::ScanBackendSelected(backend) => {
+                self.scan_backend = backend;
+                Command::none()
+            }
+            Message::SampleCountChanged(value) => {
+                self.sample_count_input = input::normalize_number(&value);
+                Command::none()
+            }
+            Message::SamplingDistributionSelected(distribution) => {
+                self.sampling_distribution = distribution;
+                Command::none()
+            }
+
+            // --- Core Actions ---
+            // When the "Visualize" button is pressed, we need to process the inputs.
+            // This includes parsing the inputs, generating the Collatz sequences,
+            // and creating the chart.
+            // Range charts read the range inputs instead and are handled separately.
+            // Every run is also appended to the experiment log, when it is enabled.
+            Message::Visualize if self.chart_kind.uses_range() => {
+                let visualize_command = self.visualize_range();
+                Command::batch(vec![visualize_command, self.log_run()])
+            }
+            Message::Visualize if self.chart_kind == ChartKind::Fractal => {
+                let visualize_command = self.visualize_fractal();
+                Command::batch(vec![visualize_command, self.log_run()])
+            }
+            Message::Visualize if self.chart_kind == ChartKind::MergedGraph => {
+                let visualize_command = self.visualize_graph();
+                Command::batch(vec![visualize_command, self.log_run()])
+            }
+            Message::Visualize if self.chart_kind == ChartKind::MapComparison => {
+                let visualize_command = self.visualize_comparison();
+                Command::batch(vec![visualize_command, self.log_run()])
+            }
+            Message::Visualize => {
+                self.log(&format!("Visualize \"{}\" \"{}\" ({})", self.input1.trim(), self.input2.trim(), self.chart_kind));
+                // Reset status messages and flags before processing.
+                self.clear_status();
+                self.imported_from = None; // The sequences are computed again from the inputs.
+                self.import_check = None;
+                let Some(step_limit) = self.parse_step_limit() else {
+                    return Command::none();
+                };
+                let Some(memory_budget) = self.parse_memory_budget() else {
+                    return Command::none();
+                };
+                // A new budget is kept for the next runs.
+                let save_budget = if memory_budget != self.memory_budget {
+                    self.memory_budget = memory_budget;
+                    self.user_settings.set(settings::MEMORY_BUDGET, memory_budget.to_string());
+                    Command::perform(save_settings(settings::default_path(), self.user_settings.clone()), Message::SettingsSaved)
+                } else {
+                    Command::none()
+                };
+                // The two sequences share the budget; it is checked again once the extra series are computed.
+                let budget_terms = sequence_budget::budget_terms(memory_budget);
+                let max_terms = budget_terms / 2;
+                let previous_values = (self.value1, self.value2);
+
+                // Every phase is timed for the diagnostics panel.
+                let mut timings = diagnostics::PhaseTimings::new();
+                
+                // Processing the inputs
+                // Each input is parsed on its own: an invalid value only clears its own sequence
+                // (its error is shown under the field), so the other value is still visualized.
+                // If parsing succeeds, generate the Collatz sequence and calculate statistics.
+                match timings.time("Parse", || input::parse_start_value(&self.input1)) {
+                    Ok(Some(value)) => {
+                        self.value1 = Some(value);
+                        // Generate the Collatz sequence and calculate its statistics.
+                        let (sequence, stats, downsampled) = self.generate_within_budget(value, step_limit, max_terms, &mut timings);
+                        self.sequence1 = sequence;
+                        self.step_limits[0] = step_limit;
+                        self.stats1 = Some(stats);
+                        self.downsampled[0] = downsampled;
+                        self.arithmetic1 = Some(timings.time("Flight time", || collatz::total_stopping_time_auto(value)));
+                        self.wide1 = self.overflowed(0).then(|| timings.time("Wide trajectory", || collatz::wide_trajectory(value, step_limit)));
+                        self.wide_bits[0] = match self.wide1 {
+                            Some(_) => timings.time("Wide trajectory", || collatz::wide_bit_lengths(value, step_limit)),
+                            None => Vec::new(),
+                        };
+                        // The statistics are those of the trajectory: its tail is only changed for the chart
+                        // (a glide has no such tail, and the tail of a downsampled sequence is not kept).
+                        if !self.glide_only && downsampled.is_none() {
+                            self.tail_handling.apply(&mut self.sequence1, self.tail_cycles);
+                        }
+                    }
+                    // Empty or invalid input: no sequence.
+                    Ok(None) | Err(_) => {
+                        self.value1 = None;
+                        self.sequence1.clear();
+                        self.stats1 = None;
+                        self.downsampled[0] = None;
+                        self.arithmetic1 = None;
+                        self.wide1 = None;
+                        self.wide_bits[0].clear();
+                    }
+                }
+                match timings.time("Parse", || input::parse_start_value(&self.input2)) {
+                    Ok(Some(value)) => {
+                        self.value2 = Some(value);
+                        let (sequence, stats, downsampled) = self.generate_within_budget(value, step_limit, max_terms, &mut timings);
+                        self.sequence2 = sequence;
+                        self.step_limits[1] = step_limit;
+                        self.stats2 = Some(stats);
+                        self.downsampled[1] = downsampled;
+                        self.arithmetic2 = Some(timings.time("Flight time", || collatz::total_stopping_time_auto(value)));
+                        self.wide2 = self.overflowed(1).then(|| timings.time("Wide trajectory", || collatz::wide_trajectory(value, step_limit)));
+                        self.wide_bits[1] = match self.wide2 {
+                            Some(_) => timings.time("Wide trajectory", || collatz::wide_bit_lengths(value, step_limit)),
+                            None => Vec::new(),
+                        };
+                        if !self.glide_only && downsampled.is_none() {
+                            self.tail_handling.apply(&mut self.sequence2, self.tail_cycles);
+                        }
+                    }
+                    Ok(None) | Err(_) => {
+                        self.value2 = None;
+                        self.sequence2.clear();
+                        self.stats2 = None;
+                        self.downsampled[1] = None;
+                        self.arithmetic2 = None;
+                        self.wide2 = None;
+                        self.wide_bits[1].clear();
+                    }
+                }
+                
+                // The series asked for by "Compare neighbors" or "Plot selected siblings" are only drawn by this run.
+                // The doublings of the family mode are computed by every run while the mode is on.
+                self.extra_series.clear();
+                let mut extra_strides = Vec::new();
+                let requested_series = self.requested_series.take();
+                if let Some(value) = self.value1 {
+                    let extra = if let Some(requested) = requested_series {
+                        requested
+                    } else if self.family_mode {
+                        collatz::doublings(value, self.family_doublings)
+                    } else {
+                        Vec::new()
+                    };
+                    for extra in extra {
+                        let (mut sequence, _, downsampled) = self.generate_within_budget(extra, step_limit, max_terms, &mut timings);
+                        if !self.glide_only && downsampled.is_none() {
+                            self.tail_handling.apply(&mut sequence, self.tail_cycles);
+                        }
+                        self.extra_series.push((extra, sequence));
+                        extra_strides.push(downsampled.map_or(1, |downsampled| downsampled.stride));
+                    }
+                }
+                self.fit_memory_budget(budget_terms, &extra_strides);
+                let step_stride = self.step_stride();
+                for bits in &mut self.wide_bits {
+                    sequence_budget::decimate(bits, step_stride);
+                }
+                self.refresh_steps_shown();
+
+                // New values are shown whole: the zoom of the previous chart does not apply to them.
+                if (self.value1, self.value2) != previous_values {
+                    self.chart_options.view = None;
+                    self.table_selection = sequence_table::RowSelection::default();
+                }
+
+                // The chart phases are added when the chart task completes.
+                self.diagnostics = Some(timings);
+
+                Command::batch(vec![self.chart_sequences(), self.log_run(), self.store_run(), save_budget])
+            }
+            
+            // When the "Randomize" button is pressed, generate two random numbers
+            // between 1 and 10000 (inclusive) and set them as the input values.
+            // Then, call the Visualize function to generate the sequences and chart.
+            Message::Randomize => {
+                let mut rng = rand::thread_rng(); // Create a random number generator.
+                
+                let max_rand = 10000; // Maximum random number.
+                let random1 = rng.gen_range(1..=max_rand);
+                let random2 = rng.gen_range(1..=max_rand);
+                
+                // Set the random values as input strings.
+                // This will update the input fields in the UI.
+                self.input1 = random1.to_string();
+                self.input2 = random2.to_string();
+                
+                // Call the Visualize function to generate the sequences and chart.
+                // This is done by sending a Message::Visualize.
+                // The Visualize function will parse the inputs and generate the sequences.
+                // If the inputs are valid, it will also generate the chart.
+                self.update(Message::Visualize)
+            }
+            
+            // When the "Save Chart" button is pressed, we need to save the generated chart.
+            // If no chart was generated, show an error message.
+            // If a chart was generated, copy it to a new file with a timestamped name.
+            Message::SaveChart => {
+                // Check if there are sequences to save.
+                // If both sequences are empty, show an error message.
+                // Range charts have no sequence: only the chart itself is needed.
+                if self.chart_kind.uses_sequences() && self.sequence1.is_empty() && self.sequence2.is_empty() {
+                    self.error_message = "Ne sequence to save".to_string();
+                    return Command::none();
+                }
+                
+                // Check if a chart was generated.
+                // If no chart was generated, show an error message.
+                // The chart_path is an Option<String>, so we need to check if it's Some.
+                // If it's None, it means no chart was generated yet.
+                if self.chart_path.is_none() {
+                    self.error_message = "No graph to save".to_string();
+                    return Command::none();
+                }
+                
+                self.chart_saved = false; // Reset the chart saved flag before saving.
+                
+                // Generate a new filename for the saved chart.
+                // Use the current date and time to ensure uniqueness.
+                let filename = self.saved_chart_filename();
+                
+                // Create a command to save the chart.
+                // This is an async task, so we use Command::perform.
+                // The result of the task will be sent back as a Message::ChartSaved.
+                // The save_chart function will copy the temporary chart file to a new file, in the chosen format.
+                Command::perform(
+                    save_chart(
+                        self.chart_path.clone().unwrap(),
+                        filename,
+                        self.image_format,
+                        self.image_quality,
+                        self.chart_metadata(),
+                    ),
+                    Message::ChartSaved,
+                )
+            }
+            
+            // When the "Copy to Clipboard" button is pressed, we need to copy the sequences
+            // to the system clipboard.
+            // If no sequences were generated, show an error message.
+            // If sequences were generated, format them and copy them to the clipboard.
+            // In map comparison mode, the variant trajectories are copied instead.
+            Message::CopyToClipboard if self.chart_kind == ChartKind::MapComparison => {
+                if self.comparison.is_empty() {
+                    self.error_message = "No sequence to copy".to_string();
+                    return Command::none();
+                }
+
+                self.copied_to_clipboard = false;
+                self.link_copied = false;
+                self.stat_copied = None;
+                self.summary_copied = false;
+
+                Command::perform(
+                    copy_text_to_clipboard(format_comparison(&self.comparison)),
+                    Message::ClipboardCopied,
+                )
+            }
+            Message::CopyToClipboard => {
+                if self.sequence1.is_empty() && self.sequence2.is_empty() {
+                    self.error_message = "No sequence to copy".to_string();
+                    return Command::none();
+                }
+                if self.refuse_downsampled("copy their terms") {
+                    return Command::none();
+                }
+                
+                self.copied_to_clipboard = false; // Reset the copied to clipboard flags before copying.
+                self.link_copied = false;
+                self.stat_copied = None;
+                self.summary_copied = false;
+                
+                // Create a command to copy the sequences to the clipboard.
+                // This is an async task, so we use Command::perform.
+                // The result of the task will be sent back as a Message::ClipboardCopied.
+                Command::perform(
+                    copy_sequences_to_clipboard(
+                        self.value1,
+                        self.value2,
+                        self.sequence1.clone(),
+                        self.sequence2.clone(),
+                        [self.name1.clone(), self.name2.clone()],
+                    ),
+                    Message::ClipboardCopied,
+                )
+            }
+            
+            // When the "Copy link" button is pressed, the `collatz://` link of the current exploration
+            // (values, chart kind, scale) is placed on the clipboard, to be shared with other users.
+            Message::CopyLink => {
+                self.link_copied = false;
+                self.stat_copied = None;
+                self.summary_copied = false;
+                self.copied_to_clipboard = false;
+                match self.share_link() {
+                    Some(link) => Command::perform(copy_text_to_clipboard(link.to_link()), Message::LinkCopied),
+                    None => {
+                        self.error_message = "No value to share".to_string();
+                        Command::none()
+                    }
+                }
+            }
+            Message::LinkCopied(result) => {
+                match result {
+                    Ok(()) => {
+                        self.link_copied = true;
+                        self.error_message = String::new();
+                    }
+                    Err(e) => self.error_message = format!("Error while copying: {}", e),
+                }
+                Command::none()
+            }
+
+            // When the "Describe" button is pressed, the accessible summary (the chart and the statistics
+            // described in sentences) is placed on the clipboard, to be read by a screen reader or pasted.
+            Message::CopySummary => {
+                self.copied_to_clipboard = false;
+                self.link_copied = false;
+                self.stat_copied = None;
+                self.summary_copied = false;
+                Command::perform(copy_text_to_clipboard(self.accessible_summary()), Message::SummaryCopied)
+            }
+            Message::SummaryCopied(result) => {
+                match result {
+                    Ok(()) => {
+                        self.summary_copied = true;
+                        self.error_message = String::new();
+                    }
+                    Err(e) => self.error_message = format!("Error while copying: {}", e),
+                }
+                Command::none()
+            }
+
+            // When the copy button of a cell of the statistics grid is pressed, its value alone
+            // (without the unit) is placed on the clipboard.
+            Message::CopyStat(label, value) => {
+                self.copied_to_clipboard = false;
+                self.link_copied = false;
+                self.stat_copied = None;
+                self.summary_copied = false;
+                Command::perform(copy_text_to_clipboard(value), move |result| Message::StatCopied(label, result))
+            }
+            Message::StatCopied(label, result) => {
+                match result {
+                    Ok(()) => {
+                        self.stat_copied = Some(label);
+                        self.error_message = String::new();
+                    }
+                    Err(e) => self.error_message = format!("Error while copying: {}", e),
+                }
+                Command::none()
+            }
+
+            // --- Chart Generation ---
+            // When the chart generation task completes, we receive a result.
+            // If the result is Ok, we set the chart path to the generated file.
+            // If the result is Err, we set the error message.
+            // We also clear the error message if the chart was generated successfully.
+            // A chart of an older generation is deleted instead: a newer one replaces it.
+            Message::ChartGenerated(generation, result) => {
+                if generation != self.chart_generation {
+                    return discard_stale_chart(result.ok());
+                }
+                let replace_command = match result {
+                    Ok(path) => {
+                        self.error_message = String::new();
+                        self.show_chart(Some(path))
+                    }
+                    Err(e) => {
+                        self.report_failure(format!("Error generating chart: {}", e));
+                        self.show_chart(None)
+                    }
+                };
+                // A permanent copy of the new chart, if enabled.
+                Command::batch(vec![replace_command, self.auto_save_chart()])
+            }
+            
+            // The sequence chart also reports the timings of its phases.
+            Message::SequenceChartGenerated(generation, result) => {
+                if generation != self.chart_generation {
+                    return discard_stale_chart(result.ok().map(|(path, _)| path));
+                }
+                self.sequence_render = None;
+                let replace_command = match result {
+                    Ok((path, chart_timings)) => {
+                        self.error_message = String::new();
+                        if let Some(timings) = &mut self.diagnostics {
+                            timings.extend(chart_timings);
+                        }
+                        self.show_chart(Some(path))
+                    }
+                    Err(e) => {
+                        self.report_failure(format!("Error generating chart: {}", e));
+                        self.show_chart(None)
+                    }
+                };
+                // The view moved by the gestures during the rendering is drawn now.
+                let gesture_command = if std::mem::take(&mut self.gesture_render_pending) {
+                    self.chart_sequences()
+                } else {
+                    Command::none()
+                };
+                Command::batch(vec![replace_command, self.auto_save_chart(), gesture_command])
+            }
+
+            // When a range chart task completes, we receive the chart and the range summary.
+            // Every chunk replaces the chart with one drawn from more results.
+            Message::RangeScanProgress(job, result) => {
+                let Some(scan) = self.range_scan.as_mut().filter(|scan| scan.job == job) else {
+                    return Command::none(); // Update of a scan that was replaced since.
+                };
+                match result {
+                    Ok(update) => {
+                        scan.scanned = update.scanned;
+                        scan.elapsed = update.elapsed;
+                        let complete = scan.scanned >= scan.total();
+                        if complete {
+                            scan.running = false;
+                        }
+                        let cleanup_command = self.show_chart(Some(update.chart_path));
+                        self.range_summary = Some(update.summary.clone());
+                        self.error_message = String::new();
+                        // Only the chart of the whole range is saved, not every intermediate one.
+                        let done_commands = if complete {
+                            let summary = &update.summary;
+                            let body = format!(
+                                "{} to {}: longest trajectory {} steps (starting at {})",
+                                summary.range_start, summary.range_end, summary.max_time, summary.max_time_start,
+                            );
+                            vec![self.auto_save_chart(), self.notify_job_done("Range scan done", body)]
+                        } else {
+                            Vec::new()
+                        };
+                        Command::batch(std::iter::once(cleanup_command).chain(done_commands))
+                    }
+                    Err(e) => {
+                        scan.running = false;
+                        self.report_failure(format!("Error generating chart: {}", e));
+                        Command::none()
+                    }
+                }
+            }
+
+            // When the Monte Carlo task completes, we receive the histogram and the sample report.
+            Message::SampleGenerated(generation, result) => {
+                if generation != self.chart_generation {
+                    return discard_stale_chart(result.ok().map(|(path, _)| path));
+                }
+                let replace_command = match result {
+                    Ok((path, report)) => {
+                        self.sample_report = Some(report);
+                        self.error_message = String::new();
+                        self.show_chart(Some(path))
+                    }
+                    Err(e) => {
+                        self.report_failure(format!("Error generating chart: {}", e));
+                        self.sample_report = None;
+                        self.show_chart(None)
+                    }
+                };
+                Command::batch(vec![replace_command, self.auto_save_chart()])
+            }
+
+            // When a range chart task completes (histogram, peaks), we receive the chart and the range summary.
+            Message::RangeChartGenerated(generation, result) => {
+                if generation != self.chart_generation {
+                    return discard_stale_chart(result.ok().map(|(path, _)| path));
+                }
+                let replace_command = match result {
+                    Ok((path, summary)) => {
+                        self.range_summary = Some(summary);
+                        self.error_message = String::new();
+                        self.show_chart(Some(path))
+                    }
+                    Err(e) => {
+                        self.report_failure(format!("Error generating chart: {}", e));
+                        self.range_summary = None;
+                        self.show_chart(None)
+                    }
+                };
+                Command::batch(vec![replace_command, self.auto_save_chart()])
+            }
+
+            // When the cumulative distribution task completes, we receive the chart, the range summary and the curve.
+            // The curve of a different range becomes the reference of the next chart.
+            Message::CdfGenerated(generation, result) => {
+                if generation != self.chart_generation {
+                    return discard_stale_chart(result.ok().map(|(path, _, _)| path));
+                }
+                let replace_command = match result {
+                    Ok((path, summary, curve)) => {
+                        if self.cdf_current.as_ref().is_some_and(|current| current.0 != curve.0) {
+                            self.cdf_reference = self.cdf_current.take();
+                        }
+                        self.cdf_current = Some(curve);
+                        self.range_summary = Some(summary);
+                        self.error_message = String::new();
+                        self.show_chart(Some(path))
+                    }
+                    Err(e) => {
+                        self.report_failure(format!("Error generating chart: {}", e));
+                        self.range_summary = None;
+                        self.show_chart(None)
+                    }
+                };
+                Command::batch(vec![replace_command, self.auto_save_chart()])
+            }
+
+            // When the "trajectories through a value" task completes, we receive the chart and the counts.
+            Message::ThroughGenerated(generation, result) => {
+                if generation != self.chart_generation {
+                    return discard_stale_chart(result.ok().map(|(path, _)| path));
+                }
+                let replace_command = match result {
+                    Ok((path, counts)) => {
+                        self.through_counts = Some(counts);
+                        self.error_message = String::new();
+                        self.show_chart(Some(path))
+                    }
+                    Err(e) => {
+                        self.report_failure(format!("Error generating chart: {}", e));
+                        self.through_counts = None;
+                        self.show_chart(None)
+                    }
+                };
+                Command::batch(vec![replace_command, self.auto_save_chart()])
+            }
+
+            // When the "most-visited values" task completes, we receive the chart and the ranking.
+            Message::MostVisitedGenerated(generation, result) => {
+                if generation != self.chart_generation {
+                    return discard_stale_chart(result.ok().map(|(path, _)| path));
+                }
+                let replace_command = match result {
+                    Ok((path, ranking)) => {
+                        self.most_visited = Some(ranking);
+                        self.error_message = String::new();
+                        self.show_chart(Some(path))
+                    }
+                    Err(e) => {
+                        self.report_failure(format!("Error generating chart: {}", e));
+                        self.most_visited = None;
+                        self.show_chart(None)
+                    }
+                };
+                Command::batch(vec![replace_command, self.auto_save_chart()])
+            }
+
+            // When a verification chunk completes, keep its progress and start the next chunk,
+            // unless the verification was stopped (its checkpoint is already on disk).
+            Message::VerificationProgress(job, result) => {
+                if job != self.verification_job {
+                    return Command::none(); // Chunk of a verification that was replaced since.
+                }
+                match result {
+                    Ok(mut checkpoint) => {
+                        // The report may have been requested (or not) while the chunk was running.
+                        checkpoint.report = self.verification_report;
+                        let next = if self.verification_running && !checkpoint.is_complete() {
+                            self.verification_chunk(checkpoint.clone())
+                        } else if checkpoint.is_complete() {
+                            self.verification_running = false;
+                            let body = format!(
+                                "{} to {}: {} delay records",
+                                checkpoint.range_start,
+                                checkpoint.range_end,
+                                checkpoint.records.len(),
+                            );
+                            Command::batch(vec![
+                                self.notify_job_done("Verification done", body),
+                                self.write_verification_report(&checkpoint),
+                            ])
+                        } else {
+                            self.verification_running = false;
+                            Command::none()
+                        };
+                        self.verification = Some(checkpoint);
+                        next
+                    }
+                    Err(e) => {
+                        self.verification_running = false;
+                        self.report_failure(format!("Error during verification: {}", e));
+                        Command::none()
+                    }
+                }
+            }
+
+            Message::ResumeVerification => {
+                match self.verification.clone() {
+                    Some(checkpoint) if !self.verification_running && !checkpoint.is_complete() => {
+                        self.error_message = String::new();
+                        self.verification_job += 1;
+                        self.verification_running = true;
+                        self.verification_chunk(checkpoint)
+                    }
+                    _ => Command::none(),
+                }
+            }
+
+            // The chunk in progress still completes and writes its checkpoint.
+            Message::PauseVerification => {
+                self.verification_running = false;
+                Command::none()
+            }
+
+            // The report is requested in the checkpoint, so that a verification resumed after a restart still writes it.
+            Message::VerificationReportToggled(report) => {
+                self.verification_report = report;
+                if let Some(checkpoint) = self.verification.as_mut().filter(|checkpoint| !checkpoint.is_complete()) {
+                    checkpoint.report = report;
+                }
+                Command::none()
+            }
+            Message::VerificationReportWritten(path, result) => {
+                match result {
+                    Ok(()) => self.verification_report_path = Some(path),
+                    Err(e) => self.report_failure(format!("Error while writing the verification report: {}", e)),
+                }
+                Command::none()
+            }
+
+            // Pausing drops the subscription; the results so far stay in `range_scan`.
+            Message::PauseRangeScan => {
+                if let Some(scan) = &mut self.range_scan {
+                    scan.running = false;
+                }
+                Command::none()
+            }
+            Message::ResumeRangeScan => {
+                if let Some(scan) = &mut self.range_scan {
+                    if !scan.running && scan.scanned < scan.total() {
+                        scan.segment += 1;
+                        scan.running = true;
+                    }
+                }
+                Command::none()
+            }
+
+            // When the chart saving task completes, we receive a result.
+            // If the result is Ok, we set the chart saved flag to true.
+            // If the result is Err, we set the error message.
+            // We also clear the error message if the chart was saved successfully.
+            Message::ChartSaved(result) => {
+                match result {
+                    Ok(()) => {
+                        self.chart_saved = true;
+                        self.error_message = String::new();
+                    }
+                    Err(e) => {
+                        self.report_failure(format!("Error while saving: {}", e));
+                    }
+                }
+                Command::none() // No further command needed after chart saving.
+            }
+            
+            // When the clipboard copy task completes, we receive a result.
+            // If the result is Ok, we set the copied to clipboard flag to true.
+            // If the result is Err, we set the error message.
+            // We also clear the error message if the copy was successful.
+            Message::ClipboardCopied(result) => {
+                match result {
+                    Ok(()) => {
+                        self.copied_to_clipboard = true;
+                        self.error_message = String::new();
+                    }
+                    Err(e) => {
+                        self.error_message = format!("Error while copying: {}", e);
+                    }
+                }
+                Command::none() // No further command needed after clipboard copy.
+            }
+            
+            // The sequence chart is re-rendered at print resolution; other charts are printed as displayed.
+            Message::PrintChart => {
+                let Some(chart_path) = self.chart_path.clone() else {
+                    self.error_message = "No graph to print".to_string();
+                    return Command::none();
+                };
+                self.error_message = String::new();
+                self.chart_printed = false;
+
+                let now = Local::now();
+                let print_path = temp_file_path(&format!("print_{}.png", now.format("%Y%m%d_%H%M%S")));
+                let print_chart_file = if self.chart_kind == ChartKind::Sequences {
+                    Some((
+                        print_path,
+                        self.chart_series(),
+                        self.transform,
+                        self.sequence_chart_options(),
+                    ))
+                } else {
+                    None
+                };
+                Command::perform(off_ui_thread(print_chart(chart_path, print_chart_file)), Message::ChartPrinted)
+            }
+            Message::ChartPrinted(result) => {
+                match result {
+                    Ok(()) => {
+                        self.chart_printed = true;
+                        self.error_message = String::new();
+                    }
+                    Err(e) => self.report_failure(format!("Error while printing: {}", e)),
+                }
+                Command::none()
+            }
+
+            // Each sequence is written to its own binary file, for programs that read raw numbers.
+            Message::BinaryEncodingSelected(encoding) => {
+                self.binary_encoding = encoding;
+                Command::none()
+            }
+            Message::ExportBinary => {
+                if self.sequence1.is_empty() && self.sequence2.is_empty() {
+                    self.error_message = "No sequence to export".to_string();
+                    return Command::none();
+                }
+                if self.refuse_downsampled("export their terms") {
+                    return Command::none();
+                }
+                self.error_message = String::new();
+                self.sequences_exported = false;
+                self.steps_exported = false;
+                self.table_exported = false;
+                self.run_diff_exported = false;
+                self.graph_exported = false;
+                self.results_exported = false;
+
+                let now = Local::now();
+                let files = [(self.value1, &self.sequence1), (self.value2, &self.sequence2)]
+                    .into_iter()
+                    .filter(|(_, sequence)| !sequence.is_empty())
+                    .map(|(value, sequence)| {
+                        let filename = format!("collatz_{}_{}.bin", value.unwrap_or(0), now.format("%Y%m%d_%H%M%S"));
+                        (PathBuf::from(filename), sequence.clone())
+                    })
+                    .collect();
+                Command::perform(export_binary(files, self.binary_encoding), Message::BinaryExported)
+            }
+            // An imported sequence is charted as it is, without recomputing it:
+            // it replaces the first sequence, and the second one is cleared.
+            Message::ImportPathChanged(value) => {
+                self.import_path_input = value;
+                Command::none()
+            }
+
+            // The jobs of a work plan run one after the other in the background, while the window stays usable.
+            // A plan imported while another one runs is queued after it, and its outputs go to the same folder.
+            Message::PlanPathChanged(value) => {
+                self.plan_path_input = value;
+                Command::none()
+            }
+            Message::RunPlan => {
+                let path = self.plan_path_input.trim().to_string();
+                if path.is_empty() {
+                    self.error_message = "No work plan to run".to_string();
+                    return Command::none();
+                }
+                self.error_message = String::new();
+                Command::perform(load_plan(path), Message::PlanLoaded)
+            }
+            Message::PlanLoaded(result) => {
+                let jobs = match result {
+                    Ok(jobs) => jobs,
+                    Err(e) => {
+                        self.error_message = e;
+                        return Command::none();
+                    }
+                };
+                if self.plan_running.is_none() && self.plan_queue.is_empty() {
+                    self.plan_done.clear();
+                    self.plan_dir = PathBuf::from(format!("collatz_plan_{}", Local::now().format("%Y%m%d_%H%M%S")));
+                }
+                let first = self.plan_done.len() + self.plan_queue.len() + usize::from(self.plan_running.is_some());
+                self.plan_queue.extend(jobs.into_iter().enumerate().map(|(index, job)| (first + index, job)));
+                self.start_next_job()
+            }
+            Message::PlanJobDone(result) => {
+                if let Some((index, job)) = self.plan_running.take() {
+                    self.plan_done.push((index, job, result));
+                }
+                if !self.plan_queue.is_empty() {
+                    return self.start_next_job();
+                }
+                let failed = self.plan_done.iter().filter(|(_, _, result)| result.is_err()).count();
+                let summary = match failed {
+                    0 => format!("{} jobs done, in {}", self.plan_done.len(), self.plan_dir.display()),
+                    _ => format!("{} jobs done, {} failed, in {}", self.plan_done.len(), failed, self.plan_dir.display()),
+                };
+                self.notify_job_done("Work plan done", summary)
+            }
+
+            // The jobs panel gathers the background tasks: the range scan, the verification and the work plan.
+            Message::ShowJobsToggled(show) => {
+                self.show_jobs = show;
+                Command::none()
+            }
+            // A cancelled scan stops for good: its chart and summary stay as they were.
+            Message::CancelRangeScan => {
+                self.range_scan = None;
+                Command::none()
+            }
+            // The job reports its progress in `plan_progress`: the panel is drawn again to show it.
+            Message::PlanProgressTick => Command::none(),
+            // A waiting job is dropped; the running one stops at its next check, and reports it.
+            Message::CancelPlanJob(index) => {
+                if self.plan_running.as_ref().is_some_and(|(running, _)| *running == index) {
+                    self.plan_cancel.store(true, Ordering::Relaxed);
+                } else if let Some(position) = self.plan_queue.iter().position(|(queued, _)| *queued == index) {
+                    if let Some((index, job)) = self.plan_queue.remove(position) {
+                        self.plan_done.push((index, job, Err("Cancelled".to_string())));
+                    }
+                }
+                Command::none()
+            }
+            // The main output of a job (its chart, else its statistics or data) opens in the default application.
+            Message::OpenPlanResult(index) => {
+                let Some((_, job, _)) = self.plan_done.iter().find(|(done, _, _)| *done == index) else {
+                    return Command::none();
+                };
+                let extension = match job.outputs.iter().find(|&&output| output == work_plan::JobOutput::Chart) {
+                    Some(_) => "png",
+                    None => match job.outputs.first() {
+                        Some(work_plan::JobOutput::Csv) => "csv",
+                        _ => "txt",
+                    },
+                };
+                let path = self.plan_dir.join(job.file_stem(index)).with_extension(extension);
+                Command::perform(open_with_default_app(path), Message::PlanResultOpened)
+            }
+            Message::PlanResultOpened(result) => {
+                if let Err(e) = result {
+                    self.error_message = e;
+                }
+                Command::none()
+            }
+
+            // A finished job is notified on the desktop only while the window is in the background:
+            // otherwise its result is already in sight.
+            Message::NotifyJobsToggled(notify_jobs) => {
+                self.notify_jobs = notify_jobs;
+                Command::none()
+            }
+            // The new pool is used from the next chunk of the running jobs on.
+            Message::WorkerThreadsSelected(worker_threads) => {
+                self.worker_threads = worker_threads;
+                workers::set_worker_threads(worker_threads);
+                self.user_settings.set(settings::WORKER_THREADS, worker_threads.to_setting());
+                Command::perform(save_settings(settings::default_path(), self.user_settings.clone()), Message::SettingsSaved)
+            }
+            Message::WindowFocusChanged(focused) => {
+                self.window_focused = focused;
+                Command::none()
+            }
+            Message::NotificationSent(result) => {
+                if let Err(e) = result {
+                    self.error_message = e;
+                }
+                Command::none()
+            }
+            Message::AutoSaveToggled(auto_save) => {
+                self.auto_save = auto_save;
+                Command::none()
+            }
+            Message::AutoSaveDirChanged(value) => {
+                self.auto_save_dir_input = value;
+                Command::none()
+            }
+            Message::ImageFormatSelected(format) => {
+                self.image_format = format;
+                Command::none()
+            }
+            Message::ImageQualitySelected(quality) => {
+                self.image_quality = quality;
+                Command::none()
+            }
+            Message::ChartAutoSaved(result) => {
+                match result {
+                    Ok(path) => self.auto_saved_to = Some(path),
+                    Err(e) => self.report_failure(format!("Error while saving automatically: {}", e)),
+                }
+                Command::none()
+            }
+            Message::ImportSequence => {
+                let path = self.import_path_input.trim().to_string();
+                if path.is_empty() {
+                    self.error_message = "No file to import".to_string();
+                    return Command::none();
+                }
+                self.error_message = String::new();
+                // Saved charts carry the description of their run instead of a sequence.
+                if path.to_ascii_lowercase().ends_with(".png") {
+                    return Command::perform(read_chart_run(path), Message::ChartRunRead);
+                }
+                Command::perform(import_sequence(path), Message::SequenceImported)
+            }
+            Message::FileDropped(path) => {
+                self.import_path_input = path.to_string_lossy().to_string();
+                self.update(Message::ImportSequence)
+            }
+            Message::ChartRunRead(result) => {
+                match result {
+                    Ok(run) => self.chart_run = Some(run),
+                    Err(e) => self.error_message = format!("Error while importing: {}", e),
+                }
+                Command::none()
+            }
+            Message::ApplyChartRun => match self.chart_run.take() {
+                Some((_, link)) => self.apply_link(link),
+                None => Command::none(),
+            },
+            Message::DismissChartRun => {
+                self.chart_run = None;
+                Command::none()
+            }
+            Message::SequenceImported(Ok((path, sequence))) => {
+                self.clear_status();
+                if !self.chart_kind.uses_sequences() {
+                    self.chart_kind = ChartKind::Sequences;
+                }
+
+                self.input1 = sequence[0].to_string();
+                self.input2 = String::new();
+                self.value1 = Some(sequence[0]);
+                self.value2 = None;
+                // A sequence too long for the memory budget is kept downsampled, with the statistics of every term.
+                let budget_terms = sequence_budget::budget_terms(self.memory_budget);
+                if sequence.len() > budget_terms {
+                    let streamed = sequence_budget::stream(sequence, budget_terms);
+                    let (length, last) = (streamed.stats.length, streamed.last.unwrap_or(1));
+                    self.downsampled = [
+                        Some(sequence_budget::Downsampled { stride: streamed.stride, length, truncated: false, last }),
+                        None,
+                    ];
+                    self.stats1 = Some(streamed.stats);
+                    self.sequence1 = streamed.terms;
+                } else {
+                    self.downsampled = [None, None];
+                    self.stats1 = Some(collatz::calculate_stats(&sequence));
+                    self.sequence1 = sequence;
+                }
+                self.stats2 = None;
+                self.sequence2.clear();
+                self.refresh_steps_shown();
+                self.imported_from = Some(path);
+                self.chart_options.view = None;
+                self.table_selection = sequence_table::RowSelection::default();
+                self.import_check = None;
+                self.diagnostics = None;
+                self.chart_sequences()
+            }
+            Message::SequenceImported(Err(e)) => {
+                self.error_message = format!("Error while importing: {}", e);
+                Command::none()
+            }
+
+            // The imported sequence is recomputed from its first term, and the first
+            // term disagreeing with the Collatz rule is reported.
+            Message::VerifyImport => {
+                if self.downsampled[0].is_some() {
+                    self.error_message =
+                        "The imported sequence is downsampled to fit the memory budget: raise the budget to verify it".to_string();
+                    return Command::none();
+                }
+                let check = collatz::check_sequence(&self.sequence1);
+                self.error_message = match check {
+                    Ok(()) => String::new(),
+                    Err(mismatch) => format!(
+                        "The imported sequence disagrees with the Collatz rule at step {}",
+                        mismatch.index
+                    ),
+                };
+                self.import_check = Some(check);
+                Command::none()
+            }
+            Message::BinaryExported(result) => {
+                match result {
+                    Ok(()) => {
+                        self.sequences_exported = true;
+                        self.error_message = String::new();
+                    }
+                    Err(e) => self.report_failure(format!("Error while exporting: {}", e)),
+                }
+                Command::none()
+            }
+
+            // The screenshot is composed off-screen: the inputs, the chart and the statistics are drawn
+            // into one image, so it contains the whole view even when the window has to be scrolled.
+            Message::ExportView => {
+                self.error_message = String::new();
+                self.view_exported = false;
+
+                let mut header = vec![format!("Chart: {}", self.chart_kind)];
+                if self.chart_kind.uses_range() {
+                    header.push(format!("Range: {} to {}", self.range_start_input.trim(), self.range_end_input.trim()));
+                } else {
+                    header.push(format!("Values: {}  {}", self.input1.trim(), self.input2.trim()));
+                }
+                if self.chart_kind == ChartKind::Sequences {
+                    header.push(format!("Plotted: {}", self.transform.axis_label()));
+                }
+                if self.chart_kind == ChartKind::Difference {
+                    header.push(format!("Plotted: {}", self.difference_mode.axis_label()));
+                }
+                if self.chart_kind == ChartKind::MonteCarlo {
+                    header.push(format!(
+                        "Samples: {} ({})",
+                        self.sample_count_input.trim(),
+                        self.sampling_distribution.to_string().to_lowercase()
+                    ));
+                }
+
+                let now = Local::now();
+                let filename = format!("collatz_view_{}.png", now.format("%Y%m%d_%H%M%S"));
+                Command::perform(
+                    off_ui_thread(export_view(PathBuf::from(filename), header, self.chart_path.clone(), self.statistics_text())),
+                    Message::ViewExported,
+                )
+            }
+            Message::ViewExported(result) => {
+                match result {
+                    Ok(()) => {
+                        self.view_exported = true;
+                        self.error_message = String::new();
+                    }
+                    Err(e) => self.report_failure(format!("Error while exporting the view: {}", e)),
+                }
+                Command::none()
+            }
+
+            // A second window is another instance of the application, with its own state,
+            // so that two explorations can be viewed side by side.
+            Message::OpenWindow => Command::perform(open_new_window(), Message::WindowOpened),
+            Message::WindowOpened(result) => {
+                if let Err(e) = result {
+                    self.error_message = format!("Unable to open a new window: {}", e);
+                }
+                Command::none()
+            }
+
+            // A failed write is reported, but the run itself is not affected.
+            // The open history panel is refreshed with the new run.
+            Message::ExperimentLogged(result) => {
+                match result {
+                    Ok(()) if self.show_history => Command::perform(
+                        load_history(experiment_log::default_path(), HISTORY_LENGTH),
+                        Message::HistoryLoaded,
+                    ),
+                    Ok(()) => Command::none(),
+                    Err(e) => {
+                        self.report_failure(e);
+                        Command::none()
+                    }
+                }
+            }
+
+            // A failed write of the results database is reported, but the run itself is not affected.
+            // The open browser is refreshed with the new results.
+            Message::ResultsStored(result) => match result {
+                Ok(_) if self.show_results_db => {
+                    Command::perform(load_results_db(results_db::default_path()), Message::ResultsDbLoaded)
+                }
+                Ok(_) => Command::none(),
+                Err(e) => {
+                    self.report_failure(e);
+                    Command::none()
+                }
+            },
+
+            // The report is written next to the exported files, for the user to attach to a bug report.
+            Message::SaveErrorReport => {
+                let Some(failure) = &self.error_dialog else {
+                    return Command::none();
+                };
+                let path = PathBuf::from(format!("collatz_error_report_{}.txt", Local::now().format("%Y%m%d_%H%M%S")));
+                let report = error_report::report_text(failure, env!("CARGO_PKG_VERSION"), &self.recent_log.lines());
+                Command::perform(write_text_file(path.clone(), report), move |result| {
+                    Message::ErrorReportSaved(path.clone(), result)
+                })
+            }
+            Message::ErrorReportSaved(path, result) => {
+                self.error_report_saved = Some(result.map(|()| path));
+                Command::none()
+            }
+            Message::CloseErrorDialog => {
+                self.error_dialog = None;
+                self.error_report_saved = None;
+                Command::none()
+            }
+
+            // The temporary charts of the window (the current one and the cached ones) are deleted
+            // when it closes.
+            // Closing does not depend on it: the files of this process left behind are swept once the window is closed.
+            Message::CloseRequested => {
+                let mut paths = self.recent_charts.drain();
+                paths.extend(self.chart_path.take());
+                Command::perform(cleanup_temp_files(paths), Message::ReadyToClose)
+            }
+            Message::ReadyToClose(result) => {
+                if let Err(e) = result {
+                    println!("Warning: Unable to delete temporary file: {}", e);
+                }
+                iced::window::close()
+            }
+
+            // When the cleanup task completes, we receive a result.
+            // If the result is Ok, we ignore it (cleanup is not critical).
+            // If the result is Err, we print a warning message.
+            // This is done to avoid cluttering the directory with old files.
+            Message::CleanupOldTempFiles(result) => {
+                // On peut ignorer le résultat, car ce n'est pas critique si le nettoyage échoue
+                // Mais on pourrait ajouter un log ou une notification en cas d'erreur
+                if let Err(e) = result {
+                    println!("Warning: Unable to delete old temporary file: {}", e);
+                    self.log(&format!("Warning: Unable to delete old temporary file: {}", e));
+                }
+                Command::none() // No further command needed after cleanup.
+            }
+        }
+    }
+
+    /// Background streams of the application: the running range scan, if any, and the progress of the running
+    /// work plan while the jobs panel is shown.
+    /// The keyboard is also listened to, for the previous/next value shortcuts and the keyboard navigation.
+    fn subscription(&self) -> Subscription<Message> {
+        let range_scan = match &self.range_scan {
+            Some(scan) if scan.running => range_scan_subscription(scan, self.stopping_time_cache.clone()),
+            _ => Subscription::none(),
+        };
+        let plan_progress = if self.show_jobs && self.plan_running.is_some() {
+            iced::time::every(std::time::Duration::from_millis(250)).map(|_| Message::PlanProgressTick)
+        } else {
+            Subscription::none()
+        };
+        Subscription::batch([
+            range_scan,
+            plan_progress,
+            iced::subscription::events_with(keyboard_shortcut),
+            iced::subscription::events_with(modifiers_changed),
+            iced::subscription::events_with(close_request),
+            iced::subscription::events_with(window_focus),
+            iced::subscription::events_with(file_dropped),
+        ])
+    }
+
+    // ==========================================================================
+    //                              View Function
+    // ==========================================================================
+    /// This function is called to render the application's UI.
+    /// It returns an `Element` that represents the entire UI.
+    /// The UI is built using a combination of widgets (buttons, text inputs, etc.).
+    /// The `view` function is responsible for creating the layout and appearance of the application.
+    /// It uses the current state of the application to determine what to display.
+    fn view(&self) -> Element<'_, Message> {
+        // An unexpected failure takes the whole window until its dialog is closed.
+        if let Some(failure) = &self.error_dialog {
+            return self.error_dialog_view(failure);
+        }
+
+        // Title of the application
+        let title = text("Collatz Conjecture Visualizer")
+            .size(28)
+            .style(Color::from_rgb(0.2, 0.4, 0.8));
+        
+        // Input fields
+        // Two text inputs for the user to enter integers.
+        // The first input is required, the second is optional.
+        // An invalid value gets a red border and its error under the field.
+        // The small buttons beside each input adjust its value (+1, -1, x2, next odd).
+        // In the linked input mode, value 2 is derived from value 1 and cannot be edited.
+        // In the family mode, value 2 is not used at all.
+        let linked = self.input_link != input::InputLink::Off || self.family_mode;
+        let value2_on_input = (!linked).then_some(Message::Input2Changed as fn(String) -> Message);
+        let input_row = row![
+            text("Value 1:").size(16),
+            value_input("Enter an integer", &self.input1, Some(Message::Input1Changed), self.input_error(0)),
+            adjustment_buttons(0, true),
+            horizontal_space(Length::Fixed(20.0)),
+            text("Value 2:").size(16),
+            value_input("Enter an integer (optional)", &self.input2, value2_on_input, self.input_error(1)),
+            adjustment_buttons(1, !linked),
+            text("Linked:").size(16),
+            focus_ring::cycle(pick_list(&input::InputLink::ALL[..], Some(self.input_link), Message::InputLinkSelected)),
+            focus_ring::press(checkbox("Auto-visualize", self.auto_visualize, Message::AutoVisualizeToggled)),
+            focus_ring::press(checkbox("Log runs", self.log_experiments, Message::LogExperimentsToggled)),
+            focus_ring::press(checkbox("Store results", self.store_results, Message::StoreResultsToggled)),
+            focus_ring::press(checkbox("History", self.show_history, Message::ShowHistoryToggled)),
+            focus_ring::press(checkbox("Results", self.show_results_db, Message::ShowResultsDbToggled)),
+            focus_ring::press(checkbox("Guess", self.game.is_some(), Message::GameToggled)),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        // Chart kind selector
+        // The range inputs only matter for range charts, so they are only shown for them.
+        let mut kind_row = row![
+            text("Chart:").size(16),
+            focus_ring::cycle(pick_list(&ChartKind::ALL[..], Some(self.chart_kind), Message::ChartKindSelected)),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center);
+
+        if self.chart_kind == ChartKind::Sequences {
+            kind_row = kind_row
+                .push(horizontal_space(Length::Fixed(20.0)))
+                .push(text("Plot:").size(16))
+                .push(focus_ring::cycle(pick_list(
+                    &transform::TermTransform::ALL[..],
+                    Some(self.transform),
+                    Message::TransformSelected,
+                )))
+                .push(focus_ring::press(checkbox("Chart options", self.show_chart_options, Message::ShowChartOptionsToggled)))
+                .push(focus_ring::press(checkbox("Family", self.family_mode, Message::FamilyToggled)));
+            if self.family_mode {
+                kind_row = kind_row
+                    .push(text("Doublings:").size(16))
+                    .push(focus_ring::cycle(pick_list(&FAMILY_DOUBLINGS[..], Some(self.family_doublings), Message::FamilyDoublingsSelected)));
+            }
+            kind_row = kind_row
+                .push(text("Tail:").size(16))
+                .push(focus_ring::cycle(pick_list(&collatz::TailHandling::ALL[..], Some(self.tail_handling), Message::TailHandlingSelected)));
+            if self.tail_handling == collatz::TailHandling::RepeatCycle {
+                kind_row = kind_row
+                    .push(text("Times:").size(16))
+                    .push(focus_ring::cycle(pick_list(&TAIL_CYCLES[..], Some(self.tail_cycles), Message::TailCyclesSelected)));
+            }
+        }
+
+        if self.chart_kind == ChartKind::Difference {
+            kind_row = kind_row
+                .push(horizontal_space(Length::Fixed(20.0)))
+                .push(text("Plot:").size(16))
+                .push(focus_ring::cycle(pick_list(
+                    &difference::DifferenceMode::ALL[..],
+                    Some(self.difference_mode),
+                    Message::DifferenceModeSelected,
+                )));
+        }
+
+        if self.chart_kind.uses_sequences() {
+            kind_row = kind_row
+                .push(horizontal_space(Length::Fixed(20.0)))
+                .push(focus_ring::press(checkbox("Base 3", self.show_ternary, Message::ShowTernaryToggled)))
+                .push(focus_ring::press(checkbox("Steps", self.show_steps, Message::ShowStepsToggled)))
+                .push(focus_ring::press(checkbox("Table", self.show_table, Message::ShowTableToggled)))
+                .push(focus_ring::press(checkbox("Siblings", self.show_siblings, Message::ShowSiblingsToggled)))
+                .push(focus_ring::press(checkbox("Diagnostics", self.show_diagnostics, Message::ShowDiagnosticsToggled)));
+        }
+
+        // The glide-only mode applies to the sequences and to the scans of the density and scatter charts.
+        if self.chart_kind.uses_sequences()
+            || matches!(self.chart_kind, ChartKind::StoppingTimeDensity | ChartKind::StoppingTimeScatter)
+        {
+            kind_row = kind_row.push(focus_ring::press(checkbox("Glide only", self.glide_only, Message::GlideOnlyToggled)));
+        }
+
+        if self.chart_kind.uses_range() {
+            kind_row = kind_row
+                .push(horizontal_space(Length::Fixed(20.0)))
+                .push(text("From:").size(16))
+                .push(text_input("Range start", &self.range_start_input)
+                    .on_input(Message::RangeStartChanged)
+                    .on_submit(Message::Visualize)
+                    .padding(10))
+                .push(text("To:").size(16))
+                .push(text_input("Range end", &self.range_end_input)
+                    .on_input(Message::RangeEndChanged)
+                    .on_submit(Message::Visualize)
+                    .padding(10));
+        }
+
+        // Exhaustive scans can run on another backend, when more than one is compiled in.
+        if matches!(self.chart_kind, ChartKind::StoppingTimeDensity | ChartKind::StoppingTimeScatter)
+            && ScanBackend::ALL.len() > 1
+        {
+            kind_row = kind_row
+                .push(focus_ring::cycle(pick_list(ScanBackend::ALL, Some(self.scan_backend), Message::ScanBackendSelected)));
+        }
+
+        if self.chart_kind == ChartKind::MonteCarlo {
+            kind_row = kind_row
+                .push(text("Samples:").size(16))
+                .push(text_input("M", &self.sample_count_input)
+                    .on_input(Message::SampleCountChanged)
+                    .on_submit(Message::Visualize)
+                    .padding(10)
+                    .width(Length::Fixed(90.0)))
+                .push(focus_ring::cycle(pick_list(
+                    &sampling::SamplingDistribution::ALL[..],
+                    Some(self.sampling_distribution),
+                    Message::SamplingDistributionSelected,
+                )));
+        }
+
+        if self.chart_kind == ChartKind::Verification {
+            kind_row = kind_row.push(focus_ring::press(checkbox(
+                "Overnight report",
+                self.verification_report,
+                Message::VerificationReportToggled,
+            )));
+            if self.verification_running {
+                kind_row = kind_row.push(focus_ring::press(button("Pause").on_press(Message::PauseVerification).padding(10)));
+            } else if self.verification.as_ref().is_some_and(|checkpoint| !checkpoint.is_complete()) {
+                kind_row = kind_row
+                    .push(focus_ring::press(button("Resume verification").on_press(Message::ResumeVerification).padding(10)));
+            }
+        }
+
+        // A range scan in progress can be paused, and a paused one resumed.
+        if let Some(scan) = self.range_scan.as_ref().filter(|scan| scan.kind == self.chart_kind) {
+            if scan.running {
+                kind_row = kind_row.push(focus_ring::press(button("Pause").on_press(Message::PauseRangeScan).padding(10)));
+            } else if scan.scanned < scan.total() {
+                kind_row = kind_row.push(focus_ring::press(button("Resume").on_press(Message::ResumeRangeScan).padding(10)));
+            }
+        }
+
+        if self.chart_kind.uses_sequences() || self.chart_kind == ChartKind::MapComparison {
+            kind_row = kind_row
+                .push(text("Step limit:").size(16))
+                .push(text_input("Steps", &self.step_limit_input)
+                    .on_input(Message::StepLimitChanged)
+                    .on_submit(Message::Visualize)
+                    .padding(10)
+                    .width(Length::Fixed(90.0)))
+                .push(text("Memory (MB):").size(16))
+                .push(text_input("MB", &self.memory_budget_input)
+                    .on_input(Message::MemoryBudgetChanged)
+                    .on_submit(Message::Visualize)
+                    .padding(10)
+                    .width(Length::Fixed(70.0)));
+            // Sequences too long for the budget are pointed out next to it.
+            if self.step_stride() > 1 {
+                kind_row = kind_row.push(
+                    text(format!("Downsampled: 1 term in {} kept", self.step_stride()))
+                        .size(14)
+                        .style(Color::from_rgb(0.8, 0.4, 0.0)),
+                );
+            }
+        }
+
+        if self.chart_kind == ChartKind::MapComparison {
+            kind_row = kind_row
+                .push(horizontal_space(Length::Fixed(20.0)))
+                .push(text("Divergent above:").size(16))
+                .push(text_input("Bound", &self.divergence_bound_input)
+                    .on_input(Message::DivergenceBoundChanged)
+                    .on_submit(Message::Visualize)
+                    .padding(10)
+                    .width(Length::Fixed(160.0)))
+                .push(text("for").size(16))
+                .push(text_input("K", &self.divergence_steps_input)
+                    .on_input(Message::DivergenceStepsChanged)
+                    .on_submit(Message::Visualize)
+                    .padding(10)
+                    .width(Length::Fixed(60.0)))
+                .push(text("steps").size(16));
+        }
+
+        if self.chart_kind == ChartKind::MergedGraph {
+            kind_row = kind_row
+                .push(text("Starts:").size(16))
+                .push(text_input("e.g. 27, 31, 41", &self.graph_starts_input)
+                    .on_input(Message::GraphStartsChanged)
+                    .on_submit(Message::Visualize)
+                    .padding(10));
+            if self.merged_graph.is_some() {
+                kind_row = kind_row.push(focus_ring::press(button("Export edges").on_press(Message::ExportGraph).padding(10)));
+            }
+        }
+
+        if self.chart_kind == ChartKind::Fractal {
+            kind_row = kind_row
+                .push(horizontal_space(Length::Fixed(20.0)))
+                .push(text("Center:").size(16))
+                .push(text_input("Real part", &self.fractal_re_input)
+                    .on_input(Message::FractalReChanged)
+                    .on_submit(Message::Visualize)
+                    .padding(10)
+                    .width(Length::Fixed(90.0)))
+                .push(text("+ i").size(16))
+                .push(text_input("Imaginary part", &self.fractal_im_input)
+                    .on_input(Message::FractalImChanged)
+                    .on_submit(Message::Visualize)
+                    .padding(10)
+                    .width(Length::Fixed(90.0)))
+                .push(text("Width:").size(16))
+                .push(text_input("Width", &self.fractal_width_input)
+                    .on_input(Message::FractalWidthChanged)
+                    .on_submit(Message::Visualize)
+                    .padding(10)
+                    .width(Length::Fixed(90.0)))
+                .push(focus_ring::press(button("Zoom in").on_press(Message::ZoomFractal(0.5)).padding(10)))
+                .push(focus_ring::press(button("Zoom out").on_press(Message::ZoomFractal(2.0)).padding(10)));
+        }
+
+        if self.chart_kind == ChartKind::StoppingTimeHistogram {
+            kind_row = kind_row
+                .push(text("Bins:").size(16))
+                .push(text_input("Bins", &self.histogram_bins_input)
+                    .on_input(Message::HistogramBinsChanged)
+                    .on_submit(Message::Visualize)
+                    .padding(10)
+                    .width(Length::Fixed(60.0)));
+        }
+
+        if self.chart_kind == ChartKind::StoppingTimeScatter {
+            kind_row = kind_row
+                .push(text("Color by T mod:").size(16))
+                .push(text_input("k", &self.scatter_modulus_input)
+                    .on_input(Message::ScatterModulusChanged)
+                    .on_submit(Message::Visualize)
+                    .padding(10)
+                    .width(Length::Fixed(60.0)));
+        }
+        
+        // Chart options form
+        // Custom texts and axes of the sequence chart (blank fields keep the automatic ones).
+        let options_row = if self.show_chart_options && self.chart_kind == ChartKind::Sequences {
+            column![
+                row![
+                    text("Caption:").size(16),
+                    text_input("Automatic", &self.chart_options.caption)
+                        .on_input(Message::CaptionChanged)
+                        .padding(10),
+                    text("Subtitle:").size(16),
+                    text_input("None", &self.chart_options.subtitle)
+                        .on_input(Message::SubtitleChanged)
+                        .padding(10),
+                    text("Line:").size(16),
+                    focus_ring::cycle(pick_list(
+                        &chart_options::LINE_WIDTHS[..],
+                        Some(self.chart_options.line_width),
+                        Message::LineWidthSelected,
+                    )),
+                    text("Colors:").size(16),
+                    focus_ring::cycle(pick_list(&chart_options::Palette::ALL[..], Some(self.chart_options.palette), Message::PaletteSelected)),
+                    focus_ring::press(checkbox("Anti-aliasing", self.chart_options.antialias, Message::AntialiasToggled)),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+                row![
+                    text("X axis:").size(16),
+                    text_input("Step", &self.chart_options.x_label)
+                        .on_input(Message::XLabelChanged)
+                        .padding(10),
+                    text("Y axis:").size(16),
+                    text_input(self.transform.axis_label(), &self.chart_options.y_label)
+                        .on_input(Message::YLabelChanged)
+                        .padding(10),
+                    text("Ticks:").size(16),
+                    focus_ring::cycle(pick_list(
+                        &chart_options::TICK_SIZES[..],
+                        Some(self.chart_options.tick_size),
+                        Message::TickSizeSelected,
+                    )),
+                    focus_ring::cycle(pick_list(
+                        &chart_options::TickFormat::ALL[..],
+                        Some(self.chart_options.tick_format),
+                        Message::TickFormatSelected,
+                    )),
+                    text("Text size (%):").size(16),
+                    focus_ring::cycle(pick_list(&chart_options::TEXT_SCALES[..], Some(self.chart_options.text_scale), Message::TextScaleSelected)),
+                    focus_ring::press(checkbox("Log scale", self.chart_options.log_scale, Message::LogScaleToggled)),
+                    text("Y scale:").size(16),
+                    focus_ring::cycle(pick_list(
+                        &chart_options::YScaling::ALL[..],
+                        Some(self.chart_options.y_scaling),
+                        Message::YScalingSelected,
+                    )),
+                    text("Layout:").size(16),
+                    focus_ring::cycle(pick_list(
+                        &chart_options::ChartLayout::ALL[..],
+                        Some(self.chart_options.layout),
+                        Message::LayoutSelected,
+                    )),
+                    text("Align:").size(16),
+                    focus_ring::cycle(pick_list(
+                        &chart_options::SequenceAlignment::ALL[..],
+                        Some(self.chart_options.alignment),
+                        Message::AlignmentSelected,
+                    )),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+                self.series_options_row(0, &self.input1, &self.name1, Message::Name1Changed),
+                self.series_options_row(1, &self.input2, &self.name2, Message::Name2Changed),
+            ]
+            .spacing(5)
+        } else {
+            column![]
+        };
+
+        // Button row
+        // A row of buttons for user actions.
+        // Each button has an action associated with it (e.g., Visualize, Randomize).
+        let button_row = container(
+            row![
+                focus_ring::press(button("◀").on_press(Message::PreviousValue).padding(10)),
+                focus_ring::press(button("Visualize").on_press(Message::Visualize).padding(10)),
+                focus_ring::press(button("▶").on_press(Message::NextValue).padding(10)),
+                focus_ring::press(button("Compare neighbors").on_press(Message::CompareNeighbors).padding(10)),
+                focus_ring::press(button("Randomize").on_press(Message::Randomize).padding(10)),
+                focus_ring::press(button("Save the graph").on_press(Message::SaveChart).padding(10)),
+                focus_ring::press(button("Copy the sequence").on_press(Message::CopyToClipboard).padding(10)),
+                focus_ring::press(button("Copy link").on_press(Message::CopyLink).padding(10)),
+                focus_ring::press(button("Describe").on_press(Message::CopySummary).padding(10)),
+                focus_ring::press(button("Print").on_press(Message::PrintChart).padding(10)),
+                focus_ring::press(button("Export view").on_press(Message::ExportView).padding(10)),
+                focus_ring::press(button("New window").on_press(Message::OpenWindow).padding(10)),
+                focus_ring::press(button("Help").on_press(Message::ShowTutorial).padding(10)),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center) // Centre les boutons dans la rangée
+        )
+        .width(Length::Fill) // Force le conteneur à prendre toute la largeur
+        .center_x(); // Centre le conteneur lui-même
+
+        // Sequence file row
+        // Exchange of the sequences with other programs, as files.
+        let mut file_row = row![
+                text("Sequence files:").size(16),
+                focus_ring::cycle(pick_list(
+                    &sequence_file::BinaryEncoding::ALL[..],
+                    Some(self.binary_encoding),
+                    Message::BinaryEncodingSelected,
+                )),
+                focus_ring::press(button("Export binary").on_press(Message::ExportBinary).padding(10)),
+                text_input("File to import (CSV, JSON, binary or saved PNG chart)", &self.import_path_input)
+                    .on_input(Message::ImportPathChanged)
+                    .on_submit(Message::ImportSequence)
+                    .padding(10),
+                focus_ring::press(button("Import").on_press(Message::ImportSequence).padding(10)),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center);
+        if self.imported_from.is_some() {
+            file_row = file_row.push(focus_ring::press(button("Verify").on_press(Message::VerifyImport).padding(10)));
+        }
+        file_row = file_row
+            .push(text("Image format:"))
+            .push(focus_ring::cycle(pick_list(&image_format::ImageFormat::ALL[..], Some(self.image_format), Message::ImageFormatSelected)));
+        if self.image_format.is_lossy() {
+            file_row = file_row
+                .push(text("Quality:"))
+                .push(focus_ring::cycle(pick_list(&image_format::QUALITIES[..], Some(self.image_quality), Message::ImageQualitySelected)));
+        }
+        file_row = file_row
+            .push(focus_ring::press(checkbox("Auto-save to", self.auto_save, Message::AutoSaveToggled)))
+            .push(text_input("Folder (current if blank)", &self.auto_save_dir_input)
+                .on_input(Message::AutoSaveDirChanged)
+                .padding(10));
+        let file_row = container(file_row).width(Length::Fill).center_x();
+
+        // Work plan row
+        // A file of values and ranges to process in the background, with the progress of its jobs.
+        let total_jobs = self.plan_done.len() + self.plan_queue.len() + usize::from(self.plan_running.is_some());
+        let failed_jobs = self.plan_done.iter().filter(|(_, _, result)| result.is_err()).count();
+        let plan_status = match &self.plan_running {
+            Some((index, job)) => format!("Job {} of {} running: {}", index + 1, total_jobs, job.target),
+            None if total_jobs == 0 => String::new(),
+            None if failed_jobs > 0 => {
+                format!("{} jobs done, {} failed, in {}", total_jobs, failed_jobs, self.plan_dir.display())
+            }
+            None => format!("{} jobs done, in {}", total_jobs, self.plan_dir.display()),
+        };
+        let plan_row = container(
+            row![
+                text("Work plan:").size(16),
+                text_input("Plan file (a value or a range per line, with its outputs)", &self.plan_path_input)
+                    .on_input(Message::PlanPathChanged)
+                    .on_submit(Message::RunPlan)
+                    .padding(10),
+                focus_ring::press(button("Run plan").on_press(Message::RunPlan).padding(10)),
+                text(plan_status).size(14),
+                focus_ring::press(checkbox("Jobs", self.show_jobs, Message::ShowJobsToggled)),
+                focus_ring::press(checkbox("Notify", self.notify_jobs, Message::NotifyJobsToggled)),
+                text("Threads:").size(16),
+                focus_ring::cycle(pick_list(
+                    workers::WorkerThreads::choices(workers::available_cores()),
+                    Some(self.worker_threads),
+                    Message::WorkerThreadsSelected,
+                )),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
+        )
+        .width(Length::Fill)
+        .center_x();
+
+        // Run of an imported chart image, offered for reconstruction.
+        let chart_run_row = match &self.chart_run {
+            Some((description, _)) => row![
+                text(description).size(16),
+                focus_ring::press(button("Restore run").on_press(Message::ApplyChartRun).padding(10)),
+                focus_ring::press(button("Dismiss").on_press(Message::DismissChartRun).padding(10)),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
+            None => row![],
+        };
+        
+        // Status message
+        // A message to display the status of the application.
+        // This can be an error message, success message, or empty.
+        let status_message = if !self.error_message.is_empty() {
+            text(&self.error_message).style(Color::from_rgb(0.8, 0.2, 0.2))
+        } else if self.chart_saved {
+            text("Chart saved").style(Color::from_rgb(0.2, 0.8, 0.2))
+        } else if self.chart_printed {
+            text("Chart sent to the printer").style(Color::from_rgb(0.2, 0.8, 0.2))
+        } else if self.view_exported {
+            text("View exported").style(Color::from_rgb(0.2, 0.8, 0.2))
+        } else if self.sequences_exported {
+            text("Sequences exported").style(Color::from_rgb(0.2, 0.8, 0.2))
+        } else if self.steps_exported {
+            text("Steps exported").style(Color::from_rgb(0.2, 0.8, 0.2))
+        } else if self.table_exported {
+            text("Table exported").style(Color::from_rgb(0.2, 0.8, 0.2))
+        } else if self.run_diff_exported {
+            text("Comparison of the runs exported").style(Color::from_rgb(0.2, 0.8, 0.2))
+        } else if self.graph_exported {
+            text("Graph edges exported").style(Color::from_rgb(0.2, 0.8, 0.2))
+        } else if self.results_exported {
+            text("Results exported").style(Color::from_rgb(0.2, 0.8, 0.2))
+        } else if self.copied_to_clipboard {
+            text("Sequences copied to clipboard").style(Color::from_rgb(0.2, 0.8, 0.2))
+        } else if self.link_copied {
+            text("Link copied to clipboard").style(Color::from_rgb(0.2, 0.8, 0.2))
+        } else if let Some(label) = self.stat_copied {
+            text(format!("{} copied to clipboard", label)).style(Color::from_rgb(0.2, 0.8, 0.2))
+        } else if self.summary_copied {
+            text("Accessible summary copied to clipboard").style(Color::from_rgb(0.2, 0.8, 0.2))
+        } else if let Some(path) = self.auto_saved_to.as_ref().filter(|_| self.auto_save) {
+            text(format!("Chart saved to {}", path)).style(Color::from_rgb(0.2, 0.8, 0.2))
+        } else {
+            text("") // Empty text if no message to display
+        };
+        
+        // Statistics section
+        // This section displays the statistics of the generated sequences.
+        // If no sequences were generated, show a message indicating that.
+        // If sequences were generated, display their statistics.
+        // The statistics include flight time, maximum altitude, even/odd counts, and downtime.
+        // The statistics are displayed in a scrollable container.
+        let summary = self.statistics_text();
+        let stats_content = if self.chart_kind == ChartKind::MonteCarlo {
+            // A sample only estimates the range: report the spread and the confidence interval.
+            container(container(text(summary).size(14)).padding(10).width(Length::Fill))
+        } else if self.chart_kind == ChartKind::Verification {
+            // Progress of the verification, and the delay records found so far (most recent first).
+            container(
+                scrollable(container(text(summary).size(14)).padding(10).width(Length::Fill))
+                    .height(Length::Fixed(150.0))
+            )
+        } else if self.chart_kind == ChartKind::MostVisited {
+            // One line per value: rank, value, visits.
+            container(
+                scrollable(
+                    container(text(summary).size(14).font(iced::Font::MONOSPACE))
+                        .padding(10)
+                        .width(Length::Fill)
+                )
+                .height(Length::Fixed(150.0))
+            )
+        } else if self.chart_kind.uses_range() || matches!(self.chart_kind, ChartKind::Fractal | ChartKind::MergedGraph) {
+            // Range charts summarize the whole range instead of individual sequences, the fractal its window
+            // and the merged graph its merge nodes.
+            container(container(text(summary).size(14)).padding(10).width(Length::Fill))
+        } else if self.chart_kind == ChartKind::MapComparison {
+            // One line per map: the table makes the differences between variants easy to compare.
+            container(
+                scrollable(
+                    container(text(summary).size(14).font(iced::Font::MONOSPACE))
+                        .padding(10)
+                        .width(Length::Fill)
+                )
+                .height(Length::Fixed(150.0))
+            )
+        } else if self.sequence1.is_empty() && self.sequence2.is_empty() {
+            container(text(summary))
+        } else {
+            // One label/value row per statistic, grouped by sequence: each value has its own cell,
+            // with a button copying it alone, and hovering a label explains the statistic.
+            // The grid scrolls if it is too long.
+            let mut grid = column![].spacing(2);
+            let import_text = self.import_text();
+            if !import_text.is_empty() {
+                grid = grid.push(text(import_text.trim_end()).size(14));
+            }
+            for group in self.stat_groups() {
+                grid = grid.push(text(format!("{}:", group.title)).size(16));
+                for stat in group.rows {
+                    grid = grid.push(
+                        row![
+                            container(
+                                tooltip(
+                                    text(format!("{} (?)", stat.label)).size(14),
+                                    i18n::text(self.language, stat.help),
+                                    tooltip::Position::Bottom,
+                                )
+                                .size(13)
+                                .gap(5)
+                                .style(iced::theme::Container::Box)
+                            )
+                            .width(Length::FillPortion(1)),
+                            text(stat.display_value()).size(14).width(Length::FillPortion(1)),
+                            focus_ring::press(button(text("Copy").size(12))
+                                .on_press(Message::CopyStat(stat.label, stat.value))
+                                .style(iced::theme::Button::Text)
+                                .padding(2)),
+                        ]
+                        .spacing(10)
+                        .align_items(Alignment::Center),
+                    );
+                }
+                grid = grid.push(vertical_space(Length::Fixed(5.0)));
+            }
+            container(
+                scrollable(
+                    container(grid)
+                        .padding(10)
+                        .width(Length::Fill)
+                )
+                .height(Length::Fixed(150.0))
+            )
+        };
+        
+        // Style the statistics section
+        // This section has a border and a fixed height.
+        // The background color is set to a light gray.
+        // The text is displayed in a scrollable container.
+        // The statistics section is styled to match the application's theme.
+        // The container has a border and a fixed height.
+        let stats_section = container(stats_content)
+            .width(Length::Fill)
+            .style(|theme: &Theme| {
+                container::Appearance {
+                    border_width: 1.0,
+                    border_color: theme.extended_palette().background.strong.color,
+                    ..Default::default()
+                }
+            });
+        
+        // Chart section
+        // This section displays the generated chart.
+        // If a chart was generated, display it as an image.
+        // If no chart was generated, display a message indicating that.
         // The chart is displayed in a container with a fixed height.
+        // The sequence chart can be dragged (or pinched on a touchscreen) to pan and zoom it.
+        // Every chart has a context menu (right click).
         let chart = if let Some(path) = &self.chart_path {
+            let image = image::Image::new(path.clone())
+                .width(Length::Fill)
+                .height(Length::Fixed(400.0))
+                .content_fit(iced::ContentFit::Contain);
+            let mut content = chart_area::ChartArea::new(image).on_right_click(Message::ChartMenuOpened);
+            if self.chart_kind == ChartKind::Sequences {
+                content = content.on_gesture(Message::ChartGesture);
+            }
+            if self.chart_menu.is_some() {
+                content = content.menu(self.chart_menu(), Message::ChartMenuClosed);
+            }
+            container(content)
+            .width(Length::Fill)
+            .height(Length::Fixed(400.0))
+        } else { // If no chart was generated, show a message
             container(
-                image::Image::new(path.clone())
+                text("No graph generated")
+                    .width(Length::Fill)
+                    .height(Length::Fixed(400.0))
+                    .horizontal_alignment(iced::alignment::Horizontal::Center)
+                    .vertical_alignment(iced::alignment::Vertical::Center)
+            )
+            .width(Length::Fill)
+            .height(Length::Fixed(400.0))
+            .style(|theme: &Theme| {
+                container::Appearance {
+                    border_width: 1.0,
+                    border_color: theme.extended_palette().background.strong.color,
+                    ..Default::default() // Default appearance
+                }
+            })
+        };
+        
+        // Base-3 companion panel
+        // Every term written in base 3, one line per step, digits right-aligned so that
+        // the 3-adic structure of the trajectory lines up vertically.
+        let ternary_panel = if self.show_ternary && self.chart_kind.uses_sequences() {
+            let mut panel_text = String::new();
+            for (value, sequence) in [(self.value1, &self.sequence1), (self.value2, &self.sequence2)] {
+                if let (Some(value), false) = (value, sequence.is_empty()) {
+                    let matrix = representation::digit_matrix(sequence, 3);
+                    panel_text.push_str(&format!("Base 3 -- {}:\n", value));
+                    for step in 0..matrix.rows.len() {
+                        panel_text.push_str(&format!("{:>5}  {}\n", step, matrix.row_string(step)));
+                    }
+                    panel_text.push('\n');
+                }
+            }
+            if panel_text.is_empty() {
+                panel_text.push_str("No sequence generated");
+            }
+            column![
+                text("Base 3:").size(18),
+                container(
+                    scrollable(
+                        container(text(panel_text).size(14).font(iced::Font::MONOSPACE))
+                            .padding(10)
+                            .width(Length::Fill)
+                    )
+                    .height(Length::Fixed(200.0))
+                )
+                .width(Length::Fill)
+                .style(|theme: &Theme| {
+                    container::Appearance {
+                        border_width: 1.0,
+                        border_color: theme.extended_palette().background.strong.color,
+                        ..Default::default()
+                    }
+                }),
+            ]
+            .spacing(5)
+        } else {
+            column![]
+        };
+
+        // Step-by-step panel
+        // Every step of the sequences with the rule applied, e.g. "27 is odd → 3×27+1 = 82".
+        let steps_panel = if self.show_steps && self.chart_kind.uses_sequences() {
+            column![
+                row![
+                    text("Steps:").size(18),
+                    horizontal_space(Length::Fill),
+                    focus_ring::press(button("Export steps").on_press(Message::ExportSteps).padding(5)),
+                ]
+                .align_items(Alignment::Center),
+                container(
+                    scrollable(
+                        container(text(&self.steps_shown).size(14).font(iced::Font::MONOSPACE))
+                            .padding(10)
+                            .width(Length::Fill)
+                    )
+                    .height(Length::Fixed(200.0))
+                )
+                .width(Length::Fill)
+                .style(|theme: &Theme| {
+                    container::Appearance {
+                        border_width: 1.0,
+                        border_color: theme.extended_palette().background.strong.color,
+                        ..Default::default()
+                    }
+                }),
+            ]
+            .spacing(5)
+        } else {
+            column![]
+        };
+
+        // Sequence table panel
+        // The terms of one sequence, one per row; clicking and shift-clicking rows selects the steps to copy.
+        let table_panel = if self.show_table && self.chart_kind.uses_sequences() {
+            let mut header = row![text("Table:").size(18)].spacing(10).align_items(Alignment::Center);
+            if !self.sequence1.is_empty() && !self.sequence2.is_empty() {
+                for index in 0..2 {
+                    let value = [self.value1, self.value2][index].unwrap_or(0);
+                    let label = self.chart_options.series_label(index, value);
+                    header = header.push(radio(label, index, Some(self.table_sequence), Message::TableSequenceSelected));
+                }
+            }
+            for filter in sequence_table::Filter::ALL {
+                header = header.push(focus_ring::press(checkbox(filter.label(), self.table_filters.contains(&filter), move |on| {
+                    Message::TableFilterToggled(filter, on)
+                })));
+            }
+            let selected = match self.table_selection.range() {
+                Some(steps) if steps.start() == steps.end() => format!("Step {} selected", steps.start()),
+                Some(steps) => format!("Steps {} to {} selected", steps.start(), steps.end()),
+                None => "Click a row, shift-click another to select a range".to_string(),
+            };
+            header = header
+                .push(horizontal_space(Length::Fill))
+                .push(text(selected).size(14))
+                .push(text("Format:").size(16))
+                .push(focus_ring::cycle(pick_list(&sequence_table::CopyFormat::ALL[..], Some(self.table_format), Message::TableFormatSelected)))
+                .push(focus_ring::press(button("Copy selection").on_press(Message::CopyTableRows).padding(5)))
+                .push(focus_ring::press(button("Export table view").on_press(Message::ExportTable).padding(5)));
+            // Column chooser: a few columns to read the trajectory, all of them to analyze it.
+            let columns = sequence_table::Column::ALL.into_iter().fold(
+                row![text("Columns:").size(16)].spacing(10).align_items(Alignment::Center),
+                |columns, column| {
+                    columns.push(focus_ring::press(checkbox(column.label(), self.table_columns.contains(&column), move |on| {
+                        Message::TableColumnToggled(column, on)
+                    })))
+                },
+            );
+            column![
+                header,
+                columns,
+                container(scrollable(self.table_view()).height(Length::Fixed(250.0)))
+                    .width(Length::Fill)
+                    .style(|theme: &Theme| {
+                        container::Appearance {
+                            border_width: 1.0,
+                            border_color: theme.extended_palette().background.strong.color,
+                            ..Default::default()
+                        }
+                    }),
+            ]
+            .spacing(5)
+        } else {
+            column![]
+        };
+
+        // Sibling finder panel
+        // The starting values merging with value 1 within the budget of steps, with the ones to plot ticked.
+        let siblings_panel = if self.show_siblings && self.chart_kind.uses_sequences() {
+            let header = row![
+                text("Siblings:").size(18),
+                text("Steps:").size(16),
+                text_input("Steps", &self.sibling_steps_input)
+                    .on_input(Message::SiblingStepsChanged)
+                    .on_submit(Message::FindSiblings)
+                    .width(Length::Fixed(60.0))
+                    .padding(5),
+                text("Below:").size(16),
+                text_input("Bound", &self.sibling_bound_input)
+                    .on_input(Message::SiblingBoundChanged)
+                    .on_submit(Message::FindSiblings)
+                    .width(Length::Fixed(120.0))
+                    .padding(5),
+                focus_ring::press(button("Find").on_press(Message::FindSiblings).padding(5)),
+                horizontal_space(Length::Fill),
+                text(format!("{} of {} selected", self.sibling_selection.len(), MAX_PLOTTED_SIBLINGS)).size(14),
+                focus_ring::press(button("Plot selected").on_press(Message::PlotSiblings).padding(5)),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center);
+            column![
+                header,
+                container(scrollable(self.siblings_view()).height(Length::Fixed(200.0)))
+                    .width(Length::Fill)
+                    .style(|theme: &Theme| {
+                        container::Appearance {
+                            border_width: 1.0,
+                            border_color: theme.extended_palette().background.strong.color,
+                            ..Default::default()
+                        }
+                    }),
+            ]
+            .spacing(5)
+        } else {
+            column![]
+        };
+
+        // Diagnostics panel
+        // Time spent in each phase of the last sequence visualization (collapsed by default),
+        // then the memory and files held by the application.
+        let diagnostics_panel = if self.show_diagnostics && self.chart_kind.uses_sequences() {
+            let table = match &self.diagnostics {
+                Some(timings) => timings.format(),
+                None => "Nothing visualized yet".to_string(),
+            };
+            let framed = |table: String| {
+                container(container(text(table).size(14).font(iced::Font::MONOSPACE)).padding(10))
+                    .width(Length::Fill)
+                    .style(|theme: &Theme| {
+                        container::Appearance {
+                            border_width: 1.0,
+                            border_color: theme.extended_palette().background.strong.color,
+                            ..Default::default()
+                        }
+                    })
+            };
+            column![
+                text("Diagnostics:").size(18),
+                framed(table),
+                row![
+                    text("Resources:").size(16),
+                    focus_ring::press(button("Refresh").on_press(Message::CountTempFiles).padding(5)),
+                    focus_ring::press(button("Free memory").on_press(Message::FreeMemory).padding(5)),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+                framed(self.resource_usage().format()),
+            ]
+            .spacing(5)
+        } else {
+            column![]
+        };
+
+        // Game panel
+        // The value to guess and the guess inputs, then the answer and the points of the round.
+        let game_panel = if let Some(game) = &self.game {
+            let round = match game.phase {
+                game::Phase::Guessing => column![
+                    text(format!("How many steps does {} take to reach 1, and how high does it climb?", game.start)).size(16),
+                    row![
+                        text("Flight time:").size(16),
+                        text_input("Steps", &self.flight_guess_input)
+                            .on_input(Message::FlightGuessChanged)
+                            .on_submit(Message::RevealGuess)
+                            .padding(10),
+                        text("Peak:").size(16),
+                        text_input("Maximum value", &self.peak_guess_input)
+                            .on_input(Message::PeakGuessChanged)
+                            .on_submit(Message::RevealGuess)
+                            .padding(10),
+                        focus_ring::press(button("Reveal").on_press(Message::RevealGuess).padding(10)),
+                    ]
+                    .spacing(10)
+                    .align_items(Alignment::Center),
+                ],
+                game::Phase::Revealed(result) => {
+                    let guessed = |guess: Option<u64>| guess.map_or("no guess".to_string(), |guess| guess.to_string());
+                    column![
+                        text(format!(
+                            "{} takes {} steps (your guess: {}) and climbs to {} (your guess: {}): +{} points",
+                            game.start,
+                            result.flight_time,
+                            guessed(result.guessed_flight_time.map(|guess| guess as u64)),
+                            result.peak,
+                            guessed(result.guessed_peak),
+                            result.points,
+                        ))
+                        .size(16),
+                        focus_ring::press(button("Next value").on_press(Message::NextGuess).padding(10)),
+                    ]
+                }
+            };
+            column![
+                text(format!("Guess -- score: {} points in {} rounds", game.score, game.rounds)).size(18),
+                container(round.spacing(10).padding(10))
+                    .width(Length::Fill)
+                    .style(|theme: &Theme| {
+                        container::Appearance {
+                            border_width: 1.0,
+                            border_color: theme.extended_palette().background.strong.color,
+                            ..Default::default()
+                        }
+                    }),
+            ]
+            .spacing(5)
+        } else {
+            column![]
+        };
+
+        // History panel
+        // Latest runs of the experiment log; clicking one restores its inputs and charts it again,
+        // and ticking two of them compares their statistics ("Export diff").
+        let history_panel = if self.show_history {
+            let mut entries = column![].spacing(2);
+            for (index, run) in self.history.iter().enumerate() {
+                entries = entries.push(
+                    row![
+                        focus_ring::press(checkbox("", self.compared_runs.contains(run), move |on| Message::CompareRunToggled(index, on))),
+                        focus_ring::press(button(text(describe_run(run)).size(14).font(iced::Font::MONOSPACE))
+                            .on_press(Message::RestoreRun(index))
+                            .style(iced::theme::Button::Text)
+                            .width(Length::Fill)),
+                    ]
+                    .align_items(Alignment::Center),
+                );
+            }
+            if self.history.is_empty() {
+                let hint = if self.log_experiments { "No run logged yet" } else { "No run logged yet (check \"Log runs\")" };
+                entries = entries.push(text(hint).size(14));
+            }
+            let compared = match self.compared_runs.len() {
+                2 => "2 runs ticked",
+                1 => "1 run ticked, tick another to compare them",
+                _ => "Tick two runs to compare them",
+            };
+            column![
+                row![
+                    text("History:").size(18),
+                    horizontal_space(Length::Fill),
+                    text(compared).size(14),
+                    focus_ring::press(button("Export diff").on_press(Message::ExportRunDiff).padding(5)),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+                container(scrollable(container(entries).padding(5).width(Length::Fill)).height(Length::Fixed(200.0)))
+                    .width(Length::Fill)
+                    .style(|theme: &Theme| {
+                        container::Appearance {
+                            border_width: 1.0,
+                            border_color: theme.extended_palette().background.strong.color,
+                            ..Default::default()
+                        }
+                    }),
+            ]
+            .spacing(5)
+        } else {
+            column![]
+        };
+
+        // Database browser panel
+        // The stored results passing the filters, sorted by the clicked column; clicking one charts it again.
+        let results_panel = if self.show_results_db {
+            let filter = |index: usize, placeholder: &str, width: f32| {
+                text_input(placeholder, &self.results_filter_inputs[index])
+                    .on_input(move |value| Message::ResultsFilterChanged(index, value))
+                    .width(Length::Fixed(width))
+                    .padding(5)
+            };
+            column![
+                row![
+                    text("Results:").size(18),
+                    text("Stopping time:").size(16),
+                    filter(0, "Min", 60.0),
+                    text("to").size(16),
+                    filter(1, "Max", 60.0),
+                    text("Peak:").size(16),
+                    filter(2, "Min", 100.0),
+                    text("to").size(16),
+                    filter(3, "Max", 100.0),
+                    text("Since:").size(16),
+                    filter(4, "YYYY-MM-DD", 110.0),
+                    horizontal_space(Length::Fill),
+                    focus_ring::press(button("Export CSV").on_press(Message::ExportResults).padding(5)),
+                ]
+                .spacing(10)
+                .align_items(Alignment::Center),
+                container(scrollable(self.results_view()).height(Length::Fixed(250.0)))
+                    .width(Length::Fill)
+                    .style(|theme: &Theme| {
+                        container::Appearance {
+                            border_width: 1.0,
+                            border_color: theme.extended_palette().background.strong.color,
+                            ..Default::default()
+                        }
+                    }),
+            ]
+            .spacing(5)
+        } else {
+            column![]
+        };
+
+        // Jobs panel
+        // The background tasks, with their progress: the range scan, the verification and the jobs of the work plan.
+        let jobs_panel = if self.show_jobs {
+            column![
+                text("Jobs:").size(18),
+                container(scrollable(self.jobs_view()).height(Length::Fixed(200.0)))
+                    .width(Length::Fill)
+                    .style(|theme: &Theme| {
+                        container::Appearance {
+                            border_width: 1.0,
+                            border_color: theme.extended_palette().background.strong.color,
+                            ..Default::default()
+                        }
+                    }),
+            ]
+            .spacing(5)
+        } else {
+            column![]
+        };
+
+        // Create the main content of the application
+        // This includes the title, input fields, buttons, status message, and chart.
+        // The content is arranged in a vertical column.
+        // Each section is separated by vertical space for better readability.
+        let content = column![
+            title,
+            vertical_space(Length::Fixed(20.0)),
+            self.tour_section(tutorial::TourStep::Inputs, input_row.into()),
+            game_panel,
+            vertical_space(Length::Fixed(10.0)),
+            kind_row,
+            options_row,
+            vertical_space(Length::Fixed(10.0)),
+            self.tour_section(tutorial::TourStep::Buttons, button_row.into()),
+            file_row,
+            plan_row,
+            chart_run_row,
+            vertical_space(Length::Fixed(10.0)),
+            status_message,
+            vertical_space(Length::Fixed(20.0)),
+            self.tour_section(tutorial::TourStep::Chart, chart.into()),
+            vertical_space(Length::Fixed(20.0)),
+            text("Statistics:").size(18),
+            vertical_space(Length::Fixed(5.0)),
+            self.tour_section(tutorial::TourStep::Statistics, stats_section.into()),
+            vertical_space(Length::Fixed(10.0)),
+            ternary_panel,
+            steps_panel,
+            table_panel,
+            siblings_panel,
+            diagnostics_panel,
+            history_panel,
+            results_panel,
+            jobs_panel,
+        ]
+        .spacing(5)
+        .padding(20)
+        .max_width(800);
+        
+        // Create a container for the main content
+        // The container is centered horizontally, and the whole page scrolls
+        // when the optional panels make it taller than the window.
+        scrollable(
+            container(content)
+                .width(Length::Fill)
+                .center_x()
+        )
+        .height(Length::Fill)
+        .into()
+    }
+}
+
+// ==========================================================================
+//                              Helper Methods
+// ==========================================================================
+impl CollatzApp {
+    /// Returns the fingerprint of the chart of the current sequences: everything it is drawn from.
+    fn sequence_chart_fingerprint(&self) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        (
+            self.chart_kind,
+            self.value1,
+            self.value2,
+            &self.sequence1,
+            &self.sequence2,
+            &self.extra_series,
+            self.transform,
+            self.difference_mode,
+            self.sequence_chart_options(),
+        )
+            .hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns the series of the sequence chart: value 1 and value 2 (with an empty sequence if not set),
+    /// or value 1 and its neighbors, doublings or chosen siblings (value 2 is cleared by them).
+    /// Value 1 keeps its name and style; the extra series take the colors of the next series.
+    /// The series are given as plotted: with the transform applied to their terms (see `plotted_wide_bits`).
+    fn chart_series(&self) -> Vec<(Option<u64>, Vec<u64>)> {
+        self.chart_series_terms()
+            .into_iter()
+            .enumerate()
+            .map(|(position, (value, sequence))| match self.plotted_wide_bits(position) {
+                Some(bits) => (value, bits.to_vec()),
+                None => (value, sequence.iter().map(|&term| self.transform.apply(term)).collect()),
+            })
+            .collect()
+    }
+
+    /// Returns the bit lengths plotted in place of the terms of the series at `position` of the sequence chart, if any:
+    /// the bit-length view follows a sequence cut where a term outgrows 64 bits to the end of its trajectory.
+    fn plotted_wide_bits(&self, position: usize) -> Option<&[u64]> {
+        // Sequence 2 is only the second series without extra series.
+        let index = match position {
+            0 => 0,
+            1 if self.extra_series.is_empty() => 1,
+            _ => return None,
+        };
+        let bits = self.wide_bits[index].as_slice();
+        (self.transform == transform::TermTransform::BitLength && self.imported_from.is_none() && !bits.is_empty()).then_some(bits)
+    }
+
+    /// Returns the series of the sequence chart like `chart_series`, without copying their terms
+    /// (for the frame of the chart and the terms under the cursor).
+    fn chart_series_terms(&self) -> Vec<(Option<u64>, &[u64])> {
+        let mut series = vec![(self.value1, self.sequence1.as_slice())];
+        if self.extra_series.is_empty() {
+            series.push((self.value2, self.sequence2.as_slice()));
+        } else {
+            series.extend(self.extra_series.iter().map(|(value, sequence)| (Some(*value), sequence.as_slice())));
+        }
+        series
+    }
+
+    /// Draws the chart of the current sequences (computed or imported) in the background.
+    fn chart_sequences(&mut self) -> Command<Message> {
+        // The chart on screen already shows these sequences with these options: keep it.
+        let fingerprint = self.sequence_chart_fingerprint();
+        if self.chart_path.is_some() && self.chart_fingerprint == Some(fingerprint) {
+            return Command::none();
+        }
+
+        // A recent chart of the same sequences with the same options is shown again at once.
+        // Starting a generation discards the chart still being rendered, if any.
+        if let Some(path) = self.recent_charts.take(fingerprint) {
+            self.next_chart_generation();
+            self.chart_fingerprint = Some(fingerprint);
+            return self.show_chart(Some(path));
+        }
+
+        // If at least one sequence is generated, proceed to generate the chart.
+        // If both sequences are empty, do nothing.
+        if !self.sequence1.is_empty() || !self.sequence2.is_empty() {
+            // The current chart stays on screen until the new one replaces it (see `show_chart`).
+            // Generate a new filename for the chart, in the temporary directory.
+            // Use the current date and time, and the generation number (several charts
+            // may be generated within a second), to ensure uniqueness.
+            let generation = self.next_chart_generation();
+            self.chart_fingerprint = Some(fingerprint);
+            let now = Local::now();
+            let path = temp_file_path(&format!("{}_{}.png", now.format("%Y%m%d_%H%M%S"), generation));
+            
+            // Generate the chart and save it to the temporary file.
+            // The chart generation is an async task, so we use Command::perform.
+            // The result of the task will be sent back as a Message::ChartGenerated,
+            // with the number of this generation (older ones still running are discarded when done).
+            // The chart will be generated with the sequences and values provided.
+            // The bit matrix and the difference chart are other pictures of the same sequences.
+            // Rendering is CPU-bound, so it runs on a thread of its own (see `off_ui_thread`).
+            if self.chart_kind == ChartKind::BitMatrix {
+                Command::perform(
+                    off_ui_thread(generate_bit_matrix_chart(
+                        path.clone(),
+                        self.sequence1.clone(),
+                        self.sequence2.clone(),
+                    )),
+                    move |result| Message::ChartGenerated(generation, result),
+                )
+            } else if self.chart_kind == ChartKind::Difference {
+                Command::perform(
+                    off_ui_thread(generate_difference_chart(
+                        path.clone(),
+                        self.value1,
+                        self.value2,
+                        self.sequence1.clone(),
+                        self.sequence2.clone(),
+                        self.difference_mode,
+                    )),
+                    move |result| Message::ChartGenerated(generation, result),
+                )
+            } else {
+                self.sequence_render = Some(generation);
+                Command::perform(
+                    off_ui_thread(generate_chart(
+                        path.clone(),
+                        self.chart_series(),
+                        self.transform,
+                        self.sequence_chart_options(),
+                    )),
+                    move |result| Message::SequenceChartGenerated(generation, result),
+                )
+            }
+        } else {
+            Command::none() // No command needed if no sequences are generated.
+        }
+    }
+
+    /// Builds the row of the chart options form for one series: its name, line style and markers.
+    ///
+    /// # Arguments
+    /// * `series` - 0 for the first sequence, 1 for the second.
+    /// * `input` - Text of the series' input box (used in the automatic name).
+    /// * `name` - Custom name of the series.
+    /// * `on_name` - Message sent when the name is edited.
+    fn series_options_row<'a>(
+        &self,
+        series: usize,
+        input: &str,
+        name: &str,
+        on_name: fn(String) -> Message,
+    ) -> iced::widget::Row<'a, Message> {
+        let style = self.chart_options.series_styles[series];
+        row![
+            text(format!("Name {}:", series + 1)).size(16),
+            text_input(&format!("Sequence {}", input.trim()), name)
+                .on_input(on_name)
+                .padding(10),
+            focus_ring::cycle(pick_list(&chart_options::LineStyle::ALL[..], Some(style.line), move |line| {
+                Message::LineStyleSelected(series, line)
+            })),
+            focus_ring::press(checkbox("Markers", style.markers, move |markers| Message::MarkersToggled(series, markers))),
+            focus_ring::cycle(pick_list(&chart_options::MarkerShape::ALL[..], Some(style.marker), move |marker| {
+                Message::MarkerShapeSelected(series, marker)
+            })),
+        ]
+        .spacing(10)
+        .align_items(Alignment::Center)
+    }
+
+    /// Returns the settings of the sequence chart: the chart options, with the names of the sequences.
+    fn sequence_chart_options(&self) -> chart_options::ChartOptions {
+        // A sequence cut at the step limit is pointed out under the caption.
+        let truncated_limits: Vec<usize> = if self.imported_from.is_none() {
+            let extra = self.extra_series.iter().filter(|(_, sequence)| self.is_truncated(sequence, self.step_limits[0]));
+            (0..2)
+                .filter(|&index| self.sequence_truncated(index))
+                .map(|index| self.step_limits[index])
+                .chain(extra.map(|_| self.step_limits[0]))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        // So is a tail that is not stopped at 1, and a chart of downsampled sequences.
+        let mut notes = Vec::new();
+        if let Some(step_limit) = truncated_limits.iter().max() {
+            notes.push(format!("truncated at {} steps", step_limit));
+        }
+        let step_stride = self.step_stride();
+        if step_stride > 1 {
+            notes.push(format!("downsampled to 1 term in {} (memory budget)", step_stride));
+        }
+        if self.imported_from.is_none() && (0..2).any(|index| self.overflowed(index) && self.plotted_wide_bits(index).is_none()) {
+            notes.push("stopped where a term outgrows 64 bits (the statistics follow the whole trajectory)".to_string());
+        }
+        if self.imported_from.is_none() && self.glide_only {
+            notes.push("glide only: stopped once below the starting value".to_string());
+        } else if self.imported_from.is_none() {
+            match self.tail_handling {
+                collatz::TailHandling::StopAtOne => {}
+                collatz::TailHandling::RepeatCycle => notes.push(format!("4-2-1 cycle repeated {} times", self.tail_cycles)),
+                collatz::TailHandling::TrimDescent => notes.push("final descent through powers of two trimmed".to_string()),
+            }
+        }
+        let subtitle = match (notes.is_empty(), self.chart_options.subtitle.trim()) {
+            (true, _) => self.chart_options.subtitle.clone(),
+            (false, "") => {
+                let notes = notes.join(" -- ");
+                notes[..1].to_uppercase() + &notes[1..]
+            }
+            (false, subtitle) => format!("{} -- {}", subtitle, notes.join(" -- ")),
+        };
+        // The name of value 2 does not belong to the extra series drawn in its place.
+        let name2 = if self.extra_series.is_empty() { self.name2.clone() } else { String::new() };
+        chart_options::ChartOptions {
+            series_names: [self.name1.clone(), name2],
+            subtitle,
+            step_stride,
+            ..self.chart_options.clone()
+        }
+    }
+
+    /// Returns the statistics shown below the chart, for the selected chart kind.
+    /// The statistics section displays it, and full-view screenshots write it under the chart.
+    fn statistics_text(&self) -> String {
+        if self.chart_kind == ChartKind::MonteCarlo {
+            // A sample only estimates the range: report the spread and the confidence interval.
+            match &self.sample_report {
+                Some(report) => format!(
+                    "Sample: {} starting values drawn ({}) from {} to {}\n\
+                     Mean total stopping time: {:.2} steps (95% CI: {:.2} to {:.2})\n\
+                     Standard deviation: {:.2} steps\n\
+                     Median: {} steps, 5th-95th percentiles: {} to {} steps\n\
+                     Shortest: {} steps, longest: {} steps (starting at {}){}",
+                    report.times.len() + report.failed,
+                    report.distribution.to_string().to_lowercase(),
+                    report.range_start,
+                    report.range_end,
+                    report.mean,
+                    report.ci95.0,
+                    report.ci95.1,
+                    report.std_dev,
+                    report.quantile(0.5),
+                    report.quantile(0.05),
+                    report.quantile(0.95),
+                    report.quantile(0.0),
+                    report.quantile(1.0),
+                    report.max_time_start,
+                    if report.failed > 0 {
+                        format!("\nFailed (overflow): {}", report.failed)
+                    } else {
+                        String::new()
+                    },
+                ),
+                None => "No sample drawn".to_string(),
+            }
+        } else if self.chart_kind == ChartKind::Verification {
+            // Progress of the verification, and the delay records found so far (most recent first).
+            match &self.verification {
+                Some(checkpoint) => {
+                    let state = if checkpoint.is_complete() {
+                        "complete"
+                    } else if self.verification_running {
+                        "running"
+                    } else {
+                        "paused (can be resumed)"
+                    };
+                    let mut lines = vec![
+                        format!(
+                            "Verification of {} to {}: {}",
+                            checkpoint.range_start, checkpoint.range_end, state
+                        ),
+                        format!(
+                            "Verified: {} values ({:.2}%), every one reaching 1",
+                            checkpoint.verified,
+                            checkpoint.progress() * 100.0
+                        ),
+                        format!("Elapsed: {:.1} s", checkpoint.elapsed_secs),
+                    ];
+                    if self.verification_running {
+                        lines.push(diagnostics::format_eta(
+                            checkpoint.verified,
+                            checkpoint.total(),
+                            std::time::Duration::from_secs_f64(checkpoint.elapsed_secs),
+                        ));
+                    }
+                    if let Some(path) = self.verification_report_path.as_ref().filter(|_| checkpoint.is_complete()) {
+                        lines.push(format!("Report written to {}", path.display()));
+                    } else if checkpoint.report {
+                        lines.push("A report will be written when the verification completes".to_string());
+                    }
+                    if !checkpoint.unverified.is_empty() {
+                        lines.push(format!(
+                            "Not verified (beyond {} bits): {}",
+                            collatz::MAX_BIG_BITS,
+                            checkpoint.unverified.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(", ")
+                        ));
+                    }
+                    lines.push(format!("Delay records ({}):", checkpoint.records.len()));
+                    for record in checkpoint.records.iter().rev() {
+                        lines.push(format!("  {}: {} steps", record.start, record.stopping_time));
+                    }
+                    lines.join("\n")
+                }
+                None => "No verification started".to_string(),
+            }
+        } else if self.chart_kind == ChartKind::MergedGraph {
+            match &self.merged_graph {
+                Some(graph) => {
+                    let merges = graph.merge_nodes();
+                    format!(
+                        "Starts: {}\nNodes: {}, edges: {}\nMerge nodes (trajectories funneling in): {}",
+                        graph.starts.iter().map(|start| start.to_string()).collect::<Vec<_>>().join(", "),
+                        graph.nodes.len(),
+                        graph.edge_count(),
+                        if merges.is_empty() {
+                            "none".to_string()
+                        } else {
+                            merges.iter().map(|(node, degree)| format!("{} ({})", node, degree)).collect::<Vec<_>>().join(", ")
+                        },
+                    )
+                }
+                None => "No graph drawn".to_string(),
+            }
+        } else if self.chart_kind == ChartKind::Fractal {
+            match &self.fractal_window {
+                Some(window) => format!(
+                    "Window: center {} {} {}i, width {}\n\
+                     Colored points escape (|z| > {:e}) under f(z) = (2 + 7z - (2 + 5z) cos(pi z)) / 4, \
+                     the brighter the sooner; black points are still bounded after {} iterations",
+                    window.center.re,
+                    if window.center.im < 0.0 { "-" } else { "+" },
+                    window.center.im.abs(),
+                    window.width,
+                    fractal::ESCAPE_RADIUS,
+                    fractal::MAX_ITERATIONS,
+                ),
+                None => "No fractal drawn".to_string(),
+            }
+        } else if self.chart_kind == ChartKind::ThroughValue {
+            // The share of the range going through the value, then the terms where the most trajectories join.
+            match &self.through_counts {
+                Some((range, counts)) if !counts.is_empty() => {
+                    let (value, through) = counts[0];
+                    let (_, all) = counts[counts.len() - 1];
+                    let hubs = collatz::hub_values(counts, HUB_COUNT)
+                        .iter()
+                        .map(|(term, joining)| format!("{} (+{})", term, joining))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    format!(
+                        "Range: {}\nTrajectories through {}: {} of {} ({:.2}%)\nHubs of its trajectory (trajectories joining): {}",
+                        range,
+                        value,
+                        through,
+                        all,
+                        through as f64 / all.max(1) as f64 * 100.0,
+                        if hubs.is_empty() { "none".to_string() } else { hubs },
+                    )
+                }
+                _ => "No range computed".to_string(),
+            }
+        } else if self.chart_kind == ChartKind::MostVisited {
+            // The ranking as a table, the share of the range visiting each value included.
+            match &self.most_visited {
+                Some((range_start, range_end, ranking)) => {
+                    let starts = range_end - range_start + 1;
+                    let mut table = format!(
+                        "Range: {} to {} (powers of 2 left out)\n{:>4}  {:>20}  {:>10}  {:>7}\n",
+                        range_start, range_end, "Rank", "Value", "Visits", "Share"
+                    );
+                    for (rank, (value, visits)) in ranking.iter().enumerate() {
+                        table.push_str(&format!(
+                            "{:>4}  {:>20}  {:>10}  {:>6.2}%\n",
+                            rank + 1,
+                            value,
+                            visits,
+                            *visits as f64 / starts as f64 * 100.0,
+                        ));
+                    }
+                    table
+                }
+                None => "No range computed".to_string(),
+            }
+        } else if self.chart_kind.uses_range() {
+            // Range charts summarize the whole range instead of individual sequences.
+            // A glide scan measures the glides instead of the whole trajectories.
+            let glides = self.range_scan.as_ref().is_some_and(|scan| scan.measure == ScanMeasure::Glide);
+            let (longest, average) = if glides {
+                ("Longest glide", "Average glide")
+            } else {
+                ("Longest trajectory", "Average total stopping time")
+            };
+            match &self.range_summary {
+                Some(summary) => format!(
+                    "{}Range: {} to {}\n{}: {} steps (starting at {})\n{}: {:.2} steps{}",
+                    match &self.range_scan {
+                        Some(scan) if scan.scanned < scan.total() => format!(
+                            "Scanned: {} of {} values ({:.1}%) -- {}\n",
+                            scan.scanned,
+                            scan.total(),
+                            scan.scanned as f64 / scan.total() as f64 * 100.0,
+                            if scan.running {
+                                diagnostics::format_eta(scan.scanned, scan.total(), scan.elapsed)
+                            } else {
+                                "paused".to_string()
+                            },
+                        ),
+                        _ => String::new(),
+                    },
+                    summary.range_start,
+                    summary.range_end,
+                    longest,
+                    summary.max_time,
+                    summary.max_time_start,
+                    average,
+                    summary.mean_time,
+                    if summary.skipped > 0 {
+                        format!("\nSkipped (overflow): {}", summary.skipped)
+                    } else {
+                        String::new()
+                    },
+                ),
+                None if self.range_scan.as_ref().is_some_and(|scan| scan.running) => "Scanning...".to_string(),
+                None => "No range computed".to_string(),
+            }
+        } else if self.chart_kind == ChartKind::MapComparison {
+            // One line per map: the table makes the differences between variants easy to compare.
+            if self.comparison.is_empty() {
+                "No comparison computed".to_string()
+            } else {
+                let mut lines = vec![format!(
+                    "{:<6} {:>8} {:>22} {:>22}  {}",
+                    "Map", "Steps", "Maximum", "Minimum", "Outcome"
+                )];
+                for trajectory in &self.comparison {
+                    let max = trajectory.sequence.iter().max().copied().unwrap_or(0);
+                    let min = trajectory.sequence.iter().min().copied().unwrap_or(0);
+                    lines.push(format!(
+                        "{:<6} {:>8} {:>22} {:>22}  {}",
+                        trajectory.map.name(),
+                        trajectory.sequence.len() - 1,
+                        max,
+                        min,
+                        trajectory.termination.describe(),
+                    ));
+                }
+                // Cycles found by Brent's algorithm are detailed below the table.
+                for trajectory in &self.comparison {
+                    if let Some(cycle) = &trajectory.cycle {
+                        lines.push(format!("\n{}: {}", trajectory.map.name(), describe_cycle(trajectory.map, cycle)));
+                    }
+                }
+
+                // Known cycles panel: every cataloged cycle, with the ones entered above marked.
+                lines.push("\nKnown cycles:".to_string());
+                for known in collatz::KNOWN_CYCLES {
+                    let entered = self.comparison.iter().any(|trajectory| {
+                        trajectory.cycle.as_ref()
+                            .and_then(|cycle| collatz::identify_cycle(trajectory.map, cycle))
+                            .is_some_and(|found| std::ptr::eq(found, known))
+                    });
+                    lines.push(format!(
+                        "{} {:<5} {:<13} length {:>2}{}",
+                        if entered { ">" } else { " " },
+                        known.map.name(),
+                        known.name,
+                        known.members.len(),
+                        if entered { "  <- entered" } else { "" },
+                    ));
+                }
+                lines.join("\n")
+            }
+        } else if self.sequence1.is_empty() && self.sequence2.is_empty() {
+            "No sequence generated".to_string()
+        } else {
+            let mut stats_text = self.import_text();
+            stats_text.push_str(&stats_grid::to_text(&self.stat_groups()));
+            stats_text
+        }
+    }
+
+    /// Returns where the imported first sequence comes from and the result of its verification,
+    /// one line each, or an empty string when the sequences were computed by "Visualize".
+    fn import_text(&self) -> String {
+        let mut stats_text = String::new();
+
+        // An imported sequence was not computed by the application: tell where it comes from.
+        if let Some(path) = &self.imported_from {
+            stats_text.push_str(&format!("Imported from: {}\n", path));
+            match &self.import_check {
+                Some(Ok(())) if self.sequence1.last() == Some(&1) => {
+                    stats_text.push_str("Verified: every term follows the Collatz rule\n");
+                }
+                Some(Ok(())) => {
+                    stats_text.push_str("Verified: every term follows the Collatz rule, but the sequence stops before 1\n");
+                }
+                Some(Err(mismatch)) => stats_text.push_str(&format!(
+                    "Verification failed at step {}: found {}, the rule gives {} (from {})\n",
+                    mismatch.index,
+                    mismatch.found,
+                    mismatch.expected.map_or("a term beyond 64 bits".to_string(), |n| n.to_string()),
+                    self.sequence1[mismatch.index - 1],
+                )),
+                None => {}
+            }
+        }
+
+        stats_text
+    }
+
+    /// Reads the memory budget input. An invalid budget is reported in the error message.
+    fn parse_memory_budget(&mut self) -> Option<usize> {
+        match self.memory_budget_input.trim().parse::<usize>() {
+            Ok(budget) if budget > 0 => Some(budget),
+            _ => {
+                self.error_message = "The memory budget must be a positive number of megabytes".to_string();
+                None
+            }
+        }
+    }
+
+    /// Reads the step limit input. An invalid limit is reported in the error message.
+    fn parse_step_limit(&mut self) -> Option<usize> {
+        match self.step_limit_input.trim().parse::<usize>() {
+            Ok(limit) if limit > 0 => Some(limit),
+            _ => {
+                self.error_message = "The step limit must be a positive integer".to_string();
+                None
+            }
+        }
+    }
+
+    /// Starts the guided tour at its first step, with the sample value in the first input if it is empty.
+    fn start_tour(&mut self) {
+        self.tour = Some(tutorial::TourStep::Inputs);
+        if self.input1.trim().is_empty() {
+            self.input1 = tutorial::SAMPLE_VALUE.to_string();
+        }
+    }
+
+    /// Returns a section of the window as it is, or, when the guided tour is at `step`,
+    /// preceded by the explanation of the step and surrounded by a highlighted border.
+    fn tour_section<'a>(&self, step: tutorial::TourStep, section: Element<'a, Message>) -> Element<'a, Message> {
+        if self.tour != Some(step) {
+            return section;
+        }
+        let last = step.next().is_none();
+        let mut buttons = row![].spacing(10);
+        if step.previous().is_some() {
+            buttons = buttons.push(focus_ring::press(button("Back").on_press(Message::TourPrevious).padding(5)));
+        }
+        buttons = buttons
+            .push(focus_ring::press(button(if last { "Done" } else { "Next" }).on_press(Message::TourNext).padding(5)))
+            .push(focus_ring::press(button("Close").on_press(Message::TourDismissed).style(iced::theme::Button::Text).padding(5)));
+        let card = container(
+            column![
+                text(i18n::text(self.language, step.title_key())).size(16),
+                text(i18n::text(self.language, step.text_key())).size(14),
+                buttons,
+            ]
+            .spacing(5)
+        )
+        .padding(10)
+        .width(Length::Fill)
+        .style(iced::theme::Container::Box);
+        container(column![card, section].spacing(5))
+            .padding(5)
+            .width(Length::Fill)
+            .style(|theme: &Theme| {
+                container::Appearance {
+                    border_width: 2.0,
+                    border_radius: 4.0.into(),
+                    border_color: theme.extended_palette().primary.strong.color,
+                    ..Default::default()
+                }
+            })
+            .into()
+    }
+
+    /// Returns the terms of the sequence listed by the sequence table.
+    fn table_rows(&self) -> &[u64] {
+        match self.table_sequence {
+            1 if !self.sequence2.is_empty() => &self.sequence2,
+            _ if self.sequence1.is_empty() => &self.sequence2,
+            _ => &self.sequence1,
+        }
+    }
+
+    /// Returns the rows of the sequence table: a header, then one row per term left by the filters
+    /// (up to `MAX_TABLE_ROWS`), each a button selecting it, highlighted when selected.
+    fn table_view<'a>(&self) -> Element<'a, Message> {
+        if self.step_stride() > 1 {
+            return text("The sequences are downsampled to fit the memory budget: raise it to list their terms").size(14).into();
+        }
+        let width = |column: sequence_table::Column| {
+            Length::Fixed(match column {
+                sequence_table::Column::Value | sequence_table::Column::Delta => 220.0,
+                _ => 70.0,
+            })
+        };
+        let columns = &self.table_columns;
+        let cells = |texts: Vec<String>| {
+            columns.iter().zip(texts).fold(row![].spacing(10), |cells, (&column, cell)| {
+                cells.push(text(cell).size(14).font(iced::Font::MONOSPACE).width(width(column)))
+            })
+        };
+        let sequence = self.table_rows();
+        let steps = sequence_table::filtered_steps(sequence, &self.table_filters);
+        let shown = &steps[..steps.len().min(sequence_table::MAX_TABLE_ROWS)];
+        let header = cells(columns.iter().map(|column| column.header().to_string()).collect());
+        let mut rows = column![container(header).padding([2, 5])];
+        for (&step, row_cells) in shown.iter().zip(sequence_table::rows(sequence, shown, columns)) {
+            let style = if self.table_selection.contains(step) {
+                iced::theme::Button::Primary
+            } else {
+                iced::theme::Button::Text
+            };
+            rows = rows.push(
+                focus_ring::press(button(cells(row_cells))
+                    .on_press(Message::TableRowClicked(step))
+                    .style(style)
                     .width(Length::Fill)
-                    .height(Length::Fixed(400.0))
-                    .content_fit(iced::ContentFit::Contain)
+                    .padding([2, 5])),
+            );
+        }
+        if steps.len() > sequence_table::MAX_TABLE_ROWS {
+            rows = rows.push(
+                text(format!("First {} of {} rows shown", sequence_table::MAX_TABLE_ROWS, steps.len())).size(14),
+            );
+        } else if sequence.is_empty() {
+            rows = rows.push(text("No sequence generated").size(14));
+        } else if steps.is_empty() {
+            rows = rows.push(text("No term passes the filters").size(14));
+        }
+        rows.padding(5).into()
+    }
+
+    /// Returns the table of the database browser: a header of sortable columns, then one clickable row per result.
+    fn results_view<'a>(&self) -> Element<'a, Message> {
+        let query = match results_db::ResultsQuery::parse(&self.results_filter_inputs) {
+            Ok(query) => query,
+            Err(e) => return column![text(e).size(14)].padding(5).into(),
+        };
+        let results = results_db::query(&self.results_db, &query, self.results_sort, self.results_descending);
+        let width = |column: results_db::SortColumn| {
+            Length::Fixed(match column {
+                results_db::SortColumn::Start | results_db::SortColumn::Peak => 170.0,
+                results_db::SortColumn::LastSeen => 100.0,
+                _ => 70.0,
+            })
+        };
+        let header = results_db::SortColumn::ALL.into_iter().fold(row![].spacing(10), |header, column| {
+            let label = match (column == self.results_sort, self.results_descending) {
+                (true, true) => format!("{} ▼", column.header()),
+                (true, false) => format!("{} ▲", column.header()),
+                (false, _) => column.header().to_string(),
+            };
+            header.push(
+                focus_ring::press(button(text(label).size(14))
+                    .on_press(Message::ResultsSortClicked(column))
+                    .style(iced::theme::Button::Text)
+                    .width(width(column))
+                    .padding(0)),
+            )
+        });
+        let mut rows = column![container(header).padding([2, 5])];
+        for result in results.iter().take(MAX_LISTED_RESULTS) {
+            let cells = results_db::SortColumn::ALL.into_iter().fold(row![].spacing(10), |cells, column| {
+                cells.push(text(column.cell(result)).size(14).font(iced::Font::MONOSPACE).width(width(column)))
+            });
+            rows = rows.push(
+                focus_ring::press(button(cells)
+                    .on_press(Message::OpenStoredResult(result.start))
+                    .style(iced::theme::Button::Text)
+                    .width(Length::Fill)
+                    .padding([2, 5])),
+            );
+        }
+        if results.len() > MAX_LISTED_RESULTS {
+            rows = rows.push(text(format!("First {} of {} results shown", MAX_LISTED_RESULTS, results.len())).size(14));
+        } else if self.results_db.results.is_empty() {
+            let hint = if self.store_results { "No result stored yet" } else { "No result stored yet (check \"Store results\")" };
+            rows = rows.push(text(hint).size(14));
+        } else {
+            rows = rows.push(text(format!("{} of {} results", results.len(), self.results_db.results.len())).size(14));
+        }
+        rows.padding(5).into()
+    }
+
+    /// Returns the list of the jobs panel: one line per background task, with its state, its progress
+    /// and its actions (cancel while it waits or runs, open its result once done).
+    fn jobs_view<'a>(&self) -> Element<'a, Message> {
+        let job_row = |description: String, state: &str, progress: Option<f32>, actions: Vec<(&'static str, Message)>| {
+            let mut line = row![
+                text(description).size(14).width(Length::Fill),
+                text(state).size(14).width(Length::Fixed(90.0)),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center);
+            if let Some(progress) = progress {
+                line = line.push(progress_bar(0.0..=1.0, progress).width(Length::Fixed(120.0)).height(Length::Fixed(10.0)));
+            }
+            actions.into_iter().fold(line, |line, (label, message)| {
+                line.push(focus_ring::press(button(text(label).size(14)).on_press(message).padding([2, 5])))
+            })
+        };
+        let mut rows = column![].spacing(4);
+        if let Some(scan) = &self.range_scan {
+            let state = match (scan.running, scan.scanned >= scan.total()) {
+                (_, true) => "Completed",
+                (true, false) => "Running",
+                (false, false) => "Paused",
+            };
+            let mut actions = vec![("Open result", Message::ChartKindSelected(scan.kind))];
+            if scan.scanned < scan.total() {
+                actions.push(("Cancel", Message::CancelRangeScan));
+            }
+            rows = rows.push(job_row(
+                format!("Range scan ({}): {} to {}", scan.kind, scan.range_start, scan.range_end),
+                state,
+                Some(scan.scanned as f32 / scan.total() as f32),
+                actions,
+            ));
+        }
+        if let Some(checkpoint) = &self.verification {
+            let state = match (self.verification_running, checkpoint.is_complete()) {
+                (_, true) => "Completed",
+                (true, false) => "Running",
+                (false, false) => "Paused",
+            };
+            // A verification is paused rather than cancelled: its checkpoint is kept, to resume it later.
+            let mut actions = vec![("Open result", Message::ChartKindSelected(ChartKind::Verification))];
+            if self.verification_running {
+                actions.push(("Pause", Message::PauseVerification));
+            } else if !checkpoint.is_complete() {
+                actions.push(("Resume", Message::ResumeVerification));
+            }
+            rows = rows.push(job_row(
+                format!("Verification: {} to {}", checkpoint.range_start, checkpoint.range_end),
+                state,
+                Some(checkpoint.progress() as f32),
+                actions,
+            ));
+        }
+        // The jobs of the work plan, in the order of the plan.
+        let mut plan_jobs: Vec<(usize, Element<'a, Message>)> = Vec::new();
+        for (index, job, result) in &self.plan_done {
+            let (description, state, actions) = match result {
+                Ok(summary) => (summary.clone(), "Completed", vec![("Open result", Message::OpenPlanResult(*index))]),
+                Err(e) if e == "Cancelled" => (job.target.to_string(), "Cancelled", Vec::new()),
+                Err(e) => (e.clone(), "Failed", Vec::new()),
+            };
+            plan_jobs.push((*index, job_row(format!("Plan job {}: {}", index + 1, description), state, None, actions).into()));
+        }
+        if let Some((index, job)) = &self.plan_running {
+            let state = if self.plan_cancel.load(Ordering::Relaxed) { "Cancelling" } else { "Running" };
+            let description = format!("Plan job {}: {}", index + 1, job.target);
+            // A range is scanned chunk by chunk; a single sequence takes no time to follow.
+            let progress = match job.target {
+                work_plan::JobTarget::Range(start, end) => {
+                    Some(self.plan_progress.load(Ordering::Relaxed) as f32 / (end - start).saturating_add(1) as f32)
+                }
+                work_plan::JobTarget::Value(_) => None,
+            };
+            let actions = vec![("Cancel", Message::CancelPlanJob(*index))];
+            plan_jobs.push((*index, job_row(description, state, progress, actions).into()));
+        }
+        for (index, job) in &self.plan_queue {
+            let description = format!("Plan job {}: {}", index + 1, job.target);
+            plan_jobs.push((*index, job_row(description, "Queued", None, vec![("Cancel", Message::CancelPlanJob(*index))]).into()));
+        }
+        plan_jobs.sort_by_key(|(index, _)| *index);
+        let no_job = plan_jobs.is_empty() && self.range_scan.is_none() && self.verification.is_none();
+        rows = plan_jobs.into_iter().fold(rows, |rows, (_, line)| rows.push(line));
+        if no_job {
+            rows = rows.push(text("No background job").size(14));
+        }
+        rows.padding(5).into()
+    }
+
+    /// Returns the list of the sibling finder: one line per sibling, with the checkbox to plot it.
+    fn siblings_view<'a>(&self) -> Element<'a, Message> {
+        let Some(value) = self.siblings_of else {
+            return column![text("Enter a value 1, then press \"Find\"").size(14)].padding(5).into();
+        };
+        let mut rows = column![].spacing(2);
+        for sibling in self.siblings.iter().take(MAX_LISTED_SIBLINGS) {
+            let line = format!(
+                "{} merges at {} after {} steps ({} steps for {})",
+                sibling.value, sibling.merge_value, sibling.steps, sibling.n_steps, value,
+            );
+            let number = sibling.value;
+            rows = rows.push(
+                focus_ring::press(checkbox(line, self.sibling_selection.contains(&number), move |on| Message::SiblingToggled(number, on))
+                    .size(14)
+                    .text_size(14)),
+            );
+        }
+        if self.siblings.len() > MAX_LISTED_SIBLINGS {
+            rows = rows.push(text(format!("First {} of {} siblings shown", MAX_LISTED_SIBLINGS, self.siblings.len())).size(14));
+        } else if self.siblings.is_empty() {
+            rows = rows.push(text(format!("No sibling of {} within these steps and bound", value)).size(14));
+        }
+        rows.padding(5).into()
+    }
+
+    /// Returns the context menu of the chart: the entries about the sequences only on the sequence chart.
+    fn chart_menu<'a>(&self) -> Element<'a, Message> {
+        let entry = |label: &'static str, entry: ChartMenuEntry| {
+            focus_ring::press(button(text(label).size(14))
+                .on_press(Message::ChartMenuSelected(entry))
+                .style(iced::theme::Button::Text)
+                .width(Length::Fill)
+                .padding(5))
+        };
+        let mut entries = column![
+            entry("Save image", ChartMenuEntry::SaveImage),
+            entry("Copy image", ChartMenuEntry::CopyImage),
+        ];
+        if self.chart_kind == ChartKind::Sequences {
+            entries = entries
+                .push(entry("Copy data at cursor", ChartMenuEntry::CopyData))
+                .push(entry("Reset zoom", ChartMenuEntry::ResetZoom));
+        }
+        container(entries.width(Length::Fixed(180.0)))
+            .padding(5)
+            .style(iced::theme::Container::Box)
+            .into()
+    }
+
+    /// Returns the terms of the sequences at a place of the sequence chart, one line per series,
+    /// e.g. "Sequence 27: step 77, value 9232" ("Copy data at cursor" of the context menu of the chart).
+    /// The terms of downsampled sequences are given with their steps in the whole sequences.
+    ///
+    /// # Arguments
+    /// * `image_fraction` - Horizontal position on the chart image (0.0 at its left edge, 1.0 at its right edge).
+    ///
+    /// # Returns
+    /// * `String` - The lines, empty if no sequence has a term there.
+    fn data_at_position(&self, image_fraction: f32) -> String {
+        // A series plotted with the bit lengths of its whole trajectory gives them rather than its terms.
+        let series: Vec<(Option<u64>, &[u64], &str)> = self
+            .chart_series_terms()
+            .into_iter()
+            .enumerate()
+            .map(|(position, (value, sequence))| match self.plotted_wide_bits(position) {
+                Some(bits) => (value, bits, "bit length"),
+                None => (value, sequence, "value"),
+            })
+            .collect();
+        let sequences: Vec<&[u64]> = series.iter().map(|&(_, sequence, _)| sequence).collect();
+        let alignment = self.chart_options.alignment;
+        let (origin, len) = alignment.frame(&sequences);
+        let view = self.chart_options.view.map_or(chart_view::ChartView::full(len), |view| view.clamped(len));
+        let position = view.position_at(chart_view::plot_fraction(image_fraction));
+        let stride = self.step_stride();
+        let mut lines = Vec::new();
+        for (index, (value, sequence, measure)) in series.into_iter().enumerate() {
+            let kept = position.checked_sub(origin - alignment.anchor(sequence));
+            if let Some((kept, term)) = kept.and_then(|kept| Some((kept, sequence.get(kept)?))) {
+                let label = self.chart_options.series_label(index, value.unwrap_or(0));
+                lines.push(format!("{}: step {}, {} {}", label, kept * stride, measure, term));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Returns the step-by-step explanation of the sequences: for each one, a title,
+    /// then one line per step with the rule applied (shown in the steps panel and exported as is).
+    ///
+    /// # Arguments
+    /// * `max_steps` - Number of steps listed for each sequence, followed by the number of steps left out
+    ///   (None lists them all).
+    fn steps_text(&self, max_steps: Option<usize>) -> String {
+        // The kept terms of downsampled sequences are not consecutive: no rule leads from one to the next.
+        if self.step_stride() > 1 {
+            return "The sequences are downsampled to fit the memory budget: raise it to explain their steps".to_string();
+        }
+        let mut steps_text = String::new();
+        for (value, sequence, name) in [(self.value1, &self.sequence1, &self.name1), (self.value2, &self.sequence2, &self.name2)] {
+            if let (Some(value), false) = (value, sequence.is_empty()) {
+                steps_text.push_str(&format!("Steps of {}:\n", chart_options::describe_value(value, name)));
+                // Only the terms of the steps listed are annotated.
+                let steps = sequence.len() - 1;
+                let listed = max_steps.map_or(steps, |max_steps| max_steps.min(steps));
+                for step in collatz::annotate_steps(&sequence[..=listed]) {
+                    steps_text.push_str(&format!("{:>5}  {}\n", step.index + 1, step.describe()));
+                }
+                if listed < steps {
+                    steps_text.push_str(&format!("       ... {} more steps: export them to read them all\n", steps - listed));
+                } else if sequence.last() == Some(&1) {
+                    steps_text.push_str("       1 is reached: the sequence stops\n");
+                }
+                steps_text.push('\n');
+            }
+        }
+        if steps_text.is_empty() {
+            "No sequence generated".to_string()
+        } else {
+            steps_text.trim_end().to_string()
+        }
+    }
+
+    /// Writes the text of the steps panel again, from the current sequences (see `steps_text`).
+    fn refresh_steps_shown(&mut self) {
+        self.steps_shown = self.steps_text(Some(MAX_STEPS_SHOWN));
+    }
+
+    /// Returns the accessible summary of what is on screen: the kind of chart, then for the sequence charts
+    /// how its axes read and each trajectory described in sentences, then the statistics as text.
+    /// It is written from the data, so that it says the same as the chart without looking at it.
+    fn accessible_summary(&self) -> String {
+        let mut paragraphs = vec![format!("{} chart.", self.chart_kind)];
+        if self.chart_kind == ChartKind::Sequences {
+            paragraphs.push(summary::describe_sequence_axes(self.transform.axis_label(), self.chart_options.log_scale));
+        }
+        if self.chart_kind.uses_sequences() {
+            let sequences = [
+                (self.value1, &self.stats1, &self.name1, &self.sequence1),
+                (self.value2, &self.stats2, &self.name2, &self.sequence2),
+            ];
+            for (index, (value, stats, name, sequence)) in sequences.into_iter().enumerate() {
+                if let (Some(value), Some(stats), Some(&first)) = (value, stats, sequence.first()) {
+                    let label = chart_options::describe_value(value, name);
+                    // The last term of a downsampled sequence is not always kept.
+                    let last = self.downsampled[index].map_or(sequence[sequence.len() - 1], |downsampled| downsampled.last);
+                    paragraphs.push(summary::describe_trajectory(&label, first, last, stats));
+                }
+            }
+        }
+        paragraphs.push(self.statistics_text());
+        paragraphs.join("\n\n")
+    }
+
+    /// Returns the statistics of the sequences, one group per visualized sequence.
+    /// The statistics section shows them as a grid, and `statistics_text` writes them as text.
+    fn stat_groups(&self) -> Vec<stats_grid::StatGroup> {
+        let sequences = [
+            (self.value1, &self.stats1, &self.name1, &self.sequence1, &self.arithmetic1, &self.wide1),
+            (self.value2, &self.stats2, &self.name2, &self.sequence2, &self.arithmetic2, &self.wide2),
+        ];
+        let mut groups: Vec<stats_grid::StatGroup> = sequences
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, (value, stats, name, sequence, arithmetic, wide))| {
+                let (value, stats) = (value?, stats.as_ref()?);
+                let mut rows = stats_grid::sequence_rows(stats);
+                let downsampled = self.downsampled[index];
+                if self.imported_from.is_none() {
+                    let truncated = self.sequence_truncated(index);
+                    // A sequence cut at the step limit says so first, and so does a glide.
+                    if truncated {
+                        rows.insert(0, stats_grid::truncated_row(self.step_limits[index]));
+                    } else if self.glide_only {
+                        rows.insert(0, stats_grid::glide_row(sequence));
+                    }
+                    // The arithmetic follows, with the full flight time of a sequence stopped by overflow
+                    // (its flight time is shorter than the one of the arithmetic that went on to 1).
+                    if let Some(arithmetic) = arithmetic {
+                        let overflowed = !truncated && arithmetic.steps != Some(stats.length - 1);
+                        rows.extend(stats_grid::arithmetic_rows(arithmetic, overflowed));
+                        // Then the whole trajectory, beyond the overflow.
+                        if let Some(wide) = wide.as_ref().filter(|_| overflowed) {
+                            rows.extend(stats_grid::full_trajectory_rows(wide));
+                        }
+                        // A sequence that reached 1 says how its end is shown.
+                        if !truncated && !overflowed && !self.glide_only && downsampled.is_none() {
+                            rows.extend(stats_grid::tail_row(self.tail_handling, self.tail_cycles, sequence));
+                        }
+                    }
+                }
+                // A sequence kept downsampled says so before anything else.
+                if let Some(downsampled) = downsampled {
+                    rows.insert(0, stats_grid::downsampled_row(downsampled.stride));
+                }
+                Some(stats_grid::StatGroup {
+                    title: format!("Statistics for {}", chart_options::describe_value(value, name)),
+                    rows,
+                })
+            })
+            .collect();
+        // The comparisons below need every term: they are left out for downsampled sequences.
+        if self.step_stride() > 1 {
+            return groups;
+        }
+        // In the family mode, n and its doublings are compared by the tail they share.
+        if let Some(value) = self.value1.filter(|_| self.family_mode && !self.extra_series.is_empty()) {
+            let sequences: Vec<&[u64]> = std::iter::once(self.sequence1.as_slice())
+                .chain(self.extra_series.iter().map(|(_, sequence)| sequence.as_slice()))
+                .collect();
+            groups.push(stats_grid::StatGroup {
+                title: format!("Family of {}", chart_options::describe_value(value, &self.name1)),
+                rows: stats_grid::family_rows(&collatz::shared_tail(&sequences)),
+            });
+        }
+        // Two sequences are also compared with each other.
+        if let (Some(first), Some(second)) = (self.value1, self.value2) {
+            if !self.sequence1.is_empty() && !self.sequence2.is_empty() {
+                groups.push(stats_grid::StatGroup {
+                    title: format!(
+                        "Comparison of {} and {}",
+                        chart_options::describe_value(first, &self.name1),
+                        chart_options::describe_value(second, &self.name2),
+                    ),
+                    rows: stats_grid::comparison_rows(&collatz::path_compression(&self.sequence1, &self.sequence2)),
+                });
+            }
+        }
+        groups
+    }
+
+    /// Computes the sequence of a starting value: its whole trajectory, or its glide in the glide-only mode.
+    fn generate(&self, start: u64, step_limit: usize) -> Vec<u64> {
+        if self.glide_only {
+            collatz::generate_glide_limited(start, step_limit)
+        } else {
+            collatz::generate_sequence_limited(start, step_limit)
+        }
+    }
+
+    /// Tells whether a computed sequence was cut at the step limit it was computed with (see `generate`).
+    fn is_truncated(&self, sequence: &[u64], step_limit: usize) -> bool {
+        if self.glide_only {
+            collatz::is_glide_truncated(sequence, step_limit)
+        } else {
+            collatz::is_truncated(sequence, step_limit)
+        }
+    }
+
+    /// Computes the sequence of a starting value and its statistics within the memory budget.
+    /// A sequence that may not fit (its step limit allows more than `max_terms` terms) is streamed:
+    /// its statistics are computed along with its terms, and it is kept downsampled if it does not fit indeed.
+    ///
+    /// # Returns
+    /// * The sequence (every term, or one term in the stride), its statistics, and what is known of it if downsampled.
+    fn generate_within_budget(
+        &self,
+        start: u64,
+        step_limit: usize,
+        max_terms: usize,
+        timings: &mut diagnostics::PhaseTimings,
+    ) -> (Vec<u64>, collatz::CollatzStats, Option<sequence_budget::Downsampled>) {
+        // A glide stops once below its start, long before any budget.
+        if step_limit < max_terms || self.glide_only {
+            let sequence = timings.time("Generate", || self.generate(start, step_limit));
+            let stats = timings.time("Stats", || collatz::calculate_stats(&sequence));
+            return (sequence, stats, None);
+        }
+        let streamed = timings.time("Generate", || sequence_budget::stream(collatz::trajectory(start, step_limit), max_terms));
+        // Like `collatz::is_truncated`, from what is known of the whole sequence.
+        let truncated = streamed.stats.length == step_limit.saturating_add(1) && streamed.last != Some(1);
+        let downsampled = (streamed.stride > 1).then_some(sequence_budget::Downsampled {
+            stride: streamed.stride,
+            length: streamed.stats.length,
+            truncated,
+            last: streamed.last.unwrap_or(start),
+        });
+        (streamed.terms, streamed.stats, downsampled)
+    }
+
+    /// Keeps the sequences of a run within the memory budget, all with the same stride so that they are drawn
+    /// on the same steps: they are downsampled to the largest stride among them, then further until their terms fit together.
+    ///
+    /// # Arguments
+    /// * `budget_terms` - Number of terms the budget holds (see `sequence_budget::budget_terms`).
+    /// * `extra_strides` - Stride of each extra series, as computed.
+    fn fit_memory_budget(&mut self, budget_terms: usize, extra_strides: &[usize]) {
+        let own = |downsampled: Option<sequence_budget::Downsampled>| downsampled.map_or(1, |downsampled| downsampled.stride);
+        let series: Vec<(usize, usize)> = [
+            (self.sequence1.len(), own(self.downsampled[0])),
+            (self.sequence2.len(), own(self.downsampled[1])),
+        ]
+        .into_iter()
+        .chain(self.extra_series.iter().zip(extra_strides).map(|((_, sequence), &stride)| (sequence.len(), stride)))
+        .collect();
+        let kept = |stride: usize| series.iter().map(|&(length, own)| length.div_ceil(stride / own)).sum::<usize>();
+        let mut stride = series.iter().map(|&(_, own)| own).max().unwrap_or(1);
+        while kept(stride) > budget_terms.max(series.len()) {
+            stride *= 2;
+        }
+        if stride == 1 {
+            return;
+        }
+        for index in 0..2 {
+            let sequence = [&self.sequence1, &self.sequence2][index];
+            if sequence.is_empty() {
+                continue;
+            }
+            let (factor, downsampled) = match self.downsampled[index] {
+                Some(downsampled) => (stride / downsampled.stride, sequence_budget::Downsampled { stride, ..downsampled }),
+                None => {
+                    let truncated = self.is_truncated(sequence, self.step_limits[index]);
+                    let (length, last) = (sequence.len(), sequence[sequence.len() - 1]);
+                    (stride, sequence_budget::Downsampled { stride, length, truncated, last })
+                }
+            };
+            let sequence = if index == 0 { &mut self.sequence1 } else { &mut self.sequence2 };
+            sequence_budget::decimate(sequence, factor);
+            self.downsampled[index] = Some(downsampled);
+        }
+        for ((_, sequence), own) in self.extra_series.iter_mut().zip(extra_strides) {
+            sequence_budget::decimate(sequence, stride / own);
+        }
+    }
+
+    /// Clears the error message and the confirmations of the previous actions (saved, exported, copied...),
+    /// before a new chart is drawn.
+    fn clear_status(&mut self) {
+        self.error_message = String::new();
+        self.chart_saved = false;
+        self.chart_printed = false;
+        self.view_exported = false;
+        self.sequences_exported = false;
+        self.steps_exported = false;
+        self.table_exported = false;
+        self.run_diff_exported = false;
+        self.graph_exported = false;
+        self.results_exported = false;
+        self.copied_to_clipboard = false;
+        self.link_copied = false;
+        self.stat_copied = None;
+        self.summary_copied = false;
+    }
+
+    /// Reports an unexpected failure (chart backend error, I/O error): its message is shown under the buttons,
+    /// logged, and the error dialog opens with it, to save a report for a bug report (see `error_report`).
+    fn report_failure(&mut self, message: String) {
+        self.log(&message);
+        self.error_dialog = Some(error_report::Failure {
+            message: message.clone(),
+            time: Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+        });
+        self.error_report_saved = None;
+        self.error_message = message;
+    }
+
+    /// Adds a line to the application log kept for the error reports.
+    fn log(&mut self, line: &str) {
+        self.recent_log.push(&Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false), line);
+    }
+
+    /// Returns the error dialog of an unexpected failure: the error, and the buttons saving its report and closing it.
+    fn error_dialog_view<'a>(&self, failure: &error_report::Failure) -> Element<'a, Message> {
+        let bordered = |theme: &Theme| container::Appearance {
+            border_width: 1.0,
+            border_color: theme.extended_palette().background.strong.color,
+            ..Default::default()
+        };
+        let saved = match &self.error_report_saved {
+            Some(Ok(path)) => format!("Report saved to {}", path.display()),
+            Some(Err(e)) => e.clone(),
+            None => String::new(),
+        };
+        let dialog = column![
+            text("Something went wrong").size(22).style(Color::from_rgb(0.8, 0.0, 0.0)),
+            text(format!("At {}", failure.time)).size(14),
+            container(scrollable(text(failure.message.clone()).size(14).font(iced::Font::MONOSPACE)).height(Length::Fixed(150.0)))
+                .padding(10)
+                .width(Length::Fill)
+                .style(bordered),
+            text("The report holds the error, the version of the application and the latest log lines, \
+                  to attach to a bug report.")
+                .size(14),
+            row![
+                focus_ring::press(button("Save error report").on_press(Message::SaveErrorReport).padding(10)),
+                focus_ring::press(button("Close").on_press(Message::CloseErrorDialog).padding(10)),
+                text(saved).size(14),
+            ]
+            .spacing(10)
+            .align_items(Alignment::Center),
+        ]
+        .spacing(10)
+        .padding(20)
+        .max_width(600);
+        container(container(dialog).style(bordered))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .center_y()
+            .into()
+    }
+
+    /// Returns the memory and files held by the application, for the diagnostics panel.
+    /// The shared data in use by a running task (a scan holding the stopping-time table) is not waited for.
+    fn resource_usage(&self) -> diagnostics::ResourceUsage {
+        let terms = self.sequence1.capacity()
+            + self.sequence2.capacity()
+            + self.extra_series.iter().map(|(_, sequence)| sequence.capacity()).sum::<usize>();
+        let scan_results = self
+            .range_scan
+            .as_ref()
+            .and_then(|scan| scan.results.try_lock().ok().map(|results| results.times.capacity()))
+            .unwrap_or(0);
+        let stopping_time_cache = self.stopping_time_cache.try_lock().ok();
+        diagnostics::ResourceUsage {
+            sequence_bytes: (terms * std::mem::size_of::<u64>()) as u64,
+            scan_bytes: (scan_results * std::mem::size_of::<(u64, usize)>()) as u64,
+            stopping_times: stopping_time_cache.as_ref().map(|cache| (cache.memory_bytes(), cache.cached_values())),
+            stopping_time_hits: stopping_time_cache.as_ref().map_or((0, 0), |cache| cache.hit_counts()),
+            cached_charts: self.recent_charts.chart_count(),
+            chart_hits: self.recent_charts.hit_counts(),
+            temp_files: self.temp_files,
+        }
+    }
+
+    /// Refuses an action that needs every term of the sequences (exports, copies) while they are kept downsampled:
+    /// it would write one term in the stride as if it were the whole sequence.
+    ///
+    /// # Returns
+    /// * `bool` - Whether the action is refused (its error message is shown).
+    fn refuse_downsampled(&mut self, action: &str) -> bool {
+        let refused = self.step_stride() > 1;
+        if refused {
+            self.error_message = format!("The sequences are downsampled to fit the memory budget: raise it to {}", action);
+        }
+        refused
+    }
+
+    /// Returns the number of steps between two kept terms of the sequences: 1 unless they are kept downsampled.
+    fn step_stride(&self) -> usize {
+        self.downsampled.iter().flatten().map(|downsampled| downsampled.stride).max().unwrap_or(1)
+    }
+
+    /// Tells whether the computed sequence 1 (`index` 0) or 2 stopped before 1 because a term did not fit in 64 bits:
+    /// neither cut at the step limit nor a glide, it is shorter than the trajectory of the automatic arithmetic.
+    fn overflowed(&self, index: usize) -> bool {
+        let (stats, arithmetic) = [(&self.stats1, &self.arithmetic1), (&self.stats2, &self.arithmetic2)][index];
+        let (Some(stats), Some(arithmetic)) = (stats, arithmetic) else {
+            return false;
+        };
+        !self.glide_only && !self.sequence_truncated(index) && arithmetic.steps != Some(stats.length - 1)
+    }
+
+    /// Tells whether sequence 1 (`index` 0) or 2 was cut at the step limit, even if it is kept downsampled.
+    fn sequence_truncated(&self, index: usize) -> bool {
+        match self.downsampled[index] {
+            Some(downsampled) => downsampled.truncated,
+            None => self.is_truncated([&self.sequence1, &self.sequence2][index], self.step_limits[index]),
+        }
+    }
+
+    /// Visualizes the values again after a change of the way their sequences are computed (family mode, tail),
+    /// if their chart is shown. Imported sequences are kept: "Visualize" would replace them.
+    fn revisualize(&mut self) -> Command<Message> {
+        let shown = self.value1.is_some() || self.value2.is_some();
+        if self.chart_kind.uses_sequences() && shown && self.imported_from.is_none() {
+            self.update(Message::Visualize)
+        } else {
+            Command::none()
+        }
+    }
+
+    /// Derives value 2 from value 1 in the linked input mode. Does nothing when the mode is off.
+    fn follow_input_link(&mut self) {
+        if let Some(linked) = input::linked_value(&self.input1, self.input_link) {
+            self.input2 = linked;
+        }
+    }
+
+    /// Returns the error of a value input, if its content is not a valid value for the chart kind.
+    /// Inputs not used by the chart kind (e.g. both values for range charts) are not checked.
+    ///
+    /// # Arguments
+    /// * `index` - 0 for the first input, 1 for the second.
+    fn input_error(&self, index: usize) -> Option<String> {
+        let input = if index == 0 { &self.input1 } else { &self.input2 };
+        if self.chart_kind == ChartKind::MapComparison {
+            // The map comparison only reads the first value, which may be negative.
+            (index == 0).then(|| input::parse_signed_start_value(input).err()).flatten()
+        } else if self.chart_kind.uses_sequences() {
+            input::parse_start_value(input).err()
+        } else {
+            None
+        }
+    }
+
+    /// Builds the `collatz://` link of the current exploration: the values of the inputs used by
+    /// the chart kind (range bounds for range charts), the chart kind and the scale of the Y axis.
+    ///
+    /// # Returns
+    /// * `Some(DeepLink)` - The link to share.
+    /// * `None` - No input holds a value to share.
+    fn share_link(&self) -> Option<deep_link::DeepLink> {
+        // Only integers are shared: a link cannot carry a half-typed value.
+        let value = |input: &str| Some(input.trim().to_string()).filter(|input| input.parse::<i128>().is_ok());
+        let mut link = deep_link::DeepLink {
+            // The sequences are the default chart, so their links stay short.
+            chart: Some(self.chart_kind.slug().to_string()).filter(|_| self.chart_kind != ChartKind::Sequences),
+            log_scale: self.chart_options.log_scale,
+            ..deep_link::DeepLink::default()
+        };
+        if self.chart_kind.uses_range() {
+            link.range_start = value(&self.range_start_input);
+            link.range_end = value(&self.range_end_input);
+            if self.chart_kind == ChartKind::ThroughValue {
+                link.first = value(&self.input1);
+            }
+        } else {
+            link.first = value(&self.input1);
+            link.second = value(&self.input2);
+        }
+
+        let values = [&link.first, &link.second, &link.range_start, &link.range_end];
+        values.iter().any(|value| value.is_some()).then_some(link)
+    }
+
+    /// Applies the parameters of a `collatz://` link (or of the metadata of a saved chart)
+    /// to the inputs, then visualizes as if the button had been pressed.
+    fn apply_link(&mut self, link: deep_link::DeepLink) -> Command<Message> {
+        self.input1 = link.first.unwrap_or_default();
+        self.input2 = link.second.unwrap_or_default();
+        // Links written by newer versions may name unknown charts: the sequences are shown instead.
+        if let Some(kind) = link.chart.as_deref().and_then(ChartKind::from_slug) {
+            self.chart_kind = kind;
+        }
+        if let Some(start) = link.range_start {
+            self.range_start_input = start;
+        }
+        if let Some(end) = link.range_end {
+            self.range_end_input = end;
+        }
+        self.chart_options.log_scale = link.log_scale;
+        self.update(Message::Visualize)
+    }
+
+    /// Describes the run shown by the chart, for the metadata of the saved PNG files:
+    /// the application and its version, the starting values (or range), the map and the link of the run.
+    fn chart_metadata(&self) -> Vec<(String, String)> {
+        let mut metadata = vec![(
+            "Software".to_string(),
+            format!("Collatz Conjecture Visualizer {}", env!("CARGO_PKG_VERSION")),
+        )];
+        metadata.push(("Chart".to_string(), self.chart_kind.to_string()));
+        if let Some(link) = self.share_link() {
+            let values = if self.chart_kind.uses_range() {
+                format!("{} to {}", link.range_start.as_deref().unwrap_or("?"), link.range_end.as_deref().unwrap_or("?"))
+            } else {
+                [&link.first, &link.second].into_iter().flatten().cloned().collect::<Vec<_>>().join(", ")
+            };
+            metadata.push(("Starting values".to_string(), values));
+            metadata.push(("Link".to_string(), link.to_link()));
+        }
+        let maps: &[collatz::CollatzMap] = if self.chart_kind == ChartKind::MapComparison {
+            &collatz::CollatzMap::ALL
+        } else {
+            &[collatz::CollatzMap::Classic]
+        };
+        metadata.push(("Map".to_string(), maps.iter().map(|map| map.name()).collect::<Vec<_>>().join(", ")));
+        metadata
+    }
+
+    /// Returns the name under which a chart is saved, e.g. `collatz_20261015_140312.png`,
+    /// with the extension of the chosen image format.
+    /// Bit matrices are saved under their own name, as they are not plots.
+    fn saved_chart_filename(&self) -> String {
+        let prefix = if self.chart_kind == ChartKind::BitMatrix { "collatz_bits" } else { "collatz" };
+        format!("{}_{}.{}", prefix, Local::now().format("%Y%m%d_%H%M%S"), self.image_format.extension())
+    }
+
+    /// Saves a permanent copy of the chart that was just generated into the auto-save folder, if enabled.
+    fn auto_save_chart(&self) -> Command<Message> {
+        match &self.chart_path {
+            Some(chart_path) if self.auto_save => Command::perform(
+                auto_save_chart(
+                    chart_path.clone(),
+                    PathBuf::from(self.auto_save_dir_input.trim()),
+                    self.saved_chart_filename(),
+                    self.image_format,
+                    self.image_quality,
+                    self.chart_metadata(),
+                ),
+                Message::ChartAutoSaved,
+            ),
+            _ => Command::none(),
+        }
+    }
+
+    /// Appends the run that was just visualized to the experiment log, if it is enabled:
+    /// its inputs (range bounds for range charts, window of the fractal, starting values of the merged graph)
+    /// and the statistics of its sequences.
+    fn log_run(&self) -> Command<Message> {
+        if !self.log_experiments {
+            return Command::none();
+        }
+        // The fractal logs its center ("re, im") and its width, the merged graph its starting values.
+        let inputs = match self.chart_kind {
+            kind if kind.uses_range() => [self.range_start_input.trim().to_string(), self.range_end_input.trim().to_string()],
+            ChartKind::Fractal => [
+                format!("{}, {}", self.fractal_re_input.trim(), self.fractal_im_input.trim()),
+                self.fractal_width_input.trim().to_string(),
+            ],
+            ChartKind::MergedGraph => [self.graph_starts_input.trim().to_string(), String::new()],
+            _ => [self.input1.trim().to_string(), self.input2.trim().to_string()],
+        };
+        // Only the sequence charts compute sequences from both inputs.
+        let sequence_record = |value: Option<u64>, stats: &Option<collatz::CollatzStats>| match (value, stats) {
+            (Some(value), Some(stats)) if self.chart_kind.uses_sequences() => Some(experiment_log::SequenceRecord {
+                value,
+                flight_time: stats.length.saturating_sub(1),
+                peak: stats.max_value,
+                stopping_time: stats.stopping_time,
+            }),
+            _ => None,
+        };
+        let record = experiment_log::RunRecord {
+            timestamp: Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+            chart: self.chart_kind.slug().to_string(),
+            inputs,
+            sequences: [sequence_record(self.value1, &self.stats1), sequence_record(self.value2, &self.stats2)],
+        };
+        Command::perform(
+            append_experiment(experiment_log::default_path(), record),
+            Message::ExperimentLogged,
+        )
+    }
+
+    /// Stores the statistics of the sequences that were just computed in the results database, if it is enabled.
+    /// Only whole trajectories down to 1 are stored: not the imported, truncated or overflowed sequences, nor the glides.
+    fn store_run(&self) -> Command<Message> {
+        if !self.store_results || self.imported_from.is_some() || self.glide_only {
+            return Command::none();
+        }
+        let timestamp = Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false);
+        let sequences = [(self.value1, &self.stats1, &self.arithmetic1), (self.value2, &self.stats2, &self.arithmetic2)];
+        let results: Vec<results_db::StoredResult> = sequences
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, (value, stats, arithmetic))| {
+                let (value, stats) = (value?, stats.as_ref()?);
+                let flight_time = stats.length.checked_sub(1)?;
+                let complete = !self.sequence_truncated(index) && arithmetic.as_ref()?.steps == Some(flight_time);
+                complete.then(|| results_db::StoredResult {
+                    start: value,
+                    map: collatz::CollatzMap::Classic.name().to_string(),
+                    flight_time,
+                    stopping_time: stats.stopping_time,
+                    peak: stats.max_value,
+                    first_seen: timestamp.clone(),
+                    last_seen: timestamp.clone(),
+                    runs: 1,
+                })
+            })
+            .collect();
+        if results.is_empty() {
+            return Command::none();
+        }
+        Command::perform(store_results(results_db::default_path(), results), Message::ResultsStored)
+    }
+
+    /// Shows a desktop notification for a finished background job, if they are enabled and the window is in the background.
+    fn notify_job_done(&self, title: &'static str, body: String) -> Command<Message> {
+        if !self.notify_jobs || self.window_focused {
+            return Command::none();
+        }
+        Command::perform(send_notification(title, body), Message::NotificationSent)
+    }
+
+    /// Starts the next job of the work plan in the background, unless one is running or none is left.
+    fn start_next_job(&mut self) -> Command<Message> {
+        if self.plan_running.is_some() {
+            return Command::none();
+        }
+        let Some((index, job)) = self.plan_queue.pop_front() else {
+            return Command::none();
+        };
+        self.plan_cancel = Arc::new(AtomicBool::new(false));
+        self.plan_progress = Arc::new(AtomicU64::new(0));
+        let task = run_plan_job(self.plan_dir.clone(), index, job.clone(), self.plan_cancel.clone(), self.plan_progress.clone());
+        self.plan_running = Some((index, job));
+        Command::perform(off_ui_thread(task), Message::PlanJobDone)
+    }
+
+    /// Parses the range inputs and starts the range computation in the background.
+    fn visualize_range(&mut self) -> Command<Message> {
+        // Reset status messages and flags before processing.
+        self.clear_status();
+
+        // Both bounds are required for a range chart.
+        let (start, end) = match (
+            self.range_start_input.trim().parse::<u64>(),
+            self.range_end_input.trim().parse::<u64>(),
+        ) {
+            (Ok(start), Ok(end)) => (start, end),
+            (Err(_), _) => {
+                self.error_message = "Invalid range start".to_string();
+                return Command::none();
+            }
+            (_, Err(_)) => {
+                self.error_message = "Invalid range end".to_string();
+                return Command::none();
+            }
+        };
+
+        if start == 0 {
+            self.error_message = "The range start must be greater than 0".to_string();
+            return Command::none();
+        }
+        if end < start {
+            self.error_message = "The range end must not be lower than its start".to_string();
+            return Command::none();
+        }
+
+        // Sampling never scans the range, so it accepts ranges of any size.
+        if self.chart_kind == ChartKind::MonteCarlo {
+            return self.visualize_sample(start, end);
+        }
+
+        // A verification runs chunk by chunk with checkpoints, so it accepts ranges of any size too.
+        if self.chart_kind == ChartKind::Verification {
+            return self.start_verification(start, end);
+        }
+
+        // The scatters draw every point (and the ranking keeps every visited value in memory),
+        // so they accept smaller ranges than the density chart.
+        let max_range = if matches!(
+            self.chart_kind,
+            ChartKind::StoppingTimeScatter | ChartKind::PeakScatter | ChartKind::MostVisited
+        ) {
+            MAX_SCATTER_RANGE
+        } else {
+            MAX_DENSITY_RANGE
+        };
+        if end - start >= max_range {
+            self.error_message = format!("The range may not exceed {} values", max_range);
+            return Command::none();
+        }
+
+        // The histogram is computed in one batch: the chart only shows the whole distribution.
+        if self.chart_kind == ChartKind::StoppingTimeHistogram {
+            return self.visualize_histogram(start, end);
+        }
+        if self.chart_kind == ChartKind::StoppingTimeCdf {
+            return self.visualize_cdf(start, end);
+        }
+        if self.chart_kind == ChartKind::ThroughValue {
+            // The value comes from the first input, the starting values from the range.
+            let value = match input::parse_start_value(&self.input1) {
+                Ok(Some(value)) => value,
+                Ok(None) => {
+                    self.error_message = "Enter the value to pass through as Value 1".to_string();
+                    return Command::none();
+                }
+                Err(e) => {
+                    self.error_message = e;
+                    return Command::none();
+                }
+            };
+            let generation = self.next_chart_generation();
+            let now = Local::now();
+            let path = temp_file_path(&format!("through_{}_{}.png", now.format("%Y%m%d_%H%M%S"), generation));
+            return Command::perform(
+                off_ui_thread(generate_through_chart(path, value, start, end)),
+                move |result| Message::ThroughGenerated(generation, result),
+            );
+        }
+        if self.chart_kind == ChartKind::MostVisited {
+            let generation = self.next_chart_generation();
+            let now = Local::now();
+            let path = temp_file_path(&format!("visited_{}_{}.png", now.format("%Y%m%d_%H%M%S"), generation));
+            return Command::perform(
+                off_ui_thread(generate_most_visited_chart(path, start, end)),
+                move |result| Message::MostVisitedGenerated(generation, result),
+            );
+        }
+        if self.chart_kind == ChartKind::PeakScatter {
+            let generation = self.next_chart_generation();
+            let now = Local::now();
+            let path = temp_file_path(&format!("peaks_{}_{}.png", now.format("%Y%m%d_%H%M%S"), generation));
+            return Command::perform(
+                off_ui_thread(generate_peak_chart(path, start, end)),
+                move |result| Message::RangeChartGenerated(generation, result),
+            );
+        }
+
+        // An empty modulus means "no coloring"; 1 would put every point in the same class anyway.
+        let modulus = match self.scatter_modulus_input.trim() {
+            "" => 1,
+            text => match text.parse::<u64>() {
+                Ok(k) if k > 0 => k,
+                _ => {
+                    self.error_message = "The modulus must be a positive integer".to_string();
+                    return Command::none();
+                }
+            },
+        };
+
+        // The scan runs in a subscription (see `subscription`); the previous chart stays
+        // on screen until the first chunk replaces it.
+        self.range_scan_job += 1;
+        self.next_chart_generation(); // A chart still being generated must not replace the scan's.
+        self.range_summary = None;
+        self.range_scan = Some(RangeScan {
+            job: self.range_scan_job,
+            segment: 0,
+            kind: self.chart_kind,
+            range_start: start,
+            range_end: end,
+            modulus,
+            backend: self.scan_backend,
+            measure: if self.glide_only { ScanMeasure::Glide } else { ScanMeasure::TotalStoppingTime },
+            results: Arc::new(Mutex::new(ScanResults::default())),
+            scanned: 0,
+            elapsed: std::time::Duration::ZERO,
+            running: true,
+        });
+        Command::none()
+    }
+
+    /// Starts the computation of the stopping-time histogram of the range `start..=end` in the background.
+    fn visualize_histogram(&mut self, start: u64, end: u64) -> Command<Message> {
+        let bins = match self.histogram_bins_input.trim().parse::<usize>() {
+            Ok(bins) if bins > 0 => bins,
+            _ => {
+                self.error_message = "The number of bins must be a positive integer".to_string();
+                return Command::none();
+            }
+        };
+
+        let generation = self.next_chart_generation();
+        let now = Local::now();
+        let path = temp_file_path(&format!("histogram_{}_{}.png", now.format("%Y%m%d_%H%M%S"), generation));
+
+        Command::perform(
+            off_ui_thread(generate_histogram_chart(path, start, end, bins)),
+            move |result| Message::RangeChartGenerated(generation, result),
+        )
+    }
+
+    /// Starts the computation of the cumulative distribution of the stopping times of the range `start..=end`
+    /// in the background. The distribution of the previous range, if different, is drawn for comparison.
+    fn visualize_cdf(&mut self, start: u64, end: u64) -> Command<Message> {
+        let label = format!("{} to {}", start, end);
+        let reference = match &self.cdf_current {
+            Some(current) if current.0 != label => Some(current.clone()),
+            _ => self.cdf_reference.clone(),
+        };
+
+        let generation = self.next_chart_generation();
+        let now = Local::now();
+        let path = temp_file_path(&format!("cdf_{}_{}.png", now.format("%Y%m%d_%H%M%S"), generation));
+
+        Command::perform(
+            off_ui_thread(generate_cdf_chart(path, start, end, reference)),
+            move |result| Message::CdfGenerated(generation, result),
+        )
+    }
+
+    /// Starts a Monte Carlo sample of the range `start..=end` in the background.
+    fn visualize_sample(&mut self, start: u64, end: u64) -> Command<Message> {
+        let count = match self.sample_count_input.trim().parse::<usize>() {
+            Ok(count) if (1..=MAX_SAMPLES).contains(&count) => count,
+            _ => {
+                self.error_message = format!("The number of samples must be between 1 and {}", MAX_SAMPLES);
+                return Command::none();
+            }
+        };
+
+        // A fresh seed for every run; the sample is reproducible from within `sampling`.
+        let seed = rand::thread_rng().gen::<u64>();
+
+        let generation = self.next_chart_generation();
+        let now = Local::now();
+        let path = temp_file_path(&format!("sample_{}_{}.png", now.format("%Y%m%d_%H%M%S"), generation));
+
+        Command::perform(
+            off_ui_thread(generate_sample_chart(
+                path,
+                start,
+                end,
+                count,
+                self.sampling_distribution,
+                seed,
+            )),
+            move |result| Message::SampleGenerated(generation, result),
+        )
+    }
+
+    /// Starts the verification of the range `start..=end`, replacing any previous one.
+    fn start_verification(&mut self, start: u64, end: u64) -> Command<Message> {
+        // A verification draws no chart: the previous one would no longer match the page.
+        let cleanup_command = self.show_chart(None);
+
+        let checkpoint = verification::Checkpoint { report: self.verification_report, ..verification::Checkpoint::new(start, end) };
+        self.verification = Some(checkpoint.clone());
+        self.verification_report_path = None;
+        self.verification_job += 1;
+        self.verification_running = true;
+
+        Command::batch(vec![cleanup_command, self.verification_chunk(checkpoint)])
+    }
+
+    /// Writes the report of a completed verification to the current directory, if it was requested.
+    fn write_verification_report(&self, checkpoint: &verification::Checkpoint) -> Command<Message> {
+        if !checkpoint.report {
+            return Command::none();
+        }
+        let now = Local::now();
+        let path = PathBuf::from(format!(
+            "collatz_verification_{}-{}_{}.txt",
+            checkpoint.range_start,
+            checkpoint.range_end,
+            now.format("%Y%m%d_%H%M%S"),
+        ));
+        let report = verification::report(checkpoint, &now.to_rfc3339_opts(chrono::SecondsFormat::Secs, false));
+        Command::perform(write_text_file(path.clone(), report), move |result| {
+            Message::VerificationReportWritten(path.clone(), result)
+        })
+    }
+
+    /// Returns a command verifying the next chunk of `checkpoint` in the background.
+    fn verification_chunk(&self, checkpoint: verification::Checkpoint) -> Command<Message> {
+        let job = self.verification_job;
+        Command::perform(
+            off_ui_thread(run_verification_chunk(checkpoint, verification::default_checkpoint_path())),
+            move |result| Message::VerificationProgress(job, result),
+        )
+    }
+
+    /// Shows a chart of the latest generation in place of the current one.
+    /// The replaced chart goes into the cache of recent charts if it is a sequence chart;
+    /// any other one, like the charts evicted from the cache, is deleted.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the new chart image, or None to show no chart.
+    ///
+    /// # Returns
+    /// * A command deleting the temporary files no longer needed.
+    fn show_chart(&mut self, path: Option<String>) -> Command<Message> {
+        let mut stale = Vec::new();
+        if let Some(old_path) = self.chart_path.take() {
+            match self.shown_fingerprint.take() {
+                Some(fingerprint) => stale.extend(self.recent_charts.insert(fingerprint, old_path)),
+                None => stale.push(old_path),
+            }
+        }
+        self.shown_fingerprint = path.as_ref().and(self.chart_fingerprint);
+        self.chart_path = path;
+        Command::batch(stale.into_iter().map(|old_path| {
+            Command::perform(cleanup_temp_file(old_path), Message::CleanupOldTempFiles)
+        }))
+    }
+
+    /// Starts a new chart generation and returns its number: the charts of the previous ones
+    /// will be discarded when they complete.
+    fn next_chart_generation(&mut self) -> u64 {
+        self.chart_fingerprint = None; // The new chart replaces the current one
+        self.chart_generation += 1;
+        self.chart_generation
+    }
+
+    /// Reads the window of the fractal from its inputs. An invalid window is reported in the error message.
+    fn parse_fractal_window(&mut self) -> Option<fractal::FractalWindow> {
+        let number = |text: &str| text.trim().parse::<f64>().ok().filter(|value| value.is_finite());
+        match (number(&self.fractal_re_input), number(&self.fractal_im_input), number(&self.fractal_width_input)) {
+            (Some(re), Some(im), Some(width)) if width > 0.0 => Some(fractal::FractalWindow {
+                center: fractal::Complex::new(re, im),
+                width,
+            }),
+            _ => {
+                self.error_message = "The center must be two numbers and the width a positive number".to_string();
+                None
+            }
+        }
+    }
+
+    /// Handles "Visualize" for the escape-time fractal: the window is rendered in the background.
+    fn visualize_fractal(&mut self) -> Command<Message> {
+        self.clear_status();
+        let Some(window) = self.parse_fractal_window() else {
+            return Command::none();
+        };
+        self.fractal_window = Some(window);
+
+        let generation = self.next_chart_generation();
+        let now = Local::now();
+        let path = temp_file_path(&format!("fractal_{}_{}.png", now.format("%Y%m%d_%H%M%S"), generation));
+        Command::perform(
+            off_ui_thread(generate_fractal_chart(path, window)),
+            move |result| Message::ChartGenerated(generation, result),
+        )
+    }
+
+    /// Handles "Visualize" for the merged trajectory graph: builds the graph of the starting values
+    /// and draws it in the background.
+    fn visualize_graph(&mut self) -> Command<Message> {
+        self.clear_status();
+        let mut starts = Vec::new();
+        for value in self.graph_starts_input.split(|c: char| c == ',' || c == ';' || c.is_whitespace()) {
+            match input::parse_start_value(value) {
+                Ok(Some(start)) => starts.push(start),
+                Ok(None) => {} // Empty piece between separators
+                Err(e) => {
+                    self.error_message = e;
+                    return Command::none();
+                }
+            }
+        }
+        if starts.is_empty() || starts.len() > MAX_GRAPH_STARTS {
+            self.error_message = format!("Enter between 1 and {} starting values", MAX_GRAPH_STARTS);
+            return Command::none();
+        }
+        let Some(step_limit) = self.parse_step_limit() else {
+            return Command::none();
+        };
+        let graph = collatz::merged_graph(&starts, step_limit);
+        self.merged_graph = Some(graph.clone());
+
+        let generation = self.next_chart_generation();
+        let now = Local::now();
+        let path = temp_file_path(&format!("graph_{}_{}.png", now.format("%Y%m%d_%H%M%S"), generation));
+        Command::perform(
+            off_ui_thread(generate_graph_chart(path, graph)),
+            move |result| Message::ChartGenerated(generation, result),
+        )
+    }
+
+    /// Handles "Visualize" for the map comparison chart.
+    /// The first value is run through every map variant; negative values are accepted.
+    fn visualize_comparison(&mut self) -> Command<Message> {
+        self.clear_status();
+
+        // An invalid value is reported under the input.
+        let start = match input::parse_signed_start_value(&self.input1) {
+            Ok(Some(value)) => value,
+            Ok(None) => {
+                self.error_message = "Enter a value to compare".to_string();
+                return Command::none();
+            }
+            Err(_) => return Command::none(),
+        };
+
+        // A trajectory staying above the bound for K consecutive steps is given up as divergent.
+        let divergence = match (
+            self.divergence_bound_input.trim().parse::<u64>(),
+            self.divergence_steps_input.trim().parse::<usize>(),
+        ) {
+            (Ok(bound), Ok(steps)) if steps > 0 => collatz::DivergenceRule { bound, steps },
+            _ => {
+                self.error_message = "The divergence bound and its number of steps must be positive integers".to_string();
+                return Command::none();
+            }
+        };
+
+        // The step limit only bounds the trajectories of the comparison: the limits of the sequences are kept.
+        let Some(step_limit) = self.parse_step_limit() else {
+            return Command::none();
+        };
+
+        // Trajectories are bounded by the step limit, so they are cheap to compute here.
+        self.comparison = collatz::CollatzMap::ALL
+            .iter()
+            .map(|&map| collatz::generate_variant_sequence(map, start, step_limit, divergence))
+            .collect();
+
+        let generation = self.next_chart_generation();
+        let now = Local::now();
+        let path = temp_file_path(&format!("maps_{}_{}.png", now.format("%Y%m%d_%H%M%S"), generation));
+
+        Command::perform(
+            off_ui_thread(generate_comparison_chart(path, start, self.comparison.clone(), self.chart_options.palette)),
+            move |result| Message::ChartGenerated(generation, result),
+        )
+    }
+}
+
+// ==========================================================================
+//                              Async Functions
+// ==========================================================================
+
+/// Returns the directory of the temporary chart files: a folder of the system temporary directory,
+/// so that the application also runs from a read-only or shared directory, and leaves the current one clean.
+fn temp_dir() -> PathBuf {
+    std::env::temp_dir().join("collatz_visualizer")
+}
+
+/// Returns the beginning of the names of the temporary files of this process. The other windows are other
+/// processes, with files of their own: a window only ever sweeps its own files (see `cleanup_all_temp_files`).
+fn temp_file_prefix() -> String {
+    format!("temp_collatz_{}_", std::process::id())
+}
+
+/// Returns the path of a temporary file named `name` (after the prefix of this process),
+/// creating the temporary directory if needed.
+/// If it cannot be created, the file goes directly into the system temporary directory.
+fn temp_file_path(name: &str) -> PathBuf {
+    let dir = temp_dir();
+    let name = format!("{}{}", temp_file_prefix(), name);
+    match fs::create_dir_all(&dir) {
+        Ok(()) => dir.join(name),
+        Err(_) => std::env::temp_dir().join(name),
+    }
+}
+
+/// Runs a chart task (rendering, PNG encoding, range scan chunk...) on a thread of its own,
+/// and waits for its result without blocking the executor.
+/// These tasks are CPU-bound: on the executor, a big chart would delay the other tasks
+/// and the interface updates until it is encoded.
+/// The task runs in the worker pool, so its parallel computations use the number of threads set by the user.
+///
+/// # Arguments
+/// * `task` - The task to run (an `async fn` of this file that never waits on anything).
+///
+/// # Returns
+/// * The result of the task, or an error if its thread stopped before finishing (a panic).
+async fn off_ui_thread<T, F>(task: F) -> Result<T, String>
+where
+    F: std::future::Future<Output = Result<T, String>> + Send + 'static,
+    T: Send + 'static,
+{
+    let (sender, receiver) = futures::channel::oneshot::channel();
+    std::thread::Builder::new()
+        .name("chart-render".to_string())
+        .spawn(move || {
+            let _ = sender.send(workers::install(|| futures::executor::block_on(task)));
+        })
+        .map_err(|e| format!("Unable to start the rendering thread: {}", e))?;
+    receiver
+        .await
+        .unwrap_or_else(|_| Err("The rendering thread stopped unexpectedly".to_string()))
+}
+
+/// Deletes the chart of a generation that was replaced while it was running, if it was written.
+fn discard_stale_chart(path: Option<String>) -> Command<Message> {
+    match path {
+        Some(path) => Command::perform(cleanup_temp_file(path), Message::CleanupOldTempFiles),
+        None => Command::none(),
+    }
+}
+
+/// Function to clean up temporary files
+/// This function checks if a file is a temporary file and deletes it.
+/// It takes a path as input and returns a Result indicating success or failure.
+/// The function is asynchronous, allowing it to be run in the background.
+async fn cleanup_temp_file(path: String) -> Result<(), String> {
+    // Checks if the file is a temporary file
+    // Temporary files are identified by their name pattern.
+    if path.contains("temp_collatz_") && path.ends_with(".png") {
+        // Attempt to delete the temporary file
+        match fs::remove_file(&path) {
+            Ok(_) => Ok(()), // File deleted successfully
+            Err(e) => Err(format!("Error deleting temporary file: {}", e)), // Error deleting the file
+        }
+    } else {
+        // If the file is not a temporary file, return Ok
+        Ok(())
+    }
+}
+
+/// Sequence chart to re-render for printing: print file path, series (values and sequences), transform and chart options.
+type PrintChartFile = (
+    PathBuf,
+    Vec<(Option<u64>, Vec<u64>)>,
+    transform::TermTransform,
+    chart_options::ChartOptions,
+);
+
+/// Asynchronously prints a chart.
+/// The sequence chart (`print_chart_file`) is re-rendered at print resolution first;
+/// any other chart is printed from its displayed image (`chart_path`).
+/// The image goes to the system printing: the print dialog of Paint on Windows,
+/// the default printer (`lp`) elsewhere. There is no print dialog outside Windows:
+/// the printer and its options are those set as the defaults of the system (e.g. with `lpoptions`).
+/// Both commands return once the image is handed to the print queue, so the re-rendered file is deleted then.
+async fn print_chart(chart_path: String, print_chart_file: Option<PrintChartFile>) -> Result<(), String> {
+    let (path, rendered) = match print_chart_file {
+        Some((path, sequences, transform, options)) => {
+            let options = chart_options::ChartOptions { scale: PRINT_SCALE, ..options };
+            (generate_chart(path, sequences, transform, options).await?.0, true)
+        }
+        None => (chart_path, false),
+    };
+
+    let status = if cfg!(target_os = "windows") {
+        std::process::Command::new("mspaint").arg("/p").arg(&path).status()
+    } else {
+        std::process::Command::new("lp").arg(&path).status()
+    };
+    if rendered {
+        if let Err(e) = cleanup_temp_file(path).await {
+            println!("Warning: Unable to delete the print file: {}", e);
+        }
+    }
+    let status = status.map_err(|e| format!("Unable to start the print command: {}", e))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("The print command failed ({})", status))
+    }
+}
+
+/// Asynchronously launches another instance of the application, in its own window.
+/// iced 0.10 has no multi-window support, so the second window is a separate process
+/// (its temporary files are named after it, so the windows leave each other's charts alone).
+async fn open_new_window() -> Result<(), String> {
+    let executable = std::env::current_exe().map_err(|e| e.to_string())?;
+    std::process::Command::new(executable)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Deletes several temporary files, e.g. the charts of the window when it closes.
+/// Every file is attempted; the first error, if any, is returned.
+async fn cleanup_temp_files(paths: Vec<String>) -> Result<(), String> {
+    let mut result = Ok(());
+    for path in paths {
+        if let Err(e) = cleanup_temp_file(path).await {
+            result = result.and(Err(e));
+        }
+    }
+    result
+}
+
+/// Counts the files of the temporary directory and their total size (a missing directory holds none).
+async fn count_temp_files(dir: PathBuf) -> Result<(usize, u64), String> {
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((0, 0)),
+        Err(e) => return Err(format!("Unable to read the temporary directory: {}", e)),
+    };
+    let sizes: Vec<u64> = entries
+        .filter_map(|entry| entry.ok()?.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .collect();
+    Ok((sizes.len(), sizes.iter().sum()))
+}
+
+/// Drops the cached data of the "Free memory" action: saves and unloads the stopping-time table,
+/// then deletes the images of the recent charts taken out of their cache.
+async fn free_cached_data(stopping_time_cache: Arc<Mutex<cache::StoppingTimeCache>>, chart_paths: Vec<String>) -> Result<(), String> {
+    let unloaded = match stopping_time_cache.lock() {
+        Ok(mut cache) => cache.unload().map_err(|e| format!("Unable to save the stopping-time cache: {}", e)),
+        Err(_) => Ok(()), // A scan panicked while using it: nothing to save.
+    };
+    cleanup_temp_files(chart_paths).await?;
+    unloaded
+}
+
+/// Function to clean up all temporary files
+/// This function checks the temporary directory of the application for the temporary files
+/// of this process (see `temp_file_prefix`) and deletes them: the files of the other windows,
+/// and anything else in the system temporary directory, are left alone.
+/// It returns a Result indicating success or failure.
+async fn cleanup_all_temp_files() -> Result<(), String> {
+    // Read the directory entries
+    // The temporary directory does not exist until a chart is generated.
+    let entries = match fs::read_dir(temp_dir()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(format!("Unable to read the temporary directory: {}", e)),
+    };
+    let prefix = temp_file_prefix();
+    
+    // Iterate through the directory entries
+    for entry in entries.flatten() { // For each valid entry in the directory
+        if let Ok(file_type) = entry.file_type() { // Check if the entry is a file
+            if file_type.is_file() {
+                if let Ok(file_name) = entry.file_name().into_string() { // Get the file name
+                    if file_name.starts_with(&prefix) && file_name.ends_with(".png") {
+                        if let Err(e) = fs::remove_file(entry.path()) { // Attempt to delete the file
+                            println!("Warning: Unable to delete temporary file {}: {}", file_name, e);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    
+    Ok(()) // Return success if all temporary files were processed
+}
+
+/// Asynchronously generates a chart for the Collatz sequences.
+/// This function takes a path and the series to draw: value 1 and value 2, then the extra series
+/// of the neighborhood compare, if any. It generates a chart image and saves it to the specified path.
+/// The series are plotted as given: the transform is already applied to their terms (see `chart_series`).
+async fn generate_chart(
+    path: PathBuf, // Path to save the chart image
+    sequences: Vec<(Option<u64>, Vec<u64>)>, // Starting value and plotted values of each series (empty if none)
+    transform: transform::TermTransform, // Transform applied to each term, named on the Y axis
+    options: chart_options::ChartOptions, // User settings of the chart (custom caption, resolution...)
+) -> Result<(String, diagnostics::PhaseTimings), String> {
+    if sequences.iter().all(|(_, sequence)| sequence.is_empty()) {
+        return Err("No sequence to visualize".to_string());
+    }
+
+    // Rendering, PNG encoding and disk write are timed separately for the diagnostics panel,
+    // so the chart is drawn into a memory buffer rather than directly into the file.
+    // Every size below (fonts, margins, lines) is multiplied by `scale`, so that a printed
+    // chart looks like the one on screen, only sharper.
+    // With anti-aliasing, everything is drawn at a larger scale and the image is scaled down afterwards.
+    let output_size = (800 * options.scale.max(1), 400 * options.scale.max(1));
+    let scale = if options.antialias {
+        (options.scale.max(1) * SUPERSAMPLING).min(MAX_RENDER_SCALE)
+    } else {
+        options.scale.max(1)
+    };
+    let mut timings = diagnostics::PhaseTimings::new();
+    let render_timer = std::time::Instant::now();
+    let (width, height) = (800 * scale, 400 * scale);
+    let mut buffer = vec![0u8; (width * height * 3) as usize];
+
+    // Create the drawing area on the buffer.
+    let root = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| e.to_string())?;
+    
+    // Determine the X position of the aligned steps and the number of steps to show.
+    // This is used to set the X-axis range of the chart: aligned at the start, it is the longest sequence;
+    // aligned at the peak or at the final 1, the sequences are shifted so that these steps line up.
+    let framed: Vec<&[u64]> = sequences.iter().map(|(_, sequence)| sequence.as_slice()).collect();
+    let (origin, max_len) = options.alignment.frame(&framed);
+    
+    // The caption describes the chart, unless the user replaced it with their own text.
+    let caption = options.caption_or(format!(
+        "Collatz Conjecture {}{}",
+        caption_values(&sequences.iter().filter_map(|(value, _)| *value).collect::<Vec<_>>()),
+        match transform { // Mention the transform, if any
+            transform::TermTransform::Value => String::new(),
+            other => format!(" ({})", other.axis_label().to_lowercase()),
+        },
+    ));
+
+    // Small multiples: one panel per sequence, stacked vertically under the caption.
+    // The panels share the X range, so that the steps line up, and each has its own Y scale.
+    let series: Vec<(&Vec<u64>, Option<u64>, RGBColor)> = sequences
+        .iter()
+        .enumerate()
+        .map(|(index, (value, sequence))| (sequence, *value, palette_color(options.palette, index)))
+        .collect();
+    let no_sequence = Vec::new();
+    let drawn = series.iter().filter(|(sequence, _, _)| !sequence.is_empty()).count();
+    if options.layout == chart_options::ChartLayout::SmallMultiples && drawn > 1 {
+        let mut title_area = root.titled(&caption, ("sans-serif", options.text_size(20) * scale)).map_err(|e| e.to_string())?;
+        if let Some(subtitle) = options.subtitle() {
+            title_area = title_area.titled(subtitle, ("sans-serif", options.text_size(15) * scale)).map_err(|e| e.to_string())?;
+        }
+        // A panel holds a single sequence, so the Y scale is its own whatever the Y scaling option.
+        let panel_options = chart_options::ChartOptions { y_scaling: chart_options::YScaling::Shared, ..options.clone() };
+        // Each panel keeps the other series as empty ones, so that its series keeps its index (name, style).
+        let panels = title_area.split_evenly((drawn, 1));
+        let shown = series.iter().enumerate().filter(|(_, (sequence, _, _))| !sequence.is_empty());
+        for (panel, (index, _)) in panels.iter().zip(shown) {
+            let panel_series: Vec<_> = series
+                .iter()
+                .enumerate()
+                .map(|(other, &(sequence, value, color))| if other == index { (sequence, value, color) } else { (&no_sequence, None, color) })
+                .collect();
+            draw_sequence_chart(panel, None, &panel_series, (origin, max_len), transform, &panel_options, scale)?;
+        }
+    } else {
+        // With a subtitle, the caption is drawn as a title above the whole area,
+        // and the subtitle takes its place as the (smaller) caption of the chart.
+        let (plot_area, caption, caption_size) = match options.subtitle() {
+            Some(subtitle) => (
+                root.titled(&caption, ("sans-serif", options.text_size(20) * scale)).map_err(|e| e.to_string())?,
+                subtitle.to_string(),
+                options.text_size(15) * scale,
+            ),
+            None => (root.clone(), caption, options.text_size(20) * scale),
+        };
+        draw_sequence_chart(&plot_area, Some((&caption, caption_size)), &series, (origin, max_len), transform, &options, scale)?;
+    }
+    
+    // Ensure all drawing operations are finalized and written to the backend (the buffer).
+    // The chart and the drawing area borrow the buffer, so they are dropped before encoding it.
+    root.present().map_err(|e| e.to_string())?;
+    drop(root);
+    timings.add("Chart render", render_timer.elapsed());
+
+    // A supersampled chart is scaled down to its size, which smooths the lines and the text.
+    let image = ::image::RgbImage::from_raw(width, height, buffer)
+        .ok_or_else(|| "Invalid chart buffer".to_string())?;
+    let image = if (width, height) != output_size {
+        timings.time("Anti-aliasing", || {
+            ::image::imageops::resize(&image, output_size.0, output_size.1, ::image::imageops::FilterType::Triangle)
+        })
+    } else {
+        image
+    };
+
+    // Encode the image as a PNG file in memory, then write it to the temporary file.
+    let png = timings.time("Encode", || {
+        let mut png = std::io::Cursor::new(Vec::new());
+        image
+            .write_to(&mut png, ::image::ImageOutputFormat::Png)
+            .map_err(|e| e.to_string())?;
+        Ok::<_, String>(png.into_inner())
+    })?;
+    timings.time("Disk write", || fs::write(&path, png)).map_err(|e| e.to_string())?;
+    
+    // Return the path of the generated chart file.
+    // The path is returned as a String.
+    // This path can be used to access the file later (e.g., for saving or displaying).
+    // The path is converted to a string using `to_string_lossy` to handle any invalid UTF-8 characters.
+    // This is a safe way to convert the path to a string.
+    Ok((path.to_string_lossy().to_string(), timings))
+}
+
+/// Returns the starting values of the caption of the sequence chart: "-- 27", "-- 27 and 31", "-- 27, 26 and 28".
+fn caption_values(values: &[u64]) -> String {
+    match values {
+        [] => String::new(),
+        [value] => format!("-- {}", value),
+        [first @ .., last] => format!(
+            "-- {} and {}",
+            first.iter().map(u64::to_string).collect::<Vec<_>>().join(", "),
+            last,
+        ),
+    }
+}
+
+/// Returns the color of the series `index` in a palette, for plotters.
+fn palette_color(palette: chart_options::Palette, index: usize) -> RGBColor {
+    let (red, green, blue) = palette.color(index);
+    RGBColor(red, green, blue)
+}
+
+/// Draws a sequence chart (axes, series and legend) on a drawing area:
+/// the whole image, or one panel of the small multiples.
+///
+/// # Arguments
+/// * `area` - Where to draw the chart.
+/// * `caption` - Caption of the chart and its font size, if any.
+/// * `series` - (plotted values, starting value, color) of each sequence; empty sequences are skipped.
+/// * `x_frame` - X position of the aligned steps and number of steps of the X axis
+///   (shared by the panels of the small multiples).
+/// * `transform` - Transform applied to the terms (for the Y axis description).
+/// * `options` - User settings of the chart.
+/// * `scale` - Resolution multiplier of every size.
+fn draw_sequence_chart<DB: DrawingBackend>(
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+    caption: Option<(&str, u32)>,
+    series: &[(&Vec<u64>, Option<u64>, RGBColor)],
+    x_frame: (usize, usize),
+    transform: transform::TermTransform,
+    options: &chart_options::ChartOptions,
+    scale: u32,
+) -> Result<(), String> {
+    // Only the X positions of the view are drawn (every step unless the chart was zoomed or panned),
+    // and the Y axis is fitted to the terms in view, or to every term after a zoom of the X axis alone.
+    let (origin, max_len) = x_frame;
+    let view = options.view.map_or(chart_view::ChartView::full(max_len), |view| view.clamped(max_len));
+    let x_range = view.start..view.end.max(view.start + 1);
+    let y_frame = if view.fit_y { x_range.clone() } else { 0..max_len };
+
+    // Create a chart builder
+    // This sets up the chart's appearance and layout.
+    // The chart is a Cartesian 2D chart with X and Y axes.
+    // The X-axis represents the step number, and the Y-axis represents the value.
+    // The chart is built using the `plotters` library.
+    let mut builder = ChartBuilder::on(area); // Create a new chart builder
+    if let Some((caption, caption_size)) = caption {
+        builder.caption(caption, ("sans-serif", caption_size)); // Set the chart caption, a string that describes the chart.
+    }
+    builder
+        .margin(10 * scale) // Margin around the chart
+        .x_label_area_size(30 * options.text_size(options.tick_size) / 12 * scale) // Space reserved for X-axis labels (grows with the tick font)
+        .y_label_area_size(60 * options.text_size(options.tick_size) / 12 * scale); // Space reserved for Y-axis labels (adjust if numbers get large)
+
+    // Build the coordinate system (Cartesian 2D).
+    // X-axis range: 0 to max_len (number of steps).
+    // Y-axis range: 0 to slightly above max_value, or 1 to slightly above max_value on a log scale
+    // (a log scale spreads the many small terms of a trajectory that would be flattened by its peak).
+    let tick_format = options.tick_format;
+    match options.y_scaling {
+        chart_options::YScaling::Shared => {
+            // A log scale has no 0, so null values (possible with a transform) are drawn at 1.
+            let floor = if options.log_scale { 1 } else { 0 };
+            let plotted = plotted_series(series, options, origin, &x_range, |_, v| v.max(floor), |_| String::new());
+            let fitted = plotted_series(series, options, origin, &y_frame, |_, v| v.max(floor), |_| String::new());
+            let max_value = fitted.iter().flat_map(|series| series.points.iter().map(|&(_, v)| v)).max().unwrap_or(1);
+            let y_desc = options.y_label_or(transform.axis_label()).to_string();
+            let y_format = |value: &u64| tick_format.format(*value);
+            if options.log_scale {
+                let mut chart = builder
+                    .build_cartesian_2d(x_range.clone(), (1..(max_value.max(1) + 1)).log_scale())
+                    .map_err(|e| e.to_string())?; // Handle errors during chart building
+                draw_sequence_series(&mut chart, &plotted, options, origin, &y_desc, &y_format, scale)?;
+            } else {
+                let mut chart = builder
+                    .build_cartesian_2d(x_range.clone(), 0..(max_value + 1))
+                    .map_err(|e| e.to_string())?; // Handle errors during chart building
+                draw_sequence_series(&mut chart, &plotted, options, origin, &y_desc, &y_format, scale)?;
+            }
+        }
+        chart_options::YScaling::Normalized => {
+            // Each sequence is divided by its own maximum, so a short trajectory is not flattened
+            // by a value orders of magnitude higher; the legend gives each maximum to read the values back.
+            let maximum = |sequence: &[u64]| sequence.iter().copied().max().unwrap_or(0).max(1) as f64;
+            let plotted = plotted_series(
+                series,
+                options,
+                origin,
+                &x_range,
+                |sequence, v| v as f64 / maximum(sequence),
+                |sequence| format!(" (max {})", tick_format.format(maximum(sequence) as u64)),
+            );
+            let y_desc = options.y_label_or("Share of the maximum").to_string();
+            let y_format = |share: &f64| format_percentage(share * 100.0);
+            if options.log_scale {
+                // The smallest positive share sets the bottom of the axis; null values are drawn there.
+                let fitted = plotted_series(
+                    series,
+                    options,
+                    origin,
+                    &y_frame,
+                    |sequence, v| v as f64 / maximum(sequence),
+                    |_| String::new(),
+                );
+                let smallest = fitted
+                    .iter()
+                    .flat_map(|series| series.points.iter().map(|&(_, share)| share))
+                    .filter(|&share| share > 0.0)
+                    .fold(1.0, f64::min);
+                let plotted: Vec<_> = plotted
+                    .into_iter()
+                    .map(|series| PlottedSeries {
+                        points: series.points.iter().map(|&(x, share)| (x, share.max(smallest))).collect(),
+                        ..series
+                    })
+                    .collect();
+                let mut chart = builder
+                    .build_cartesian_2d(x_range.clone(), (smallest..1.0).log_scale())
+                    .map_err(|e| e.to_string())?;
+                draw_sequence_series(&mut chart, &plotted, options, origin, &y_desc, &y_format, scale)?;
+            } else {
+                let mut chart = builder
+                    .build_cartesian_2d(x_range.clone(), 0.0..1.05)
+                    .map_err(|e| e.to_string())?;
+                draw_sequence_series(&mut chart, &plotted, options, origin, &y_desc, &y_format, scale)?;
+            }
+        }
+        chart_options::YScaling::DualAxis => {
+            // The first sequence is read on the left axis, the second on a right axis with its own range.
+            let floor = if options.log_scale { 1 } else { 0 };
+            let plotted = plotted_series(series, options, origin, &x_range, |_, v| v.max(floor), |_| String::new());
+            let first = plotted.iter().find(|series| series.index == 0);
+            let second = plotted.iter().find(|series| series.index == 1);
+            let fitted = plotted_series(series, options, origin, &y_frame, |_, v| v.max(floor), |_| String::new());
+            let maximum = |index: usize| {
+                let series = fitted.iter().find(|series| series.index == index);
+                series.and_then(|series| series.points.iter().map(|&(_, v)| v).max()).unwrap_or(1).max(1)
+            };
+            let (max1, max2) = (maximum(0), maximum(1));
+            let y_label = options.y_label_or(transform.axis_label());
+            let y_desc = |series: Option<&PlottedSeries<u64>>| match series {
+                Some(series) => format!("{} ({})", y_label, series.label),
+                None => y_label.to_string(),
+            };
+            let y_desc = [y_desc(first), y_desc(second)];
+            let y_desc = [y_desc[0].as_str(), y_desc[1].as_str()];
+            builder.right_y_label_area_size(60 * options.text_size(options.tick_size) / 12 * scale); // Space reserved for the right axis labels
+            if options.log_scale {
+                let mut chart = builder
+                    .build_cartesian_2d(x_range.clone(), (1..(max1 + 1)).log_scale())
+                    .map_err(|e| e.to_string())?
+                    .set_secondary_coord(x_range.clone(), (1..(max2 + 1)).log_scale());
+                draw_dual_axis_series(&mut chart, first, second, options, origin, y_desc, scale)?;
+            } else {
+                let mut chart = builder
+                    .build_cartesian_2d(x_range.clone(), 0..(max1 + 1))
+                    .map_err(|e| e.to_string())?
+                    .set_secondary_coord(x_range.clone(), 0..(max2 + 1));
+                draw_dual_axis_series(&mut chart, first, second, options, origin, y_desc, scale)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes a percentage with as many decimals as needed to show its first significant digit
+/// (ticks of a log scale go down to 0.01% and below).
+fn format_percentage(percent: f64) -> String {
+    let decimals = if percent >= 1.0 || percent <= 0.0 {
+        0
+    } else {
+        (-percent.log10()).ceil() as usize
+    };
+    format!("{:.*}%", decimals, percent)
+}
+
+/// Writes the X label of a position as a step relative to the aligned steps (negative before them).
+/// Aligned at the start, the origin is 0 and the labels are the steps themselves.
+fn format_relative_step(position: usize, origin: usize, tick_format: chart_options::TickFormat) -> String {
+    if position < origin {
+        format!("-{}", tick_format.format((origin - position) as u64))
+    } else {
+        tick_format.format((position - origin) as u64)
+    }
+}
+
+/// A sequence as drawn on the sequence chart: its points and its legend entry.
+struct PlottedSeries<V> {
+    index: usize, // 0 for the first sequence, 1 for the second (selects the series style)
+    points: Vec<(usize, V)>, // (X position, plotted value) of every term (the step, shifted by the alignment)
+    color: RGBColor, // Color of the series
+    label: String, // Legend entry
+}
+
+/// Converts the sequences to the series drawn on the sequence chart. Empty sequences are left out.
+///
+/// # Arguments
+/// * `series` - (plotted values, starting value, color) of each sequence.
+/// * `options` - User settings of the chart (names of the series, alignment).
+/// * `origin` - X position of the aligned steps.
+/// * `visible` - X positions in view: the terms elsewhere are left out.
+/// * `y` - Plotted value of a term, given the whole sequence and the term.
+/// * `label_suffix` - Text appended to the legend entry of a sequence (e.g. its maximum).
+fn plotted_series<V>(
+    series: &[(&Vec<u64>, Option<u64>, RGBColor)],
+    options: &chart_options::ChartOptions,
+    origin: usize,
+    visible: &std::ops::Range<usize>,
+    y: impl Fn(&[u64], u64) -> V,
+    label_suffix: impl Fn(&[u64]) -> String,
+) -> Vec<PlottedSeries<V>> {
+    series
+        .iter()
+        .enumerate()
+        .filter(|(_, (sequence, _, _))| !sequence.is_empty())
+        .map(|(index, &(sequence, value, color))| PlottedSeries {
+            index,
+            // Shifted right so that the aligned step of the sequence is at the origin.
+            points: {
+                let shift = origin - options.alignment.anchor(sequence);
+                sequence
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| visible.contains(&(i + shift)))
+                    .map(|(i, &v)| (i + shift, y(sequence, v)))
+                    .collect()
+            },
+            color,
+            // Custom name or "Sequence n"
+            label: format!("{}{}", options.series_label(index, value.unwrap_or(0)), label_suffix(sequence)),
+        })
+        .collect()
+}
+
+/// Draws the mesh, the series and the legend of the sequence chart, on a linear or a logarithmic Y axis.
+///
+/// # Arguments
+/// * `chart` - Chart whose coordinate system is already built.
+/// * `series` - The series to draw.
+/// * `options` - User settings of the chart (styles, tick format...).
+/// * `origin` - X position of the aligned steps (step 0 of the X labels).
+/// * `y_desc` - Description of the Y axis.
+/// * `y_format` - Text of the Y tick labels.
+/// * `scale` - Resolution multiplier of every size.
+fn draw_sequence_series<'a, DB, Y>(
+    chart: &mut ChartContext<'a, DB, Cartesian2d<plotters::coord::types::RangedCoordusize, Y>>,
+    series: &[PlottedSeries<Y::ValueType>],
+    options: &chart_options::ChartOptions,
+    origin: usize,
+    y_desc: &str,
+    y_format: &dyn Fn(&Y::ValueType) -> String,
+    scale: u32,
+) -> Result<(), String>
+where
+    DB: DrawingBackend + 'a,
+    Y: plotters::coord::ranged1d::Ranged + plotters::coord::ranged1d::ValueFormatter<Y::ValueType>,
+    Y::ValueType: Clone + 'static,
+{
+    // Configure the chart's mesh (grid lines and labels).
+    // The mesh is the grid that appears behind the chart.
+    // The X-axis is labeled with step numbers, and the Y-axis with values.
+    // The axis descriptions and the tick labels follow the chart options.
+    // The X positions of downsampled sequences are labeled with the steps of their terms.
+    let tick_format = options.tick_format;
+    let stride = options.step_stride.max(1);
+    chart.configure_mesh()
+        .x_desc(options.x_label_or(options.alignment.axis_label()))
+        .y_desc(y_desc)
+        .axis_desc_style(("sans-serif", options.text_size(15) * scale))
+        .label_style(("sans-serif", options.text_size(options.tick_size) * scale))
+        .x_label_formatter(&|step| format_relative_step(*step * stride, origin * stride, tick_format))
+        .y_label_formatter(y_format)
+        .draw()
+        .map_err(|e| e.to_string())?;
+    
+    // Draw the sequences, in the colors of the palette.
+    for series in series {
+        draw_styled_series(chart, series, options, scale)?;
+    }
+    draw_legend(chart, options, scale)
+}
+
+/// Draws the sequence chart with a second Y axis on the right: the first sequence is plotted
+/// on the left axis and the second one on the right axis, each axis in the color of its sequence.
+///
+/// # Arguments
+/// * `chart` - Chart whose primary and secondary coordinate systems are already built.
+/// * `first` - The series plotted on the left axis, if any.
+/// * `second` - The series plotted on the right axis, if any.
+/// * `options` - User settings of the chart (styles, tick format...).
+/// * `origin` - X position of the aligned steps (step 0 of the X labels).
+/// * `y_desc` - Description of each Y axis.
+/// * `scale` - Resolution multiplier of every size.
+fn draw_dual_axis_series<'a, DB, Y, SY>(
+    chart: &mut DualCoordChartContext<
+        'a,
+        DB,
+        Cartesian2d<plotters::coord::types::RangedCoordusize, Y>,
+        Cartesian2d<plotters::coord::types::RangedCoordusize, SY>,
+    >,
+    first: Option<&PlottedSeries<u64>>,
+    second: Option<&PlottedSeries<u64>>,
+    options: &chart_options::ChartOptions,
+    origin: usize,
+    y_desc: [&str; 2],
+    scale: u32,
+) -> Result<(), String>
+where
+    DB: DrawingBackend + 'a,
+    Y: plotters::coord::ranged1d::Ranged<ValueType = u64> + plotters::coord::ranged1d::ValueFormatter<u64>,
+    SY: plotters::coord::ranged1d::Ranged<ValueType = u64> + plotters::coord::ranged1d::ValueFormatter<u64>,
+{
+    // The tick labels of each Y axis take the color of its sequence (the right axis line too;
+    // the left one is shared with the X axis, like the style of the axis descriptions).
+    let tick_format = options.tick_format;
+    let stride = options.step_stride.max(1);
+    let y_format = |value: &u64| tick_format.format(*value);
+    chart.configure_mesh()
+        .x_desc(options.x_label_or(options.alignment.axis_label()))
+        .y_desc(y_desc[0])
+        .axis_desc_style(("sans-serif", options.text_size(15) * scale))
+        .label_style(("sans-serif", options.text_size(options.tick_size) * scale))
+        .y_label_style(("sans-serif", options.text_size(options.tick_size) * scale).into_font().color(&palette_color(options.palette, 0)))
+        .x_label_formatter(&|step| format_relative_step(*step * stride, origin * stride, tick_format))
+        .y_label_formatter(&y_format)
+        .draw()
+        .map_err(|e| e.to_string())?;
+    chart.configure_secondary_axes()
+        .y_desc(y_desc[1])
+        .axis_style(palette_color(options.palette, 1))
+        .axis_desc_style(("sans-serif", options.text_size(15) * scale))
+        .label_style(("sans-serif", options.text_size(options.tick_size) * scale).into_font().color(&palette_color(options.palette, 1)))
+        .y_label_formatter(&y_format)
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(first) = first {
+        draw_styled_series(&mut **chart, first, options, scale)?;
+    }
+    if let Some(second) = second {
+        draw_styled_series(&mut SecondaryAxis(chart), second, options, scale)?;
+    }
+    draw_legend(chart, options, scale)
+}
+
+/// Chart area on which a series can be drawn: a chart, or the secondary axis of a dual-axis chart.
+/// Both draw the same elements, so the styles of the series are applied by `draw_styled_series` alone.
+trait SeriesCanvas<'a, DB: DrawingBackend, V> {
+    /// Draws elements positioned at (step, value) coordinates, and returns their legend entry.
+    fn draw<E, R, S>(&mut self, elements: S) -> Result<&mut SeriesAnno<'a, DB>, String>
+    where
+        for<'b> &'b E: plotters::element::PointCollection<'b, (usize, V)>,
+        E: plotters::element::Drawable<DB>,
+        R: std::borrow::Borrow<E>,
+        S: IntoIterator<Item = R>;
+}
+
+impl<'a, DB, Y> SeriesCanvas<'a, DB, Y::ValueType>
+    for ChartContext<'a, DB, Cartesian2d<plotters::coord::types::RangedCoordusize, Y>>
+where
+    DB: DrawingBackend,
+    Y: plotters::coord::ranged1d::Ranged,
+{
+    fn draw<E, R, S>(&mut self, elements: S) -> Result<&mut SeriesAnno<'a, DB>, String>
+    where
+        for<'b> &'b E: plotters::element::PointCollection<'b, (usize, Y::ValueType)>,
+        E: plotters::element::Drawable<DB>,
+        R: std::borrow::Borrow<E>,
+        S: IntoIterator<Item = R>,
+    {
+        self.draw_series(elements).map_err(|e| e.to_string())
+    }
+}
+
+/// The secondary (right) axis of a dual-axis chart, as a canvas for the series plotted on it.
+struct SecondaryAxis<'c, 'a, DB: DrawingBackend, Y: plotters::coord::ranged1d::Ranged, SY: plotters::coord::ranged1d::Ranged>(
+    &'c mut DualCoordChartContext<
+        'a,
+        DB,
+        Cartesian2d<plotters::coord::types::RangedCoordusize, Y>,
+        Cartesian2d<plotters::coord::types::RangedCoordusize, SY>,
+    >,
+);
+
+impl<'a, DB, Y, SY> SeriesCanvas<'a, DB, SY::ValueType> for SecondaryAxis<'_, 'a, DB, Y, SY>
+where
+    DB: DrawingBackend,
+    Y: plotters::coord::ranged1d::Ranged,
+    SY: plotters::coord::ranged1d::Ranged,
+{
+    fn draw<E, R, S>(&mut self, elements: S) -> Result<&mut SeriesAnno<'a, DB>, String>
+    where
+        for<'b> &'b E: plotters::element::PointCollection<'b, (usize, SY::ValueType)>,
+        E: plotters::element::Drawable<DB>,
+        R: std::borrow::Borrow<E>,
+        S: IntoIterator<Item = R>,
+    {
+        self.0.draw_secondary_series(elements).map_err(|e| e.to_string())
+    }
+}
+
+/// Draws a series with its style: a solid or dashed line, with a marker on every step if requested
+/// (the styles tell the series apart in grayscale prints), and registers its legend entry.
+fn draw_styled_series<'a, DB, V>(
+    canvas: &mut impl SeriesCanvas<'a, DB, V>,
+    series: &PlottedSeries<V>,
+    options: &chart_options::ChartOptions,
+    scale: u32,
+) -> Result<(), String>
+where
+    DB: DrawingBackend + 'a,
+    V: Clone + 'static,
+{
+    let (style, color) = (options.series_style(series.index), series.color);
+    let line_width = options.line_width.max(1) * scale;
+    let line_style = color.stroke_width(line_width);
+    let (dash, gap) = (6 * scale as i32, 4 * scale as i32);
+    let points = series.points.iter().cloned();
+
+    if style.markers {
+        canvas.draw(points.clone().map(|point| SeriesGlyph { coord: point, style, color, scale, line_width, legend: false }))?;
+    }
+
+    match style.line {
+        chart_options::LineStyle::Solid => canvas.draw(LineSeries::new(points, line_style)),
+        chart_options::LineStyle::Dashed => canvas.draw(DashedLineSeries::new(points, dash, gap, line_style)),
+    }? // Handle errors during drawing
+    .label(&series.label)
+    .legend(move |(x, y)| SeriesGlyph { coord: (x, y), style, color, scale, line_width, legend: true });
+    Ok(())
+}
+
+/// Draws the legend of the sequence chart.
+/// The legend is a small box that describes the colors used in the chart.
+/// It shows which color corresponds to which sequence.
+/// The legend is placed at the top right corner of the chart.
+fn draw_legend<'a, DB: DrawingBackend + 'a, CT: CoordTranslate>(
+    chart: &mut ChartContext<'a, DB, CT>,
+    options: &chart_options::ChartOptions,
+    scale: u32,
+) -> Result<(), String> {
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .label_font(("sans-serif", options.text_size(12) * scale))
+        .legend_area_size((30 * options.text_size(12) / 12 * scale) as i32)
+        .draw()
+        .map_err(|e| e.to_string())
+}
+
+/// Builds a value input with its inline validation: a red border and the error under the field.
+///
+/// # Arguments
+/// * `placeholder` - Text shown when the input is empty.
+/// * `value` - Content of the input.
+/// * `on_input` - Message sent when the content changes, or None for an input that cannot be edited.
+/// * `error` - Why the content is invalid, if it is.
+fn value_input<'a>(
+    placeholder: &str,
+    value: &str,
+    on_input: Option<fn(String) -> Message>,
+    error: Option<String>,
+) -> iced::widget::Column<'a, Message> {
+    // Without `on_input`, the input is disabled (a value derived in the linked input mode).
+    let mut field = text_input(placeholder, value).on_submit(Message::Visualize).padding(10);
+    if let Some(on_input) = on_input {
+        field = field.on_input(on_input);
+    }
+    if error.is_some() {
+        field = field.style(iced::theme::TextInput::Custom(Box::new(InvalidInput)));
+    }
+    let mut column = column![field].spacing(2);
+    if let Some(error) = error {
+        column = column.push(text(error).size(12).style(Color::from_rgb(0.8, 0.2, 0.2)));
+    }
+    column
+}
+
+/// Reads a guess of the "Guess" game: a whole number, or nothing if the input is blank.
+fn parse_guess(text: &str) -> Result<Option<u64>, std::num::ParseIntError> {
+    let text = text.trim();
+    if text.is_empty() {
+        Ok(None)
+    } else {
+        text.parse().map(Some)
+    }
+}
+
+/// Maps the keys of the keyboard navigation to messages:
+/// - the left and right arrow keys to the previous/next value;
+/// - Tab and Shift+Tab to moving the focus to the next/previous input, button, checkbox or selector,
+///   in the order of the window;
+/// - Enter and Space, with nothing focused, to the default action (see `Message::DefaultAction`);
+/// - Escape, outside the inputs, to closing the guided tour (in an input, it leaves the input).
+///
+/// Keys captured by a widget (e.g. moving the cursor of a focused input, or Enter on a focused button) are left to it;
+/// the inputs do not capture Tab, so that it always moves the focus.
+fn keyboard_shortcut(event: iced::Event, status: iced::event::Status) -> Option<Message> {
+    use iced::keyboard::{Event::KeyPressed, KeyCode};
+
+    if status == iced::event::Status::Captured {
+        return None;
+    }
+    let iced::Event::Keyboard(KeyPressed { key_code, modifiers }) = event else {
+        return None;
+    };
+    match key_code {
+        KeyCode::Left => Some(Message::PreviousValue),
+        KeyCode::Right => Some(Message::NextValue),
+        KeyCode::Tab if modifiers.shift() => Some(Message::FocusPrevious),
+        KeyCode::Tab => Some(Message::FocusNext),
+        KeyCode::Enter | KeyCode::NumpadEnter | KeyCode::Space => Some(Message::DefaultAction),
+        KeyCode::Escape => Some(Message::TourDismissed),
+        _ => None,
+    }
+}
+
+/// Follows the modifier keys held (see `Message::ModifiersChanged`).
+fn modifiers_changed(event: iced::Event, _status: iced::event::Status) -> Option<Message> {
+    match event {
+        iced::Event::Keyboard(iced::keyboard::Event::ModifiersChanged(modifiers)) => {
+            Some(Message::ModifiersChanged(modifiers))
+        }
+        _ => None,
+    }
+}
+
+/// Follows the focus of the window (see `Message::WindowFocusChanged`).
+fn window_focus(event: iced::Event, _status: iced::event::Status) -> Option<Message> {
+    match event {
+        iced::Event::Window(iced::window::Event::Focused) => Some(Message::WindowFocusChanged(true)),
+        iced::Event::Window(iced::window::Event::Unfocused) => Some(Message::WindowFocusChanged(false)),
+        _ => None,
+    }
+}
+
+/// Turns the request to close the window into a message, so that the application cleans up
+/// before closing (the window does not close on its own, see `exit_on_close_request` in `main`).
+fn close_request(event: iced::Event, _status: iced::event::Status) -> Option<Message> {
+    match event {
+        iced::Event::Window(iced::window::Event::CloseRequested) => Some(Message::CloseRequested),
+        _ => None,
+    }
+}
+
+/// Turns a file dropped onto the window into an import of that file.
+fn file_dropped(event: iced::Event, _status: iced::event::Status) -> Option<Message> {
+    match event {
+        iced::Event::Window(iced::window::Event::FileDropped(path)) => Some(Message::FileDropped(path)),
+        _ => None,
+    }
+}
+
+/// Builds the adjustment buttons of a value input (+1, -1, x2, next odd).
+///
+/// # Arguments
+/// * `index` - 0 for the first input, 1 for the second.
+/// * `enabled` - Whether the buttons can be pressed (not for a value derived in the linked input mode).
+fn adjustment_buttons<'a>(index: usize, enabled: bool) -> iced::widget::Row<'a, Message> {
+    input::Adjustment::ALL
+        .into_iter()
+        .fold(row![].spacing(2), |buttons, adjustment| {
+            buttons.push(
+                focus_ring::press(button(text(adjustment.to_string()).size(12))
+                    .on_press_maybe(enabled.then_some(Message::AdjustInput(index, adjustment)))
+                    .padding([4, 6])),
+            )
+        })
+}
+
+/// Style of an input holding an invalid value: the default style, with a red border.
+struct InvalidInput;
+
+impl text_input::StyleSheet for InvalidInput {
+    type Style = Theme;
+
+    fn active(&self, style: &Theme) -> text_input::Appearance {
+        text_input::Appearance {
+            border_color: Color::from_rgb(0.8, 0.2, 0.2),
+            ..style.active(&iced::theme::TextInput::Default)
+        }
+    }
+
+    fn focused(&self, style: &Theme) -> text_input::Appearance {
+        text_input::Appearance {
+            border_color: Color::from_rgb(0.8, 0.2, 0.2),
+            ..style.focused(&iced::theme::TextInput::Default)
+        }
+    }
+
+    fn placeholder_color(&self, style: &Theme) -> Color {
+        style.placeholder_color(&iced::theme::TextInput::Default)
+    }
+
+    fn value_color(&self, style: &Theme) -> Color {
+        style.value_color(&iced::theme::TextInput::Default)
+    }
+
+    fn disabled_color(&self, style: &Theme) -> Color {
+        style.disabled_color(&iced::theme::TextInput::Default)
+    }
+
+    fn selection_color(&self, style: &Theme) -> Color {
+        style.selection_color(&iced::theme::TextInput::Default)
+    }
+
+    fn disabled(&self, style: &Theme) -> text_input::Appearance {
+        style.disabled(&iced::theme::TextInput::Default)
+    }
+}
+
+/// Marker of a point of a sequence series, or legend entry of the series.
+/// It is drawn with the backend primitives, so that every line and marker style is the same element type.
+struct SeriesGlyph<C> {
+    coord: C, // Point of the series, or position of the legend entry
+    style: chart_options::SeriesStyle, // Line and marker style of the series
+    color: RGBColor, // Color of the series
+    scale: u32, // Resolution multiplier
+    line_width: u32, // Width of the series line, in pixels (already scaled)
+    legend: bool, // Whether to draw the legend entry (a piece of line and a marker) instead of a single marker
+}
+
+impl<'a, C> plotters::element::PointCollection<'a, C> for &'a SeriesGlyph<C> {
+    type Point = &'a C;
+    type IntoIter = std::iter::Once<&'a C>;
+
+    fn point_iter(self) -> Self::IntoIter {
+        std::iter::once(&self.coord)
+    }
+}
+
+impl<C, DB: DrawingBackend> plotters::element::Drawable<DB> for SeriesGlyph<C> {
+    fn draw<I: Iterator<Item = plotters_backend::BackendCoord>>(
+        &self,
+        mut points: I,
+        backend: &mut DB,
+        _parent_dim: (u32, u32),
+    ) -> Result<(), plotters_backend::DrawingErrorKind<DB::ErrorType>> {
+        let Some((mut x, y)) = points.next() else {
+            return Ok(());
+        };
+        let scale = self.scale as i32;
+        let line = self.color.stroke_width(self.line_width);
+        let fill = self.color.filled();
+
+        // Legend entry: a short piece of the line, with the marker in its middle.
+        if self.legend {
+            let width = 20 * scale;
+            match self.style.line {
+                chart_options::LineStyle::Solid => backend.draw_line((x, y), (x + width, y), &line)?,
+                chart_options::LineStyle::Dashed => {
+                    for start in (0..width).step_by(5 * scale as usize) {
+                        backend.draw_line((x + start, y), (x + (start + 3 * scale).min(width), y), &line)?;
+                    }
+                }
+            }
+            if !self.style.markers {
+                return Ok(());
+            }
+            x += width / 2;
+        }
+
+        let size = 3 * scale;
+        match self.style.marker {
+            chart_options::MarkerShape::Circle => backend.draw_circle((x, y), size as u32, &fill, true),
+            chart_options::MarkerShape::Square => backend.draw_rect((x - size, y - size), (x + size, y + size), &fill, true),
+            chart_options::MarkerShape::Triangle => {
+                backend.fill_polygon(vec![(x, y - size - 1), (x - size - 1, y + size), (x + size + 1, y + size)], &fill)
+            }
+            chart_options::MarkerShape::Cross => {
+                backend.draw_line((x - size, y - size), (x + size, y + size), &line)?;
+                backend.draw_line((x - size, y + size), (x + size, y - size), &line)
+            }
+        }
+    }
+}
+
+/// Computes the total stopping time of every starting value of a range with the chosen backend.
+/// The CPU backend goes through the persistent cache (saved by the caller once the whole scan is done).
+fn scan_stopping_times(
+    backend: ScanBackend,
+    range_start: u64,
+    range_end: u64,
+    stopping_time_cache: &Mutex<cache::StoppingTimeCache>,
+) -> Result<Vec<(u64, usize)>, String> {
+    match backend {
+        ScanBackend::Cpu => {
+            // A poisoned lock (a previous scan panicked) falls back to the plain computation.
+            let mut cache = match stopping_time_cache.lock() {
+                Ok(cache) => cache,
+                Err(_) => return Ok(collatz::stopping_times_in_range(range_start, range_end)),
+            };
+            Ok(cache.stopping_times_in_range(range_start, range_end))
+        }
+        #[cfg(feature = "gpu")]
+        ScanBackend::Gpu => gpu::stopping_times_in_range(range_start, range_end),
+    }
+}
+
+/// Streams a range scan: the range is scanned chunk by chunk, from where the scan stands,
+/// and after each chunk the chart is redrawn from all the results so far and sent to the interface.
+/// The results accumulate in `scan.results`, which the interface keeps along with the scan:
+/// dropping the subscription (pause) loses at most the chunk in progress.
+fn range_scan_subscription(
+    scan: &RangeScan, // Scan to run
+    stopping_time_cache: Arc<Mutex<cache::StoppingTimeCache>>, // Memoized stopping times
+) -> Subscription<Message> {
+    let scan = scan.clone(); // Only the shared results are cloned by reference
+    iced::subscription::channel((scan.job, scan.segment), 4, move |mut output| async move {
+        let chunk = (scan.total() / STREAM_UPDATES).max(MIN_STREAM_CHUNK);
+
+        loop {
+            // The chunk is scanned and drawn off the executor, like every chart.
+            let update = {
+                let (scan, stopping_time_cache) = (scan.clone(), stopping_time_cache.clone());
+                off_ui_thread(async move { scan_range_chunk(&scan, chunk, &stopping_time_cache) }).await
+            };
+            let done = update.as_ref().map_or(true, |update| update.scanned >= scan.total());
+            let _ = output.send(Message::RangeScanProgress(scan.job, update)).await;
+            if done {
+                break;
+            }
+        }
+
+        // The whole range is in the cache now; failing to persist it only costs time on the next run.
+        if let Ok(mut cache) = stopping_time_cache.lock() {
+            if let Err(e) = cache.save() {
+                println!("Warning: Unable to save the stopping-time cache: {}", e);
+            }
+        }
+
+        // A subscription never ends by itself: it is dropped by the interface once the scan is done.
+        loop {
+            futures::future::pending::<()>().await;
+        }
+    })
+}
+
+/// Scans the next `chunk` starting values of a range scan, adds them to its results,
+/// and draws the chart of all the results so far.
+fn scan_range_chunk(
+    scan: &RangeScan, // Scan being run
+    chunk: u64, // Maximum number of starting values to scan
+    stopping_time_cache: &Mutex<cache::StoppingTimeCache>, // Memoized stopping times
+) -> Result<RangeScanUpdate, String> {
+    let mut results = scan.results.lock().map_err(|_| "The scan results are unavailable".to_string())?;
+    // A scan paused right after its last chunk (before the report arrived) only needs its chart redrawn.
+    if results.scanned < scan.total() {
+        let from = scan.range_start + results.scanned;
+        let to = from + (chunk - 1).min(scan.range_end - from);
+        let timer = std::time::Instant::now();
+        // Glides are computed directly: they are too short to be worth the cache or the GPU.
+        let chunk_times = match scan.measure {
+            ScanMeasure::TotalStoppingTime => scan_stopping_times(scan.backend, from, to, stopping_time_cache)?,
+            ScanMeasure::Glide => collatz::glides_in_range(from, to),
+        };
+        // The scatter chart plots every point of its (small) range; the density chart only keeps the counts
+        // of its cells, so a range of 50 million values never holds more than a chunk of pairs.
+        if scan.kind == ChartKind::StoppingTimeScatter {
+            results.times.extend(chunk_times);
+        } else {
+            let tally = results.tally.get_or_insert_with(|| {
+                collatz::StoppingTimeTally::new(scan.range_start, scan.range_end, DENSITY_X_BINS)
+            });
+            chunk_times.into_iter().for_each(|(n, t)| tally.add(n, t));
+        }
+        results.scanned += to - from + 1;
+        results.elapsed += timer.elapsed();
+    }
+    let results = &mut *results;
+    let (scanned, elapsed) = (results.scanned, results.elapsed);
+
+    // Every update gets its own file: the interface may still be showing the previous one.
+    let now = Local::now();
+    let path = temp_file_path(&format!(
+        "range_{}_{}_{}.png",
+        now.format("%Y%m%d_%H%M%S"),
+        scan.job,
+        scanned
+    ));
+    let (mut summary, counted) = if scan.kind == ChartKind::StoppingTimeScatter {
+        let times = &results.times;
+        (draw_scatter_chart(&path, scan.range_start, scan.range_end, scan.modulus, scan.measure, times)?, times.len() as u64)
+    } else {
+        let tally = results.tally.get_or_insert_with(|| {
+            collatz::StoppingTimeTally::new(scan.range_start, scan.range_end, DENSITY_X_BINS)
+        });
+        (draw_density_chart(&path, scan.measure, tally)?, tally.count())
+    };
+    // The chart spans the whole range, but only the values scanned so far can have been skipped.
+    summary.skipped = (scanned - counted) as usize;
+
+    Ok(RangeScanUpdate {
+        chart_path: path.to_string_lossy().to_string(),
+        summary,
+        scanned,
+        elapsed,
+    })
+}
+
+/// Draws a density chart of total stopping times (or glides) over a range.
+/// Each cell of the 2D histogram is colored according to the number of starting values it contains
+/// (logarithmic scale), which stays readable for millions of points where a scatter plot would not.
+/// Returns the range summary, so it can be displayed.
+fn draw_density_chart(
+    path: &std::path::Path, // Path to save the chart image
+    measure: ScanMeasure, // What the times are: total stopping times or glides
+    tally: &collatz::StoppingTimeTally, // Counts of the stopping times computed so far
+) -> Result<collatz::RangeSummary, String> {
+    // Bin the results.
+    let density = collatz::stopping_time_density(tally, DENSITY_Y_BINS);
+    let summary = &density.summary;
+
+    let root = BitMapBackend::new(&path, (800, 400)).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| e.to_string())?;
+
+    // X axis: starting values. Y axis: total stopping times.
+    // Plotted as floats so that the cells can be placed at fractional positions.
+    let x_min = summary.range_start as f64;
+    let x_max = summary.range_end as f64 + 1.0;
+    let y_max = summary.max_time as f64 + 1.0;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(
+            format!("{} -- {} to {}", measure.plural(), summary.range_start, summary.range_end),
+            ("sans-serif", 20),
+        )
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(60)
+        .build_cartesian_2d(x_min..x_max, 0.0..y_max)
+        .map_err(|e| e.to_string())?;
+
+    chart.configure_mesh()
+        .disable_mesh()
+        .x_desc("Starting value")
+        .y_desc(measure.label())
+        .axis_desc_style(("sans-serif", 15))
+        .x_label_formatter(&|x| format!("{:.0}", x))
+        .y_label_formatter(&|y| format!("{:.0}", y))
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    // Size of a cell in chart coordinates.
+    let cell_width = (x_max - x_min) / density.x_bins as f64;
+    let cell_height = y_max / density.y_bins as f64;
+
+    // Colors use a logarithmic scale: a few very dense cells would otherwise wash out everything else.
+    let log_max = (density.max_count as f64 + 1.0).ln();
+    let colormap = ViridisRGB;
+
+    chart
+        .draw_series(
+            (0..density.x_bins)
+                .flat_map(|x| (0..density.y_bins).map(move |y| (x, y)))
+                .filter(|&(x, y)| density.count(x, y) > 0)
+                .map(|(x, y)| {
+                    let level = (density.count(x, y) as f64 + 1.0).ln() / log_max;
+                    let color = colormap.get_color(level as f32);
+                    let x0 = x_min + x as f64 * cell_width;
+                    let y0 = y as f64 * cell_height;
+                    Rectangle::new(
+                        [(x0, y0), (x0 + cell_width, y0 + cell_height)],
+                        color.filled(),
+                    )
+                }),
+        )
+        .map_err(|e| e.to_string())?;
+
+    root.present().map_err(|e| e.to_string())?;
+
+    Ok(density.summary)
+}
+
+/// Asynchronously draws a Monte Carlo sample of total stopping times and plots its distribution.
+/// The histogram is overlaid with the sample mean and its 95% confidence interval.
+/// Returns the chart path together with the sample report, so it can be displayed.
+async fn generate_sample_chart(
+    path: PathBuf, // Path to save the chart image
+    range_start: u64, // First starting value of the range
+    range_end: u64, // Last starting value of the range
+    count: usize, // Number of random starting values
+    distribution: sampling::SamplingDistribution, // How the starting values are drawn
+    seed: u64, // Seed of the random generator
+) -> Result<(String, sampling::SampleReport), String> {
+    let report = sampling::sample_stopping_times(range_start, range_end, count, distribution, seed);
+    if report.times.is_empty() {
+        return Err("No trajectory could be computed".to_string());
+    }
+
+    // Count how many samples have each stopping time.
+    let min_time = report.quantile(0.0);
+    let max_time = report.quantile(1.0);
+    let mut counts = vec![0usize; max_time - min_time + 1];
+    for &t in &report.times {
+        counts[t - min_time] += 1;
+    }
+    let max_count = counts.iter().copied().max().unwrap_or(1);
+
+    let root = BitMapBackend::new(&path, (800, 400)).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| e.to_string())?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(
+            format!("Sampled total stopping times -- {} to {}", range_start, range_end),
+            ("sans-serif", 20),
+        )
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(60)
+        .build_cartesian_2d(min_time as f64..(max_time + 1) as f64, 0..max_count + 1)
+        .map_err(|e| e.to_string())?;
+
+    chart.configure_mesh()
+        .disable_x_mesh()
+        .x_desc("Total stopping time")
+        .y_desc("Samples")
+        .axis_desc_style(("sans-serif", 15))
+        .x_label_formatter(&|x| format!("{:.0}", x))
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    // One bar per stopping time.
+    chart
+        .draw_series(counts.iter().enumerate().filter(|&(_, &c)| c > 0).map(|(i, &c)| {
+            let t = (min_time + i) as f64;
+            Rectangle::new([(t, 0), (t + 1.0, c)], BLUE.mix(0.6).filled())
+        }))
+        .map_err(|e| e.to_string())?;
+
+    // Confidence interval of the mean (shaded band) and the mean itself (red line).
+    chart
+        .draw_series(std::iter::once(Rectangle::new(
+            [(report.ci95.0, 0), (report.ci95.1.max(report.ci95.0 + 0.2), max_count + 1)],
+            RED.mix(0.2).filled(),
+        )))
+        .map_err(|e| e.to_string())?
+        .label(format!("95% CI of the mean: {:.2} to {:.2}", report.ci95.0, report.ci95.1))
+        .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 20, y + 5)], RED.mix(0.2).filled()));
+    chart
+        .draw_series(LineSeries::new(
+            vec![(report.mean, 0), (report.mean, max_count + 1)],
+            RED,
+        ))
+        .map_err(|e| e.to_string())?
+        .label(format!("Mean: {:.2}", report.mean))
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    root.present().map_err(|e| e.to_string())?;
+
+    Ok((path.to_string_lossy().to_string(), report))
+}
+
+/// Asynchronously computes the total stopping times of a range and plots their distribution.
+/// Returns the chart path together with the range summary, so it can be displayed.
+async fn generate_histogram_chart(
+    path: PathBuf, // Path to save the chart image
+    range_start: u64, // First starting value of the range
+    range_end: u64, // Last starting value of the range
+    bins: usize, // Largest number of bins
+) -> Result<(String, collatz::RangeSummary), String> {
+    // Only the counts per stopping time are kept, whatever the size of the range.
+    let tally = collatz::tally_stopping_times(range_start, range_end, 1);
+    if tally.count() == 0 {
+        return Err("No trajectory could be computed".to_string());
+    }
+    let histogram = collatz::stopping_time_histogram(&tally, bins);
+    let max_count = histogram.counts.iter().copied().max().unwrap_or(1);
+    let first = histogram.min_time as f64;
+    let last = (histogram.min_time + histogram.counts.len() * histogram.bin_width) as f64;
+
+    let root = BitMapBackend::new(&path, (800, 400)).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| e.to_string())?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(
+            format!("Total stopping times -- {} to {}", range_start, range_end),
+            ("sans-serif", 20),
+        )
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(60)
+        .build_cartesian_2d(first..last, 0..max_count + 1)
+        .map_err(|e| e.to_string())?;
+
+    chart.configure_mesh()
+        .disable_x_mesh()
+        .x_desc(if histogram.bin_width > 1 {
+            format!("Total stopping time (bins of {} steps)", histogram.bin_width)
+        } else {
+            "Total stopping time".to_string()
+        })
+        .y_desc("Starting values")
+        .axis_desc_style(("sans-serif", 15))
+        .x_label_formatter(&|x| format!("{:.0}", x))
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    // One bar per bin.
+    chart
+        .draw_series(histogram.counts.iter().enumerate().filter(|&(_, &c)| c > 0).map(|(i, &c)| {
+            let t = (histogram.min_time + i * histogram.bin_width) as f64;
+            Rectangle::new([(t, 0), (t + histogram.bin_width as f64, c)], BLUE.mix(0.6).filled())
+        }))
+        .map_err(|e| e.to_string())?;
+
+    // The mean total stopping time (red line).
+    chart
+        .draw_series(LineSeries::new(
+            vec![(histogram.summary.mean_time, 0), (histogram.summary.mean_time, max_count + 1)],
+            RED,
+        ))
+        .map_err(|e| e.to_string())?
+        .label(format!("Mean: {:.2}", histogram.summary.mean_time))
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    root.present().map_err(|e| e.to_string())?;
+
+    Ok((path.to_string_lossy().to_string(), histogram.summary))
+}
+
+/// Asynchronously computes the total stopping times of a range and plots their empirical cumulative
+/// distribution, over the curve of a reference range if any.
+/// Returns the chart path together with the range summary and the curve, so they can be displayed and compared.
+async fn generate_cdf_chart(
+    path: PathBuf, // Path to save the chart image
+    range_start: u64, // First starting value of the range
+    range_end: u64, // Last starting value of the range
+    reference: Option<CdfCurve>, // Curve of a previous range, drawn for comparison
+) -> Result<(String, collatz::RangeSummary, CdfCurve), String> {
+    // Only the counts per stopping time are kept, whatever the size of the range.
+    let tally = collatz::tally_stopping_times(range_start, range_end, 1);
+    if tally.count() == 0 {
+        return Err("No trajectory could be computed".to_string());
+    }
+    let summary = tally.summary();
+    let curve = (format!("{} to {}", range_start, range_end), collatz::stopping_time_cdf(&tally.time_counts()));
+    let max_time = reference
+        .iter()
+        .chain(std::iter::once(&curve))
+        .filter_map(|(_, points)| points.last().map(|&(t, _)| t))
+        .max()
+        .unwrap_or(0);
+
+    let root = BitMapBackend::new(&path, (800, 400)).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| e.to_string())?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(format!("Cumulative distribution of total stopping times -- {}", curve.0), ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0..max_time + 1, 0.0..1.0)
+        .map_err(|e| e.to_string())?;
+
+    chart.configure_mesh()
+        .x_desc("Total stopping time")
+        .y_desc("Fraction of starting values")
+        .axis_desc_style(("sans-serif", 15))
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    // Each curve is a staircase: the fraction only changes at the stopping times found.
+    let staircase = |points: &[(usize, f64)]| {
+        let mut steps = vec![(points.first().map_or(0, |&(t, _)| t), 0.0)];
+        for &(t, fraction) in points {
+            let previous = steps.last().map_or(0.0, |&(_, f)| f);
+            steps.push((t, previous));
+            steps.push((t, fraction));
+        }
+        steps.push((max_time + 1, 1.0));
+        steps
+    };
+    if let Some((label, points)) = &reference {
+        chart
+            .draw_series(LineSeries::new(staircase(points), BLACK.mix(0.4)))
+            .map_err(|e| e.to_string())?
+            .label(label.clone())
+            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLACK.mix(0.4)));
+    }
+    chart
+        .draw_series(LineSeries::new(staircase(&curve.1), BLUE.stroke_width(2)))
+        .map_err(|e| e.to_string())?
+        .label(curve.0.clone())
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLUE.stroke_width(2)));
+
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::LowerRight)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    root.present().map_err(|e| e.to_string())?;
+
+    Ok((path.to_string_lossy().to_string(), summary, curve))
+}
+
+/// Asynchronously computes the peak of every starting value of a range and plots it against the starting value,
+/// both axes logarithmic, with the n² reference line (peaks above it are rare and stand out).
+/// Returns the chart path together with the range summary, so it can be displayed.
+async fn generate_peak_chart(
+    path: PathBuf, // Path to save the chart image
+    range_start: u64, // First starting value of the range
+    range_end: u64, // Last starting value of the range
+) -> Result<(String, collatz::RangeSummary), String> {
+    let (peaks, summary) = collatz::peaks_in_range(range_start, range_end);
+    let Some(&(highest_start, highest)) = peaks.iter().max_by_key(|&&(_, p)| p) else {
+        return Err("No trajectory could be computed".to_string());
+    };
+    let top = (highest as f64).max(range_end as f64 * range_end as f64) * 2.0;
+
+    let root = BitMapBackend::new(&path, (800, 400)).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| e.to_string())?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(format!("Peak vs starting value -- {} to {}", range_start, range_end), ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(60)
+        .build_cartesian_2d(
+            (range_start as f64..(range_end as f64 + 1.0)).log_scale(),
+            (range_start as f64..top).log_scale(),
+        )
+        .map_err(|e| e.to_string())?;
+
+    chart.configure_mesh()
+        .x_desc("Starting value n")
+        .y_desc("Peak")
+        .axis_desc_style(("sans-serif", 15))
+        .x_label_formatter(&|x| format!("{:.0}", x))
+        .y_label_formatter(&|y| format!("{:.0e}", y))
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    chart
+        .draw_series(peaks.iter().map(|&(n, p)| Circle::new((n as f64, p as f64), 1, BLUE.mix(0.5).filled())))
+        .map_err(|e| e.to_string())?
+        .label(format!("Highest peak: {} (starting at {})", highest, highest_start))
+        .legend(|(x, y)| Circle::new((x + 10, y), 3, BLUE.filled()));
+
+    // The n² line, a straight line of slope 2 on log-log axes.
+    let steps = 100;
+    let ratio = (range_end as f64 / range_start as f64).powf(1.0 / steps as f64);
+    chart
+        .draw_series(LineSeries::new(
+            (0..=steps).map(|i| {
+                let n = range_start as f64 * ratio.powi(i);
+                (n, n * n)
+            }),
+            RED,
+        ))
+        .map_err(|e| e.to_string())?
+        .label("n²")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED));
+
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperLeft)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    root.present().map_err(|e| e.to_string())?;
+
+    Ok((path.to_string_lossy().to_string(), summary))
+}
+
+/// Asynchronously counts the trajectories of a range passing through each term of the trajectory of `value`
+/// and plots the counts step by step (log scale), with the hubs of the trajectory marked.
+/// Returns the chart path together with the counts, so they can be displayed.
+async fn generate_through_chart(
+    path: PathBuf, // Path to save the chart image
+    value: u64, // Value whose trajectory is followed
+    range_start: u64, // First starting value of the range
+    range_end: u64, // Last starting value of the range
+) -> Result<(String, ThroughCounts), String> {
+    let counts = collatz::passes_through(value, range_start, range_end);
+    let max_count = counts.iter().map(|&(_, count)| count).max().unwrap_or(0);
+    if max_count == 0 {
+        return Err("No trajectory could be computed".to_string());
+    }
+    let hubs = collatz::hub_values(&counts, HUB_COUNT);
+
+    let root = BitMapBackend::new(&path, (800, 400)).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| e.to_string())?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(
+            format!("Trajectories of {} to {} through the trajectory of {}", range_start, range_end, value),
+            ("sans-serif", 20),
+        )
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0..counts.len(), (1..max_count * 2).log_scale())
+        .map_err(|e| e.to_string())?;
+
+    chart.configure_mesh()
+        .x_desc(format!("Step of the trajectory of {}", value))
+        .y_desc("Trajectories passing through")
+        .axis_desc_style(("sans-serif", 15))
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    // Terms no trajectory of the range reaches (above the range) have no point on the log scale.
+    chart
+        .draw_series(LineSeries::new(
+            counts.iter().enumerate().filter(|(_, &(_, count))| count > 0).map(|(i, &(_, count))| (i, count)),
+            BLUE,
+        ))
+        .map_err(|e| e.to_string())?;
+
+    // The hubs, labeled with their term.
+    chart
+        .draw_series(hubs.iter().filter_map(|&(term, _)| {
+            let step = counts.iter().position(|&(t, _)| t == term)?;
+            Some(
+                EmptyElement::at((step, counts[step].1))
+                    + Circle::new((0, 0), 4, RED.filled())
+                    + Text::new(term.to_string(), (6, 4), ("sans-serif", 13).into_font().color(&RED)),
             )
-            .width(Length::Fill)
-            .height(Length::Fixed(400.0))
-        } else { // If no chart was generated, show a message
-            container(
-                text("No graph generated")
-                    .width(Length::Fill)
-                    .height(Length::Fixed(400.0))
-                    .horizontal_alignment(iced::alignment::Horizontal::Center)
-                    .vertical_alignment(iced::alignment::Vertical::Center)
+        }))
+        .map_err(|e| e.to_string())?;
+
+    root.present().map_err(|e| e.to_string())?;
+
+    Ok((path.to_string_lossy().to_string(), (format!("{} to {}", range_start, range_end), counts)))
+}
+
+/// Asynchronously ranks the values visited the most often by the trajectories of a range
+/// and draws the ranking as a bar chart, the most visited value first.
+/// Returns the chart path together with the ranking, so it can be displayed as a table.
+async fn generate_most_visited_chart(
+    path: PathBuf, // Path to save the chart image
+    range_start: u64, // First starting value of the range
+    range_end: u64, // Last starting value of the range
+) -> Result<(String, VisitRanking), String> {
+    let ranking = collatz::most_visited(range_start, range_end, TOP_VISITED);
+    let Some(&(_, max_visits)) = ranking.first() else {
+        return Err("No value visited outside the powers of 2".to_string());
+    };
+
+    let root = BitMapBackend::new(&path, (800, 400)).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| e.to_string())?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(format!("Most-visited values -- {} to {}", range_start, range_end), ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(50)
+        .y_label_area_size(60)
+        .build_cartesian_2d((0..ranking.len()).into_segmented(), 0..max_visits + max_visits / 10 + 1)
+        .map_err(|e| e.to_string())?;
+
+    // One bar per value, labeled with the value itself.
+    chart.configure_mesh()
+        .disable_x_mesh()
+        .x_desc("Value (powers of 2 left out)")
+        .y_desc("Trajectories visiting it")
+        .axis_desc_style(("sans-serif", 15))
+        .x_labels(ranking.len())
+        .x_label_formatter(&|segment| match segment {
+            SegmentValue::CenterOf(index) => ranking.get(*index).map_or(String::new(), |(value, _)| value.to_string()),
+            _ => String::new(),
+        })
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    chart
+        .draw_series(ranking.iter().enumerate().map(|(index, &(_, visits))| {
+            Rectangle::new(
+                [(SegmentValue::Exact(index), 0), (SegmentValue::Exact(index + 1), visits)],
+                BLUE.mix(0.6).filled(),
             )
-            .width(Length::Fill)
-            .height(Length::Fixed(400.0))
-            .style(|theme: &Theme| {
-                container::Appearance {
-                    border_width: 1.0,
-                    border_color: theme.extended_palette().background.strong.color,
-                    ..Default::default() // Default appearance
-                }
-            })
-        };
-        
-        // Create the main content of the application
-        // This includes the title, input fields, buttons, status message, and chart.
-        // The content is arranged in a vertical column.
-        // Each section is separated by vertical space for better readability.
-        let content = column![
-            title,
-            vertical_space(Length::Fixed(20.0)),
-            input_row,
-            vertical_space(Length::Fixed(10.0)),
-            button_row,
-            vertical_space(Length::Fixed(10.0)),
-            status_message,
-            vertical_space(Length::Fixed(20.0)),
-            chart,
-            vertical_space(Length::Fixed(20.0)),
-            text("Statistics:").size(18),
-            vertical_space(Length::Fixed(5.0)),
-            stats_section,
-        ]
-        .spacing(5)
-        .padding(20)
-        .max_width(800);
-        
-        // Create a container for the main content
-        // The container has a fixed width and height, and is centered in the window.
-        container(content)
-            .width(Length::Fill)
-            .height(Length::Fill)
-            .center_x()
-            .center_y()
-            .into()
+        }))
+        .map_err(|e| e.to_string())?;
+
+    root.present().map_err(|e| e.to_string())?;
+
+    Ok((path.to_string_lossy().to_string(), (range_start, range_end, ranking)))
+}
+
+/// Color of the points of residue class `class` (out of `modulus` classes) on the scatter chart.
+/// Classes are spread evenly around the hue circle; a modulus of 1 gives plain blue points.
+fn residue_color(class: u64, modulus: u64) -> HSLColor {
+    if modulus <= 1 {
+        return HSLColor(0.6, 0.8, 0.4);
+    }
+    HSLColor(class as f64 / modulus as f64, 0.8, 0.45)
+}
+
+/// Draws a scatter plot of (starting value, total stopping time or glide) over a range.
+/// When `modulus` is greater than 1, each point is colored by its total stopping time mod `modulus`,
+/// which exposes the class structure that a single-color scatter hides.
+/// Returns the range summary, so it can be displayed.
+fn draw_scatter_chart(
+    path: &std::path::Path, // Path to save the chart image
+    range_start: u64, // First starting value of the range
+    range_end: u64, // Last starting value of the range
+    modulus: u64, // Number of residue classes used to color the points (1 = single color)
+    measure: ScanMeasure, // What the times are: total stopping times or glides
+    times: &[(u64, usize)], // Stopping times computed so far
+) -> Result<collatz::RangeSummary, String> {
+    let summary = collatz::summarize_stopping_times(range_start, range_end, times);
+
+    let root = BitMapBackend::new(&path, (800, 400)).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| e.to_string())?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(
+            if modulus > 1 {
+                format!("{} mod {} -- {} to {}", measure.plural(), modulus, range_start, range_end)
+            } else {
+                format!("{} -- {} to {}", measure.plural(), range_start, range_end)
+            },
+            ("sans-serif", 20),
+        )
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(60)
+        .build_cartesian_2d(range_start..range_end + 1, 0..summary.max_time + 1)
+        .map_err(|e| e.to_string())?;
+
+    chart.configure_mesh()
+        .disable_mesh()
+        .x_desc("Starting value")
+        .y_desc(measure.label())
+        .axis_desc_style(("sans-serif", 15))
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    if modulus <= MAX_LEGEND_CLASSES {
+        // One series per residue class, so that each class gets its own legend entry.
+        for class in 0..modulus {
+            let color = residue_color(class, modulus);
+            let series = chart
+                .draw_series(
+                    times.iter()
+                        .filter(|&&(_, t)| t as u64 % modulus == class)
+                        .map(|&(n, t)| Circle::new((n, t), 1, color.filled())),
+                )
+                .map_err(|e| e.to_string())?;
+            if modulus > 1 {
+                series
+                    .label(format!("T mod {} = {}", modulus, class))
+                    .legend(move |(x, y)| Circle::new((x + 10, y), 3, color.filled()));
+            }
+        }
+
+        if modulus > 1 {
+            chart
+                .configure_series_labels()
+                .background_style(WHITE.mix(0.8))
+                .border_style(BLACK)
+                .draw()
+                .map_err(|e| e.to_string())?;
+        }
+    } else {
+        // Too many classes for a legend: color every point in a single pass.
+        chart
+            .draw_series(times.iter().map(|&(n, t)| {
+                Circle::new((n, t), 1, residue_color(t as u64 % modulus, modulus).filled())
+            }))
+            .map_err(|e| e.to_string())?;
+    }
+
+    root.present().map_err(|e| e.to_string())?;
+
+    Ok(summary)
+}
+
+/// Signed logarithm used by the map comparison chart: sign(v) * log10(1 + |v|).
+/// Variant trajectories can span many orders of magnitude (5n+1 often grows until overflow)
+/// and can be negative, so a plain linear or logarithmic axis would hide most of them.
+fn signed_log10(value: i64) -> f64 {
+    let magnitude = (1.0 + value.unsigned_abs() as f64).log10();
+    if value < 0 { -magnitude } else { magnitude }
+}
+
+/// Asynchronously generates a chart overlaying the trajectories of one starting value
+/// under several map variants.
+async fn generate_comparison_chart(
+    path: PathBuf, // Path to save the chart image
+    start: i64, // Starting value shared by every trajectory
+    trajectories: Vec<collatz::VariantTrajectory>, // One trajectory per map
+    palette: chart_options::Palette, // Colors of the trajectories
+) -> Result<String, String> {
+    if trajectories.is_empty() {
+        return Err("No sequence to visualize".to_string());
+    }
+
+    let root = BitMapBackend::new(&path, (800, 400)).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| e.to_string())?;
+
+    // Axis ranges cover every trajectory, in signed-log units.
+    let max_len = trajectories.iter().map(|t| t.sequence.len()).max().unwrap_or(1);
+    let all_values = || trajectories.iter().flat_map(|t| t.sequence.iter().copied().map(signed_log10));
+    let y_max = all_values().fold(0.0, f64::max) + 0.5;
+    let y_min = all_values().fold(0.0, f64::min) - 0.5;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(format!("Map comparison -- {}", start), ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(60)
+        .build_cartesian_2d(0..max_len, y_min..y_max)
+        .map_err(|e| e.to_string())?;
+
+    chart.configure_mesh()
+        .x_desc("Step")
+        .y_desc("sign(v) * log10(1 + |v|)")
+        .axis_desc_style(("sans-serif", 15))
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    for (index, trajectory) in trajectories.iter().enumerate() {
+        let color = palette_color(palette, index);
+        chart
+            .draw_series(LineSeries::new(
+                trajectory.sequence.iter().enumerate().map(|(i, &v)| (i, signed_log10(v))),
+                color,
+            ))
+            .map_err(|e| e.to_string())?
+            .label(format!("{} ({})", trajectory.map.name(), trajectory.termination.describe()))
+            .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+
+        // Highlight the point where the trajectory enters a cycle, naming the cycle if it is known.
+        if let Some(cycle) = &trajectory.cycle {
+            let name = collatz::identify_cycle(trajectory.map, cycle).map_or("unknown cycle", |known| known.name);
+            let entry = (cycle.entry_index, signed_log10(cycle.entry_value()));
+            chart
+                .draw_series(std::iter::once(
+                    EmptyElement::at(entry)
+                        + Circle::new((0, 0), 5, color.filled())
+                        + Text::new(name.to_string(), (8, -14), ("sans-serif", 13).into_font().color(&color)),
+                ))
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    chart
+        .configure_series_labels()
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    root.present().map_err(|e| e.to_string())?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Asynchronously generates the difference chart of two sequences: at each step where both have a term,
+/// the difference of the terms or the logarithm of their ratio, around a zero line (equal terms).
+/// When the trajectories merge at the same step, the identical tail is marked from its first step.
+async fn generate_difference_chart(
+    path: PathBuf, // Path to save the chart image
+    value1: Option<u64>, // First starting value
+    value2: Option<u64>, // Second starting value
+    sequence1: Vec<u64>, // First Collatz sequence
+    sequence2: Vec<u64>, // Second Collatz sequence
+    mode: difference::DifferenceMode, // Difference or log-ratio
+) -> Result<String, String> {
+    if sequence1.is_empty() || sequence2.is_empty() {
+        return Err("The difference chart needs two sequences".to_string());
+    }
+
+    let values = mode.apply(&sequence1, &sequence2);
+    let merged_at = difference::identical_from(&sequence1, &sequence2);
+
+    let root = BitMapBackend::new(&path, (800, 400)).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| e.to_string())?;
+
+    // The Y range always includes 0, so that the zero line (equal terms) is visible.
+    let y_max = values.iter().copied().fold(0.0, f64::max);
+    let y_min = values.iter().copied().fold(0.0, f64::min);
+    let margin = ((y_max - y_min) * 0.05).max(0.5);
+
+    let caption = format!(
+        "{} -- {} vs {}{}",
+        mode,
+        value1.map_or("?".to_string(), |v| v.to_string()),
+        value2.map_or("?".to_string(), |v| v.to_string()),
+        merged_at.map_or(String::new(), |step| format!(" (identical from step {})", step)),
+    );
+    let mut chart = ChartBuilder::on(&root)
+        .caption(caption, ("sans-serif", 20))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(70)
+        .build_cartesian_2d(0..values.len(), (y_min - margin)..(y_max + margin))
+        .map_err(|e| e.to_string())?;
+
+    chart.configure_mesh()
+        .x_desc("Step")
+        .y_desc(mode.axis_label())
+        .y_label_formatter(&|y| match mode {
+            // Differences of large terms are written compactly (1.23M), with their sign.
+            difference::DifferenceMode::Difference => {
+                let magnitude = chart_options::TickFormat::Compact.format(y.abs().round() as u64);
+                if *y <= -0.5 { format!("-{}", magnitude) } else { magnitude }
+            }
+            difference::DifferenceMode::LogRatio => format!("{:.1}", y),
+        })
+        .axis_desc_style(("sans-serif", 15))
+        .draw()
+        .map_err(|e| e.to_string())?;
+
+    chart
+        .draw_series(LineSeries::new([(0, 0.0), (values.len(), 0.0)], BLACK.mix(0.5)))
+        .map_err(|e| e.to_string())?;
+
+    // Above the zero line, the first term is the larger; below it, the second one.
+    chart
+        .draw_series(LineSeries::new(values.iter().enumerate().map(|(i, &v)| (i, v)), RGBColor(128, 0, 128)))
+        .map_err(|e| e.to_string())?;
+
+    // The identical tail lies on the zero line: highlight where it starts.
+    if let Some(step) = merged_at {
+        chart
+            .draw_series(std::iter::once(Circle::new((step, 0.0), 5, GREEN.filled())))
+            .map_err(|e| e.to_string())?;
+    }
+
+    root.present().map_err(|e| e.to_string())?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Asynchronously renders the bit matrix of one or two sequences.
+/// Each row of the image is a step and each column a bit of the term (black for 1, white for 0),
+/// most significant bit on the left. This makes the carry propagation of the 3n+1 step visible.
+/// When two sequences are given, their matrices are drawn side by side.
+async fn generate_bit_matrix_chart(
+    path: PathBuf, // Path to save the image
+    sequence1: Vec<u64>, // First Collatz sequence
+    sequence2: Vec<u64>, // Second Collatz sequence
+) -> Result<String, String> {
+    let matrices: Vec<representation::DigitMatrix> = [sequence1, sequence2]
+        .iter()
+        .filter(|sequence| !sequence.is_empty())
+        .map(|sequence| representation::digit_matrix(sequence, 2))
+        .collect();
+    if matrices.is_empty() {
+        return Err("No sequence to visualize".to_string());
     }
-}
 
-// ==========================================================================
-//                              Async Functions
-// ==========================================================================
+    // Pick the largest integer cell size that keeps the image reasonable: bits stay crisp squares.
+    let total_bits: u32 = matrices.iter().map(|m| m.width as u32).sum();
+    let max_rows = matrices.iter().map(|m| m.rows.len() as u32).max().unwrap_or(1);
+    let cell = (800 / total_bits.max(1))
+        .min(BIT_MATRIX_MAX_HEIGHT / max_rows.max(1))
+        .clamp(1, BIT_CELL_MAX_SIZE);
 
-/// Function to clean up temporary files
-/// This function checks if a file is a temporary file and deletes it.
-/// It takes a path as input and returns a Result indicating success or failure.
-/// The function is asynchronous, allowing it to be run in the background.
-async fn cleanup_temp_file(path: String) -> Result<(), String> {
-    // Checks if the file is a temporary file
-    // Temporary files are identified by their name pattern.
-    if path.contains("temp_collatz_") && path.ends_with(".png") {
-        // Attempt to delete the temporary file
-        match fs::remove_file(&path) {
-            Ok(_) => Ok(()), // File deleted successfully
-            Err(e) => Err(format!("Error deleting temporary file: {}", e)), // Error deleting the file
-        }
-    } else {
-        // If the file is not a temporary file, return Ok
-        Ok(())
-    }
-}
+    // Matrices are separated by a gray gap of one cell.
+    let gap = if matrices.len() > 1 { cell } else { 0 };
+    let width = total_bits * cell + gap;
+    let height = max_rows * cell;
+    let mut img = ::image::RgbImage::from_pixel(width, height, ::image::Rgb([255, 255, 255]));
 
-/// Function to clean up all temporary files
-/// This function checks the current directory for temporary files and deletes them.
-/// It returns a Result indicating success or failure.
-async fn cleanup_all_temp_files() -> Result<(), String> {
-    // Get the current directory
-    let current_dir = match std::env::current_dir() {
-        Ok(dir) => dir, // Current directory obtained successfully
-        Err(e) => return Err(format!("Error getting current directory: {}", e)), // Error getting the directory
-    };
-    
-    // Read the directory entries
-    let entries = match fs::read_dir(current_dir) {
-        Ok(entries) => entries,
-        Err(e) => return Err(format!("Error reading directory: {}", e)),
-    };
-    
-    // Iterate through the directory entries
-    for entry in entries { // For each entry in the directory
-        if let Ok(entry) = entry { // Check if the entry is valid
-            if let Ok(file_type) = entry.file_type() { // Check if the entry is a file
-                if file_type.is_file() {
-                    if let Ok(file_name) = entry.file_name().into_string() { // Get the file name
-                        if file_name.starts_with("temp_collatz_") && file_name.ends_with(".png") {
-                            if let Err(e) = fs::remove_file(entry.path()) { // Attempt to delete the file
-                                println!("Warning: Unable to delete temporary file {}: {}", file_name, e);
-                            }
-                        }
+    let mut x_offset = 0;
+    for (index, matrix) in matrices.iter().enumerate() {
+        if index > 0 {
+            // Paint the separating gap.
+            for x in x_offset..x_offset + gap {
+                for y in 0..height {
+                    img.put_pixel(x, y, ::image::Rgb([160, 160, 160]));
+                }
+            }
+            x_offset += gap;
+        }
+        for (row_index, row) in matrix.rows.iter().enumerate() {
+            for (bit_index, &bit) in row.iter().enumerate() {
+                if bit == 0 {
+                    continue;
+                }
+                let x0 = x_offset + bit_index as u32 * cell;
+                let y0 = row_index as u32 * cell;
+                for x in x0..x0 + cell {
+                    for y in y0..y0 + cell {
+                        img.put_pixel(x, y, ::image::Rgb([0, 0, 0]));
                     }
                 }
             }
         }
+        x_offset += matrix.width as u32 * cell;
     }
-    
-    Ok(()) // Return success if all temporary files were processed
+
+    img.save(&path).map_err(|e| e.to_string())?;
+
+    Ok(path.to_string_lossy().to_string())
 }
 
-/// Asynchronously generates a chart for the Collatz sequences.
-/// This function takes a path, two optional values, and two sequences.
-/// It generates a chart image and saves it to the specified path.
-async fn generate_chart(
+/// Asynchronously draws the merged trajectory graph as a tree hanging from 1 (see `trajectory_graph::layout`):
+/// the merge nodes are emphasized in red, the starting values in green, both labeled with their value.
+async fn generate_graph_chart(
     path: PathBuf, // Path to save the chart image
-    value1: Option<u64>, // First value for the Collatz sequence
-    value2: Option<u64>, // Second value for the Collatz sequence 
-    sequence1: Vec<u64>, // First Collatz sequence
-    sequence2: Vec<u64>, // Second Collatz sequence
+    graph: collatz::TrajectoryGraph, // Graph to draw
 ) -> Result<String, String> {
-    if sequence1.is_empty() && sequence2.is_empty() {
-        return Err("No sequence to visualize".to_string());
-    }
-    
-    // Create a temporary file for the chart
-    // The file will be created in the current directory with a unique name.
-    // The file will be overwritten if it already exists.
+    let positions = trajectory_graph::layout(&graph);
+    let width = positions.values().map(|position| position.x).fold(0.0, f64::max) + 1.0;
+    let height = positions.values().map(|position| position.level).max().unwrap_or(0) + 1;
+    let merges = graph.merge_nodes();
+
     let root = BitMapBackend::new(&path, (800, 400)).into_drawing_area();
     root.fill(&WHITE).map_err(|e| e.to_string())?;
-    
-    // Determine the maximum length of the sequences
-    // This is used to set the X-axis range of the chart.
-    // The maximum value is used to set the Y-axis range of the chart.
-    // The maximum value is determined by the highest value in both sequences.
-    // If both sequences are empty, return an error.
-    let max_len = sequence1.len().max(sequence2.len());
-    let max_value = sequence1.iter().copied().chain(sequence2.iter().copied())
-        .max().unwrap_or(1);
-    
-    // Create a chart builder
-    // This sets up the chart's appearance and layout.
-    // The chart is a Cartesian 2D chart with X and Y axes.
-    // The X-axis represents the step number, and the Y-axis represents the value.
-    // The chart is built using the `plotters` library.
-    // The chart is drawn on the drawing area created earlier.
-    let mut chart = ChartBuilder::on(&root) // Create a new chart builder
-        .caption( // Set the chart caption, a string that describes the chart.
-            format!(
-                "Collatz Conjecture {}{}",
-                value1.map_or(String::new(), |v| format!("-- {}", v)), // Handle missing value1
-                value2.map_or(String::new(), |v| format!(" and {}", v)), // Append value2 if present
-            ),
-            ("sans-serif", 20), // Font and size for caption
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption(
+            format!("Merged trajectories of {} starting values", graph.starts.len()),
+            ("sans-serif", 20),
         )
-        .margin(10) // Margin around the chart
-        .x_label_area_size(30) // Space reserved for X-axis labels
-        .y_label_area_size(60) // Space reserved for Y-axis labels (adjust if numbers get large)
-        // Build the coordinate system (Cartesian 2D).
-        // X-axis range: 0 to max_len (number of steps).
-        // Y-axis range: 0 to slightly above max_value.
-        .build_cartesian_2d(0..max_len, 0..(max_value as u64 + 1))
-        .map_err(|e| e.to_string())?; // Handle errors during chart building
-    
-    // Configure the chart's mesh (grid lines and labels).
-    // The mesh is the grid that appears behind the chart.
-    // The X-axis is labeled with step numbers, and the Y-axis with values.
-    // The axis description style is set to a sans-serif font with size 15.
+        .margin(20)
+        .build_cartesian_2d(-0.5..width - 0.5, -0.5..height as f64)
+        .map_err(|e| e.to_string())?;
+
+    let point = |node: &u64| {
+        let position = positions[node];
+        (position.x, position.level as f64)
+    };
+
+    // The edges, from each term to the next one (down towards 1).
+    chart
+        .draw_series(graph.successors.iter().map(|(term, next)| {
+            PathElement::new(vec![point(term), point(next)], BLACK.mix(0.4))
+        }))
+        .map_err(|e| e.to_string())?;
+    chart
+        .draw_series(graph.nodes.iter().map(|node| Circle::new(point(node), 2, BLUE.filled())))
+        .map_err(|e| e.to_string())?;
+
+    // The merge nodes and the starting values, labeled.
+    let labeled = |node: u64, color: RGBColor| {
+        EmptyElement::at(point(&node))
+            + Circle::new((0, 0), 5, color.filled())
+            + Text::new(node.to_string(), (7, -6), ("sans-serif", 13).into_font().color(&color))
+    };
+    chart
+        .draw_series(merges.iter().map(|&(node, _)| labeled(node, RED)))
+        .map_err(|e| e.to_string())?;
+    chart
+        .draw_series(graph.starts.iter().map(|&start| labeled(start, GREEN)))
+        .map_err(|e| e.to_string())?;
+
+    root.present().map_err(|e| e.to_string())?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Asynchronously renders the escape-time fractal over a window of the complex plane.
+/// Every pixel of the plotting area is one point of the window, colored by the number of iterations
+/// it takes to escape (black if it stays bounded), with the real and imaginary axes around it.
+async fn generate_fractal_chart(
+    path: PathBuf, // Path to save the chart image
+    window: fractal::FractalWindow, // Region of the complex plane to draw
+) -> Result<String, String> {
+    let root = BitMapBackend::new(&path, (800, 400)).into_drawing_area();
+    root.fill(&WHITE).map_err(|e| e.to_string())?;
+
+    // The ranges follow the shape of the plotting area, so that the pixels are square.
+    let build_chart = |re_range: (f64, f64), im_range: (f64, f64)| {
+        ChartBuilder::on(&root)
+            .caption("Collatz escape-time fractal", ("sans-serif", 20))
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(60)
+            .build_cartesian_2d(re_range.0..re_range.1, im_range.0..im_range.1)
+    };
+    let (columns, rows) = build_chart((0.0, 1.0), (0.0, 1.0)).map_err(|e| e.to_string())?.plotting_area().dim_in_pixel();
+    let (re_range, im_range) = window.ranges(columns, rows);
+    let mut chart = build_chart(re_range, im_range).map_err(|e| e.to_string())?;
+
     chart.configure_mesh()
-        .x_desc("Step")
-        .y_desc("Value")
+        .disable_mesh()
+        .x_desc("Re(z)")
+        .y_desc("Im(z)")
         .axis_desc_style(("sans-serif", 15))
         .draw()
         .map_err(|e| e.to_string())?;
-    
-    // Draw the first sequence
-    // The first sequence is drawn in red.
-    // The sequence is represented as a line on the chart.
-    // Each point on the line corresponds to a step in the sequence.
-    if !sequence1.is_empty() {
-        chart
-            .draw_series(LineSeries::new( // Draw the first sequence
-                sequence1.iter().enumerate().map(|(i, &v)| (i, v)), // Enumerate the sequence
-                // Convert the sequence to a series of points (x, y) for plotting.
-                &RED, // Color of the line (red)
-            ))
-            .map_err(|e| e.to_string())? // Handle errors during drawing
-            .label(format!("Sequence {}", value1.unwrap_or(0))) // Label for the first sequence
-            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &RED)); // Legend entry for the first sequence
-    }
-    
-    // Draw the second sequence
-    if !sequence2.is_empty() {
-        chart
-            .draw_series(LineSeries::new(
-                sequence2.iter().enumerate().map(|(i, &v)| (i, v)),
-                &BLUE,
-            ))
-            .map_err(|e| e.to_string())?
-            .label(format!("Sequence {}", value2.unwrap_or(0)))
-            .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &BLUE));
+
+    let times = fractal::render(&window, columns, rows, fractal::MAX_ITERATIONS);
+    let plotting_area = chart.plotting_area();
+    let (re_step, im_step) = ((re_range.1 - re_range.0) / columns as f64, (im_range.1 - im_range.0) / rows as f64);
+    for (index, time) in times.iter().enumerate() {
+        let (column, row) = (index as u32 % columns, index as u32 / columns);
+        let color = match time {
+            // Quick escapes are bright, slow ones dark: the boundary of the bounded set stands out.
+            Some(n) => HSLColor(
+                0.66 - 0.66 * (*n as f64 / fractal::MAX_ITERATIONS as f64).sqrt(),
+                1.0,
+                0.5 - 0.4 * *n as f64 / fractal::MAX_ITERATIONS as f64,
+            )
+            .to_rgba(),
+            None => BLACK.to_rgba(),
+        };
+        let point = (
+            re_range.0 + (column as f64 + 0.5) * re_step,
+            im_range.1 - (row as f64 + 0.5) * im_step,
+        );
+        plotting_area.draw_pixel(point, &color).map_err(|e| e.to_string())?;
     }
-    
-    // Configure the legend
-    // The legend is a small box that describes the colors used in the chart.
-    // It shows which color corresponds to which sequence.
-    // The legend is placed at the top right corner of the chart.
-    chart
-        .configure_series_labels()
-        .background_style(&WHITE.mix(0.8))
-        .border_style(&BLACK)
-        .draw()
-        .map_err(|e| e.to_string())?;
-    
-    // Ensure all drawing operations are finalized and written to the backend (the file).
-    // This is important to ensure the chart is saved correctly.
-    // The `present` method finalizes the drawing and writes the image to the file.
-    // If this fails, it means there was an error writing the file.
+
     root.present().map_err(|e| e.to_string())?;
-    
-    // Return the path of the generated chart file.
-    // The path is returned as a String.
-    // This path can be used to access the file later (e.g., for saving or displaying).
-    // The path is converted to a string using `to_string_lossy` to handle any invalid UTF-8 characters.
-    // This is a safe way to convert the path to a string.
+
     Ok(path.to_string_lossy().to_string())
 }
 
+/// Asynchronously verifies the next chunk of a range verification, then writes its checkpoint.
+/// Once the whole range is verified, the checkpoint is deleted: there is nothing left to resume.
+async fn run_verification_chunk(
+    checkpoint: verification::Checkpoint, // Progress so far
+    path: PathBuf, // Location of the checkpoint file
+) -> Result<verification::Checkpoint, String> {
+    let checkpoint = verification::verify_chunk(checkpoint, VERIFICATION_CHUNK);
+
+    if checkpoint.is_complete() {
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(format!("Unable to delete the checkpoint: {}", e));
+            }
+            _ => {}
+        }
+    } else {
+        verification::save_checkpoint(&path, &checkpoint)
+            .map_err(|e| format!("Unable to write the checkpoint: {}", e))?;
+    }
+
+    Ok(checkpoint)
+}
+
 /// Asynchronously saves the chart by copying the temporary file to a permanent location.
 /// This function takes the temporary file path and the desired target path.
 /// It returns a Result indicating success or failure.
@@ -822,12 +8248,392 @@ async fn generate_chart(
 async fn save_chart(
     temp_path: String, // Path of the temporary chart file
     target_path: String, // Desired permanent filename (not full path yet)
+    format: image_format::ImageFormat, // Format of the saved file
+    quality: u8, // Quality of the lossy formats
+    metadata: Vec<(String, String)>, // Description of the run, written into PNG files
 ) -> Result<(), String> {
-    // Attempt to copy the file from the temporary path to the target path.
-    fs::copy(&temp_path, &target_path)
-        .map_err(|e| format!("Error copying chart file: {}", e))?;
-    
-    Ok(()) // If copy succeeded, return Ok.
+    // Copy the file from the temporary path to the target path, converted to the format.
+    image_format::save_as(temp_path.as_ref(), target_path.as_ref(), format, quality, &metadata)
+}
+
+/// Asynchronously appends a run to the experiment log (see `experiment_log::append`).
+async fn append_experiment(path: PathBuf, record: experiment_log::RunRecord) -> Result<(), String> {
+    experiment_log::append(&path, &record)
+}
+
+/// Asynchronously adds the results of a run to the results database (see `results_db::store`).
+async fn store_results(path: PathBuf, results: Vec<results_db::StoredResult>) -> Result<usize, String> {
+    results_db::store(&path, results)
+}
+
+/// Asynchronously reads the results database (see `results_db::load`).
+async fn load_results_db(path: PathBuf) -> Result<results_db::ResultsDb, String> {
+    results_db::load(&path)
+}
+
+/// Asynchronously reads the latest runs of the experiment log (see `experiment_log::read_latest`).
+async fn load_history(path: PathBuf, limit: usize) -> Result<Vec<experiment_log::RunRecord>, String> {
+    experiment_log::read_latest(&path, limit)
+}
+
+/// Describes a past run on one line of the history panel, e.g.
+/// "2026-10-15 14:03  Sequences  27: 111 steps, peak 9232 | 31: 106 steps, peak 9232".
+fn describe_run(run: &experiment_log::RunRecord) -> String {
+    // "2026-10-15T14:03:12+02:00" -> "2026-10-15 14:03"
+    let time = run.timestamp.get(..16).unwrap_or(&run.timestamp).replacen('T', " ", 1);
+    let kind = ChartKind::from_slug(&run.chart);
+    let kind_name = kind.map_or(run.chart.clone(), |kind| kind.to_string());
+    let details = if kind.is_some_and(ChartKind::uses_range) {
+        format!("{} to {}", run.inputs[0], run.inputs[1])
+    } else if run.sequences.iter().any(Option::is_some) {
+        run.sequences
+            .iter()
+            .flatten()
+            .map(|s| format!("{}: {} steps, peak {}", s.value, s.flight_time, s.peak))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    } else {
+        run.inputs.iter().map(|input| input.as_str()).filter(|input| !input.is_empty()).collect::<Vec<_>>().join(", ")
+    };
+    format!("{}  {}  {}", time, kind_name, details)
+}
+
+/// Asynchronously copies a generated chart into the auto-save folder (created if needed), in the chosen format.
+/// Charts generated within the same second get a numbered name instead of replacing each other.
+///
+/// # Returns
+/// * `Ok(String)` - The path of the copy.
+/// * `Err(String)` - If the folder cannot be created or the file cannot be copied.
+async fn auto_save_chart(
+    temp_path: String,
+    folder: PathBuf,
+    filename: String,
+    format: image_format::ImageFormat,
+    quality: u8,
+    metadata: Vec<(String, String)>,
+) -> Result<String, String> {
+    if !folder.as_os_str().is_empty() {
+        fs::create_dir_all(&folder).map_err(|e| format!("Error creating {}: {}", folder.display(), e))?;
+    }
+    let (stem, extension) = filename.rsplit_once('.').unwrap_or((&filename, "png"));
+    let mut target = folder.join(&filename);
+    let mut copy = 2;
+    while target.exists() {
+        target = folder.join(format!("{}_{}.{}", stem, copy, extension));
+        copy += 1;
+    }
+    image_format::save_as(temp_path.as_ref(), &target, format, quality, &metadata)?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Asynchronously reads the run described by the metadata of a saved chart (see `chart_metadata`).
+///
+/// # Returns
+/// * `Ok((String, DeepLink))` - A description of the run, e.g. "collatz_20261015_140312.png shows
+///   Sequences of 27, 31 (Collatz Conjecture Visualizer 0.1.0)", and its link.
+/// * `Err(String)` - If the file cannot be read, or is not a chart saved with its run.
+async fn read_chart_run(path: String) -> Result<(String, deep_link::DeepLink), String> {
+    let bytes = fs::read(&path).map_err(|e| format!("Error reading {}: {}", path, e))?;
+    let metadata = image_format::read_png_text(&bytes)?;
+    let entry = |keyword: &str| metadata.iter().find(|(k, _)| k == keyword).map(|(_, text)| text.as_str());
+    let link = entry("Link").ok_or_else(|| format!("{} was not saved with the description of its run", path))?;
+    let link = deep_link::parse(link)?;
+    let name = PathBuf::from(&path).file_name().map_or(path.clone(), |name| name.to_string_lossy().to_string());
+    let mut description = format!(
+        "{} shows {} of {}",
+        name,
+        entry("Chart").unwrap_or("a chart"),
+        entry("Starting values").unwrap_or("?"),
+    );
+    if let Some(software) = entry("Software") {
+        description.push_str(&format!(" ({})", software));
+    }
+    Ok((description, link))
+}
+
+/// Asynchronously reads a sequence file (CSV, JSON or binary, see `sequence_file::parse_sequence`).
+///
+/// # Returns
+/// * `Ok((String, Vec<u64>))` - The path of the file and the terms it contains.
+/// * `Err(String)` - If the file cannot be read or is not a sequence file.
+async fn import_sequence(path: String) -> Result<(String, Vec<u64>), String> {
+    let bytes = fs::read(&path).map_err(|e| format!("Error reading {}: {}", path, e))?;
+    let sequence = sequence_file::parse_sequence(&bytes)?;
+    Ok((path, sequence))
+}
+
+/// Asynchronously reads a work plan (see `work_plan::parse_plan`).
+async fn load_plan(path: String) -> Result<Vec<work_plan::PlanJob>, String> {
+    let text = fs::read_to_string(&path).map_err(|e| format!("Error reading {}: {}", path, e))?;
+    work_plan::parse_plan(&text)
+}
+
+/// Runs a job of a work plan: computes its sequence or its range, then writes its outputs
+/// (statistics as text, chart, data as CSV) to the folder of the plan, named after the job.
+///
+/// # Arguments
+/// * `dir` - Folder of the outputs of the plan, created if needed.
+/// * `index` - Number of the job in the plan (from 0).
+/// * `job` - The job.
+/// * `cancel` - Flag set to stop the job: it is checked between the outputs, and between the chunks of a range.
+/// * `progress` - Number of values of the range scanned so far, updated after each chunk.
+///
+/// # Returns
+/// * `Ok(String)` - A line describing the result, e.g. "27: flight time 111, peak 9232".
+/// * `Err(String)` - If the job cannot be computed, an output cannot be written, or the job was cancelled.
+async fn run_plan_job(
+    dir: PathBuf,
+    index: usize,
+    job: work_plan::PlanJob,
+    cancel: Arc<AtomicBool>,
+    progress: Arc<AtomicU64>,
+) -> Result<String, String> {
+    let check_cancel = || if cancel.load(Ordering::Relaxed) { Err("Cancelled".to_string()) } else { Ok(()) };
+    fs::create_dir_all(&dir).map_err(|e| format!("Error creating {}: {}", dir.display(), e))?;
+    let stem = dir.join(job.file_stem(index));
+    let write = |extension: &str, content: String| {
+        let path = stem.with_extension(extension);
+        fs::write(&path, content).map_err(|e| format!("Error writing {}: {}", path.display(), e))
+    };
+    match job.target {
+        work_plan::JobTarget::Value(value) => {
+            let sequence = collatz::generate_sequence(value);
+            let stats = collatz::calculate_stats(&sequence);
+            for output in &job.outputs {
+                check_cancel()?;
+                match output {
+                    work_plan::JobOutput::Stats => write("txt", work_plan::value_stats(&sequence))?,
+                    work_plan::JobOutput::Chart => {
+                        let series = vec![(Some(value), sequence.clone())];
+                        let options = chart_options::ChartOptions::default();
+                        generate_chart(stem.with_extension("png"), series, transform::TermTransform::Value, options).await?;
+                    }
+                    work_plan::JobOutput::Csv => {
+                        let steps = sequence.iter().enumerate().map(|(step, &term)| (step as u64, term));
+                        write("csv", work_plan::pairs_csv("step,value", steps))?
+                    }
+                }
+            }
+            Ok(format!("{}: flight time {}, peak {}", value, stats.length.saturating_sub(1), stats.max_value))
+        }
+        work_plan::JobTarget::Range(start, end) => {
+            if end - start >= MAX_DENSITY_RANGE {
+                return Err(format!("{}: ranges of a plan are limited to {} values", job.target, MAX_DENSITY_RANGE));
+            }
+            // The CSV file is written as the range is scanned: only the counts of the range are kept.
+            let csv_path = job.outputs.contains(&work_plan::JobOutput::Csv).then(|| stem.with_extension("csv"));
+            let tally = scan_plan_range(start, end, csv_path.as_deref(), &cancel, &progress)?;
+            let summary = tally.summary();
+            for output in &job.outputs {
+                check_cancel()?;
+                match output {
+                    work_plan::JobOutput::Stats => write("txt", work_plan::range_stats(&summary))?,
+                    work_plan::JobOutput::Chart => {
+                        draw_density_chart(&stem.with_extension("png"), ScanMeasure::TotalStoppingTime, &tally)?;
+                    }
+                    work_plan::JobOutput::Csv => {} // Written by the scan
+                }
+            }
+            Ok(format!("{}: longest trajectory {} steps (starting at {})", job.target, summary.max_time, summary.max_time_start))
+        }
+    }
+}
+
+/// Scans the range of a plan job `PLAN_CHUNK_SIZE` values at a time, counting the stopping times
+/// for the density chart and the statistics, and writing them to a CSV file chunk by chunk if asked.
+///
+/// # Arguments
+/// * `csv_path` - Where to write the (starting value, total stopping time) pairs, if anywhere.
+/// * `cancel` - Flag set to stop the job: it is checked between the chunks, and a half-written CSV file is deleted.
+/// * `progress` - Number of values scanned so far, updated after each chunk.
+///
+/// # Returns
+/// * `Ok(StoppingTimeTally)` - The counts of the range.
+/// * `Err(String)` - If the CSV file cannot be written, or the job was cancelled.
+fn scan_plan_range(
+    start: u64,
+    end: u64,
+    csv_path: Option<&std::path::Path>,
+    cancel: &AtomicBool,
+    progress: &AtomicU64,
+) -> Result<collatz::StoppingTimeTally, String> {
+    use std::io::Write;
+
+    let mut tally = collatz::StoppingTimeTally::new(start, end, DENSITY_X_BINS);
+    let mut csv = match csv_path {
+        Some(path) => {
+            let file = fs::File::create(path).map_err(|e| format!("Error writing {}: {}", path.display(), e))?;
+            Some((path, std::io::BufWriter::new(file)))
+        }
+        None => None,
+    };
+    let mut scan = || -> Result<(), String> {
+        if let Some((path, writer)) = &mut csv {
+            writer.write_all(b"start,total_stopping_time\n").map_err(|e| format!("Error writing {}: {}", path.display(), e))?;
+        }
+        let mut from = start;
+        loop {
+            if cancel.load(Ordering::Relaxed) {
+                return Err("Cancelled".to_string());
+            }
+            let to = from.saturating_add(PLAN_CHUNK_SIZE - 1).min(end);
+            let times = collatz::stopping_times_in_range(from, to);
+            if let Some((path, writer)) = &mut csv {
+                let rows = work_plan::csv_rows(times.iter().copied());
+                writer.write_all(rows.as_bytes()).map_err(|e| format!("Error writing {}: {}", path.display(), e))?;
+            }
+            times.into_iter().for_each(|(n, t)| tally.add(n, t));
+            progress.store(to - start + 1, Ordering::Relaxed);
+            if to == end {
+                break;
+            }
+            from = to + 1;
+        }
+        if let Some((path, writer)) = &mut csv {
+            writer.flush().map_err(|e| format!("Error writing {}: {}", path.display(), e))?;
+        }
+        Ok(())
+    };
+    match scan() {
+        Ok(()) => Ok(tally),
+        Err(e) => {
+            drop(csv); // The file is closed before it is deleted
+            if let Some(path) = csv_path {
+                let _ = fs::remove_file(path);
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Asynchronously shows a desktop notification (see `notification::notify`).
+async fn send_notification(title: &'static str, body: String) -> Result<(), String> {
+    notification::notify(title, &body)
+}
+
+/// Opens a file with the default application of the system (`start` on Windows, `open` on macOS, `xdg-open` elsewhere).
+async fn open_with_default_app(path: PathBuf) -> Result<(), String> {
+    if !path.exists() {
+        return Err(format!("{} does not exist", path.display()));
+    }
+    let status = if cfg!(target_os = "windows") {
+        std::process::Command::new("cmd").arg("/C").arg("start").arg("").arg(&path).status()
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open").arg(&path).status()
+    } else {
+        std::process::Command::new("xdg-open").arg(&path).status()
+    }
+    .map_err(|e| format!("Unable to open {}: {}", path.display(), e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Unable to open {} ({})", path.display(), status))
+    }
+}
+
+/// Asynchronously writes sequences as binary files (see `sequence_file` for the format).
+///
+/// # Arguments
+/// * `files` - Path and terms of each file to write.
+/// * `encoding` - How the terms are stored.
+async fn export_binary(files: Vec<(PathBuf, Vec<u64>)>, encoding: sequence_file::BinaryEncoding) -> Result<(), String> {
+    for (path, sequence) in files {
+        fs::write(&path, sequence_file::encode_binary(&sequence, encoding))
+            .map_err(|e| format!("Error writing {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Asynchronously writes the marker of the guided tour (see `tutorial::mark_seen`).
+async fn mark_tutorial_seen(path: PathBuf) -> Result<(), String> {
+    tutorial::mark_seen(&path).map_err(|e| format!("Error writing {}: {}", path.display(), e))
+}
+
+/// Asynchronously writes the settings file (see `settings::save`).
+async fn save_settings(path: PathBuf, user_settings: settings::UserSettings) -> Result<(), String> {
+    settings::save(&path, &user_settings).map_err(|e| format!("Error writing {}: {}", path.display(), e))
+}
+
+/// Asynchronously writes a text (e.g. the step-by-step explanation) to a file.
+async fn write_text_file(path: PathBuf, content: String) -> Result<(), String> {
+    fs::write(&path, content).map_err(|e| format!("Error writing {}: {}", path.display(), e))
+}
+
+/// Asynchronously composes a screenshot of the full view and writes it as a PNG file.
+/// The page is drawn off-screen: a title, the inputs, the current chart (scaled down if wider
+/// than the page) and the statistics, so nothing is cut off by the window size or the scrollbars.
+///
+/// # Arguments
+/// * `path` - File to write the screenshot to.
+/// * `header` - Lines describing the inputs (chart kind, values or range, options).
+/// * `chart_path` - Path of the current chart image, if any.
+/// * `statistics` - Text of the statistics section.
+async fn export_view(
+    path: PathBuf,
+    header: Vec<String>,
+    chart_path: Option<String>,
+    statistics: String,
+) -> Result<(), String> {
+    const PAGE_WIDTH: u32 = 1000;
+    const MARGIN: u32 = 20;
+    const TITLE_HEIGHT: u32 = 36;
+    const LINE_HEIGHT: u32 = 20;
+
+    // Load the chart first: its size decides the height of the page.
+    let chart = match chart_path {
+        Some(chart_path) => {
+            let chart = ::image::open(&chart_path)
+                .map_err(|e| format!("Error reading the chart: {}", e))?
+                .to_rgb8();
+            let max_width = PAGE_WIDTH - 2 * MARGIN;
+            if chart.width() > max_width {
+                let height = (chart.height() as u64 * max_width as u64 / chart.width() as u64).max(1) as u32;
+                Some(::image::imageops::resize(&chart, max_width, height, ::image::imageops::FilterType::Triangle))
+            } else {
+                Some(chart)
+            }
+        }
+        None => None,
+    };
+
+    let mut lines = vec![String::new(), "Statistics:".to_string()];
+    lines.extend(statistics.lines().map(|line| line.to_string()));
+    let header_height = TITLE_HEIGHT + header.len() as u32 * LINE_HEIGHT + MARGIN;
+    let chart_height = chart.as_ref().map_or(0, |chart| chart.height());
+    let page_height = MARGIN + header_height + chart_height + lines.len() as u32 * LINE_HEIGHT + MARGIN;
+
+    // Draw the text of the page, leaving room for the chart.
+    let mut buffer = vec![0u8; (PAGE_WIDTH * page_height * 3) as usize];
+    {
+        let root = BitMapBackend::with_buffer(&mut buffer, (PAGE_WIDTH, page_height)).into_drawing_area();
+        root.fill(&WHITE).map_err(|e| e.to_string())?;
+
+        let x = MARGIN as i32;
+        let mut y = MARGIN as i32;
+        root.draw(&Text::new("Collatz Conjecture Visualizer", (x, y), ("sans-serif", 24).into_font()))
+            .map_err(|e| e.to_string())?;
+        y += TITLE_HEIGHT as i32;
+        for line in &header {
+            root.draw(&Text::new(line.as_str(), (x, y), ("sans-serif", 15).into_font()))
+                .map_err(|e| e.to_string())?;
+            y += LINE_HEIGHT as i32;
+        }
+        y += (MARGIN + chart_height) as i32;
+        for line in &lines {
+            root.draw(&Text::new(line.as_str(), (x, y), ("monospace", 14).into_font()))
+                .map_err(|e| e.to_string())?;
+            y += LINE_HEIGHT as i32;
+        }
+        root.present().map_err(|e| e.to_string())?;
+    }
+
+    // Paste the chart below the inputs, then write the page.
+    let mut page = ::image::RgbImage::from_raw(PAGE_WIDTH, page_height, buffer)
+        .ok_or_else(|| "Invalid screenshot buffer".to_string())?;
+    if let Some(chart) = &chart {
+        ::image::imageops::overlay(&mut page, chart, MARGIN as i64, (MARGIN + header_height) as i64);
+    }
+    page.save(&path).map_err(|e| format!("Error writing the screenshot: {}", e))
 }
 
 /// Asynchronously formats the sequence data and copies it to the system clipboard.
@@ -842,6 +8648,7 @@ async fn copy_sequences_to_clipboard(
     value2: Option<u64>,
     sequence1: Vec<u64>,
     sequence2: Vec<u64>,
+    names: [String; 2], // Custom names of the sequences (blank: none)
 ) -> Result<(), String> {
     // If both sequences are empty, return an error.
     if sequence1.is_empty() && sequence2.is_empty() {
@@ -859,7 +8666,7 @@ async fn copy_sequences_to_clipboard(
     if !sequence1.is_empty() {
         // Add a header indicating which sequence it is.
         if let Some(value) = value1 {
-            clipboard_content.push_str(&format!("Sequence for {}:\n", value));
+            clipboard_content.push_str(&format!("Sequence for {}:\n", chart_options::describe_value(value, &names[0])));
         } else {
             clipboard_content.push_str("Sequence 1:\n");
         }
@@ -878,7 +8685,7 @@ async fn copy_sequences_to_clipboard(
     // Add the second sequence data if it exists.
     if !sequence2.is_empty() {
         if let Some(value) = value2 {
-            clipboard_content.push_str(&format!("Sequence for {}:\n", value));
+            clipboard_content.push_str(&format!("Sequence for {}:\n", chart_options::describe_value(value, &names[1])));
         } else {
             clipboard_content.push_str("Sequence 2:\n");
         }
@@ -888,6 +8695,45 @@ async fn copy_sequences_to_clipboard(
         }
     }
     
+    copy_text_to_clipboard(clipboard_content).await
+}
+
+/// Describes a cycle on one line: name (if known), entry point, length and members.
+fn describe_cycle(map: collatz::CollatzMap, cycle: &collatz::CycleInfo) -> String {
+    let members: Vec<String> = cycle.members.iter().map(|v| v.to_string()).collect();
+    let name = collatz::identify_cycle(map, cycle).map_or("unknown cycle", |known| known.name);
+    format!(
+        "{} entered at step {} (value {}), length {}: {}",
+        name,
+        cycle.entry_index,
+        cycle.entry_value(),
+        cycle.length,
+        members.join(" -> "),
+    )
+}
+
+/// Formats the map comparison trajectories as text, one block per map.
+fn format_comparison(trajectories: &[collatz::VariantTrajectory]) -> String {
+    let mut content = String::new();
+    for trajectory in trajectories {
+        content.push_str(&format!(
+            "Sequence under {} ({}):\n",
+            trajectory.map.name(),
+            trajectory.termination.describe(),
+        ));
+        if let Some(cycle) = &trajectory.cycle {
+            content.push_str(&format!("{}\n", describe_cycle(trajectory.map, cycle)));
+        }
+        for (i, value) in trajectory.sequence.iter().enumerate() {
+            content.push_str(&format!("Step {}: {}\n", i, value));
+        }
+        content.push('\n');
+    }
+    content
+}
+
+/// Asynchronously places the given text on the system clipboard.
+async fn copy_text_to_clipboard(clipboard_content: String) -> Result<(), String> {
     // Create a clipboard context to access the system clipboard.
     // The `clipboard` crate is used to interact with the clipboard.
     // The context is created using `ClipboardProvider::new()`.
@@ -903,21 +8749,89 @@ async fn copy_sequences_to_clipboard(
     Ok(()) // If everything succeeded, return Ok.
 }
 
+/// Asynchronously places a chart image (PNG file) on the system clipboard, as an image/png selection.
+/// Only X11 desktops are supported (the clipboard crate handles text alone).
+#[cfg(all(unix, not(any(target_os = "macos", target_os = "android"))))]
+async fn copy_image_to_clipboard(path: String) -> Result<(), String> {
+    // Each clipboard opens an X11 connection and starts the thread serving its selections, which never ends:
+    // a single one, created on the first copy, serves every image.
+    static CLIPBOARD: std::sync::OnceLock<x11_clipboard::Clipboard> = std::sync::OnceLock::new();
+
+    let png = std::fs::read(&path).map_err(|e| format!("Error reading the chart: {}", e))?;
+    let clipboard = match CLIPBOARD.get() {
+        Some(clipboard) => clipboard,
+        None => {
+            let clipboard = x11_clipboard::Clipboard::new().map_err(|e| format!("Clipboard initialization error: {}", e))?;
+            CLIPBOARD.get_or_init(|| clipboard)
+        }
+    };
+    let target = clipboard.setter.get_atom("image/png").map_err(|e| format!("Clipboard initialization error: {}", e))?;
+    clipboard
+        .store(clipboard.setter.atoms.clipboard, target, png)
+        .map_err(|e| format!("Error while copying: {}", e))
+}
+
+/// Asynchronously places a chart image on the system clipboard: not supported on this platform.
+#[cfg(not(all(unix, not(any(target_os = "macos", target_os = "android")))))]
+async fn copy_image_to_clipboard(_path: String) -> Result<(), String> {
+    Err("copying images is only supported on X11 desktops; use \"Save image\" instead".to_string())
+}
+
 // ==========================================================================
 //                              Main Function
 // ==========================================================================
 
+/// Runs the built-in benchmarks (`collatz_app bench`) and prints their table.
+/// The chart column renders a single-sequence chart to a temporary file, like "Visualize" does.
+fn run_benchmarks() {
+    let path = std::env::temp_dir().join("temp_collatz_bench.png");
+    bench::run(&|start| {
+        let sequence = collatz::generate_sequence(start);
+        futures::executor::block_on(generate_chart(
+            path.clone(),
+            vec![(Some(start), sequence)],
+            transform::TermTransform::Value,
+            chart_options::ChartOptions::default(),
+        ))
+        .map(|_| ())
+    });
+    let _ = fs::remove_file(&path);
+}
+
 fn main() -> iced::Result {
-    // Attempt to clean up any leftover temporary files.
-    // This is done to ensure that the application starts with a clean slate.
-    // The cleanup function is called asynchronously, but we use `block_on` to wait for it to finish.
-    // This is necessary because the main function cannot be async.
-    let _ = futures::executor::block_on(cleanup_all_temp_files());
-    
+    // `collatz_app bench` runs the benchmarks in the terminal instead of opening the window.
+    if std::env::args().nth(1).as_deref() == Some("bench") {
+        run_benchmarks();
+        return Ok(());
+    }
+
+    // `collatz_app register-links` makes the application the handler of the `collatz://` links.
+    if std::env::args().nth(1).as_deref() == Some("register-links") {
+        match std::env::current_exe().map_err(|e| e.to_string()).and_then(|exe| deep_link::register(&exe)) {
+            Ok(report) => println!("{}", report),
+            Err(error) => eprintln!("Could not register the links: {}", error),
+        }
+        return Ok(());
+    }
+
+    // A clicked `collatz://` link starts the application with the link as its argument.
+    let link = std::env::args()
+        .nth(1)
+        .filter(|arg| deep_link::is_deep_link(arg))
+        .map(|arg| deep_link::parse(&arg));
+
     // Run the application with the default settings.
     // The `CollatzApp` is the main application struct that implements the Iced framework.
     // The `run` method starts the application and enters the event loop.
-    // The `Settings::default()` provides the default settings for the application.
-    // The application will run until it is closed by the user.
-    CollatzApp::run(Settings::default())
+    // The `Settings::default()` provides the default settings for the application,
+    // with the link (if any) as the flags given to `CollatzApp::new`.
+    // The application will run until it is closed by the user: closing the window sends
+    // `Message::CloseRequested`, which deletes the temporary chart before closing it.
+    let result = CollatzApp::run(Settings { flags: link, exit_on_close_request: false, ..Settings::default() });
+
+    // Attempt to clean up the temporary files this process left behind (e.g. a print file still in use).
+    // The cleanup function is called asynchronously, but we use `block_on` to wait for it to finish.
+    // This is necessary because the main function cannot be async.
+    let _ = futures::executor::block_on(cleanup_all_temp_files());
+    result
 }
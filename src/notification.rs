@@ -0,0 +1,85 @@
+// File notification.rs
+// This module contains the desktop notifications, shown when a long background job (a range scan,
+// a verification, a work plan) finishes while the window is in the background.
+// They are sent with the notifier of the system: `notify-send` on Linux and the BSDs,
+// AppleScript on macOS, and a tray balloon from PowerShell on Windows.
+
+use std::process::Command;
+
+/// Writes a text as an AppleScript (or PowerShell) string literal, escaping the quotes and backslashes.
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Returns the program and the arguments showing a notification on this system.
+///
+/// # Arguments
+/// * `title` - The title of the notification.
+/// * `body` - Its text.
+pub fn notify_command(title: &str, body: &str) -> (&'static str, Vec<String>) {
+    if cfg!(target_os = "macos") {
+        let script = format!("display notification {} with title {}", quoted(body), quoted(title));
+        ("osascript", vec!["-e".to_string(), script])
+    } else if cfg!(target_os = "windows") {
+        // PowerShell strings are single-quoted: a quote is written twice.
+        let single = |text: &str| format!("'{}'", text.replace('\'', "''"));
+        let script = format!(
+            "Add-Type -AssemblyName System.Windows.Forms; \
+             $icon = New-Object System.Windows.Forms.NotifyIcon; \
+             $icon.Icon = [System.Drawing.SystemIcons]::Information; $icon.Visible = $true; \
+             $icon.ShowBalloonTip(10000, {}, {}, 'Info'); Start-Sleep -Seconds 10; $icon.Dispose()",
+            single(title),
+            single(body),
+        );
+        ("powershell", vec!["-NoProfile".to_string(), "-Command".to_string(), script])
+    } else {
+        ("notify-send", vec!["--app-name=Collatz Visualizer".to_string(), title.to_string(), body.to_string()])
+    }
+}
+
+/// Shows a notification, without waiting for it to be dismissed.
+/// The notifier is waited for on a thread of its own, so that it does not stay a zombie process once done.
+///
+/// # Returns
+/// * `Ok(())` - The notifier was started.
+/// * `Err(String)` - If the notifier of the system cannot be started (e.g. `notify-send` is not installed).
+pub fn notify(title: &str, body: &str) -> Result<(), String> {
+    let (program, args) = notify_command(title, body);
+    let mut command = Command::new(program);
+    command.args(&args);
+    // PowerShell is a console program: without this flag, a console window would flash with the notification.
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Unable to show a notification with {}: {}", program, e))?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `quoted`.
+    #[test]
+    fn test_quoted() {
+        assert_eq!(quoted("Range scan done"), "\"Range scan done\"");
+        assert_eq!(quoted("a \"b\" \\ c"), "\"a \\\"b\\\" \\\\ c\"");
+    }
+
+    // Test function for `notify_command`.
+    #[test]
+    fn test_notify_command() {
+        let (program, args) = notify_command("Work plan done", "3 jobs");
+        assert!(!program.is_empty());
+        // The texts are passed whole, whatever the system.
+        assert!(args.iter().any(|arg| arg.contains("Work plan done")));
+        assert!(args.iter().any(|arg| arg.contains("3 jobs")));
+    }
+}
@@ -0,0 +1,101 @@
+// File representation.rs
+// This module contains the positional representations of Collatz sequences.
+// It writes every term of a sequence in a given base and lays the digits out as a matrix
+// (one row per step, one column per digit), which is the basis of the bit-matrix view.
+
+/// Holds the digits of every term of a sequence, written in the same base.
+///
+/// Rows are right-aligned: the last column always holds the least significant digit,
+/// and shorter numbers are padded with zeros on the left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigitMatrix {
+    pub base: u64,          // Base used to write the terms
+    pub width: usize,       // Number of digits of the longest term
+    pub rows: Vec<Vec<u8>>, // One row of `width` digits per step, most significant digit first
+}
+
+impl DigitMatrix {
+    /// Returns row `index` as text, e.g. "0102" (digits above 9 are written as letters).
+    pub fn row_string(&self, index: usize) -> String {
+        self.rows[index]
+            .iter()
+            .map(|&digit| char::from_digit(digit as u32, 36).unwrap_or('?'))
+            .collect()
+    }
+}
+
+/// Writes `n` in the given base, most significant digit first (0 is written as a single "0").
+///
+/// # Arguments
+/// * `n` - The number to write.
+/// * `base` - The base to use (must be >= 2).
+pub fn digits_in_base(mut n: u64, base: u64) -> Vec<u8> {
+    if n == 0 {
+        return vec![0];
+    }
+    let mut digits = Vec::new();
+    while n > 0 {
+        digits.push((n % base) as u8);
+        n /= base;
+    }
+    // Digits were produced least significant first.
+    digits.reverse();
+    digits
+}
+
+/// Writes every term of `sequence` in the given base and aligns the results into a matrix.
+///
+/// # Arguments
+/// * `sequence` - The terms to write.
+/// * `base` - The base to use (must be >= 2).
+pub fn digit_matrix(sequence: &[u64], base: u64) -> DigitMatrix {
+    let written: Vec<Vec<u8>> = sequence.iter().map(|&n| digits_in_base(n, base)).collect();
+    let width = written.iter().map(|digits| digits.len()).max().unwrap_or(0);
+
+    // Pad every row with leading zeros up to the common width.
+    let rows = written
+        .into_iter()
+        .map(|digits| {
+            let mut row = vec![0; width - digits.len()];
+            row.extend(digits);
+            row
+        })
+        .collect();
+
+    DigitMatrix { base, width, rows }
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `digits_in_base`.
+    #[test]
+    fn test_digits_in_base() {
+        assert_eq!(digits_in_base(0, 2), vec![0]);
+        assert_eq!(digits_in_base(6, 2), vec![1, 1, 0]);
+        assert_eq!(digits_in_base(27, 3), vec![1, 0, 0, 0]);
+        assert_eq!(digits_in_base(u64::MAX, 2).len(), 64);
+    }
+
+    // Test function for `digit_matrix`.
+    #[test]
+    fn test_digit_matrix() {
+        // 6 -> 3 -> 10 -> 5 in binary, right-aligned on 4 bits.
+        let matrix = digit_matrix(&[6, 3, 10, 5], 2);
+        assert_eq!(matrix.width, 4);
+        assert_eq!(matrix.rows, vec![
+            vec![0, 1, 1, 0],
+            vec![0, 0, 1, 1],
+            vec![1, 0, 1, 0],
+            vec![0, 1, 0, 1],
+        ]);
+
+        // 27 -> 82 -> 41 in base 3.
+        let ternary = digit_matrix(&[27, 82, 41], 3);
+        assert_eq!(ternary.row_string(0), "01000");
+        assert_eq!(ternary.row_string(1), "10001");
+        assert_eq!(ternary.row_string(2), "01112");
+    }
+}
@@ -0,0 +1,477 @@
+// File results_db.rs
+// This module contains the results database: when enabled, the statistics of every sequence computed
+// by the application are kept in a table that grows as the user explores, and survives restarts.
+// Each row is one starting value under one map, so a value charted again is not stored twice:
+// its row only counts one more run. The table is stored in the application's data directory
+// as a tab-separated text file with a header line, which spreadsheets and pandas read as it is:
+// start	map	flight_time	stopping_time	peak	first_seen	last_seen	runs
+// 27	3n+1	111	96	9232	2026-10-15T14:03:12+02:00	2026-10-15T14:05:40+02:00	2
+// A text file takes the place of an SQLite database: the application is built without a SQL engine,
+// and the table is small enough to be read whole by the browser. Deduplication on (start, map), which
+// a UNIQUE key would give, is done when the file is read: a run only appends its rows to the file,
+// a value computed again gets a second line, and the lines of a value are merged when the file is read.
+// The keys already stored are kept in memory between runs, so a run does not read the table again;
+// the file is only read whole the first time, when another window changed it, and to rewrite it merged
+// (one line per value) once the repeated lines make up half of it.
+// The database browser queries it: filtered by stopping time, peak and date, sorted by any column,
+// and exports the results it shows as CSV (the same columns, comma-separated).
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// Columns of the table, in the order of the file.
+const HEADER: &str = "start\tmap\tflight_time\tstopping_time\tpeak\tfirst_seen\tlast_seen\truns";
+
+/// Returns the default location of the results database, in the application's data directory.
+pub fn default_path() -> PathBuf {
+    crate::cache::data_dir().join("results.tsv")
+}
+
+/// Statistics of one starting value under one map, as stored in the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredResult {
+    pub start: u64,           // Starting value
+    pub map: String,          // Name of the map (e.g. "3n+1")
+    pub flight_time: usize,   // Number of steps to reach 1
+    pub stopping_time: usize, // Number of steps to fall below the starting value
+    pub peak: u64,            // Maximum value reached
+    pub first_seen: String,   // Local time of the first run computing it, RFC 3339
+    pub last_seen: String,    // Local time of the latest run computing it, RFC 3339
+    pub runs: usize,          // Number of runs that computed it
+}
+
+impl StoredResult {
+    /// Writes the result as a line of the table (without the line break).
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.start, self.map, self.flight_time, self.stopping_time, self.peak, self.first_seen, self.last_seen, self.runs
+        )
+    }
+
+    /// Reads a line written by `to_line`.
+    fn parse_line(line: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [start, map, flight_time, stopping_time, peak, first_seen, last_seen, runs] = fields[..] else {
+            return Err(format!("Expected 8 columns, found {}", fields.len()));
+        };
+        let number = |field: &str| field.parse::<u64>().map_err(|_| format!("Invalid number: {}", field));
+        Ok(StoredResult {
+            start: number(start)?,
+            map: map.to_string(),
+            flight_time: number(flight_time)? as usize,
+            stopping_time: number(stopping_time)? as usize,
+            peak: number(peak)?,
+            first_seen: first_seen.to_string(),
+            last_seen: last_seen.to_string(),
+            runs: number(runs)? as usize,
+        })
+    }
+}
+
+/// The stored results, one per (starting value, map).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResultsDb {
+    pub results: Vec<StoredResult>,       // In the order they were first stored
+    index: HashMap<(u64, String), usize>, // Position in `results` of each (starting value, map)
+}
+
+impl ResultsDb {
+    /// Reads a table written by `to_text` or by `store`, merging the lines of the same value.
+    /// The header and the lines that cannot be read are skipped.
+    pub fn parse(text: &str) -> Self {
+        let mut db = ResultsDb::default();
+        for result in text.lines().filter(|line| *line != HEADER).filter_map(|line| StoredResult::parse_line(line).ok()) {
+            db.insert(result);
+        }
+        db
+    }
+
+    /// Writes the table, with its header line.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}\n", HEADER);
+        for result in &self.results {
+            text.push_str(&result.to_line());
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Stores a result: a new starting value is added, a known one counts one more run
+    /// (its statistics and first time are kept, its latest time is updated).
+    ///
+    /// # Returns
+    /// * `bool` - True if the result was not stored yet.
+    pub fn insert(&mut self, result: StoredResult) -> bool {
+        match self.index.get(&(result.start, result.map.clone())) {
+            Some(&position) => {
+                let stored = &mut self.results[position];
+                stored.last_seen = result.last_seen;
+                stored.runs += result.runs;
+                false
+            }
+            None => {
+                self.index.insert((result.start, result.map.clone()), self.results.len());
+                self.results.push(result);
+                true
+            }
+        }
+    }
+}
+
+/// Column of the database browser the results are sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Start,
+    FlightTime,
+    StoppingTime,
+    Peak,
+    LastSeen,
+    Runs,
+}
+
+impl SortColumn {
+    /// Every column, in the order of the browser.
+    pub const ALL: [SortColumn; 6] = [
+        SortColumn::Start,
+        SortColumn::FlightTime,
+        SortColumn::StoppingTime,
+        SortColumn::Peak,
+        SortColumn::LastSeen,
+        SortColumn::Runs,
+    ];
+
+    /// Returns the header of the column.
+    pub fn header(self) -> &'static str {
+        match self {
+            SortColumn::Start => "Start",
+            SortColumn::FlightTime => "Flight",
+            SortColumn::StoppingTime => "Stopping",
+            SortColumn::Peak => "Peak",
+            SortColumn::LastSeen => "Last seen",
+            SortColumn::Runs => "Runs",
+        }
+    }
+
+    /// Returns the text of the column for a result (the date only, for the latest time).
+    pub fn cell(self, result: &StoredResult) -> String {
+        match self {
+            SortColumn::Start => result.start.to_string(),
+            SortColumn::FlightTime => result.flight_time.to_string(),
+            SortColumn::StoppingTime => result.stopping_time.to_string(),
+            SortColumn::Peak => result.peak.to_string(),
+            SortColumn::LastSeen => result.last_seen.get(..10).unwrap_or(&result.last_seen).to_string(),
+            SortColumn::Runs => result.runs.to_string(),
+        }
+    }
+
+    /// Compares two results by this column, then by starting value.
+    fn compare(self, a: &StoredResult, b: &StoredResult) -> std::cmp::Ordering {
+        let ordering = match self {
+            SortColumn::Start => a.start.cmp(&b.start),
+            SortColumn::FlightTime => a.flight_time.cmp(&b.flight_time),
+            SortColumn::StoppingTime => a.stopping_time.cmp(&b.stopping_time),
+            SortColumn::Peak => a.peak.cmp(&b.peak),
+            // RFC 3339 times written by the same machine sort as text.
+            SortColumn::LastSeen => a.last_seen.cmp(&b.last_seen),
+            SortColumn::Runs => a.runs.cmp(&b.runs),
+        };
+        ordering.then(a.start.cmp(&b.start))
+    }
+}
+
+/// Filters of the database browser: a result is kept if it is within every bound that is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResultsQuery {
+    pub stopping_time: (Option<usize>, Option<usize>), // Smallest and largest stopping time
+    pub peak: (Option<u64>, Option<u64>),              // Smallest and largest peak
+    pub since: Option<String>,                         // First day ("YYYY-MM-DD") the result was last seen
+}
+
+impl ResultsQuery {
+    /// Reads the filters as typed in the browser: two stopping times, two peaks and a day, each optional.
+    ///
+    /// # Returns
+    /// * `Ok(ResultsQuery)` - The filters (the blank fields are not set).
+    /// * `Err(String)` - A message naming the first field that is not blank and cannot be read.
+    pub fn parse(inputs: &[String; 5]) -> Result<Self, String> {
+        fn bound<T: std::str::FromStr>(input: &str, name: &str) -> Result<Option<T>, String> {
+            match input.trim() {
+                "" => Ok(None),
+                text => text.parse().map(Some).map_err(|_| format!("Invalid {}: {}", name, text)),
+            }
+        }
+        let since = match inputs[4].trim() {
+            "" => None,
+            day => match chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d") {
+                Ok(_) => Some(day.to_string()),
+                Err(_) => return Err(format!("Invalid date (expected YYYY-MM-DD): {}", day)),
+            },
+        };
+        Ok(ResultsQuery {
+            stopping_time: (bound(&inputs[0], "stopping time")?, bound(&inputs[1], "stopping time")?),
+            peak: (bound(&inputs[2], "peak")?, bound(&inputs[3], "peak")?),
+            since,
+        })
+    }
+
+    /// Tells whether a result passes the filters.
+    pub fn keeps(&self, result: &StoredResult) -> bool {
+        let within = |value: u64, (min, max): (Option<u64>, Option<u64>)| {
+            min.is_none_or(|min| value >= min) && max.is_none_or(|max| value <= max)
+        };
+        let (min_time, max_time) = self.stopping_time;
+        within(result.stopping_time as u64, (min_time.map(|t| t as u64), max_time.map(|t| t as u64)))
+            && within(result.peak, self.peak)
+            && self.since.as_ref().is_none_or(|since| result.last_seen.get(..10).is_some_and(|day| day >= since.as_str()))
+    }
+}
+
+/// Returns the results passing the filters of a query, sorted by a column.
+///
+/// # Arguments
+/// * `db` - The stored results.
+/// * `query` - The filters.
+/// * `sort` - The column to sort by (ties are sorted by starting value).
+/// * `descending` - Whether the largest values come first.
+pub fn query<'a>(db: &'a ResultsDb, query: &ResultsQuery, sort: SortColumn, descending: bool) -> Vec<&'a StoredResult> {
+    let mut results: Vec<&StoredResult> = db.results.iter().filter(|result| query.keeps(result)).collect();
+    results.sort_by(|a, b| if descending { sort.compare(b, a) } else { sort.compare(a, b) });
+    results
+}
+
+/// Writes results as CSV, with a header line and the columns of the database file.
+pub fn to_csv(results: &[&StoredResult]) -> String {
+    let mut csv = format!("{}\n", HEADER.replace('\t', ","));
+    for result in results {
+        csv.push_str(&result.to_line().replace('\t', ","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Reads the results database. A missing file gives an empty database.
+///
+/// # Returns
+/// * `Ok(ResultsDb)` - The stored results.
+/// * `Err(String)` - If the file cannot be read.
+pub fn load(path: &Path) -> Result<ResultsDb, String> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(ResultsDb::parse(&text)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ResultsDb::default()),
+        Err(e) => Err(format!("Error reading the results database: {}", e)),
+    }
+}
+
+/// What `store` knows of the database file, so that a run does not read the whole table again.
+struct FileIndex {
+    path: PathBuf,                // Location of the database
+    keys: HashSet<(u64, String)>, // (starting value, map) of every stored result
+    lines: usize,                 // Number of result lines in the file (a value computed again has several)
+    len: u64,                     // Size of the file when last read or written, to notice a change by another window
+}
+
+impl FileIndex {
+    /// Reads the keys and the number of lines of the database file (none if it does not exist yet).
+    fn read(path: &Path) -> Result<Self, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("Error reading the results database: {}", e)),
+        };
+        let db = ResultsDb::parse(&text);
+        Ok(FileIndex {
+            path: path.to_path_buf(),
+            keys: db.index.into_keys().collect(),
+            lines: text.lines().filter(|line| *line != HEADER).count(),
+            len: text.len() as u64,
+        })
+    }
+}
+
+// Lock held while the database file is written, with what is known of the file: the runs of the application
+// store one after the other, so the lines of two runs never interleave and a merged rewrite never drops
+// the lines of another run.
+static WRITER: Mutex<Option<FileIndex>> = Mutex::new(None);
+
+/// Adds results to the database file, creating it (and its directory) if needed.
+/// The results are appended; the file is rewritten merged once the repeated lines make up half of it.
+///
+/// # Arguments
+/// * `path` - Location of the database.
+/// * `results` - The results of a run.
+///
+/// # Returns
+/// * `Ok(usize)` - The number of starting values that were not stored yet.
+/// * `Err(String)` - If the file cannot be read or written.
+pub fn store(path: &Path, results: Vec<StoredResult>) -> Result<usize, String> {
+    let mut writer = WRITER.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let error = |e: std::io::Error| format!("Error writing the results database: {}", e);
+
+    // The file is read again only if it is not the one known, or if its size changed since (another window
+    // stored a run). The index is put back once the run is written: after a failure, the file is read again.
+    let file_len = fs::metadata(path).map_or(0, |metadata| metadata.len());
+    let mut index = match writer.take() {
+        Some(index) if index.path == path && index.len == file_len => index,
+        _ => FileIndex::read(path)?,
+    };
+    let mut lines = String::new();
+    if index.len == 0 {
+        lines.push_str(HEADER);
+        lines.push('\n');
+    }
+    let mut added = 0;
+    index.lines += results.len();
+    for result in &results {
+        lines.push_str(&result.to_line());
+        lines.push('\n');
+        if index.keys.insert((result.start, result.map.clone())) {
+            added += 1;
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let repeated = index.lines - index.keys.len();
+    if repeated > 0 && repeated >= index.keys.len() {
+        // Too many repeated lines: the file is read and merged with the run. The merged table goes
+        // to a temporary file first, renamed at the end, so a crash never leaves a half-written database.
+        let mut db = load(path)?;
+        for result in results {
+            db.insert(result);
+        }
+        let text = db.to_text();
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, &text).map_err(error)?;
+        fs::rename(&temp_path, path).map_err(error)?;
+        index.lines = db.results.len();
+        index.len = text.len() as u64;
+    } else {
+        // A single write of the whole run, at the end of the file.
+        let mut file = fs::OpenOptions::new().create(true).append(true).open(path).map_err(error)?;
+        file.write_all(lines.as_bytes()).map_err(error)?;
+        index.len += lines.len() as u64;
+    }
+    *writer = Some(index);
+    Ok(added)
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    /// Returns a result of the 3n+1 map, seen once at the given time.
+    fn result(start: u64, flight_time: usize, time: &str) -> StoredResult {
+        StoredResult {
+            start,
+            map: "3n+1".to_string(),
+            flight_time,
+            stopping_time: 3,
+            peak: 9232,
+            first_seen: time.to_string(),
+            last_seen: time.to_string(),
+            runs: 1,
+        }
+    }
+
+    // Test function for `ResultsDb::insert`.
+    #[test]
+    fn test_insert() {
+        let mut db = ResultsDb::default();
+        assert!(db.insert(result(27, 111, "2026-10-15T14:00:00+02:00")));
+        assert!(db.insert(result(31, 106, "2026-10-15T14:00:00+02:00")));
+        // The same value again only counts one more run.
+        assert!(!db.insert(result(27, 111, "2026-10-15T14:05:00+02:00")));
+        assert_eq!(db.results.len(), 2);
+        let stored = &db.results[0];
+        assert_eq!((stored.runs, stored.first_seen.as_str()), (2, "2026-10-15T14:00:00+02:00"));
+        assert_eq!(stored.last_seen, "2026-10-15T14:05:00+02:00");
+        // Under another map, it is another row.
+        assert!(db.insert(StoredResult { map: "5n+1".to_string(), ..result(27, 5, "2026-10-15T14:05:00+02:00") }));
+    }
+
+    // Test function for `ResultsDb::parse` and `ResultsDb::to_text`.
+    #[test]
+    fn test_parse() {
+        let text = format!("{}\n27\t3n+1\t111\t96\t9232\tt1\tt2\t2\nnot a row\n", HEADER);
+        let db = ResultsDb::parse(&text);
+        assert_eq!(db.results.len(), 1);
+        assert_eq!((db.results[0].start, db.results[0].stopping_time, db.results[0].runs), (27, 96, 2));
+        assert_eq!(db.to_text(), format!("{}\n27\t3n+1\t111\t96\t9232\tt1\tt2\t2\n", HEADER));
+    }
+
+    // Test function for `ResultsQuery::parse`.
+    #[test]
+    fn test_results_query_parse() {
+        let inputs = |fields: [&str; 5]| fields.map(str::to_string);
+        assert_eq!(ResultsQuery::parse(&inputs(["", "", "", "", ""])), Ok(ResultsQuery::default()));
+        let query = ResultsQuery::parse(&inputs(["10", " ", "", "1000", "2026-10-15"])).unwrap();
+        assert_eq!(query.stopping_time, (Some(10), None));
+        assert_eq!(query.peak, (None, Some(1000)));
+        assert_eq!(query.since.as_deref(), Some("2026-10-15"));
+        assert!(ResultsQuery::parse(&inputs(["", "", "x", "", ""])).unwrap_err().contains("peak"));
+        assert!(ResultsQuery::parse(&inputs(["", "", "", "", "15/10/2026"])).is_err());
+    }
+
+    // Test function for `query`.
+    #[test]
+    fn test_query() {
+        let mut db = ResultsDb::default();
+        db.insert(StoredResult { stopping_time: 96, ..result(27, 111, "2026-10-15T14:00:00+02:00") });
+        db.insert(StoredResult { stopping_time: 3, peak: 16, ..result(6, 8, "2026-10-14T09:00:00+02:00") });
+        db.insert(StoredResult { stopping_time: 91, ..result(31, 106, "2026-10-16T10:00:00+02:00") });
+        let starts = |results: Vec<&StoredResult>| results.iter().map(|result| result.start).collect::<Vec<_>>();
+        let all = ResultsQuery::default();
+        assert_eq!(starts(query(&db, &all, SortColumn::Start, false)), vec![6, 27, 31]);
+        assert_eq!(starts(query(&db, &all, SortColumn::FlightTime, true)), vec![27, 31, 6]);
+        // Equal peaks are sorted by starting value.
+        assert_eq!(starts(query(&db, &all, SortColumn::Peak, false)), vec![6, 27, 31]);
+        let long = ResultsQuery { stopping_time: (Some(50), None), ..Default::default() };
+        assert_eq!(starts(query(&db, &long, SortColumn::LastSeen, true)), vec![31, 27]);
+        let recent = ResultsQuery { since: Some("2026-10-15".to_string()), peak: (None, Some(9232)), ..Default::default() };
+        assert_eq!(starts(query(&db, &recent, SortColumn::Start, false)), vec![27, 31]);
+    }
+
+    // Test function for `to_csv`.
+    #[test]
+    fn test_to_csv() {
+        let stored = result(27, 111, "2026-10-15T14:00:00+02:00");
+        assert_eq!(
+            to_csv(&[&stored]),
+            "start,map,flight_time,stopping_time,peak,first_seen,last_seen,runs\n\
+             27,3n+1,111,3,9232,2026-10-15T14:00:00+02:00,2026-10-15T14:00:00+02:00,1\n"
+        );
+        assert_eq!(to_csv(&[]).lines().count(), 1);
+    }
+
+    // Test function for `store` and `load`.
+    #[test]
+    fn test_store() {
+        let temp = TempPath::new("collatz_results_test");
+        let path = temp.path();
+        assert_eq!(load(path), Ok(ResultsDb::default()));
+        let time = "2026-10-15T14:00:00+02:00";
+        assert_eq!(store(path, vec![result(27, 111, time), result(31, 106, time)]), Ok(2));
+        assert_eq!(store(path, vec![result(27, 111, time)]), Ok(0));
+        // The run was appended: the value has two lines, merged when read.
+        assert_eq!(fs::read_to_string(path).unwrap().lines().count(), 4);
+        let db = load(path).unwrap();
+        assert_eq!(db.results.len(), 2);
+        assert_eq!((db.results[0].start, db.results[0].runs), (27, 2));
+        // Once the repeated lines make up half of the file, it is rewritten merged.
+        assert_eq!(store(path, vec![result(27, 111, time), result(31, 106, time)]), Ok(0));
+        assert_eq!(fs::read_to_string(path).unwrap().lines().count(), 3);
+        let db = load(path).unwrap();
+        assert_eq!((db.results[0].runs, db.results[1].runs), (3, 2));
+        // A file changed by another window is read again.
+        fs::write(path, format!("{}\n{}\n", HEADER, result(5, 5, time).to_line())).unwrap();
+        assert_eq!(store(path, vec![result(5, 5, time), result(27, 111, time)]), Ok(1));
+        assert_eq!(load(path).unwrap().results.len(), 2);
+    }
+}
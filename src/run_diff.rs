@@ -0,0 +1,139 @@
+// File run_diff.rs
+// This module contains the comparison of two runs of the history panel: the difference of each statistic
+// logged for their sequences (starting value, flight time, peak, stopping time), in absolute terms and in percent.
+// It shows how a small change of n moves the trajectory, and is exported as a JSON document:
+// {"first":{"timestamp":...,"inputs":["27",""]},"second":{...},
+//  "differences":[{"sequence":1,"statistic":"flight_time","first":111,"second":18,"difference":-93,"percent":-83.78},...]}
+
+use crate::experiment_log::{RunRecord, SequenceRecord};
+use crate::json::json_string;
+
+/// Difference of one statistic of a sequence between two runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatDiff {
+    pub sequence: usize,          // 1 for the first sequence of the runs, 2 for the second
+    pub statistic: &'static str,  // Name of the statistic, as in the experiment log
+    pub first: u64,               // Value in the first run
+    pub second: u64,              // Value in the second run
+}
+
+impl StatDiff {
+    /// Returns the second value minus the first.
+    pub fn difference(&self) -> i128 {
+        self.second as i128 - self.first as i128
+    }
+
+    /// Returns the difference in percent of the first value, or None if the first value is 0.
+    pub fn percent(&self) -> Option<f64> {
+        (self.first != 0).then(|| self.difference() as f64 / self.first as f64 * 100.0)
+    }
+}
+
+/// Returns the statistics of a sequence record with their names.
+fn statistics(record: &SequenceRecord) -> [(&'static str, u64); 4] {
+    [
+        ("value", record.value),
+        ("flight_time", record.flight_time as u64),
+        ("peak", record.peak),
+        ("stopping_time", record.stopping_time as u64),
+    ]
+}
+
+/// Compares the statistics of two runs, sequence by sequence (the first sequences together, then the second ones).
+/// A sequence charted by only one of the runs has nothing to be compared with, and is left out.
+pub fn diff_runs(first: &RunRecord, second: &RunRecord) -> Vec<StatDiff> {
+    let mut diffs = Vec::new();
+    for (index, (a, b)) in first.sequences.iter().zip(&second.sequences).enumerate() {
+        if let (Some(a), Some(b)) = (a, b) {
+            for ((statistic, first), (_, second)) in statistics(a).into_iter().zip(statistics(b)) {
+                diffs.push(StatDiff { sequence: index + 1, statistic, first, second });
+            }
+        }
+    }
+    diffs
+}
+
+/// Writes the comparison of two runs as a JSON document: the two runs (time and inputs),
+/// then the differences of their statistics (percent with two decimals, null when the first value is 0).
+pub fn to_json(first: &RunRecord, second: &RunRecord) -> String {
+    let run = |run: &RunRecord| {
+        format!(
+            "{{\"timestamp\": {}, \"chart\": {}, \"inputs\": [{}, {}]}}",
+            json_string(&run.timestamp),
+            json_string(&run.chart),
+            json_string(&run.inputs[0]),
+            json_string(&run.inputs[1]),
+        )
+    };
+    let differences: Vec<String> = diff_runs(first, second)
+        .iter()
+        .map(|diff| {
+            format!(
+                "    {{\"sequence\": {}, \"statistic\": \"{}\", \"first\": {}, \"second\": {}, \"difference\": {}, \"percent\": {}}}",
+                diff.sequence,
+                diff.statistic,
+                diff.first,
+                diff.second,
+                diff.difference(),
+                diff.percent().map_or("null".to_string(), |percent| format!("{:.2}", percent)),
+            )
+        })
+        .collect();
+    let differences = if differences.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[\n{}\n  ]", differences.join(",\n"))
+    };
+    format!(
+        "{{\n  \"first\": {},\n  \"second\": {},\n  \"differences\": {}\n}}\n",
+        run(first),
+        run(second),
+        differences,
+    )
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a run with the given (value, flight time, peak) of each sequence.
+    fn run(timestamp: &str, sequences: [Option<(u64, usize, u64)>; 2]) -> RunRecord {
+        RunRecord {
+            timestamp: timestamp.to_string(),
+            chart: "sequences".to_string(),
+            inputs: ["".to_string(), "".to_string()],
+            sequences: sequences.map(|sequence| {
+                sequence.map(|(value, flight_time, peak)| SequenceRecord { value, flight_time, peak, stopping_time: 3 })
+            }),
+        }
+    }
+
+    // Test function for `diff_runs`.
+    #[test]
+    fn test_diff_runs() {
+        let first = run("2026-10-15T14:00:00+02:00", [Some((27, 111, 9232)), Some((31, 106, 9232))]);
+        let second = run("2026-10-15T14:01:00+02:00", [Some((28, 18, 52)), None]);
+        let diffs = diff_runs(&first, &second);
+        // Only the first sequences are in both runs.
+        assert_eq!(diffs.len(), 4);
+        assert!(diffs.iter().all(|diff| diff.sequence == 1));
+        let flight_time = &diffs[1];
+        assert_eq!((flight_time.statistic, flight_time.difference()), ("flight_time", -93));
+        assert!((flight_time.percent().unwrap() + 83.78).abs() < 0.01);
+        assert_eq!(StatDiff { sequence: 1, statistic: "peak", first: 0, second: 4 }.percent(), None);
+    }
+
+    // Test function for `to_json`.
+    #[test]
+    fn test_to_json() {
+        let first = run("2026-10-15T14:00:00+02:00", [Some((27, 111, 9232)), None]);
+        let second = run("2026-10-15T14:01:00+02:00", [Some((28, 18, 52)), None]);
+        let json = to_json(&first, &second);
+        assert!(json.contains("\"first\": {\"timestamp\": \"2026-10-15T14:00:00+02:00\""), "{}", json);
+        assert!(json.contains(
+            "{\"sequence\": 1, \"statistic\": \"value\", \"first\": 27, \"second\": 28, \"difference\": 1, \"percent\": 3.70}"
+        ));
+        assert!(json.contains("\"statistic\": \"stopping_time\", \"first\": 3, \"second\": 3, \"difference\": 0, \"percent\": 0.00}\n  ]"));
+    }
+}
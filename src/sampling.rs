@@ -0,0 +1,185 @@
+// File sampling.rs
+// This module contains the Monte Carlo sampling of total stopping times.
+// When a range is far too large to be scanned exhaustively (e.g. starting values around 2^60),
+// random starting values are drawn from it and the statistics of the sample are reported
+// with confidence intervals instead.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::collatz;
+
+/// How the random starting values are drawn from the range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SamplingDistribution {
+    Uniform,    // Every starting value of the range is equally likely
+    LogUniform, // Every order of magnitude of the range is equally likely
+}
+
+impl SamplingDistribution {
+    /// Every distribution, in the order shown in the selector.
+    pub const ALL: [SamplingDistribution; 2] = [
+        SamplingDistribution::Uniform,
+        SamplingDistribution::LogUniform,
+    ];
+}
+
+impl std::fmt::Display for SamplingDistribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            SamplingDistribution::Uniform => "Uniform",
+            SamplingDistribution::LogUniform => "Log-uniform",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Holds the aggregate statistics of a Monte Carlo sample of total stopping times.
+#[derive(Debug, Clone)]
+pub struct SampleReport {
+    pub range_start: u64,                   // First starting value of the sampled range
+    pub range_end: u64,                     // Last starting value of the sampled range
+    pub distribution: SamplingDistribution, // How the starting values were drawn
+    pub times: Vec<usize>,                  // Total stopping times of the sample, sorted
+    pub failed: usize,                      // Draws whose trajectory could not be computed
+    pub mean: f64,                          // Sample mean
+    pub std_dev: f64,                       // Sample standard deviation
+    pub ci95: (f64, f64),                   // 95% confidence interval of the mean
+    pub max_time_start: u64,                // Sampled starting value with the longest trajectory
+}
+
+impl SampleReport {
+    /// Returns the `q`-quantile (0.0 <= q <= 1.0) of the sampled stopping times.
+    pub fn quantile(&self, q: f64) -> usize {
+        if self.times.is_empty() {
+            return 0;
+        }
+        let index = ((self.times.len() - 1) as f64 * q.clamp(0.0, 1.0)).round() as usize;
+        self.times[index]
+    }
+}
+
+/// Draws `count` random starting values from `range_start..=range_end`.
+///
+/// # Arguments
+/// * `seed` - Seed of the random generator, so that a sample can be reproduced.
+pub fn draw_starts(
+    range_start: u64,
+    range_end: u64,
+    count: usize,
+    distribution: SamplingDistribution,
+    seed: u64,
+) -> Vec<u64> {
+    let range_start = range_start.max(1);
+    let range_end = range_end.max(range_start);
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    (0..count)
+        .map(|_| match distribution {
+            SamplingDistribution::Uniform => rng.gen_range(range_start..=range_end),
+            SamplingDistribution::LogUniform => {
+                // Draw the logarithm uniformly, then go back to the integers.
+                let low = (range_start as f64).ln();
+                let high = (range_end as f64 + 1.0).ln();
+                if low >= high {
+                    // Above 2^53 a narrow range has a single f64 logarithm: its values are about
+                    // equally likely anyway, so they are drawn uniformly.
+                    return rng.gen_range(range_start..=range_end);
+                }
+                let value = rng.gen_range(low..high).exp().floor();
+                (value as u64).clamp(range_start, range_end)
+            }
+        })
+        .collect()
+}
+
+/// Samples `count` starting values from the range and computes their total stopping times in parallel.
+///
+/// # Returns
+/// * `SampleReport` - The sorted stopping times and their aggregate statistics.
+///   The confidence interval uses the normal approximation (mean ± 1.96 standard errors).
+pub fn sample_stopping_times(
+    range_start: u64,
+    range_end: u64,
+    count: usize,
+    distribution: SamplingDistribution,
+    seed: u64,
+) -> SampleReport {
+    let starts = draw_starts(range_start, range_end, count, distribution, seed);
+
+    // Every draw is independent: rayon spreads them over all the cores.
+    let results: Vec<(u64, Option<usize>)> = starts
+        .par_iter()
+        .map(|&n| (n, collatz::total_stopping_time_auto(n).steps))
+        .collect();
+
+    let failed = results.iter().filter(|(_, t)| t.is_none()).count();
+    let max_time_start = results.iter()
+        .filter_map(|&(n, t)| t.map(|t| (n, t)))
+        .max_by_key(|&(_, t)| t)
+        .map_or(range_start, |(n, _)| n);
+    let mut times: Vec<usize> = results.into_iter().filter_map(|(_, t)| t).collect();
+    times.sort_unstable();
+
+    let n = times.len() as f64;
+    let mean = if times.is_empty() { 0.0 } else { times.iter().sum::<usize>() as f64 / n };
+    let std_dev = if times.len() < 2 {
+        0.0
+    } else {
+        (times.iter().map(|&t| (t as f64 - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+    };
+    let margin = if times.is_empty() { 0.0 } else { 1.96 * std_dev / n.sqrt() };
+
+    SampleReport {
+        range_start,
+        range_end,
+        distribution,
+        times,
+        failed,
+        mean,
+        std_dev,
+        ci95: (mean - margin, mean + margin),
+        max_time_start,
+    }
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `draw_starts`.
+    #[test]
+    fn test_draw_starts() {
+        for distribution in SamplingDistribution::ALL {
+            let starts = draw_starts(1000, 2000, 500, distribution, 42);
+            assert_eq!(starts.len(), 500);
+            assert!(starts.iter().all(|&n| (1000..=2000).contains(&n)));
+            // The same seed gives the same sample.
+            assert_eq!(starts, draw_starts(1000, 2000, 500, distribution, 42));
+        }
+
+        // Log-uniform draws favor the small end of a wide range.
+        let starts = draw_starts(1, 1_000_000, 1000, SamplingDistribution::LogUniform, 7);
+        assert!(starts.iter().filter(|&&n| n < 1000).count() > 300);
+
+        // A narrow range near 2^60, where both ends have the same f64 logarithm, does not panic.
+        let start = 1u64 << 60;
+        let starts = draw_starts(start, start + 10, 100, SamplingDistribution::LogUniform, 3);
+        assert!(starts.iter().all(|&n| (start..=start + 10).contains(&n)));
+    }
+
+    // Test function for `sample_stopping_times`.
+    #[test]
+    fn test_sample_stopping_times() {
+        // Starting values around 2^60 are handled thanks to the wide arithmetic.
+        let start = 1u64 << 60;
+        let report = sample_stopping_times(start, start + 1_000_000, 200, SamplingDistribution::Uniform, 1);
+        assert_eq!(report.times.len() + report.failed, 200);
+        assert_eq!(report.failed, 0);
+        assert!(report.ci95.0 <= report.mean && report.mean <= report.ci95.1);
+        assert!(report.quantile(0.0) <= report.quantile(0.5));
+        assert!(report.quantile(0.5) <= report.quantile(1.0));
+    }
+}
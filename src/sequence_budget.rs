@@ -0,0 +1,180 @@
+// File sequence_budget.rs
+// This module contains the memory budget of the stored sequences. A sequence that would not fit in it
+// (a huge step limit, an enormous imported file) is not kept whole: its statistics are computed
+// term by term while it is generated, and only one term in `stride` is kept for the chart,
+// the stride doubling each time the kept terms outgrow the budget.
+
+use crate::collatz::CollatzStats;
+
+/// Default memory budget of the stored sequences, in megabytes.
+pub const DEFAULT_BUDGET_MB: usize = 256;
+
+/// Returns the number of terms that fit in a budget of `megabytes` (at least 2, so that a chart can be drawn).
+pub fn budget_terms(megabytes: usize) -> usize {
+    (megabytes.saturating_mul(1024 * 1024) / std::mem::size_of::<u64>()).max(2)
+}
+
+/// What is known of a sequence kept downsampled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Downsampled {
+    pub stride: usize,   // Number of steps between two kept terms (a power of two, more than 1)
+    pub length: usize,   // Number of terms of the whole sequence
+    pub truncated: bool, // Whether the whole sequence was cut at the step limit
+    pub last: u64,       // Last term of the whole sequence (not always kept)
+}
+
+/// Statistics of a sequence computed term by term, like `collatz::calculate_stats`, without keeping the terms.
+#[derive(Debug, Clone, Default)]
+pub struct StreamingStats {
+    length: usize,
+    start: u64,
+    max_value: u64,
+    max_value_index: usize,
+    even_count: usize,
+    stopping_time: Option<usize>,
+    max_bit_length: u32,
+    max_bit_length_index: usize,
+    bit_length_sum: u64,
+}
+
+impl StreamingStats {
+    /// Adds the next term of the sequence.
+    pub fn push(&mut self, term: u64) {
+        let index = self.length;
+        if index == 0 {
+            self.start = term;
+        } else if self.stopping_time.is_none() && term < self.start {
+            self.stopping_time = Some(index);
+        }
+        // Like `calculate_stats`, the last maximum gives the step of the maximum,
+        // and the first term of the largest bit length gives its step.
+        if index == 0 || term >= self.max_value {
+            self.max_value = term;
+            self.max_value_index = index;
+        }
+        let bit_length = u64::BITS - term.leading_zeros();
+        if index == 0 || bit_length > self.max_bit_length {
+            self.max_bit_length = bit_length;
+            self.max_bit_length_index = index;
+        }
+        self.bit_length_sum += bit_length as u64;
+        if term.is_multiple_of(2) {
+            self.even_count += 1;
+        }
+        self.length += 1;
+    }
+
+    /// Returns the statistics of the terms added so far.
+    pub fn finish(&self) -> CollatzStats {
+        CollatzStats {
+            length: self.length,
+            max_value: self.max_value,
+            max_value_index: self.max_value_index,
+            even_count: self.even_count,
+            odd_count: self.length - self.even_count,
+            stopping_time: self.stopping_time.unwrap_or(self.length.saturating_sub(1)),
+            max_bit_length: self.max_bit_length,
+            average_bit_length: if self.length == 0 { 0.0 } else { self.bit_length_sum as f64 / self.length as f64 },
+            max_bit_length_index: self.max_bit_length_index,
+        }
+    }
+}
+
+/// Keeps one term in `factor` (the first one included), so that term `i` becomes term `i / factor`.
+pub fn decimate(terms: &mut Vec<u64>, factor: usize) {
+    let mut index = 0;
+    terms.retain(|_| {
+        let kept = index % factor.max(1) == 0;
+        index += 1;
+        kept
+    });
+}
+
+/// A sequence read within the memory budget.
+pub struct StreamedSequence {
+    pub terms: Vec<u64>,     // The kept terms: every term, or one in `stride`
+    pub stats: CollatzStats, // The statistics of the whole sequence
+    pub stride: usize,       // Number of steps between two kept terms (1 if every term is kept)
+    pub last: Option<u64>,   // Last term of the whole sequence (not always kept)
+}
+
+/// Reads a sequence term by term, keeping at most `max_terms` of its terms.
+///
+/// # Arguments
+/// * `terms` - The terms of the sequence, e.g. `collatz::trajectory`.
+/// * `max_terms` - Largest number of terms kept (see `budget_terms`).
+///
+/// # Returns
+/// * `StreamedSequence` - The kept terms, evenly spaced, and the statistics of the whole sequence.
+pub fn stream(terms: impl IntoIterator<Item = u64>, max_terms: usize) -> StreamedSequence {
+    let max_terms = max_terms.max(2);
+    let mut stats = StreamingStats::default();
+    let mut kept = Vec::new();
+    let mut stride = 1;
+    let mut last = None;
+    for (index, term) in terms.into_iter().enumerate() {
+        stats.push(term);
+        last = Some(term);
+        if index % stride == 0 {
+            kept.push(term);
+            if kept.len() > max_terms {
+                decimate(&mut kept, 2);
+                stride *= 2;
+            }
+        }
+    }
+    StreamedSequence { terms: kept, stats: stats.finish(), stride, last }
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collatz;
+
+    // Test function for `StreamingStats`.
+    #[test]
+    fn test_streaming_stats() {
+        for start in [1, 6, 7, 27, 97] {
+            let sequence = collatz::generate_sequence(start);
+            let mut streaming = StreamingStats::default();
+            sequence.iter().for_each(|&term| streaming.push(term));
+            let (streamed, expected) = (streaming.finish(), collatz::calculate_stats(&sequence));
+            assert_eq!(
+                (streamed.length, streamed.max_value, streamed.max_value_index, streamed.even_count, streamed.odd_count),
+                (expected.length, expected.max_value, expected.max_value_index, expected.even_count, expected.odd_count),
+            );
+            assert_eq!(
+                (streamed.stopping_time, streamed.max_bit_length, streamed.max_bit_length_index),
+                (expected.stopping_time, expected.max_bit_length, expected.max_bit_length_index),
+            );
+            assert!((streamed.average_bit_length - expected.average_bit_length).abs() < 1e-9);
+        }
+    }
+
+    // Test function for `decimate`.
+    #[test]
+    fn test_decimate() {
+        let mut terms = vec![0, 1, 2, 3, 4, 5, 6];
+        decimate(&mut terms, 2);
+        assert_eq!(terms, vec![0, 2, 4, 6]);
+        decimate(&mut terms, 1);
+        assert_eq!(terms, vec![0, 2, 4, 6]);
+    }
+
+    // Test function for `stream`.
+    #[test]
+    fn test_stream() {
+        // 27 takes 111 steps: 112 terms.
+        let whole = stream(collatz::generate_sequence(27), 1000);
+        assert_eq!((whole.terms.len(), whole.stride, whole.last), (112, 1, Some(1)));
+        let streamed = stream(collatz::generate_sequence(27), 50);
+        assert_eq!(streamed.stride, 4);
+        assert!(streamed.terms.len() <= 50);
+        // Term i of the kept terms is step i * stride, and the statistics are those of the whole sequence.
+        let sequence = collatz::generate_sequence(27);
+        assert!(streamed.terms.iter().enumerate().all(|(i, &term)| sequence[i * streamed.stride] == term));
+        assert_eq!((streamed.stats.length, streamed.stats.max_value), (112, 9232));
+        assert_eq!(budget_terms(1), 131_072);
+    }
+}
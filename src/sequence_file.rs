@@ -0,0 +1,288 @@
+// File sequence_file.rs
+// This module contains the file formats used to exchange sequences with other programs.
+// Sequences can be imported from CSV, JSON or binary files, to chart trajectories computed elsewhere.
+// The binary format stores the terms as raw numbers, so that programs crunching many trajectories
+// can read them without parsing text:
+//   - 4 bytes: magic "CSQ1" (the trailing digit is the format version)
+//   - 1 byte: encoding of the terms (0 = little-endian u64, 1 = LEB128 varint)
+//   - 8 bytes: number of terms (u64, little endian)
+//   - the terms, in the chosen encoding
+
+use std::fmt;
+
+use crate::json::{self, Json};
+
+// Magic bytes at the start of a binary sequence file.
+const MAGIC: &[u8; 4] = b"CSQ1";
+
+// Size of the header: magic, encoding, number of terms.
+const HEADER_LEN: usize = 4 + 1 + 8;
+
+/// How the terms are stored in a binary sequence file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryEncoding {
+    U64,    // 8 bytes per term, little endian: fixed size, trivial to read
+    Varint, // LEB128: 7 bits per byte, so small terms (most of a trajectory) take 1 to 3 bytes
+}
+
+impl BinaryEncoding {
+    /// Every encoding, in the order shown in the selector.
+    pub const ALL: [BinaryEncoding; 2] = [BinaryEncoding::U64, BinaryEncoding::Varint];
+
+    /// Returns the byte identifying the encoding in the header.
+    fn tag(self) -> u8 {
+        match self {
+            BinaryEncoding::U64 => 0,
+            BinaryEncoding::Varint => 1,
+        }
+    }
+}
+
+impl fmt::Display for BinaryEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            BinaryEncoding::U64 => "u64 (little endian)",
+            BinaryEncoding::Varint => "varint (LEB128)",
+        })
+    }
+}
+
+/// Encodes a sequence in the binary format.
+///
+/// # Arguments
+/// * `sequence` - Terms of the sequence.
+/// * `encoding` - How the terms are stored.
+///
+/// # Returns
+/// * `Vec<u8>` - Content of the file, header included.
+pub fn encode_binary(sequence: &[u64], encoding: BinaryEncoding) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + sequence.len() * 8);
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(encoding.tag());
+    bytes.extend_from_slice(&(sequence.len() as u64).to_le_bytes());
+
+    for &term in sequence {
+        match encoding {
+            BinaryEncoding::U64 => bytes.extend_from_slice(&term.to_le_bytes()),
+            BinaryEncoding::Varint => {
+                // 7 bits per byte, lowest first; the high bit tells that more bytes follow.
+                let mut rest = term;
+                while rest >= 0x80 {
+                    bytes.push((rest as u8 & 0x7f) | 0x80);
+                    rest >>= 7;
+                }
+                bytes.push(rest as u8);
+            }
+        }
+    }
+    bytes
+}
+
+/// Decodes a file written by `encode_binary`.
+///
+/// # Returns
+/// * `Ok(Vec<u64>)` - The terms of the sequence.
+/// * `Err(String)` - Why the content is not a valid binary sequence file.
+pub fn decode_binary(bytes: &[u8]) -> Result<Vec<u64>, String> {
+    if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+        return Err("Not a binary sequence file".to_string());
+    }
+    let count = u64::from_le_bytes(bytes[5..13].try_into().unwrap());
+    let body = &bytes[HEADER_LEN..];
+
+    match bytes[4] {
+        0 => {
+            if body.len() as u64 != count.saturating_mul(8) {
+                return Err("Truncated binary sequence file".to_string());
+            }
+            Ok(body
+                .chunks_exact(8)
+                .map(|term| u64::from_le_bytes(term.try_into().unwrap()))
+                .collect())
+        }
+        1 => {
+            // Each term takes at least one byte, which bounds the allocation for corrupted counts.
+            let mut terms = Vec::with_capacity(count.min(body.len() as u64) as usize);
+            let mut term = 0u64;
+            let mut shift = 0;
+            for &byte in body {
+                if shift >= 64 || (shift == 63 && byte & 0x7f > 1) {
+                    return Err("Invalid varint in binary sequence file".to_string());
+                }
+                term |= ((byte & 0x7f) as u64) << shift;
+                if byte & 0x80 == 0 {
+                    terms.push(term);
+                    term = 0;
+                    shift = 0;
+                } else {
+                    shift += 7;
+                }
+            }
+            if shift != 0 || terms.len() as u64 != count {
+                return Err("Truncated binary sequence file".to_string());
+            }
+            Ok(terms)
+        }
+        tag => Err(format!("Unknown encoding {} in binary sequence file", tag)),
+    }
+}
+
+/// Reads a sequence from the content of a file, whatever its format:
+/// binary (see the top of this file), JSON (`[27, 82, ...]` or `{"sequence": [27, 82, ...]}`)
+/// or CSV (one term per line, one line of terms, or "step,term" rows, with an optional header line).
+///
+/// # Returns
+/// * `Ok(Vec<u64>)` - The terms of the sequence (at least one).
+/// * `Err(String)` - Why the content could not be read.
+pub fn parse_sequence(bytes: &[u8]) -> Result<Vec<u64>, String> {
+    let sequence = if bytes.starts_with(MAGIC) {
+        decode_binary(bytes)?
+    } else {
+        let text = std::str::from_utf8(bytes).map_err(|_| "Unknown file format".to_string())?;
+        let text = text.trim_start_matches('\u{feff}'); // Byte order mark written by some spreadsheets
+        if text.trim_start().starts_with(['[', '{']) {
+            parse_json(text)?
+        } else {
+            parse_csv(text)?
+        }
+    };
+
+    if sequence.is_empty() {
+        return Err("The file contains no term".to_string());
+    }
+    Ok(sequence)
+}
+
+/// Reads the terms of the first array of a JSON document (the document itself, or the first array found in it).
+fn parse_json(text: &str) -> Result<Vec<u64>, String> {
+    fn first_array(value: &Json) -> Option<&[Json]> {
+        match value {
+            Json::Array(items) => Some(items),
+            Json::Object(fields) => fields.iter().find_map(|(_, value)| first_array(value)),
+            _ => None,
+        }
+    }
+    let document = json::parse(text).map_err(|e| format!("Invalid JSON file: {}", e))?;
+    first_array(&document)
+        .ok_or_else(|| "No array in the JSON file".to_string())?
+        .iter()
+        .map(|term| match term {
+            Json::Number(field) | Json::String(field) => parse_term(field),
+            other => Err(format!("Invalid term: {:?}", other)),
+        })
+        .collect()
+}
+
+/// Reads the terms of a CSV file.
+fn parse_csv(text: &str) -> Result<Vec<u64>, String> {
+    let rows: Vec<Vec<&str>> = text
+        .lines()
+        .map(|line| {
+            line.split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+                .filter(|field| !field.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|row| !row.is_empty())
+        .collect();
+
+    // A header line (e.g. "step,value") is skipped.
+    let rows = match rows.first() {
+        Some(first) if first.iter().any(|field| parse_term(field).is_err()) => &rows[1..],
+        _ => &rows[..],
+    };
+
+    // Rows of two fields are "step,term"; otherwise every field is a term.
+    let two_columns = rows.len() > 1 && rows.iter().all(|row| row.len() == 2);
+    rows.iter()
+        .flat_map(|row| if two_columns { &row[1..] } else { &row[..] })
+        .map(|field| parse_term(field))
+        .collect()
+}
+
+/// Reads one term (quotes allowed, for programs writing big numbers as strings).
+fn parse_term(field: &str) -> Result<u64, String> {
+    let field = field.trim().trim_matches('"');
+    field.parse::<u64>().map_err(|_| {
+        if !field.is_empty() && field.bytes().all(|b| b.is_ascii_digit()) {
+            format!("The term {} does not fit in 64 bits", field)
+        } else {
+            format!("Invalid term: {}", field)
+        }
+    })
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `encode_binary`.
+    #[test]
+    fn test_encode_binary() {
+        let bytes = encode_binary(&[27, 82], BinaryEncoding::U64);
+        assert_eq!(&bytes[..4], b"CSQ1");
+        assert_eq!(bytes[4], 0); // Encoding
+        assert_eq!(bytes[5..13], 2u64.to_le_bytes()); // Number of terms
+        assert_eq!(bytes[13..21], 27u64.to_le_bytes());
+        assert_eq!(bytes.len(), 13 + 16);
+
+        // Varints: 127 fits in one byte, 128 needs two, u64::MAX needs ten.
+        assert_eq!(encode_binary(&[127], BinaryEncoding::Varint)[13..], [0x7f]);
+        assert_eq!(encode_binary(&[128], BinaryEncoding::Varint)[13..], [0x80, 0x01]);
+        assert_eq!(encode_binary(&[300], BinaryEncoding::Varint)[13..], [0xac, 0x02]);
+        assert_eq!(encode_binary(&[u64::MAX], BinaryEncoding::Varint).len(), 13 + 10);
+    }
+
+    // Test function for `decode_binary` (round trip in both encodings, then damaged files).
+    #[test]
+    fn test_decode_binary() {
+        let sequence = vec![27, 82, 41, 124, 0, 127, 128, 16_383, 16_384, u64::MAX, 1];
+        for encoding in BinaryEncoding::ALL {
+            assert_eq!(decode_binary(&encode_binary(&sequence, encoding)).unwrap(), sequence);
+        }
+
+        assert!(decode_binary(b"garbage").is_err());
+
+        let mut truncated = encode_binary(&[27, 82, 41], BinaryEncoding::U64);
+        truncated.pop();
+        assert!(decode_binary(&truncated).is_err());
+
+        let mut unfinished = encode_binary(&[200], BinaryEncoding::Varint);
+        unfinished.pop();
+        assert!(decode_binary(&unfinished).is_err());
+
+        let mut unknown = encode_binary(&[1], BinaryEncoding::U64);
+        unknown[4] = 9;
+        assert!(decode_binary(&unknown).is_err());
+    }
+
+    // Test function for `parse_sequence`.
+    #[test]
+    fn test_parse_sequence() {
+        let expected = vec![6, 3, 10, 5, 16, 8, 4, 2, 1];
+
+        // CSV: one term per line, one line, or "step,term" rows with a header.
+        assert_eq!(parse_sequence(b"6\n3\n10\n5\n16\n8\n4\n2\n1\n").unwrap(), expected);
+        assert_eq!(parse_sequence(b"6,3,10,5,16,8,4,2,1").unwrap(), expected);
+        let rows = "step,value\n0,6\n1,3\n2,10\n3,5\n4,16\n5,8\n6,4\n7,2\n8,1\n";
+        assert_eq!(parse_sequence(rows.as_bytes()).unwrap(), expected);
+
+        // JSON: a bare array, or an array in an object; big numbers may be strings.
+        assert_eq!(parse_sequence(b"[6, 3, 10, 5, 16, 8, 4, 2, 1]").unwrap(), expected);
+        assert_eq!(parse_sequence(b"{\"start\": 6, \"sequence\": [6,3,10,5,16,8,4,2,1]}").unwrap(), expected);
+        assert_eq!(parse_sequence(b"[\"18446744073709551615\"]").unwrap(), vec![u64::MAX]);
+
+        // Binary.
+        assert_eq!(parse_sequence(&encode_binary(&expected, BinaryEncoding::Varint)).unwrap(), expected);
+
+        // Errors: empty file, terms beyond 64 bits, text that is not a number.
+        assert!(parse_sequence(b"").is_err());
+        assert_eq!(
+            parse_sequence(b"[18446744073709551616]").unwrap_err(),
+            "The term 18446744073709551616 does not fit in 64 bits"
+        );
+        assert!(parse_sequence(b"6\nabc\n3").is_err());
+        assert!(parse_sequence(b"[6, 3, 10").is_err());
+        assert!(parse_sequence(b"{\"start\": 6}").is_err());
+    }
+}
@@ -0,0 +1,365 @@
+// File sequence_table.rs
+// This module contains the sequence table: the terms of a sequence one per row (step, value, parity, bit length...),
+// shown in a panel below the chart, with the columns chosen by the user. Quick filters hide the rows of no interest; they only look at the stored
+// sequence, nothing is computed again. Rows are selected by clicking one and shift-clicking another,
+// and the selected range of steps (its rows left by the filters) can be copied in one of the text formats below,
+// in which the whole table can also be exported as displayed.
+
+use std::ops::RangeInclusive;
+
+/// Largest number of rows shown in the table (the first steps of longer sequences).
+pub const MAX_TABLE_ROWS: usize = 2000;
+
+/// Columns of the sequence table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Step,      // Number of steps from the starting value
+    Value,     // The term
+    Parity,    // "even" or "odd"
+    Delta,     // Change from the previous term (blank for the starting value)
+    BitLength, // Number of binary digits of the term
+    OddCount,  // Number of odd terms up to this one (included)
+}
+
+/// Columns shown by default: enough to read the trajectory; the others are for analysis.
+pub const DEFAULT_COLUMNS: [Column; 3] = [Column::Step, Column::Value, Column::Parity];
+
+impl Column {
+    /// Every column, in the order of the table.
+    pub const ALL: [Column; 6] =
+        [Column::Step, Column::Value, Column::Parity, Column::Delta, Column::BitLength, Column::OddCount];
+
+    /// Returns the title of the column (also its key in the JSON format and in the settings).
+    pub fn header(self) -> &'static str {
+        match self {
+            Column::Step => "step",
+            Column::Value => "value",
+            Column::Parity => "parity",
+            Column::Delta => "delta",
+            Column::BitLength => "bits",
+            Column::OddCount => "odd_count",
+        }
+    }
+
+    /// Returns the name of the column in the column chooser.
+    pub fn label(self) -> &'static str {
+        match self {
+            Column::Step => "Step",
+            Column::Value => "Value",
+            Column::Parity => "Parity",
+            Column::Delta => "Delta",
+            Column::BitLength => "Bit length",
+            Column::OddCount => "Odd count",
+        }
+    }
+
+    /// Returns the cell of the column for the term at `step`.
+    ///
+    /// # Arguments
+    /// * `sequence` - The terms of the sequence.
+    /// * `step` - Step of the term.
+    /// * `odd_counts` - Number of odd terms up to each step (see `odd_counts`), read by `OddCount` only.
+    fn cell(self, sequence: &[u64], step: usize, odd_counts: &[usize]) -> String {
+        let value = sequence[step];
+        match self {
+            Column::Step => step.to_string(),
+            Column::Value => value.to_string(),
+            Column::Parity => if value.is_multiple_of(2) { "even" } else { "odd" }.to_string(),
+            Column::Delta => match step.checked_sub(1) {
+                Some(previous) => (value as i128 - sequence[previous] as i128).to_string(),
+                None => String::new(),
+            },
+            Column::BitLength => (u64::BITS - value.leading_zeros()).to_string(),
+            Column::OddCount => odd_counts[step].to_string(),
+        }
+    }
+
+    /// Tells whether the cells of the column are numbers (unquoted in JSON).
+    fn is_numeric(self) -> bool {
+        self != Column::Parity
+    }
+}
+
+/// Quick filter of the table rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    Odd,          // Odd terms only
+    AboveStart,   // Terms above the starting value
+    LocalMaximum, // Terms above both their neighbors (peaks of the trajectory)
+}
+
+impl Filter {
+    /// Every filter, in the order shown.
+    pub const ALL: [Filter; 3] = [Filter::Odd, Filter::AboveStart, Filter::LocalMaximum];
+
+    /// Returns the label of the filter in the table panel.
+    pub fn label(self) -> &'static str {
+        match self {
+            Filter::Odd => "Odd only",
+            Filter::AboveStart => "Above start",
+            Filter::LocalMaximum => "Local maxima",
+        }
+    }
+
+    /// Tells whether the row of `step` passes the filter.
+    /// A term at either end of the sequence has a single neighbor to be compared with.
+    pub fn keeps(self, sequence: &[u64], step: usize) -> bool {
+        let value = sequence[step];
+        match self {
+            Filter::Odd => value % 2 == 1,
+            Filter::AboveStart => value > sequence[0],
+            Filter::LocalMaximum => {
+                let below = |neighbor: Option<&u64>| neighbor.is_none_or(|&neighbor| neighbor < value);
+                below(step.checked_sub(1).and_then(|previous| sequence.get(previous))) && below(sequence.get(step + 1))
+            }
+        }
+    }
+}
+
+/// Returns the steps of the rows passing every filter (all of them without filters), in order.
+pub fn filtered_steps(sequence: &[u64], filters: &[Filter]) -> Vec<usize> {
+    (0..sequence.len()).filter(|&step| filters.iter().all(|filter| filter.keeps(sequence, step))).collect()
+}
+
+/// Returns the chosen columns, written in the settings: their keys separated by commas.
+pub fn format_columns(columns: &[Column]) -> String {
+    columns.iter().map(|column| column.header()).collect::<Vec<_>>().join(",")
+}
+
+/// Reads columns written by `format_columns`. Unknown keys are skipped; the columns are put in the order of
+/// the table, and the default ones are returned if none is left.
+pub fn parse_columns(text: &str) -> Vec<Column> {
+    let keys: Vec<&str> = text.split(',').map(str::trim).collect();
+    let columns: Vec<Column> = Column::ALL.into_iter().filter(|column| keys.contains(&column.header())).collect();
+    if columns.is_empty() {
+        DEFAULT_COLUMNS.to_vec()
+    } else {
+        columns
+    }
+}
+
+/// Returns the number of odd terms up to each step of a sequence (included).
+fn odd_counts(sequence: &[u64]) -> Vec<usize> {
+    sequence
+        .iter()
+        .scan(0, |count, &value| {
+            *count += (value % 2) as usize;
+            Some(*count)
+        })
+        .collect()
+}
+
+/// Returns the cells of the rows of some steps (those past the end of the sequence are left out).
+///
+/// # Arguments
+/// * `sequence` - The terms of the sequence.
+/// * `steps` - Steps of the rows, in order.
+/// * `columns` - Columns of the cells, in order.
+pub fn rows(sequence: &[u64], steps: &[usize], columns: &[Column]) -> Vec<Vec<String>> {
+    // The running count of odd terms is only needed by its column.
+    let odd_counts = if columns.contains(&Column::OddCount) { odd_counts(sequence) } else { Vec::new() };
+    steps
+        .iter()
+        .filter(|&&step| step < sequence.len())
+        .map(|&step| columns.iter().map(|column| column.cell(sequence, step, &odd_counts)).collect())
+        .collect()
+}
+
+/// Format of the copied rows and of the exported table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CopyFormat {
+    #[default]
+    Tsv,      // Tab-separated, with a header line (pasted as cells by spreadsheets)
+    Csv,      // Comma-separated, with a header line
+    Json,     // Array of objects, one per row
+    Markdown, // Table of Markdown documents
+}
+
+impl CopyFormat {
+    /// Every format, in the order shown in the selector.
+    pub const ALL: [CopyFormat; 4] = [CopyFormat::Tsv, CopyFormat::Csv, CopyFormat::Json, CopyFormat::Markdown];
+
+    /// Returns the extension of the exported files of this format (without the dot).
+    pub fn extension(self) -> &'static str {
+        match self {
+            CopyFormat::Tsv => "tsv",
+            CopyFormat::Csv => "csv",
+            CopyFormat::Json => "json",
+            CopyFormat::Markdown => "md",
+        }
+    }
+}
+
+impl std::fmt::Display for CopyFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            CopyFormat::Tsv => "Tab-separated",
+            CopyFormat::Csv => "CSV",
+            CopyFormat::Json => "JSON",
+            CopyFormat::Markdown => "Markdown",
+        })
+    }
+}
+
+/// Rows selected in the table: a range of steps, started by a click on a row
+/// and extended to another row by a shift-click.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RowSelection {
+    anchor: Option<usize>, // Step of the row clicked last without Shift
+    end: usize,            // Step of the other end of the range
+}
+
+impl RowSelection {
+    /// Records a click on the row of `step`: it alone is selected,
+    /// or, with `extend` (Shift held), every row from the previous click to it.
+    pub fn click(&mut self, step: usize, extend: bool) {
+        if !extend || self.anchor.is_none() {
+            self.anchor = Some(step);
+        }
+        self.end = step;
+    }
+
+    /// Returns the selected steps, in increasing order (None if nothing is selected).
+    pub fn range(self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.anchor?;
+        Some(anchor.min(self.end)..=anchor.max(self.end))
+    }
+
+    /// Tells whether the row of `step` is selected.
+    pub fn contains(self, step: usize) -> bool {
+        self.range().is_some_and(|range| range.contains(&step))
+    }
+}
+
+/// Writes rows of a sequence in a format.
+///
+/// # Arguments
+/// * `sequence` - The terms of the sequence.
+/// * `steps` - Steps of the rows to write, in order (those past the end of the sequence are left out).
+/// * `columns` - Columns to write, in order.
+/// * `format` - Format of the text.
+///
+/// # Returns
+/// * `String` - The rows, headed by the titles of the columns (except in JSON, where they are the keys).
+pub fn format_rows(sequence: &[u64], steps: &[usize], columns: &[Column], format: CopyFormat) -> String {
+    let rows = rows(sequence, steps, columns);
+    let headers: Vec<&str> = columns.iter().map(|column| column.header()).collect();
+    let lines = |separator: &str| {
+        std::iter::once(headers.join(separator))
+            .chain(rows.iter().map(|row| row.join(separator)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    match format {
+        CopyFormat::Tsv => lines("\t"),
+        CopyFormat::Csv => lines(","),
+        CopyFormat::Json => {
+            let objects: Vec<String> = rows
+                .iter()
+                .map(|row| {
+                    let fields: Vec<String> = columns
+                        .iter()
+                        .zip(row)
+                        .map(|(column, cell)| match (column.is_numeric(), cell.is_empty()) {
+                            (true, false) => format!("\"{}\": {}", column.header(), cell),
+                            (true, true) => format!("\"{}\": null", column.header()),
+                            (false, _) => format!("\"{}\": \"{}\"", column.header(), cell),
+                        })
+                        .collect();
+                    format!("  {{{}}}", fields.join(", "))
+                })
+                .collect();
+            if objects.is_empty() {
+                return "[]".to_string();
+            }
+            format!("[\n{}\n]", objects.join(",\n"))
+        }
+        CopyFormat::Markdown => {
+            let rule = vec!["---"; columns.len()].join(" | ");
+            std::iter::once(format!("| {} |", headers.join(" | ")))
+                .chain(std::iter::once(format!("| {} |", rule)))
+                .chain(rows.iter().map(|row| format!("| {} |", row.join(" | "))))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `RowSelection::click`.
+    #[test]
+    fn test_row_selection() {
+        let mut selection = RowSelection::default();
+        assert_eq!(selection.range(), None);
+        selection.click(5, false);
+        assert_eq!(selection.range(), Some(5..=5));
+        // Shift-clicking above the first row selects upwards; a new click starts again.
+        selection.click(2, true);
+        assert_eq!(selection.range(), Some(2..=5));
+        assert!(selection.contains(3) && !selection.contains(6));
+        selection.click(8, false);
+        selection.click(9, true);
+        assert_eq!(selection.range(), Some(8..=9));
+        // Shift-clicking with nothing selected selects the row alone.
+        let mut selection = RowSelection::default();
+        selection.click(4, true);
+        assert_eq!(selection.range(), Some(4..=4));
+    }
+
+    // Test function for `Filter::keeps` and `filtered_steps`.
+    #[test]
+    fn test_filters() {
+        let sequence = [7, 22, 11, 34, 17, 52, 26, 13, 40, 20, 10, 5, 16, 8, 4, 2, 1];
+        assert_eq!(filtered_steps(&sequence, &[Filter::Odd]), vec![0, 2, 4, 7, 11, 16]);
+        assert_eq!(filtered_steps(&sequence, &[Filter::LocalMaximum]), vec![1, 3, 5, 8, 12]);
+        // Filters add up: odd terms above the start.
+        assert_eq!(filtered_steps(&sequence, &[Filter::Odd, Filter::AboveStart]), vec![2, 4, 7]);
+        assert_eq!(filtered_steps(&sequence, &[]).len(), sequence.len());
+        // The ends of the sequence have one neighbor.
+        assert!(Filter::LocalMaximum.keeps(&[9, 28, 14], 1) && Filter::LocalMaximum.keeps(&[5], 0));
+    }
+
+    // Test function for `rows`.
+    #[test]
+    fn test_rows() {
+        let sequence = [3, 10, 5, 16, 8, 4, 2, 1];
+        let cells = rows(&sequence, &[0, 2, 7, 8], &[Column::Delta, Column::OddCount]);
+        // The starting value has no delta; step 8 is past the end.
+        let expected: Vec<Vec<String>> =
+            [["", "1"], ["-5", "2"], ["-1", "3"]].iter().map(|row| row.iter().map(|cell| cell.to_string()).collect()).collect();
+        assert_eq!(cells, expected);
+    }
+
+    // Test function for `format_columns` and `parse_columns`.
+    #[test]
+    fn test_parse_columns() {
+        let columns = [Column::Step, Column::Delta, Column::OddCount];
+        assert_eq!(parse_columns(&format_columns(&columns)), columns.to_vec());
+        // The order of the table is kept, unknown keys are skipped, and nothing known gives the defaults.
+        assert_eq!(parse_columns("bits, step, ratio"), vec![Column::Step, Column::BitLength]);
+        assert_eq!(parse_columns(""), DEFAULT_COLUMNS.to_vec());
+    }
+
+    // Test function for `format_rows`.
+    #[test]
+    fn test_format_rows() {
+        let sequence = [6, 3, 10, 5, 16];
+        assert_eq!(
+            format_rows(&sequence, &[1, 2], &Column::ALL, CopyFormat::Tsv),
+            "step\tvalue\tparity\tdelta\tbits\todd_count\n1\t3\todd\t-3\t2\t1\n2\t10\teven\t7\t4\t1",
+        );
+        assert_eq!(format_rows(&sequence, &[3, 4, 9], &[Column::Value], CopyFormat::Csv), "value\n5\n16");
+        assert_eq!(
+            format_rows(&sequence, &[0], &[Column::Step, Column::Parity, Column::Delta], CopyFormat::Json),
+            "[\n  {\"step\": 0, \"parity\": \"even\", \"delta\": null}\n]",
+        );
+        assert_eq!(
+            format_rows(&sequence, &[4], &[Column::Step, Column::Value], CopyFormat::Markdown),
+            "| step | value |\n| --- | --- |\n| 4 | 16 |",
+        );
+    }
+}
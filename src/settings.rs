@@ -0,0 +1,103 @@
+// File settings.rs
+// This module contains the user settings kept between runs, e.g. the columns chosen for the sequence table.
+// They are stored in the application's data directory as a text file of "key=value" lines,
+// which other versions of the application read without knowing every key: unknown keys are kept as they are.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Key of the columns of the sequence table (see `sequence_table::format_columns`).
+pub const TABLE_COLUMNS: &str = "table.columns";
+
+/// Key of the "Store results" checkbox, "true" when the results database is enabled (see `results_db`).
+pub const STORE_RESULTS: &str = "results.store";
+
+/// Key of the number of worker threads of the parallel computations, "all" or a number (see `workers`).
+pub const WORKER_THREADS: &str = "workers.threads";
+
+/// Key of the memory budget of the stored sequences, in megabytes (see `sequence_budget`).
+pub const MEMORY_BUDGET: &str = "memory.budget";
+
+/// Returns the location of the settings file, in the application's data directory.
+pub fn default_path() -> PathBuf {
+    crate::cache::data_dir().join("settings.txt")
+}
+
+/// Settings read from the settings file: a value per key, in the order of the file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserSettings {
+    entries: Vec<(String, String)>, // (key, value) of each setting
+}
+
+impl UserSettings {
+    /// Reads settings written by `to_text`. Lines without "=" (blank lines, comments) are skipped.
+    pub fn parse(text: &str) -> Self {
+        let entries = text
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect();
+        UserSettings { entries }
+    }
+
+    /// Writes the settings, one "key=value" line each.
+    pub fn to_text(&self) -> String {
+        self.entries.iter().map(|(key, value)| format!("{}={}\n", key, value)).collect()
+    }
+
+    /// Returns the value of a key, if it is set.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, value)| value.as_str())
+    }
+
+    /// Sets the value of a key, in place if it is already set.
+    pub fn set(&mut self, key: &str, value: String) {
+        match self.entries.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key.to_string(), value)),
+        }
+    }
+}
+
+/// Reads the settings file. A missing or unreadable file gives no settings: the defaults apply.
+pub fn load(path: &Path) -> UserSettings {
+    fs::read_to_string(path).map(|text| UserSettings::parse(&text)).unwrap_or_default()
+}
+
+/// Writes the settings file, creating the data directory if needed.
+pub fn save(path: &Path, settings: &UserSettings) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, settings.to_text())
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    // Test function for `UserSettings::parse` and `UserSettings::set`.
+    #[test]
+    fn test_user_settings() {
+        let mut settings = UserSettings::parse("# comment\ntable.columns = step,value\nother=kept\n");
+        assert_eq!(settings.get(TABLE_COLUMNS), Some("step,value"));
+        settings.set(TABLE_COLUMNS, "step".to_string());
+        settings.set("new", "1".to_string());
+        // Unknown keys are written back, in their place.
+        assert_eq!(settings.to_text(), "table.columns=step\nother=kept\nnew=1\n");
+        assert_eq!(settings.get("missing"), None);
+    }
+
+    // Test function for `save` and `load`.
+    #[test]
+    fn test_save_load() {
+        let temp = TempPath::new("collatz_settings_test");
+        assert_eq!(load(temp.path()), UserSettings::default());
+        let settings = UserSettings::parse("table.columns=step,bits");
+        save(temp.path(), &settings).unwrap();
+        assert_eq!(load(temp.path()), settings);
+    }
+}
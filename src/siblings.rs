@@ -0,0 +1,85 @@
+// File siblings.rs
+// This module contains the sibling finder: starting values whose trajectories merge with the one of n
+// within a budget of steps. Rather than computing the trajectory of every value below the bound,
+// it walks the reverse Collatz map (`collatz::predecessors`) back from the first terms of the trajectory of n,
+// so that only the values which do merge are ever visited.
+
+use crate::collatz;
+
+/// Largest budget of steps of a search. The reverse map branches, so the number of values visited
+/// grows by about 4/3 per step: 30 steps visit some hundred thousand values.
+pub const MAX_SIBLING_STEPS: usize = 30;
+
+/// A starting value whose trajectory merges with the one of n.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sibling {
+    pub value: u64,       // Starting value of the sibling
+    pub merge_value: u64, // First term of the sibling's trajectory that is a term of the trajectory of n
+    pub steps: usize,     // Steps taken by the sibling to reach the merge value
+    pub n_steps: usize,   // Steps taken by n to reach the merge value
+}
+
+/// Finds the starting values below `bound` whose trajectories merge with the one of `n` within `steps` steps:
+/// both the sibling and n reach the merge value in at most `steps` steps.
+/// The terms of the trajectory of n are not siblings (they are n itself, a few steps later).
+///
+/// # Arguments
+/// * `n` - Starting value whose siblings are searched.
+/// * `steps` - Budget of steps (at most `MAX_SIBLING_STEPS`).
+/// * `bound` - The siblings are below this value.
+///
+/// # Returns
+/// * `Vec<Sibling>` - The siblings, in increasing order of value.
+pub fn find_siblings(n: u64, steps: usize, bound: u64) -> Vec<Sibling> {
+    let steps = steps.min(MAX_SIBLING_STEPS);
+    let trajectory = collatz::generate_sequence_limited(n, steps);
+    let mut siblings = Vec::new();
+    for (n_steps, &merge_value) in trajectory.iter().enumerate() {
+        // The predecessor on the trajectory of n leads back to the earlier terms, searched on their own.
+        let previous = n_steps.checked_sub(1).map(|index| trajectory[index]);
+        let mut frontier: Vec<u64> = collatz::predecessors(merge_value)
+            .into_iter()
+            .filter(|&value| Some(value) != previous)
+            .collect();
+        for depth in 1..=steps {
+            siblings.extend(
+                frontier
+                    .iter()
+                    .filter(|&&value| value < bound)
+                    .map(|&value| Sibling { value, merge_value, steps: depth, n_steps }),
+            );
+            frontier = frontier.into_iter().flat_map(collatz::predecessors).collect();
+        }
+    }
+    siblings.sort_by_key(|sibling| sibling.value);
+    siblings
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `find_siblings`.
+    #[test]
+    fn test_find_siblings() {
+        // 6 -> 3 -> 10 -> 5: 12, 24, 48 reach 6; 20, 40, 80 and 13 -> 40 -> 20 reach 10.
+        let siblings = find_siblings(6, 3, 100);
+        let values: Vec<u64> = siblings.iter().map(|sibling| sibling.value).collect();
+        assert_eq!(values, vec![12, 13, 20, 24, 40, 48, 80]);
+        let thirteen = siblings[1];
+        assert_eq!((thirteen.merge_value, thirteen.steps, thirteen.n_steps), (10, 3, 2));
+
+        // Every sibling does merge within the budget.
+        for sibling in find_siblings(27, 12, 10_000) {
+            let trajectory = collatz::generate_sequence(sibling.value);
+            assert_eq!(trajectory[sibling.steps], sibling.merge_value);
+            assert_eq!(collatz::generate_sequence(27)[sibling.n_steps], sibling.merge_value);
+            assert!(sibling.steps <= 12 && sibling.n_steps <= 12 && sibling.value < 10_000);
+        }
+
+        // The bound and the budget limit the search.
+        assert!(find_siblings(6, 3, 12).is_empty());
+        assert_eq!(find_siblings(6, 0, 100), Vec::new());
+    }
+}
@@ -0,0 +1,341 @@
+// File stats_grid.rs
+// This module contains the structured statistics of the sequences, shown as a grid below the chart.
+// Each sequence gives a group of label/value rows, so that every value can be shown in a cell
+// of its own (and copied on its own), while the same rows still make the plain text written
+// under the chart by "Export view".
+
+use crate::collatz::{self, AutoStoppingTime, CollatzStats, PathCompression, SharedTail, TailHandling, WideTrajectory};
+
+/// One statistic of a sequence: a row of the grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatRow {
+    pub label: &'static str, // Name of the statistic, e.g. "Flight time"
+    pub value: String,       // The value alone, as copied to the clipboard
+    pub unit: &'static str,  // Unit shown after the value ("steps"), empty if none
+    pub help: &'static str,  // Key of the explanation of the statistic in the i18n catalog
+}
+
+impl StatRow {
+    /// Returns the value as displayed in its cell, with its unit.
+    pub fn display_value(&self) -> String {
+        if self.unit.is_empty() {
+            self.value.clone()
+        } else {
+            format!("{} {}", self.value, self.unit)
+        }
+    }
+}
+
+/// The statistics of one sequence: a title and its rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatGroup {
+    pub title: String,     // E.g. "Statistics for 27"
+    pub rows: Vec<StatRow>, // The statistics, in display order
+}
+
+/// Builds the rows of the statistics of a sequence.
+///
+/// # Arguments
+/// * `stats` - Statistics computed by `collatz::calculate_stats`.
+///
+/// # Returns
+/// * `Vec<StatRow>` - One row per statistic, in display order.
+pub fn sequence_rows(stats: &CollatzStats) -> Vec<StatRow> {
+    let row = |label, value: usize, unit, help| StatRow { label, value: value.to_string(), unit, help };
+    vec![
+        row("Flight time", stats.length - 1, "steps", "stat.flight_time.help"),
+        StatRow {
+            label: "Maximum altitude",
+            value: stats.max_value.to_string(),
+            unit: "",
+            help: "stat.max_altitude.help",
+        },
+        row("Step of the maximum", stats.max_value_index, "", "stat.max_step.help"),
+        row("Even values", stats.even_count, "", "stat.even_count.help"),
+        row("Odd values", stats.odd_count, "", "stat.odd_count.help"),
+        row("Downtime", stats.stopping_time, "steps", "stat.downtime.help"),
+        row("Maximum bit length", stats.max_bit_length as usize, "bits", "stat.max_bit_length.help"),
+        StatRow {
+            label: "Average bit length",
+            value: format!("{:.2}", stats.average_bit_length),
+            unit: "bits",
+            help: "stat.average_bit_length.help",
+        },
+        row("Step of the bit-length peak", stats.max_bit_length_index, "", "stat.bit_length_step.help"),
+    ]
+}
+
+/// Builds the row pointing out a sequence cut at the step limit, shown first in its group.
+pub fn truncated_row(step_limit: usize) -> StatRow {
+    StatRow { label: "Truncated at", value: step_limit.to_string(), unit: "steps", help: "stat.truncated.help" }
+}
+
+/// Builds the row pointing out a sequence kept downsampled to fit the memory budget, shown first in its group:
+/// the statistics are those of the whole sequence, but only one term in `stride` is kept for the chart.
+pub fn downsampled_row(stride: usize) -> StatRow {
+    StatRow { label: "Downsampled, kept", value: format!("1 term in {}", stride), unit: "", help: "stat.downsampled.help" }
+}
+
+/// Builds the row pointing out a sequence of the glide-only mode, shown first in its group:
+/// the sequence stops once it drops below its start, so the other statistics only describe its glide.
+///
+/// # Arguments
+/// * `sequence` - The glide, as computed by `collatz::generate_glide_limited` (not cut at the step limit).
+pub fn glide_row(sequence: &[u64]) -> StatRow {
+    let value = match (sequence.first(), sequence.last()) {
+        (Some(start), Some(last)) if last < start => format!("below {} at step {}", start, sequence.len() - 1),
+        _ => "never below the start".to_string(),
+    };
+    StatRow { label: "Glide only, stopped", value, unit: "", help: "stat.glide_only.help" }
+}
+
+/// Builds the row telling how the end of a sequence that reached 1 is shown, if it is not stopped at 1.
+/// The other statistics are those of the trajectory itself, whatever its tail on the chart.
+///
+/// # Arguments
+/// * `handling` - How the tail is shown.
+/// * `cycles` - Number of 4-2-1 cycles appended.
+/// * `sequence` - The sequence as shown (after the handling).
+pub fn tail_row(handling: TailHandling, cycles: usize, sequence: &[u64]) -> Option<StatRow> {
+    let value = match handling {
+        TailHandling::StopAtOne => return None,
+        TailHandling::RepeatCycle => format!("4-2-1 cycle repeated {} times", cycles),
+        TailHandling::TrimDescent => format!("descent from {} trimmed", sequence.last()?),
+    };
+    Some(StatRow { label: "Tail shown", value, unit: "", help: "stat.tail.help" })
+}
+
+/// Builds the rows reporting the arithmetic a trajectory needed (see `collatz::total_stopping_time_auto`).
+///
+/// # Arguments
+/// * `auto` - Total stopping time of the starting value, with its arithmetic.
+/// * `overflowed` - True if the sequence itself stopped before 1, because a term did not fit in 64 bits
+///   or because only its glide was computed: its full flight time is then given by the arithmetic.
+pub fn arithmetic_rows(auto: &AutoStoppingTime, overflowed: bool) -> Vec<StatRow> {
+    let mut value = match (auto.promoted_at, auto.big_at) {
+        (None, _) => auto.arithmetic.to_string(),
+        (Some(step), None) => format!("{} from step {}", auto.arithmetic, step),
+        (Some(step), Some(big)) => format!("{} from step {} (128-bit from step {})", auto.arithmetic, big, step),
+    };
+    if auto.steps.is_none() && auto.promoted_at.is_some() {
+        value.push_str(&format!(", stopped beyond {} bits", collatz::MAX_BIG_BITS));
+    }
+    let mut rows = vec![StatRow { label: "Arithmetic", value, unit: "", help: "stat.arithmetic.help" }];
+    if let (true, Some(steps)) = (overflowed, auto.steps) {
+        rows.push(StatRow {
+            label: "Full flight time",
+            value: steps.to_string(),
+            unit: "steps",
+            help: "stat.full_flight_time.help",
+        });
+    }
+    rows
+}
+
+/// Builds the rows of the whole trajectory of a sequence stopped by an overflow, followed beyond 64 bits
+/// by `collatz::wide_trajectory`: its maximum, which did not fit in the sequence, and where it is reached.
+pub fn full_trajectory_rows(wide: &WideTrajectory) -> Vec<StatRow> {
+    let row = |label, value: String, unit| StatRow { label, value, unit, help: "stat.full_trajectory.help" };
+    let mut rows = vec![
+        row("Full maximum altitude", wide.peak.to_string(), ""),
+        row("Step of the full maximum", wide.peak_step.to_string(), ""),
+        row("Full maximum bit length", wide.max_bits.to_string(), "bits"),
+        row("Full odd values", wide.odd_count.to_string(), ""),
+    ];
+    if !wide.reached_one {
+        rows.push(row("Full trajectory stopped at", wide.steps.to_string(), "steps"));
+    }
+    rows
+}
+
+/// Builds the rows comparing two sequences: how much of their paths they share.
+pub fn comparison_rows(compression: &PathCompression) -> Vec<StatRow> {
+    let percent = |label, share: f64, help| StatRow { label, value: format!("{:.1}", share * 100.0), unit: "%", help };
+    vec![
+        StatRow {
+            label: "Distinct values",
+            value: compression.distinct_values.to_string(),
+            unit: "",
+            help: "stat.distinct_values.help",
+        },
+        StatRow {
+            label: "Shared values",
+            value: compression.shared_values.to_string(),
+            unit: "",
+            help: "stat.shared_values.help",
+        },
+        percent("Path compression", compression.compression(), "stat.compression.help"),
+        percent("Overlap of the first", compression.first_overlap(), "stat.first_overlap.help"),
+        percent("Overlap of the second", compression.second_overlap(), "stat.second_overlap.help"),
+    ]
+}
+
+/// Builds the rows of the family mode: the tail shared by n and its doublings,
+/// and how many halving steps each of them takes before joining it.
+pub fn family_rows(tail: &SharedTail) -> Vec<StatRow> {
+    let offsets: Vec<String> = tail.offsets.iter().map(usize::to_string).collect();
+    vec![
+        StatRow {
+            label: "Family members",
+            value: tail.offsets.len().to_string(),
+            unit: "",
+            help: "stat.family_members.help",
+        },
+        StatRow {
+            label: "Shared tail",
+            value: tail.terms.to_string(),
+            unit: "terms",
+            help: "stat.shared_tail.help",
+        },
+        StatRow {
+            label: "Steps before the tail",
+            value: offsets.join(", "),
+            unit: "steps",
+            help: "stat.tail_offsets.help",
+        },
+    ]
+}
+
+/// Writes groups of statistics as plain text: the title of each group, then one "Label: value" line per row,
+/// with a blank line between groups.
+pub fn to_text(groups: &[StatGroup]) -> String {
+    groups
+        .iter()
+        .map(|group| {
+            let mut lines = vec![format!("{}:", group.title)];
+            lines.extend(group.rows.iter().map(|row| format!("{}: {}", row.label, row.display_value())));
+            lines.join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collatz;
+    use crate::i18n;
+
+    // Test function for `sequence_rows`.
+    #[test]
+    fn test_sequence_rows() {
+        // 6 -> 3 -> 10 -> 5 -> 16 -> 8 -> 4 -> 2 -> 1
+        let rows = sequence_rows(&collatz::calculate_stats(&collatz::generate_sequence(6)));
+        let find = |label| rows.iter().find(|row| row.label == label).unwrap();
+        assert_eq!(find("Flight time").value, "8");
+        assert_eq!(find("Flight time").display_value(), "8 steps");
+        assert_eq!(find("Maximum altitude").display_value(), "16");
+        assert_eq!(find("Step of the maximum").value, "4");
+        assert_eq!(find("Maximum bit length").display_value(), "5 bits");
+        assert_eq!(find("Average bit length").value, "3.00");
+        // Every statistic is explained in the catalog.
+        for row in &rows {
+            assert_ne!(i18n::text(i18n::Language::English, row.help), row.help);
+        }
+    }
+
+    // Test function for `comparison_rows`.
+    #[test]
+    fn test_comparison_rows() {
+        let compression = collatz::path_compression(&collatz::generate_sequence(6), &collatz::generate_sequence(20));
+        let rows = comparison_rows(&compression);
+        assert_eq!(rows[1].display_value(), "7");
+        assert_eq!(rows[4].display_value(), "87.5 %");
+        for row in &rows {
+            assert_ne!(i18n::text(i18n::Language::English, row.help), row.help);
+        }
+    }
+
+    // Test function for `family_rows`.
+    #[test]
+    fn test_family_rows() {
+        let family: Vec<Vec<u64>> = [6, 12, 24].iter().map(|&value| collatz::generate_sequence(value)).collect();
+        let sequences: Vec<&[u64]> = family.iter().map(Vec::as_slice).collect();
+        let rows = family_rows(&collatz::shared_tail(&sequences));
+        assert_eq!(rows[0].display_value(), "3");
+        assert_eq!(rows[1].display_value(), "9 terms");
+        assert_eq!(rows[2].display_value(), "0, 1, 2 steps");
+        for row in &rows {
+            assert_ne!(i18n::text(i18n::Language::English, row.help), row.help);
+        }
+    }
+
+    // Test function for `glide_row`.
+    #[test]
+    fn test_glide_row() {
+        let row = glide_row(&collatz::generate_glide_limited(7, collatz::DEFAULT_STEP_LIMIT));
+        assert_eq!(row.display_value(), "below 7 at step 11");
+        assert_eq!(glide_row(&[1]).display_value(), "never below the start");
+        assert_ne!(i18n::text(i18n::Language::English, row.help), row.help);
+    }
+
+    // Test function for `downsampled_row`.
+    #[test]
+    fn test_downsampled_row() {
+        let row = downsampled_row(64);
+        assert_eq!(row.display_value(), "1 term in 64");
+        assert_ne!(i18n::text(i18n::Language::English, row.help), row.help);
+    }
+
+    // Test function for `tail_row`.
+    #[test]
+    fn test_tail_row() {
+        let mut sequence = collatz::generate_sequence(6);
+        assert_eq!(tail_row(TailHandling::StopAtOne, 2, &sequence), None);
+        let row = tail_row(TailHandling::RepeatCycle, 2, &sequence).unwrap();
+        assert_eq!(row.display_value(), "4-2-1 cycle repeated 2 times");
+        TailHandling::TrimDescent.apply(&mut sequence, 0);
+        let row = tail_row(TailHandling::TrimDescent, 0, &sequence).unwrap();
+        assert_eq!(row.display_value(), "descent from 16 trimmed");
+        assert_ne!(i18n::text(i18n::Language::English, row.help), row.help);
+    }
+
+    // Test function for `arithmetic_rows`.
+    #[test]
+    fn test_arithmetic_rows() {
+        let rows = arithmetic_rows(&collatz::total_stopping_time_auto(27), false);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].display_value(), "64-bit");
+
+        // 2^64 - 1 overflows a u64 sequence at once: the flight time comes from 128 bits.
+        let auto = collatz::total_stopping_time_auto(u64::MAX);
+        let rows = arithmetic_rows(&auto, true);
+        assert_eq!(rows[0].display_value(), "128-bit from step 0");
+        assert_eq!(rows[1].value, auto.steps.unwrap().to_string());
+        for row in &rows {
+            assert_ne!(i18n::text(i18n::Language::English, row.help), row.help);
+        }
+    }
+
+    // Test function for `full_trajectory_rows`.
+    #[test]
+    fn test_full_trajectory_rows() {
+        let rows = full_trajectory_rows(&collatz::wide_trajectory(u64::MAX, 10_000));
+        assert_eq!(rows.len(), 4);
+        // The maximum does not fit in 64 bits.
+        assert!(rows[0].value.len() > u64::MAX.to_string().len());
+        assert_ne!(i18n::text(i18n::Language::English, rows[0].help), rows[0].help);
+        let rows = full_trajectory_rows(&collatz::wide_trajectory(u64::MAX, 5));
+        assert_eq!(rows.last().unwrap().display_value(), "5 steps");
+    }
+
+    // Test function for `to_text`.
+    #[test]
+    fn test_to_text() {
+        let group = |title: &str, value: &str| StatGroup {
+            title: title.to_string(),
+            rows: vec![StatRow {
+                label: "Flight time",
+                value: value.to_string(),
+                unit: "steps",
+                help: "stat.flight_time.help",
+            }],
+        };
+        assert_eq!(
+            to_text(&[group("Statistics for 6", "8"), group("Statistics for 7", "16")]),
+            "Statistics for 6:\nFlight time: 8 steps\n\nStatistics for 7:\nFlight time: 16 steps"
+        );
+        assert_eq!(to_text(&[]), "");
+    }
+}
@@ -0,0 +1,87 @@
+// File summary.rs
+// This module contains the "accessible summary": a short description of what the chart shows,
+// written in sentences from the sequences themselves rather than from the image,
+// so that it can be read by a screen reader or pasted where the chart cannot go.
+
+use crate::collatz::CollatzStats;
+
+/// Describes the trajectory of one sequence in a sentence or two: where it starts, its peak,
+/// how it ends, and the share of odd terms.
+///
+/// # Arguments
+/// * `label` - The value as shown elsewhere, e.g. "27" or "27 (Example)".
+/// * `first`, `last` - First and last terms of the whole sequence.
+/// * `stats` - Statistics of the whole sequence (of every term, even if the sequence is kept downsampled).
+///
+/// # Returns
+/// * `String` - E.g. "The sequence of 27 climbs from 27 to a peak of 9232 at step 77, then falls back to 1
+///   after 111 steps. ..."
+pub fn describe_trajectory(label: &str, first: u64, last: u64, stats: &CollatzStats) -> String {
+    if stats.length == 0 {
+        return format!("The sequence of {} is empty.", label);
+    }
+    let steps = stats.length - 1;
+    let course = if stats.max_value_index == 0 {
+        format!("starts at its highest value, {}, and", first)
+    } else {
+        format!("climbs from {} to a peak of {} at step {}, then", first, stats.max_value, stats.max_value_index)
+    };
+    let ending = if last == 1 {
+        format!("falls back to 1 after {} steps", steps)
+    } else {
+        format!("is stopped at {} after {} steps, before reaching 1", last, steps)
+    };
+    format!(
+        "The sequence of {} {} {}. {} of its {} terms are odd ({:.0}%); it first drops below its start at step {}.",
+        label,
+        course,
+        ending,
+        stats.odd_count,
+        stats.length,
+        stats.odd_count as f64 / stats.length as f64 * 100.0,
+        stats.stopping_time,
+    )
+}
+
+/// Describes the axes of the sequence chart.
+///
+/// # Arguments
+/// * `y_label` - What the vertical axis shows, e.g. "Value".
+/// * `log_scale` - Whether the vertical axis is logarithmic.
+pub fn describe_sequence_axes(y_label: &str, log_scale: bool) -> String {
+    format!(
+        "Line chart: the step on the horizontal axis, the {} on the vertical axis{}.",
+        y_label.to_lowercase(),
+        if log_scale { " (logarithmic scale)" } else { "" },
+    )
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collatz;
+
+    // Test function for `describe_trajectory`.
+    #[test]
+    fn test_describe_trajectory() {
+        let sequence = collatz::generate_sequence(27);
+        let text = describe_trajectory("27", 27, sequence[sequence.len() - 1], &collatz::calculate_stats(&sequence));
+        assert!(text.starts_with("The sequence of 27 climbs from 27 to a peak of 9232 at step 77"), "{}", text);
+        assert!(text.contains("falls back to 1 after 111 steps"));
+
+        // A sequence cut before 1 says where it stopped.
+        let sequence = collatz::generate_sequence_limited(27, 10);
+        let text = describe_trajectory("27", 27, sequence[sequence.len() - 1], &collatz::calculate_stats(&sequence));
+        assert!(text.contains("is stopped at 214 after 10 steps"), "{}", text);
+    }
+
+    // Test function for `describe_sequence_axes`.
+    #[test]
+    fn test_describe_sequence_axes() {
+        assert_eq!(
+            describe_sequence_axes("Value", true),
+            "Line chart: the step on the horizontal axis, the value on the vertical axis (logarithmic scale).",
+        );
+    }
+}
@@ -0,0 +1,34 @@
+// File test_support.rs
+// This module contains the helpers shared by the unit tests of the other modules.
+// It is only compiled for the tests.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A file of the system temporary directory used by a test, deleted when the test ends (even if it fails).
+pub struct TempPath {
+    path: PathBuf, // Location of the file, unique to the test and to the process
+}
+
+impl TempPath {
+    /// Returns a path of the temporary directory for a test, after deleting any file left there by a previous run.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the file, unique among the tests (e.g. "collatz_settings_test").
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("{}_{}", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        TempPath { path }
+    }
+
+    /// Returns the location of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
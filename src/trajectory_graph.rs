@@ -0,0 +1,93 @@
+// File trajectory_graph.rs
+// This module contains the layout of the merged trajectory graph (see `collatz::merged_graph`) for its chart.
+// The graph is a tree hanging from 1 (each term has a single successor), so it is drawn as one:
+// each node sits at the height of its distance to the root, the leaves (the starting values and
+// the terms nothing leads to) side by side, and every other node centered above the nodes leading to it.
+// The graph can also be exported as a CSV edge list, for graph tools such as Gephi or networkx.
+
+use crate::collatz::TrajectoryGraph;
+use std::collections::BTreeMap;
+
+/// Position of a node on the chart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodePosition {
+    pub x: f64,       // Horizontal position, in leaf slots (the first leaf is at 0)
+    pub level: usize, // Number of edges between the node and its root (the root is at 0)
+}
+
+/// Places the nodes of the graph as a tree.
+/// A trajectory stopped before reaching 1 (step limit, overflow) ends on its own root, placed beside the others.
+///
+/// # Returns
+/// * `BTreeMap<u64, NodePosition>` - The position of every node.
+pub fn layout(graph: &TrajectoryGraph) -> BTreeMap<u64, NodePosition> {
+    let mut predecessors: BTreeMap<u64, Vec<u64>> = BTreeMap::new();
+    for (&term, &next) in &graph.successors {
+        predecessors.entry(next).or_default().push(term);
+    }
+    let roots = graph.nodes.iter().copied().filter(|node| !graph.successors.contains_key(node));
+
+    // Depth-first, without recursion (trajectories can be thousands of steps long):
+    // a node is placed once all the nodes leading to it are.
+    let mut positions: BTreeMap<u64, NodePosition> = BTreeMap::new();
+    let mut next_leaf = 0.0;
+    let mut stack: Vec<(u64, usize, bool)> = roots.map(|root| (root, 0, false)).collect();
+    stack.reverse();
+    while let Some((node, level, children_placed)) = stack.pop() {
+        let children = predecessors.get(&node).map_or(&[][..], |children| &children[..]);
+        if !children_placed && !children.is_empty() {
+            stack.push((node, level, true));
+            stack.extend(children.iter().rev().map(|&child| (child, level + 1, false)));
+            continue;
+        }
+        let x = if children.is_empty() {
+            next_leaf += 1.0;
+            next_leaf - 1.0
+        } else {
+            children.iter().map(|child| positions[child].x).sum::<f64>() / children.len() as f64
+        };
+        positions.insert(node, NodePosition { x, level });
+    }
+    positions
+}
+
+/// Writes the edges of the graph as CSV, one `source,target` line per edge (from each term to the next),
+/// with the `Source,Target` header expected by Gephi (e.g. `networkx.from_pandas_edgelist(df, "Source", "Target",
+/// create_using=networkx.DiGraph)` reads it too).
+pub fn edge_list_csv(graph: &TrajectoryGraph) -> String {
+    let mut csv = String::from("Source,Target\n");
+    for (term, next) in &graph.successors {
+        csv.push_str(&format!("{},{}\n", term, next));
+    }
+    csv
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collatz;
+
+    // Test function for `layout`.
+    #[test]
+    fn test_layout() {
+        // 3 and 20 both lead to 10: two leaves, and 10 centered between their branches.
+        let graph = collatz::merged_graph(&[6, 20], collatz::DEFAULT_STEP_LIMIT);
+        let positions = layout(&graph);
+        assert_eq!(positions.len(), graph.nodes.len());
+        assert_eq!(positions[&1], NodePosition { x: 0.5, level: 0 });
+        assert_eq!(positions[&6], NodePosition { x: 0.0, level: 8 });
+        assert_eq!(positions[&20], NodePosition { x: 1.0, level: 7 });
+        assert_eq!(positions[&10].x, 0.5);
+    }
+
+    // Test function for `edge_list_csv`.
+    #[test]
+    fn test_edge_list_csv() {
+        let graph = collatz::merged_graph(&[3, 20], collatz::DEFAULT_STEP_LIMIT);
+        let csv = edge_list_csv(&graph);
+        assert!(csv.starts_with("Source,Target\n2,1\n"));
+        assert_eq!(csv.lines().count(), graph.edge_count() + 1);
+        assert!(csv.contains("\n3,10\n") && csv.contains("\n20,10\n"));
+    }
+}
@@ -0,0 +1,96 @@
+// File transform.rs
+// This module contains the per-term transforms that can be applied to a sequence before it is plotted.
+// A transform maps each term of a Collatz sequence to the value drawn on the Y axis,
+// so new views of a trajectory only need a new variant here.
+
+/// A function applied to every term of a sequence before plotting it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TermTransform {
+    Value,    // The term itself (no transform)
+    DigitSum, // The sum of the decimal digits of the term
+    BitLength, // The number of binary digits of the term
+}
+
+impl TermTransform {
+    /// Every transform, in the order shown in the selector.
+    pub const ALL: [TermTransform; 3] = [TermTransform::Value, TermTransform::DigitSum, TermTransform::BitLength];
+
+    /// Applies the transform to a single term.
+    pub fn apply(self, term: u64) -> u64 {
+        match self {
+            TermTransform::Value => term,
+            TermTransform::DigitSum => digit_sum(term),
+            TermTransform::BitLength => bit_length(term),
+        }
+    }
+
+    /// Returns the description of the Y axis for this transform.
+    pub fn axis_label(self) -> &'static str {
+        match self {
+            TermTransform::Value => "Value",
+            TermTransform::DigitSum => "Digit sum",
+            TermTransform::BitLength => "Bit length",
+        }
+    }
+}
+
+impl std::fmt::Display for TermTransform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The selector shows the same text as the Y axis.
+        write!(f, "{}", self.axis_label())
+    }
+}
+
+/// Computes the sum of the decimal digits of `n` (e.g. 9232 -> 9 + 2 + 3 + 2 = 16).
+///
+/// The digit sum of a `u64` never exceeds 9 * 20 = 180, which makes it a compact,
+/// bounded signal even when the terms themselves span many orders of magnitude.
+pub fn digit_sum(mut n: u64) -> u64 {
+    let mut sum = 0;
+    while n > 0 {
+        sum += n % 10;
+        n /= 10;
+    }
+    sum
+}
+
+/// Computes the number of binary digits of `n` (e.g. 9232 = 0b10010000010000 -> 14), 0 for 0.
+///
+/// The bit length is floor(log2 n) + 1: it follows the size of the terms like a log scale,
+/// but as a small integer that stays smooth and plottable whatever the size of the terms.
+pub fn bit_length(n: u64) -> u64 {
+    (u64::BITS - n.leading_zeros()) as u64
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `digit_sum`.
+    #[test]
+    fn test_digit_sum() {
+        assert_eq!(digit_sum(0), 0);
+        assert_eq!(digit_sum(7), 7);
+        assert_eq!(digit_sum(9232), 16);
+        assert_eq!(digit_sum(u64::MAX), 87);
+    }
+
+    // Test function for `bit_length`.
+    #[test]
+    fn test_bit_length() {
+        assert_eq!(bit_length(0), 0);
+        assert_eq!(bit_length(1), 1);
+        assert_eq!(bit_length(8), 4);
+        assert_eq!(bit_length(9232), 14);
+        assert_eq!(bit_length(u64::MAX), 64);
+    }
+
+    // Test function for `TermTransform::apply`.
+    #[test]
+    fn test_apply() {
+        assert_eq!(TermTransform::Value.apply(9232), 9232);
+        assert_eq!(TermTransform::DigitSum.apply(9232), 16);
+        assert_eq!(TermTransform::BitLength.apply(9232), 14);
+    }
+}
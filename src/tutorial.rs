@@ -0,0 +1,113 @@
+// File tutorial.rs
+// This module contains the guided tour shown at the first start of the application.
+// The tour goes through the main parts of the window (inputs, buttons, chart, statistics), highlighting
+// each one with a short explanation taken from the i18n catalog. Once it has been dismissed or finished,
+// a marker file in the user's data directory keeps it from starting again; the "Help" button re-opens it.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Starting value filled in the first input by the tour, if it is empty:
+/// 27 climbs to 9232 before falling back to 1, in 111 steps.
+pub const SAMPLE_VALUE: &str = "27";
+
+/// Returns the location of the marker file written once the tour has been seen,
+/// in the application's data directory.
+pub fn default_marker_path() -> PathBuf {
+    crate::cache::data_dir().join("tutorial_seen")
+}
+
+/// One step of the guided tour: the part of the window it highlights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TourStep {
+    Inputs,     // The value inputs
+    Buttons,    // The "Visualize" button and the other actions
+    Chart,      // The chart
+    Statistics, // The statistics grid
+}
+
+impl TourStep {
+    /// Every step, in the order of the tour.
+    pub const ALL: [TourStep; 4] = [TourStep::Inputs, TourStep::Buttons, TourStep::Chart, TourStep::Statistics];
+
+    /// Returns the position of the step in the tour (0 for the first one).
+    pub fn index(self) -> usize {
+        TourStep::ALL.iter().position(|&step| step == self).unwrap_or(0)
+    }
+
+    /// Returns the next step, or None after the last one.
+    pub fn next(self) -> Option<TourStep> {
+        TourStep::ALL.get(self.index() + 1).copied()
+    }
+
+    /// Returns the previous step, or None before the first one.
+    pub fn previous(self) -> Option<TourStep> {
+        self.index().checked_sub(1).map(|index| TourStep::ALL[index])
+    }
+
+    /// Returns the key of the title of the step in the i18n catalog.
+    pub fn title_key(self) -> &'static str {
+        match self {
+            TourStep::Inputs => "tour.inputs.title",
+            TourStep::Buttons => "tour.buttons.title",
+            TourStep::Chart => "tour.chart.title",
+            TourStep::Statistics => "tour.statistics.title",
+        }
+    }
+
+    /// Returns the key of the explanation of the step in the i18n catalog.
+    pub fn text_key(self) -> &'static str {
+        match self {
+            TourStep::Inputs => "tour.inputs.text",
+            TourStep::Buttons => "tour.buttons.text",
+            TourStep::Chart => "tour.chart.text",
+            TourStep::Statistics => "tour.statistics.text",
+        }
+    }
+}
+
+/// Tells whether the tour has already been seen (its marker file exists).
+pub fn is_seen(path: &Path) -> bool {
+    path.exists()
+}
+
+/// Writes the marker file, so that the tour does not start again at the next start.
+pub fn mark_seen(path: &Path) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, "")
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+    use crate::i18n;
+
+    // Test function for the navigation between the steps.
+    #[test]
+    fn test_tour_steps() {
+        assert_eq!(TourStep::Inputs.next(), Some(TourStep::Buttons));
+        assert_eq!(TourStep::Statistics.next(), None);
+        assert_eq!(TourStep::Inputs.previous(), None);
+        assert_eq!(TourStep::Chart.previous(), Some(TourStep::Buttons));
+        // Every step is explained in the catalog.
+        for step in TourStep::ALL {
+            for key in [step.title_key(), step.text_key()] {
+                assert_ne!(i18n::text(i18n::Language::English, key), key);
+            }
+        }
+    }
+
+    // Test function for `mark_seen`.
+    #[test]
+    fn test_mark_seen() {
+        let temp = TempPath::new("collatz_tutorial_test");
+        assert!(!is_seen(temp.path()));
+        mark_seen(temp.path()).unwrap();
+        assert!(is_seen(temp.path()));
+    }
+}
@@ -0,0 +1,302 @@
+// File verification.rs
+// This module contains the verification of huge ranges of starting values.
+// A verification checks that every starting value of a range reaches 1, chunk by chunk,
+// and keeps track of the delay records (trajectories longer than every previous one).
+// After each chunk, the progress is written to a checkpoint file, so that a verification
+// interrupted by closing the application (or by a crash) can be resumed where it stopped.
+// A long ("overnight") verification can also write a report when it completes: the delay records found,
+// the throughput and the anomalies (starting values it could not verify).
+
+use rayon::prelude::*;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::{cache, collatz};
+
+/// Returns the default location of the checkpoint file, in the application's data directory.
+pub fn default_checkpoint_path() -> PathBuf {
+    cache::data_dir().join("verification_checkpoint.txt")
+}
+
+/// A delay record: a starting value whose trajectory is longer than those of all the previous ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Record {
+    pub start: u64,           // Starting value setting the record
+    pub stopping_time: usize, // Its total stopping time
+}
+
+/// Holds the progress of a verification, as written to the checkpoint file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    pub range_start: u64,     // First starting value of the range
+    pub range_end: u64,       // Last starting value of the range
+    pub verified: u64,        // Number of starting values verified so far, from `range_start`
+    pub elapsed_secs: f64,    // Computation time spent so far, over all the sessions
+    pub records: Vec<Record>, // Delay records found so far, in increasing order
+    pub unverified: Vec<u64>, // Starting values whose trajectory went beyond `collatz::MAX_BIG_BITS` bits
+    pub report: bool,         // Whether a report is written when the verification completes
+}
+
+impl Checkpoint {
+    /// Creates the checkpoint of a verification that has not started yet.
+    pub fn new(range_start: u64, range_end: u64) -> Self {
+        let range_start = range_start.max(1);
+        Checkpoint {
+            range_start,
+            range_end: range_end.max(range_start),
+            verified: 0,
+            elapsed_secs: 0.0,
+            records: Vec::new(),
+            unverified: Vec::new(),
+            report: false,
+        }
+    }
+
+    /// Returns the number of starting values in the range.
+    pub fn total(&self) -> u64 {
+        self.range_end - self.range_start + 1
+    }
+
+    /// Returns whether every starting value of the range has been verified.
+    pub fn is_complete(&self) -> bool {
+        self.verified >= self.total()
+    }
+
+    /// Returns the verified fraction of the range, between 0.0 and 1.0.
+    pub fn progress(&self) -> f64 {
+        self.verified as f64 / self.total() as f64
+    }
+
+    /// Returns the first starting value that has not been verified yet.
+    pub fn next_start(&self) -> u64 {
+        self.range_start + self.verified
+    }
+}
+
+/// Verifies the next `chunk_size` starting values of a checkpoint and returns the updated checkpoint.
+/// The stopping times are computed in parallel (on 64 bits, promoted to 128 bits or arbitrary precision when needed),
+/// then scanned in order for records.
+pub fn verify_chunk(mut checkpoint: Checkpoint, chunk_size: u64) -> Checkpoint {
+    if checkpoint.is_complete() {
+        return checkpoint;
+    }
+
+    let timer = Instant::now();
+    let first = checkpoint.next_start();
+    let count = chunk_size.max(1).min(checkpoint.total() - checkpoint.verified);
+    let last = first + (count - 1);
+
+    let times: Vec<Option<usize>> = (first..=last)
+        .into_par_iter()
+        .map(|n| collatz::total_stopping_time_auto(n).steps)
+        .collect();
+
+    let mut best = checkpoint.records.last().map(|record| record.stopping_time);
+    for (start, time) in (first..=last).zip(times) {
+        match time {
+            Some(time) if best.is_none_or(|best| time > best) => {
+                checkpoint.records.push(Record { start, stopping_time: time });
+                best = Some(time);
+            }
+            Some(_) => {}
+            None => checkpoint.unverified.push(start),
+        }
+    }
+
+    checkpoint.verified += count;
+    checkpoint.elapsed_secs += timer.elapsed().as_secs_f64();
+    checkpoint
+}
+
+/// Writes a checkpoint as a small text file (one `key=value` line per field, one line per record).
+/// The data goes to a temporary file first, renamed at the end, so a crash never leaves a half-written checkpoint.
+pub fn save_checkpoint(path: &Path, checkpoint: &Checkpoint) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut content = format!(
+        "range_start={}\nrange_end={}\nverified={}\nelapsed_secs={}\n",
+        checkpoint.range_start, checkpoint.range_end, checkpoint.verified, checkpoint.elapsed_secs,
+    );
+    for record in &checkpoint.records {
+        content.push_str(&format!("record={} {}\n", record.start, record.stopping_time));
+    }
+    for start in &checkpoint.unverified {
+        content.push_str(&format!("unverified={}\n", start));
+    }
+    if checkpoint.report {
+        content.push_str("report=1\n");
+    }
+
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, path)
+}
+
+/// Reads a checkpoint written by `save_checkpoint`.
+pub fn load_checkpoint(path: &Path) -> io::Result<Checkpoint> {
+    let invalid = |line: &str| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid checkpoint line: {}", line));
+
+    let content = fs::read_to_string(path)?;
+    let mut checkpoint = Checkpoint::new(1, 1);
+    let mut has_range = (false, false);
+
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        let (key, value) = line.split_once('=').ok_or_else(|| invalid(line))?;
+        match key {
+            "range_start" => {
+                checkpoint.range_start = value.parse().map_err(|_| invalid(line))?;
+                has_range.0 = true;
+            }
+            "range_end" => {
+                checkpoint.range_end = value.parse().map_err(|_| invalid(line))?;
+                has_range.1 = true;
+            }
+            "verified" => checkpoint.verified = value.parse().map_err(|_| invalid(line))?,
+            "elapsed_secs" => checkpoint.elapsed_secs = value.parse().map_err(|_| invalid(line))?,
+            "record" => {
+                let (start, time) = value.split_once(' ').ok_or_else(|| invalid(line))?;
+                checkpoint.records.push(Record {
+                    start: start.parse().map_err(|_| invalid(line))?,
+                    stopping_time: time.parse().map_err(|_| invalid(line))?,
+                });
+            }
+            "unverified" => checkpoint.unverified.push(value.parse().map_err(|_| invalid(line))?),
+            "report" => checkpoint.report = value == "1",
+            _ => return Err(invalid(line)),
+        }
+    }
+
+    // A checkpoint without its range (or with an impossible one) cannot be resumed.
+    if has_range != (true, true)
+        || checkpoint.range_start == 0
+        || checkpoint.range_end < checkpoint.range_start
+        || checkpoint.verified > checkpoint.total()
+    {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "Incomplete checkpoint"));
+    }
+
+    Ok(checkpoint)
+}
+
+/// Writes a duration as hours, minutes and seconds, e.g. "2 h 05 min 12 s".
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    match seconds {
+        3600.. => format!("{} h {:02} min {:02} s", seconds / 3600, seconds % 3600 / 60, seconds % 60),
+        60.. => format!("{} min {:02} s", seconds / 60, seconds % 60),
+        _ => format!("{} s", seconds),
+    }
+}
+
+/// Writes the report of a completed verification.
+///
+/// # Arguments
+/// * `checkpoint` - The verification.
+/// * `finished` - Local time it completed, RFC 3339.
+///
+/// # Returns
+/// * `String` - The report: range, time and throughput, then the delay records and the anomalies, one per line.
+pub fn report(checkpoint: &Checkpoint, finished: &str) -> String {
+    let throughput = if checkpoint.elapsed_secs > 0.0 {
+        format!("{:.0} values/s", checkpoint.verified as f64 / checkpoint.elapsed_secs)
+    } else {
+        "not measured".to_string()
+    };
+    let mut lines = vec![
+        "Collatz verification report".to_string(),
+        format!(
+            "Range: {} to {} ({} starting values)",
+            checkpoint.range_start,
+            checkpoint.range_end,
+            checkpoint.total()
+        ),
+        format!("Verified: {} starting values", checkpoint.verified),
+        format!("Finished: {}", finished),
+        format!("Computation time: {} (over all the sessions)", format_duration(checkpoint.elapsed_secs)),
+        format!("Throughput: {}", throughput),
+        String::new(),
+        format!("Delay records ({}):", checkpoint.records.len()),
+    ];
+    lines.extend(checkpoint.records.iter().map(|record| format!("  {}: {} steps", record.start, record.stopping_time)));
+    lines.push(String::new());
+    if checkpoint.unverified.is_empty() {
+        lines.push("Anomalies: none, every starting value reaches 1".to_string());
+    } else {
+        lines.push(format!(
+            "Anomalies: {} starting values not verified (beyond {} bits):",
+            checkpoint.unverified.len(),
+            collatz::MAX_BIG_BITS,
+        ));
+        lines.extend(checkpoint.unverified.iter().map(|start| format!("  {}", start)));
+    }
+    lines.join("\n") + "\n"
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempPath;
+
+    // Test function for `verify_chunk`.
+    #[test]
+    fn test_verify_chunk() {
+        // Verifying in several chunks gives the same result as in a single one.
+        let mut chunked = Checkpoint::new(1, 1000);
+        while !chunked.is_complete() {
+            chunked = verify_chunk(chunked, 300);
+        }
+        let whole = verify_chunk(Checkpoint::new(1, 1000), 1000);
+        assert_eq!(chunked.records, whole.records);
+        assert_eq!(chunked.verified, 1000);
+
+        // Known delay records below 1000: 27 (111 steps) and 871 (178 steps).
+        assert!(whole.records.contains(&Record { start: 27, stopping_time: 111 }));
+        assert_eq!(whole.records.last(), Some(&Record { start: 871, stopping_time: 178 }));
+        assert!(whole.unverified.is_empty());
+
+        // The very end of the u64 range can be verified without overflowing the counters.
+        let end = verify_chunk(Checkpoint::new(u64::MAX - 9, u64::MAX), 100);
+        assert!(end.is_complete());
+        assert_eq!(end.verified, 10);
+    }
+
+    // Test function for `report`.
+    #[test]
+    fn test_report() {
+        let mut checkpoint = verify_chunk(Checkpoint::new(1, 1000), 1000);
+        checkpoint.elapsed_secs = 7512.0;
+        let report = report(&checkpoint, "2026-10-16T06:12:00+02:00");
+        assert!(report.contains("Range: 1 to 1000 (1000 starting values)\n"));
+        assert!(report.contains("Computation time: 2 h 05 min 12 s"));
+        assert!(report.contains("  871: 178 steps\n"));
+        assert!(report.contains("Anomalies: none"));
+        checkpoint.unverified.push(999);
+        assert!(super::report(&checkpoint, "").contains("Anomalies: 1 starting values not verified (beyond 65536 bits):\n  999\n"));
+    }
+
+    // Test function for `save_checkpoint` and `load_checkpoint` (round trip through the file).
+    #[test]
+    fn test_save_checkpoint() {
+        let temp = TempPath::new("collatz_verification_test.txt");
+        let path = temp.path();
+
+        let checkpoint = verify_chunk(Checkpoint::new(5, 5000), 2000);
+        save_checkpoint(path, &checkpoint).unwrap();
+        assert_eq!(load_checkpoint(path).unwrap(), checkpoint);
+        // The request for a report survives a restart.
+        let checkpoint = Checkpoint { report: true, ..checkpoint };
+        save_checkpoint(path, &checkpoint).unwrap();
+        assert!(load_checkpoint(path).unwrap().report);
+
+        // A damaged file is rejected rather than resumed from a wrong position.
+        fs::write(path, "range_start=5\nverified=abc\n").unwrap();
+        assert!(load_checkpoint(path).is_err());
+
+        let _ = fs::remove_file(path);
+    }
+}
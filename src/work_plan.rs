@@ -0,0 +1,190 @@
+// File work_plan.rs
+// This module contains the work plans: text files listing starting values and ranges to process,
+// each with the outputs wanted, which the application runs as a queue of background jobs
+// while the window stays usable. One job per line, "#" starts a comment:
+// # value or range (start..end), then the outputs: stats, chart, csv (stats if none is given)
+// 27 chart,csv
+// 1..100000 stats,chart
+// The outputs of every job of a plan are written to a folder of their own.
+
+use crate::collatz;
+
+/// What a job of a work plan is run on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobTarget {
+    Value(u64),      // The sequence of a starting value
+    Range(u64, u64), // The total stopping times of the starting values of a range (bounds included)
+}
+
+impl std::fmt::Display for JobTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobTarget::Value(value) => write!(f, "{}", value),
+            JobTarget::Range(start, end) => write!(f, "{}..{}", start, end),
+        }
+    }
+}
+
+/// Output written by a job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobOutput {
+    Stats, // The statistics, as text
+    Chart, // The chart: the sequence chart of a value, the density chart of a range
+    Csv,   // The data: the terms of a value, the stopping times of a range
+}
+
+impl JobOutput {
+    /// Every output, in the order they are written.
+    pub const ALL: [JobOutput; 3] = [JobOutput::Stats, JobOutput::Chart, JobOutput::Csv];
+
+    /// Returns the name of the output in the plans.
+    pub fn name(self) -> &'static str {
+        match self {
+            JobOutput::Stats => "stats",
+            JobOutput::Chart => "chart",
+            JobOutput::Csv => "csv",
+        }
+    }
+}
+
+/// One line of a work plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanJob {
+    pub target: JobTarget,       // What the job is run on
+    pub outputs: Vec<JobOutput>, // What it writes, in the order of `JobOutput::ALL`
+}
+
+impl PlanJob {
+    /// Returns the base name of the files of the job, e.g. "job3_27" or "job4_1-100000" for the job at `index` (from 0).
+    pub fn file_stem(&self, index: usize) -> String {
+        match self.target {
+            JobTarget::Value(value) => format!("job{}_{}", index + 1, value),
+            JobTarget::Range(start, end) => format!("job{}_{}-{}", index + 1, start, end),
+        }
+    }
+}
+
+/// Reads a work plan.
+///
+/// # Arguments
+/// * `text` - The plan, one job per line (see the top of this file).
+///
+/// # Returns
+/// * `Ok(Vec<PlanJob>)` - The jobs, in the order of the plan.
+/// * `Err(String)` - A message naming the first line that cannot be read, or saying that the plan is empty.
+pub fn parse_plan(text: &str) -> Result<Vec<PlanJob>, String> {
+    let mut jobs = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let error = |message: String| format!("Line {} of the work plan: {}", number + 1, message);
+        let (target, outputs) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let value = |text: &str| match text.trim().parse::<u64>() {
+            Ok(value) if value > 0 => Ok(value),
+            _ => Err(error(format!("invalid starting value \"{}\"", text.trim()))),
+        };
+        let target = match target.split_once("..") {
+            Some((start, end)) => {
+                let (start, end) = (value(start)?, value(end)?);
+                if start > end {
+                    return Err(error(format!("the range {}..{} is empty", start, end)));
+                }
+                JobTarget::Range(start, end)
+            }
+            None => JobTarget::Value(value(target)?),
+        };
+        let mut wanted = Vec::new();
+        for name in outputs.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            match JobOutput::ALL.into_iter().find(|output| output.name() == name) {
+                Some(output) => wanted.push(output),
+                None => return Err(error(format!("unknown output \"{}\" (expected stats, chart or csv)", name))),
+            }
+        }
+        let outputs = if wanted.is_empty() {
+            vec![JobOutput::Stats]
+        } else {
+            JobOutput::ALL.into_iter().filter(|output| wanted.contains(output)).collect()
+        };
+        jobs.push(PlanJob { target, outputs });
+    }
+    if jobs.is_empty() {
+        return Err("The work plan lists no job".to_string());
+    }
+    Ok(jobs)
+}
+
+/// Writes the statistics of the sequence of a starting value, one per line.
+pub fn value_stats(sequence: &[u64]) -> String {
+    let stats = collatz::calculate_stats(sequence);
+    format!(
+        "Starting value: {}\nFlight time: {} steps\nPeak: {} (step {})\nStopping time: {} steps\nEven terms: {}\nOdd terms: {}\n",
+        sequence.first().copied().unwrap_or(0),
+        stats.length.saturating_sub(1),
+        stats.max_value,
+        stats.max_value_index,
+        stats.stopping_time,
+        stats.even_count,
+        stats.odd_count,
+    )
+}
+
+/// Writes the summary of the total stopping times of a range, one statistic per line.
+pub fn range_stats(summary: &collatz::RangeSummary) -> String {
+    format!(
+        "Range: {} to {}\nLongest trajectory: {} steps (starting at {})\nAverage total stopping time: {:.2} steps\nSkipped (overflow): {}\n",
+        summary.range_start, summary.range_end, summary.max_time, summary.max_time_start, summary.mean_time, summary.skipped,
+    )
+}
+
+/// Writes (step, term) or (starting value, total stopping time) pairs as CSV, with a header line.
+pub fn pairs_csv<T: std::fmt::Display>(header: &str, pairs: impl Iterator<Item = (u64, T)>) -> String {
+    format!("{}\n{}", header, csv_rows(pairs))
+}
+
+/// Writes pairs as CSV lines, without a header line (a range is written chunk by chunk).
+pub fn csv_rows<T: std::fmt::Display>(pairs: impl Iterator<Item = (u64, T)>) -> String {
+    let mut csv = String::new();
+    for (x, y) in pairs {
+        csv.push_str(&format!("{},{}\n", x, y));
+    }
+    csv
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `parse_plan`.
+    #[test]
+    fn test_parse_plan() {
+        let jobs = parse_plan("# plan\n27 csv, chart\n\n1..1000   # stats by default\n").unwrap();
+        assert_eq!(
+            jobs,
+            vec![
+                PlanJob { target: JobTarget::Value(27), outputs: vec![JobOutput::Chart, JobOutput::Csv] },
+                PlanJob { target: JobTarget::Range(1, 1000), outputs: vec![JobOutput::Stats] },
+            ]
+        );
+        assert_eq!(jobs[1].file_stem(1), "job2_1-1000");
+        assert!(parse_plan("27\n0 stats").unwrap_err().starts_with("Line 2"));
+        assert!(parse_plan("10..5").unwrap_err().contains("empty"));
+        assert!(parse_plan("27 image").unwrap_err().contains("unknown output"));
+        assert!(parse_plan("# nothing\n").is_err());
+    }
+
+    // Test function for `value_stats`, `range_stats`, `pairs_csv` and `csv_rows`.
+    #[test]
+    fn test_outputs() {
+        let sequence = collatz::generate_sequence(6);
+        assert!(value_stats(&sequence).starts_with("Starting value: 6\nFlight time: 8 steps\nPeak: 16 (step 4)\n"));
+        let times = collatz::stopping_times_in_range(1, 10);
+        let summary = collatz::summarize_stopping_times(1, 10, &times);
+        assert!(range_stats(&summary).contains("Longest trajectory: 19 steps (starting at 9)"));
+        let steps = sequence.iter().take(2).enumerate().map(|(step, &term)| (step as u64, term));
+        assert_eq!(pairs_csv("step,value", steps), "step,value\n0,6\n1,3\n");
+        assert_eq!(csv_rows([(27u64, 111usize)].into_iter()), "27,111\n");
+    }
+}
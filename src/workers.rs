@@ -0,0 +1,136 @@
+// File workers.rs
+// This module contains the pool of worker threads running the parallel computations of the background tasks
+// (range scans, verification, sampling, fractal...), and the setting limiting its size.
+// Limiting the pool keeps some cores free for the rest of the machine while a long job runs.
+// The GPU scan is dispatched from a single thread, which any limit allows.
+
+use std::sync::{Arc, Mutex};
+
+/// Number of worker threads of the parallel computations: all the cores, or a fixed number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WorkerThreads {
+    #[default]
+    AllCores,      // One thread per core (the default of rayon)
+    Limited(usize), // At most this many threads (at least 1)
+}
+
+impl WorkerThreads {
+    /// Returns the choices offered for a machine with `cores` cores: all of them, then 1 to `cores - 1`.
+    pub fn choices(cores: usize) -> Vec<WorkerThreads> {
+        std::iter::once(WorkerThreads::AllCores).chain((1..cores).map(WorkerThreads::Limited)).collect()
+    }
+
+    /// Reads the setting written by `to_setting` ("all" or a number of threads). Anything else gives all the cores.
+    pub fn from_setting(value: &str) -> Self {
+        match value.trim().parse::<usize>() {
+            Ok(count) if count > 0 => WorkerThreads::Limited(count),
+            _ => WorkerThreads::AllCores,
+        }
+    }
+
+    /// Writes the setting read by `from_setting`.
+    pub fn to_setting(self) -> String {
+        match self {
+            WorkerThreads::AllCores => "all".to_string(),
+            WorkerThreads::Limited(count) => count.to_string(),
+        }
+    }
+
+    /// Returns the number of threads of the pool (0 leaves the choice to rayon: one per core).
+    fn count(self) -> usize {
+        match self {
+            WorkerThreads::AllCores => 0,
+            WorkerThreads::Limited(count) => count.max(1),
+        }
+    }
+}
+
+impl std::fmt::Display for WorkerThreads {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkerThreads::AllCores => write!(f, "All cores"),
+            WorkerThreads::Limited(1) => write!(f, "1 thread"),
+            WorkerThreads::Limited(count) => write!(f, "{} threads", count),
+        }
+    }
+}
+
+/// Returns the number of cores of the machine (1 if it cannot be known).
+pub fn available_cores() -> usize {
+    std::thread::available_parallelism().map(|cores| cores.get()).unwrap_or(1)
+}
+
+// Pool of the background computations, with the setting it was built for (built on first use).
+static POOL: Mutex<Option<(WorkerThreads, Arc<rayon::ThreadPool>)>> = Mutex::new(None);
+
+/// Changes the number of worker threads. The tasks already running finish on the previous pool.
+pub fn set_worker_threads(threads: WorkerThreads) {
+    if let Ok(mut pool) = POOL.lock() {
+        if pool.as_ref().is_some_and(|(current, _)| *current != threads) {
+            *pool = None;
+        }
+        if pool.is_none() {
+            *pool = build_pool(threads).map(|built| (threads, built));
+        }
+    }
+}
+
+/// Builds a pool of `threads` worker threads, or returns None if the threads cannot be started.
+fn build_pool(threads: WorkerThreads) -> Option<Arc<rayon::ThreadPool>> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.count())
+        .thread_name(|index| format!("collatz-worker-{}", index))
+        .build()
+        .ok()
+        .map(Arc::new)
+}
+
+/// Runs `task` in the worker pool: the parallel iterators it uses are spread over the threads of the pool only.
+/// Without a pool (the threads could not be started), the task runs on the calling thread with the global pool.
+pub fn install<T: Send>(task: impl FnOnce() -> T + Send) -> T {
+    let pool = match POOL.lock() {
+        Ok(mut pool) => {
+            if pool.is_none() {
+                *pool = build_pool(WorkerThreads::AllCores).map(|built| (WorkerThreads::AllCores, built));
+            }
+            pool.as_ref().map(|(_, pool)| Arc::clone(pool))
+        }
+        Err(_) => None,
+    };
+    match pool {
+        Some(pool) => pool.install(task),
+        None => task(),
+    }
+}
+
+// Test module: Contains unit tests for the functions in this file.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test function for `WorkerThreads::from_setting`, `WorkerThreads::to_setting` and `WorkerThreads::choices`.
+    #[test]
+    fn test_worker_threads() {
+        assert_eq!(WorkerThreads::from_setting("3"), WorkerThreads::Limited(3));
+        assert_eq!(WorkerThreads::from_setting("all"), WorkerThreads::AllCores);
+        assert_eq!(WorkerThreads::from_setting("0"), WorkerThreads::AllCores);
+        assert_eq!(WorkerThreads::from_setting(&WorkerThreads::Limited(2).to_setting()), WorkerThreads::Limited(2));
+        assert_eq!(
+            WorkerThreads::choices(3),
+            vec![WorkerThreads::AllCores, WorkerThreads::Limited(1), WorkerThreads::Limited(2)]
+        );
+        assert_eq!(WorkerThreads::Limited(1).to_string(), "1 thread");
+    }
+
+    // Test function for `install`.
+    #[test]
+    fn test_install() {
+        set_worker_threads(WorkerThreads::Limited(2));
+        assert_eq!(install(rayon::current_num_threads), 2);
+        let sum: u64 = install(|| {
+            use rayon::prelude::*;
+            (1..=100u64).into_par_iter().sum()
+        });
+        assert_eq!(sum, 5050);
+    }
+}